@@ -1,8 +1,22 @@
+mod account;
+mod cooking;
+mod editor;
+mod error;
 mod header;
+mod households;
+mod ingredients;
 mod meal_plans;
+mod recipe;
 mod recipes;
 mod scraped;
+pub use account::{AccountMenu, SignInPage};
+pub use cooking::CookingPage;
+pub use editor::{EditRecipePage, NewRecipePage};
+pub use error::ErrorAlert;
 pub use header::Header;
-pub use meal_plans::MealPlansPage;
+pub use households::{HouseholdPage, HouseholdsPage};
+pub use ingredients::IngredientsPage;
+pub use meal_plans::{MealPlanPage, MealPlansPage};
+pub use recipe::RecipePage;
 pub use recipes::{RecipeGrid, RecipesPage};
 pub use scraped::ScrapedRecipe;
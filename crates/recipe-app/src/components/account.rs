@@ -0,0 +1,163 @@
+use dioxus::prelude::*;
+use dioxus_router::prelude::*;
+
+use recipe_shared::{Credentials, UserInput, ValidationErrors, MIN_PASSWORD_LENGTH};
+
+use crate::{
+    hooks::{use_mutations, use_user, Changed},
+    server::{register, sign_in, sign_out},
+    AppError, Route,
+};
+
+#[component]
+fn FieldErrors(cx: Scope, errors: Vec<String>) -> Element {
+    cx.render(rsx!(errors.iter().map(|e| rsx!(
+        p { class: "text-error text-sm", "{e}" }
+    ))))
+}
+
+/// Signing in to an existing account, or registering a new one.
+pub fn SignInPage(cx: Scope) -> Element {
+    let registering = use_state(cx, || false);
+    let username = use_state(cx, String::new);
+    let display_name = use_state(cx, String::new);
+    let password = use_state(cx, String::new);
+    let errors = use_state(cx, ValidationErrors::default);
+    let server_error = use_state::<Option<String>>(cx, || None);
+    let navigator = use_navigator(cx);
+    let mutations = use_mutations(cx);
+
+    let submit = move |_| {
+        let registering = **registering;
+        let input = UserInput {
+            username: username.trim().to_string(),
+            display_name: Some(display_name.trim().to_string()).filter(|n| !n.is_empty()),
+            password: password.get().clone(),
+        };
+        if registering {
+            if let Err(e) = input.validate() {
+                errors.set(e);
+                return;
+            }
+        }
+        errors.set(ValidationErrors::default());
+        let navigator = navigator.clone();
+        let mutations = mutations.clone();
+        let server_error = server_error.to_owned();
+        let errors = errors.to_owned();
+        cx.spawn(async move {
+            // Everything visible may change once someone is signed in.
+            let result = if registering {
+                mutations
+                    .run(register(input), |_| vec![Changed::Account])
+                    .await
+            } else {
+                let credentials = Credentials {
+                    username: input.username,
+                    password: input.password,
+                };
+                mutations
+                    .run(sign_in(credentials), |_| vec![Changed::Account])
+                    .await
+            };
+            match result {
+                Ok(_) => {
+                    navigator.push(Route::RecipesPage {});
+                }
+                Err(AppError::Validation { errors: e }) => errors.set(e),
+                Err(e) => server_error.set(Some(e.to_string())),
+            }
+        });
+    };
+
+    let (title, action, other) = if **registering {
+        ("Register", "Create account", "I already have an account")
+    } else {
+        ("Sign in", "Sign in", "Create an account")
+    };
+    let username_value = username.get();
+    let display_name_value = display_name.get();
+    let password_value = password.get();
+    let password_hint = format!("At least {MIN_PASSWORD_LENGTH} characters");
+    cx.render(rsx!(
+        div { class: "max-w-sm mx-auto p-4 flex flex-col gap-4",
+            h1 { class: "text-2xl font-bold", "{title}" }
+            label { class: "form-control",
+                span { class: "label-text", "Username" }
+                input {
+                    class: "input input-bordered",
+                    autocomplete: "username",
+                    value: "{username_value}",
+                    oninput: move |evt| username.set(evt.value.clone()),
+                }
+                FieldErrors { errors: errors.get().get("username").to_vec() }
+            }
+            registering.then(|| rsx!(
+                label { class: "form-control",
+                    span { class: "label-text", "Display name (optional)" }
+                    input {
+                        class: "input input-bordered",
+                        value: "{display_name_value}",
+                        oninput: move |evt| display_name.set(evt.value.clone()),
+                    }
+                }
+            ))
+            label { class: "form-control",
+                span { class: "label-text", "Password" }
+                input {
+                    class: "input input-bordered",
+                    r#type: "password",
+                    placeholder: "{password_hint}",
+                    value: "{password_value}",
+                    oninput: move |evt| password.set(evt.value.clone()),
+                }
+                FieldErrors { errors: errors.get().get("password").to_vec() }
+            }
+            server_error.get().as_ref().map(|e| rsx!(div { class: "alert alert-error", "{e}" }))
+            button { class: "btn btn-primary", r#type: "button", onclick: submit, "{action}" }
+            button {
+                class: "btn btn-ghost btn-sm",
+                r#type: "button",
+                onclick: move |_| {
+                    registering.set(!**registering);
+                    server_error.set(None);
+                },
+                "{other}"
+            }
+        }
+    ))
+}
+
+/// The signed in user's name with a button to sign out, or a link to sign in.
+pub fn AccountMenu(cx: Scope) -> Element {
+    let user = use_user(cx);
+    let mutations = use_mutations(cx);
+    let navigator = use_navigator(cx);
+    cx.render(match user {
+        Some(user) => rsx!(
+            div { class: "flex items-center gap-2 ml-4",
+                span { "{user.display_name}" }
+                button {
+                    class: "btn btn-ghost btn-sm",
+                    r#type: "button",
+                    onclick: move |_| {
+                        let mutations = mutations.clone();
+                        let navigator = navigator.clone();
+                        cx.spawn(async move {
+                            match mutations.run(sign_out(), |_| vec![Changed::Account]).await {
+                                Ok(()) => {
+                                    navigator.push(Route::RecipesPage {});
+                                }
+                                Err(e) => log::error!("Failed to sign out: {:?}", e),
+                            }
+                        });
+                    },
+                    "Sign out"
+                }
+            }
+        ),
+        None => rsx!(
+            Link { to: Route::SignInPage {}, class: "btn btn-ghost btn-sm ml-4", "Sign in" }
+        ),
+    })
+}
@@ -0,0 +1,289 @@
+use chrono::{DateTime, Duration, Utc};
+use dioxus::prelude::*;
+use dioxus_query::prelude::*;
+use dioxus_router::prelude::*;
+
+use recipe_scrape::{mentions_ingredient, step_timers};
+use recipe_shared::RecipeDetail;
+
+use crate::{
+    components::{recipes::toggle, ErrorAlert},
+    hooks::{use_recipe, QueryError, QueryValue},
+    AppError, Route,
+};
+
+#[component]
+pub fn CookingPage(cx: Scope, slug: String) -> Element {
+    let recipe = use_recipe(cx, slug)?;
+    cx.render(match recipe.result().value() {
+        QueryResult::Ok(QueryValue::Recipe(Some(detail))) => rsx!(CookingView {
+            detail: detail.clone()
+        }),
+        QueryResult::Ok(_) => rsx!(ErrorAlert {
+            error: AppError::NotFound
+        }),
+        QueryResult::Err(QueryError::Server(error)) => rsx!(ErrorAlert {
+            error: error.clone()
+        }),
+        QueryResult::Loading(_) => rsx!(div { "loading" }),
+    })
+}
+
+const COOKING_MODE_ID: &str = "cooking-mode";
+
+/// A timer started from a length of time mentioned in a step.
+#[derive(Clone, Debug, PartialEq)]
+struct RunningTimer {
+    id: usize,
+    /// Which step it's for and how long, e.g. "Step 3: 20 mins".
+    label: String,
+    ends_at: DateTime<Utc>,
+    /// Whether the notification that it's done has been shown.
+    rung: bool,
+}
+
+/// Time left as it appears on a kitchen timer, e.g. "4:05" or "1:15:00".
+fn format_remaining(seconds: i64) -> String {
+    let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+    if hours > 0 {
+        format!("{hours}:{minutes:02}:{seconds:02}")
+    } else {
+        format!("{minutes}:{seconds:02}")
+    }
+}
+
+/// The recipe's method one step at a time, in text large enough to read from
+/// across the kitchen, with the ingredients to hand as a checklist. Lengths of
+/// time in a step can be started as timers, and the screen is kept awake.
+#[component]
+fn CookingView(cx: Scope, detail: RecipeDetail) -> Element {
+    let recipe = &detail.recipe;
+    let step = use_state(cx, || 0usize);
+    let show_ingredients = use_state(cx, || true);
+    let used = use_state::<Vec<i64>>(cx, Vec::new);
+    let timers = use_ref::<Vec<RunningTimer>>(cx, Vec::new);
+    let now = use_state(cx, Utc::now);
+    #[cfg(not(feature = "ssr"))]
+    {
+        // The script keeps the screen awake while cooking mode is open, ticks
+        // every second so the timers count down, and shows a notification
+        // for each timer which is done. Permission for notifications is asked
+        // for when the first timer is started, as browsers only allow that in
+        // response to a click.
+        let create_eval = use_eval(cx);
+        use_future(cx, (), |_| {
+            let create_eval = create_eval.clone();
+            let timers = timers.clone();
+            let now = now.clone();
+            async move {
+                let script = format!(
+                    r#"
+                    let lock = null;
+                    const lockScreen = async () => {{
+                        if (!("wakeLock" in navigator) || document.visibilityState !== "visible") {{
+                            return;
+                        }}
+                        try {{
+                            lock = await navigator.wakeLock.request("screen");
+                        }} catch (e) {{}}
+                    }};
+                    const askToNotify = (event) => {{
+                        if (event.target.closest("[data-timer]") && "Notification" in window
+                            && Notification.permission === "default") {{
+                            Notification.requestPermission();
+                        }}
+                    }};
+                    document.addEventListener("visibilitychange", lockScreen);
+                    document.addEventListener("click", askToNotify);
+                    lockScreen();
+                    const tick = setInterval(() => {{
+                        if (!document.getElementById("{COOKING_MODE_ID}")) {{
+                            clearInterval(tick);
+                            document.removeEventListener("visibilitychange", lockScreen);
+                            document.removeEventListener("click", askToNotify);
+                            if (lock) {{
+                                lock.release();
+                            }}
+                            return;
+                        }}
+                        dioxus.send(true);
+                    }}, 1000);
+                    while (true) {{
+                        const message = await dioxus.recv();
+                        if ("Notification" in window && Notification.permission === "granted") {{
+                            new Notification(message.notify);
+                        }}
+                        if (navigator.vibrate) {{
+                            navigator.vibrate([300, 100, 300]);
+                        }}
+                    }}
+                    "#
+                );
+                let Ok(eval) = create_eval(&script) else {
+                    return;
+                };
+                while eval.recv().await.is_ok() {
+                    let time = Utc::now();
+                    now.set(time);
+                    let done = timers.with_mut(|timers| {
+                        timers
+                            .iter_mut()
+                            .filter(|timer| !timer.rung && timer.ends_at <= time)
+                            .map(|timer| {
+                                timer.rung = true;
+                                timer.label.clone()
+                            })
+                            .collect::<Vec<_>>()
+                    });
+                    for label in done {
+                        let notify = format!("{label} is done");
+                        let _ = eval.send(serde_json::json!({ "notify": notify }));
+                    }
+                }
+            }
+        });
+    }
+    let steps = detail.steps.len();
+    let current = detail.steps.get(**step);
+    let first = **step == 0;
+    let last = **step + 1 >= steps;
+    let number = (**step + 1).min(steps);
+    let toggle_label = if **show_ingredients {
+        "Hide ingredients"
+    } else {
+        "Show ingredients"
+    };
+    let method = match current {
+        Some(current) => rsx!(
+            div { class: "flex-1 flex flex-col justify-center gap-4",
+                p { class: "text-sm uppercase opacity-70", "Step {number} of {steps}" }
+                progress { class: "progress progress-primary w-full", value: "{number}", max: "{steps}" }
+                p { class: "text-2xl md:text-4xl leading-relaxed", "{current.description}" }
+                div { class: "flex flex-wrap gap-2",
+                    step_timers(&current.description).into_iter().map(|timer| {
+                        let label = format!("Step {number}: {}", timer.label);
+                        let seconds = timer.seconds;
+                        rsx!(
+                            button {
+                                key: "{label}",
+                                class: "btn btn-secondary",
+                                r#type: "button",
+                                "data-timer": "true",
+                                onclick: move |_| timers.with_mut(|timers| {
+                                    let id = timers.iter().map(|timer| timer.id + 1).max().unwrap_or_default();
+                                    timers.push(RunningTimer {
+                                        id,
+                                        label: label.clone(),
+                                        ends_at: Utc::now() + Duration::seconds(seconds.into()),
+                                        rung: false,
+                                    });
+                                }),
+                                "Start {timer.label} timer"
+                            }
+                        )
+                    })
+                }
+            }
+        ),
+        None => rsx!(p { class: "flex-1 opacity-70", "This recipe has no method." }),
+    };
+    let forward = if last {
+        rsx!(Link {
+            to: Route::RecipePage { slug: recipe.slug.clone() },
+            class: "btn btn-lg btn-primary",
+            "Finish"
+        })
+    } else {
+        rsx!(button {
+            class: "btn btn-lg btn-primary",
+            r#type: "button",
+            onclick: move |_| step.modify(|step| step + 1),
+            "Next"
+        })
+    };
+    let running = timers.read().clone();
+    cx.render(rsx!(
+        div { id: "{COOKING_MODE_ID}", class: "min-h-screen max-w-3xl mx-auto p-4 flex flex-col gap-4",
+            div { class: "flex items-center justify-between gap-2",
+                h1 { class: "text-xl md:text-2xl font-bold", "{recipe.name}" }
+                Link {
+                    to: Route::RecipePage { slug: recipe.slug.clone() },
+                    class: "btn btn-sm btn-ghost",
+                    "Done"
+                }
+            }
+            button {
+                class: "btn btn-sm btn-outline self-start",
+                r#type: "button",
+                onclick: move |_| show_ingredients.modify(|show| !show),
+                "{toggle_label}"
+            }
+            show_ingredients.then(|| rsx!(
+                ul { class: "flex flex-col gap-1",
+                    detail.ingredients.iter().map(|ingredient| {
+                        let id = ingredient.ingredient_id;
+                        let line = ingredient.to_line();
+                        let checked = used.contains(&id);
+                        let in_step = current.map_or(false, |current| {
+                            mentions_ingredient(&current.description, &ingredient.name)
+                        });
+                        let class = match (checked, in_step) {
+                            (true, _) => "line-through opacity-50",
+                            (false, true) => "font-bold text-primary",
+                            (false, false) => "",
+                        };
+                        rsx!(
+                            li { key: "{id}",
+                                label { class: "label cursor-pointer justify-start gap-3",
+                                    input {
+                                        r#type: "checkbox",
+                                        class: "checkbox",
+                                        checked: checked,
+                                        onchange: move |_| used.with_mut(|used| toggle(used, id)),
+                                    }
+                                    span { class: "{class}", "{line}" }
+                                }
+                            }
+                        )
+                    })
+                }
+            ))
+            method
+            (!running.is_empty()).then(|| rsx!(
+                ul { class: "flex flex-col gap-2",
+                    running.iter().map(|timer| {
+                        let id = timer.id;
+                        let remaining = (timer.ends_at - **now).num_seconds().max(0);
+                        let (class, time) = if remaining == 0 {
+                            ("alert alert-success", "Done!".to_string())
+                        } else {
+                            ("alert", format_remaining(remaining))
+                        };
+                        rsx!(
+                            li { key: "{id}", class: "{class} flex justify-between",
+                                span { "{timer.label}" }
+                                span { class: "text-2xl font-mono", "{time}" }
+                                button {
+                                    class: "btn btn-sm btn-ghost",
+                                    r#type: "button",
+                                    onclick: move |_| timers.with_mut(|timers| timers.retain(|timer| timer.id != id)),
+                                    "Dismiss"
+                                }
+                            }
+                        )
+                    })
+                }
+            ))
+            div { class: "sticky bottom-0 bg-base-100 py-4 grid grid-cols-2 gap-4",
+                button {
+                    class: "btn btn-lg",
+                    r#type: "button",
+                    disabled: first,
+                    onclick: move |_| step.modify(|step| step.saturating_sub(1)),
+                    "Previous"
+                }
+                forward
+            }
+        }
+    ))
+}
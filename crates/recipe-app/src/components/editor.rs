@@ -0,0 +1,600 @@
+use dioxus::prelude::*;
+use dioxus_query::prelude::*;
+use dioxus_router::prelude::*;
+
+use recipe_scrape::ScrapedIngredient;
+use recipe_shared::{
+    Diet, IngredientInput, Nutrition, RecipeDetail, RecipeInput, Tag, TagInput, TagKind,
+    ValidationErrors,
+};
+
+use crate::{
+    components::ErrorAlert,
+    hooks::{use_mutations, use_recipe, Changed, QueryError, QueryValue},
+    server::{create_recipe, update_recipe},
+    AppError, Route,
+};
+
+/// Parse a single line of ingredient text into the input expected by the server.
+pub(crate) fn parse_ingredient(line: &str) -> Result<IngredientInput, String> {
+    let parsed: ScrapedIngredient = line.trim().parse().map_err(|e| format!("{e}"))?;
+    ingredient_input(parsed)
+}
+
+/// Convert an already parsed ingredient into the input expected by the server.
+pub(crate) fn ingredient_input(parsed: ScrapedIngredient) -> Result<IngredientInput, String> {
+    parsed.to_input()
+}
+
+/// Join the names of the tags of one kind into a comma-separated list.
+pub(crate) fn tag_list(tags: &[Tag], kind: TagKind) -> String {
+    tags.iter()
+        .filter(|tag| tag.kind == kind)
+        .map(|tag| tag.name.as_str())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Split a comma-separated list of names into tags of one kind.
+pub(crate) fn tag_inputs(kind: TagKind, list: &str) -> impl Iterator<Item = TagInput> + '_ {
+    list.split(',')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(move |name| TagInput::new(kind, name))
+}
+
+/// An ingredient or step in the form. The ID stays the same as rows are
+/// moved and removed, so each keeps its element and focus.
+#[derive(Clone, Debug, Default, PartialEq)]
+struct Row {
+    id: usize,
+    text: String,
+}
+
+/// The editable state of the recipe form, kept as raw text until saved.
+#[derive(Clone, Debug, Default, PartialEq)]
+struct RecipeForm {
+    name: String,
+    source: String,
+    notes: String,
+    image_url: String,
+    prep_time_minutes: String,
+    cooking_time_minutes: String,
+    servings: String,
+    ingredients: Vec<Row>,
+    steps: Vec<Row>,
+    categories: String,
+    cuisines: String,
+    tags: String,
+    diets: Vec<Diet>,
+    /// Declared nutrition per serving, in the order of [`Nutrition::rows`].
+    nutrition: [String; 8],
+    shared: bool,
+    /// The ID of the last row added.
+    last_row_id: usize,
+}
+
+impl RecipeForm {
+    fn from_detail(detail: &RecipeDetail) -> Self {
+        let recipe = &detail.recipe;
+        let number = |n: Option<i32>| n.map(|n| n.to_string()).unwrap_or_default();
+        let mut form = Self {
+            name: recipe.name.clone(),
+            source: recipe.source.clone().unwrap_or_default(),
+            notes: recipe.notes.clone().unwrap_or_default(),
+            image_url: recipe.image_url.clone().unwrap_or_default(),
+            prep_time_minutes: number(recipe.prep_time_minutes),
+            cooking_time_minutes: number(recipe.cooking_time_minutes),
+            servings: number(recipe.servings),
+            categories: tag_list(&detail.tags, TagKind::Category),
+            cuisines: tag_list(&detail.tags, TagKind::Cuisine),
+            tags: tag_list(&detail.tags, TagKind::Tag),
+            diets: detail.diet.declared.clone(),
+            nutrition: detail
+                .nutrition
+                .declared
+                .unwrap_or_default()
+                .rows()
+                .map(|(_, _, value)| value.map(|v| v.to_string()).unwrap_or_default()),
+            shared: recipe.shared,
+            ..Default::default()
+        };
+        for ingredient in &detail.ingredients {
+            form.add_ingredient(ingredient.to_line());
+        }
+        for step in &detail.steps {
+            form.add_step(step.description.clone());
+        }
+        form
+    }
+
+    fn new_row(&mut self, text: String) -> Row {
+        self.last_row_id += 1;
+        Row {
+            id: self.last_row_id,
+            text,
+        }
+    }
+
+    fn add_ingredient(&mut self, line: String) {
+        let row = self.new_row(line);
+        self.ingredients.push(row);
+    }
+
+    fn add_step(&mut self, description: String) {
+        let row = self.new_row(description);
+        self.steps.push(row);
+    }
+
+    fn to_input(&self) -> Result<RecipeInput, ValidationErrors> {
+        let mut errors = ValidationErrors::default();
+        let optional = |s: &str| Some(s.trim().to_string()).filter(|s| !s.is_empty());
+        let mut number = |field: &str, s: &str| match s.trim() {
+            "" => None,
+            s => s
+                .parse::<i32>()
+                .map_err(|_| errors.add(field, "must be a whole number"))
+                .ok(),
+        };
+        let prep_time_minutes = number("prep_time_minutes", &self.prep_time_minutes);
+        let cooking_time_minutes = number("cooking_time_minutes", &self.cooking_time_minutes);
+        let servings = number("servings", &self.servings);
+        let nutrition = Nutrition::from_values(self.nutrition.clone().map(|s| {
+            match s.trim() {
+                "" => None,
+                s => s
+                    .parse::<f64>()
+                    .map_err(|_| errors.add("nutrition", "values must be numbers"))
+                    .ok(),
+            }
+        }));
+
+        let mut ingredients = Vec::with_capacity(self.ingredients.len());
+        for (i, row) in self.ingredients.iter().enumerate() {
+            match parse_ingredient(&row.text) {
+                Ok(ingredient) => ingredients.push(ingredient),
+                Err(e) => errors.add(&format!("ingredients.{i}"), e),
+            }
+        }
+
+        let input = RecipeInput {
+            name: self.name.trim().to_string(),
+            source: optional(&self.source),
+            notes: optional(&self.notes),
+            image_url: optional(&self.image_url),
+            prep_time_minutes,
+            cooking_time_minutes,
+            servings,
+            ingredients,
+            steps: self.steps.iter().map(|row| row.text.clone()).collect(),
+            tags: tag_inputs(TagKind::Category, &self.categories)
+                .chain(tag_inputs(TagKind::Cuisine, &self.cuisines))
+                .chain(tag_inputs(TagKind::Tag, &self.tags))
+                .collect(),
+            diets: self.diets.clone(),
+            nutrition: Some(nutrition).filter(|n| !n.is_empty()),
+            shared: self.shared,
+        };
+        if let Err(e) = input.validate() {
+            errors.merge(e);
+        }
+        if errors.is_empty() {
+            Ok(input)
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+/// Whether the editor is creating a new recipe or editing an existing one.
+#[derive(Clone, Debug, PartialEq)]
+pub enum EditorMode {
+    New,
+    Edit(RecipeDetail),
+}
+
+#[component]
+fn FieldErrors(cx: Scope, errors: Vec<String>) -> Element {
+    cx.render(rsx!(errors.iter().map(|e| rsx!(
+        p { class: "text-error text-sm", "{e}" }
+    ))))
+}
+
+#[component]
+fn ParsedIngredient(cx: Scope, parsed: Result<IngredientInput, String>) -> Element {
+    cx.render(match parsed {
+        Ok(ingredient) => {
+            let quantity = ingredient
+                .quantity
+                .map(|q| q.to_string())
+                .unwrap_or_else(|| "–".to_string());
+            let unit = ingredient.unit.as_deref().unwrap_or("count");
+            rsx!(
+                div { class: "flex gap-2 text-sm opacity-80",
+                    span { class: "badge badge-outline", "{quantity}" }
+                    span { class: "badge badge-outline", "{unit}" }
+                    span { class: "badge badge-primary", "{ingredient.name}" }
+                    ingredient.notes.as_ref().map(|n| rsx!(span { class: "italic", "{n}" }))
+                }
+            )
+        }
+        Err(e) => rsx!(div { class: "text-warning text-sm", "{e}" }),
+    })
+}
+
+#[component]
+pub fn RecipeEditor(cx: Scope, mode: EditorMode) -> Element {
+    let draft = use_state(cx, || match mode {
+        EditorMode::New => {
+            let mut form = RecipeForm::default();
+            form.add_ingredient(String::new());
+            form.add_step(String::new());
+            form
+        }
+        EditorMode::Edit(detail) => RecipeForm::from_detail(detail),
+    });
+    let errors = use_state(cx, ValidationErrors::default);
+    let server_error = use_state::<Option<String>>(cx, || None);
+    let saving = use_state(cx, || false);
+    let navigator = use_navigator(cx);
+    let mutations = use_mutations(cx);
+
+    let id = match mode {
+        EditorMode::New => None,
+        EditorMode::Edit(detail) => Some(detail.recipe.id),
+    };
+    let save = move |_| {
+        let input = match draft.get().to_input() {
+            Ok(input) => input,
+            Err(e) => {
+                errors.set(e);
+                return;
+            }
+        };
+        errors.set(ValidationErrors::default());
+        server_error.set(None);
+        saving.set(true);
+        let navigator = navigator.clone();
+        let mutations = mutations.clone();
+        let saving = saving.to_owned();
+        let server_error = server_error.to_owned();
+        let errors = errors.to_owned();
+        cx.spawn(async move {
+            let changed = |detail: &RecipeDetail| vec![Changed::Recipe(detail.recipe.slug.clone())];
+            let result = match id {
+                Some(id) => mutations.run(update_recipe(id, input), changed).await,
+                None => mutations.run(create_recipe(input), changed).await,
+            };
+            saving.set(false);
+            match result {
+                Ok(detail) => {
+                    navigator.push(Route::RecipePage {
+                        slug: detail.recipe.slug,
+                    });
+                }
+                Err(AppError::Validation { errors: e }) => errors.set(e),
+                Err(e) => server_error.set(Some(e.to_string())),
+            }
+        });
+    };
+
+    let form = draft.get();
+    let title = match mode {
+        EditorMode::New => "New recipe",
+        EditorMode::Edit(_) => "Edit recipe",
+    };
+    let save_label = if **saving { "Saving…" } else { "Save" };
+    cx.render(rsx!(
+        div { class: "max-w-4xl mx-auto p-4 flex flex-col gap-4",
+            h1 { class: "text-2xl font-bold", "{title}" }
+            div { class: "form-control",
+                label { class: "label", r#for: "recipe_name", "Name" }
+                input {
+                    id: "recipe_name",
+                    class: "input input-bordered",
+                    value: "{form.name}",
+                    oninput: move |evt| draft.with_mut(|f| f.name = evt.value.clone()),
+                }
+                FieldErrors { errors: errors.get().get("name").to_vec() }
+            }
+            div { class: "grid grid-cols-3 gap-4",
+                div { class: "form-control",
+                    label { class: "label", r#for: "recipe_prep_time", "Prep time (minutes)" }
+                    input {
+                        id: "recipe_prep_time",
+                        class: "input input-bordered",
+                        r#type: "number",
+                        min: "0",
+                        value: "{form.prep_time_minutes}",
+                        oninput: move |evt| draft.with_mut(|f| f.prep_time_minutes = evt.value.clone()),
+                    }
+                    FieldErrors { errors: errors.get().get("prep_time_minutes").to_vec() }
+                }
+                div { class: "form-control",
+                    label { class: "label", r#for: "recipe_cooking_time", "Cooking time (minutes)" }
+                    input {
+                        id: "recipe_cooking_time",
+                        class: "input input-bordered",
+                        r#type: "number",
+                        min: "0",
+                        value: "{form.cooking_time_minutes}",
+                        oninput: move |evt| draft.with_mut(|f| f.cooking_time_minutes = evt.value.clone()),
+                    }
+                    FieldErrors { errors: errors.get().get("cooking_time_minutes").to_vec() }
+                }
+                div { class: "form-control",
+                    label { class: "label", r#for: "recipe_servings", "Servings" }
+                    input {
+                        id: "recipe_servings",
+                        class: "input input-bordered",
+                        r#type: "number",
+                        min: "1",
+                        value: "{form.servings}",
+                        oninput: move |evt| draft.with_mut(|f| f.servings = evt.value.clone()),
+                    }
+                    FieldErrors { errors: errors.get().get("servings").to_vec() }
+                }
+            }
+            div { class: "form-control",
+                label { class: "label", r#for: "recipe_source", "Source" }
+                input {
+                    id: "recipe_source",
+                    class: "input input-bordered",
+                    value: "{form.source}",
+                    oninput: move |evt| draft.with_mut(|f| f.source = evt.value.clone()),
+                }
+            }
+            div { class: "form-control",
+                label { class: "label", r#for: "recipe_image_url", "Image URL" }
+                input {
+                    id: "recipe_image_url",
+                    class: "input input-bordered",
+                    value: "{form.image_url}",
+                    oninput: move |evt| draft.with_mut(|f| f.image_url = evt.value.clone()),
+                }
+            }
+            div { class: "grid grid-cols-3 gap-4",
+                div { class: "form-control",
+                    label { class: "label", r#for: "recipe_categories", "Categories" }
+                    input {
+                        id: "recipe_categories",
+                        class: "input input-bordered",
+                        placeholder: "Dinner, Dessert",
+                        value: "{form.categories}",
+                        oninput: move |evt| draft.with_mut(|f| f.categories = evt.value.clone()),
+                    }
+                }
+                div { class: "form-control",
+                    label { class: "label", r#for: "recipe_cuisines", "Cuisines" }
+                    input {
+                        id: "recipe_cuisines",
+                        class: "input input-bordered",
+                        placeholder: "Italian",
+                        value: "{form.cuisines}",
+                        oninput: move |evt| draft.with_mut(|f| f.cuisines = evt.value.clone()),
+                    }
+                }
+                div { class: "form-control",
+                    label { class: "label", r#for: "recipe_tags", "Tags" }
+                    input {
+                        id: "recipe_tags",
+                        class: "input input-bordered",
+                        placeholder: "quick, freezable",
+                        value: "{form.tags}",
+                        oninput: move |evt| draft.with_mut(|f| f.tags = evt.value.clone()),
+                    }
+                }
+            }
+            div { class: "form-control",
+                span { class: "label", "Suitable for" }
+                div { class: "flex flex-wrap gap-4",
+                    Diet::ALL.iter().map(|diet| {
+                        let diet = *diet;
+                        let label = diet.label();
+                        let checked = form.diets.contains(&diet);
+                        rsx!(
+                            label { key: "{diet}", class: "label cursor-pointer gap-2",
+                                input {
+                                    r#type: "checkbox",
+                                    class: "checkbox checkbox-sm",
+                                    checked: checked,
+                                    onchange: move |_| draft.with_mut(|f| {
+                                        match f.diets.iter().position(|d| *d == diet) {
+                                            Some(i) => {
+                                                f.diets.remove(i);
+                                            }
+                                            None => f.diets.push(diet),
+                                        }
+                                    }),
+                                }
+                                span { "{label}" }
+                            }
+                        )
+                    })
+                }
+            }
+            label { class: "label cursor-pointer justify-start gap-2",
+                input {
+                    r#type: "checkbox",
+                    class: "checkbox checkbox-sm",
+                    checked: form.shared,
+                    onchange: move |_| draft.with_mut(|f| f.shared = !f.shared),
+                }
+                span { "Share with other users" }
+            }
+            details { class: "collapse collapse-arrow bg-base-200",
+                summary { class: "collapse-title", "Nutrition per serving" }
+                div { class: "collapse-content flex flex-col gap-2",
+                    p { class: "text-sm opacity-70",
+                        "Leave blank to estimate nutrition from the ingredients."
+                    }
+                    div { class: "grid grid-cols-4 gap-2",
+                        Nutrition::default().rows().into_iter().enumerate().map(|(i, (label, unit, _))| {
+                            let value = &form.nutrition[i];
+                            rsx!(
+                                label { key: "{label}", class: "form-control",
+                                    span { class: "label-text", "{label} ({unit})" }
+                                    input {
+                                        class: "input input-bordered input-sm",
+                                        r#type: "number",
+                                        min: "0",
+                                        step: "any",
+                                        value: "{value}",
+                                        oninput: move |evt| draft.with_mut(|f| f.nutrition[i] = evt.value.clone()),
+                                    }
+                                }
+                            )
+                        })
+                    }
+                    FieldErrors { errors: errors.get().get("nutrition").to_vec() }
+                }
+            }
+            div { class: "form-control",
+                label { class: "label", r#for: "recipe_notes", "Notes" }
+                textarea {
+                    id: "recipe_notes",
+                    class: "textarea textarea-bordered",
+                    value: "{form.notes}",
+                    oninput: move |evt| draft.with_mut(|f| f.notes = evt.value.clone()),
+                }
+            }
+
+            h2 { class: "text-xl font-bold", "Ingredients" }
+            ul { class: "flex flex-col gap-3",
+                form.ingredients.iter().enumerate().map(|(i, row)| {
+                    let line = &row.text;
+                    let parsed = parse_ingredient(line);
+                    let field_errors = errors.get().get(&format!("ingredients.{i}")).to_vec();
+                    let last = form.ingredients.len() - 1;
+                    rsx!(
+                        li { key: "{row.id}", class: "flex flex-col gap-1",
+                            div { class: "flex gap-2",
+                                input {
+                                    class: "input input-bordered input-sm flex-1",
+                                    placeholder: "200g cashews, soaked",
+                                    value: "{line}",
+                                    oninput: move |evt| draft.with_mut(|f| f.ingredients[i].text = evt.value.clone()),
+                                }
+                                button {
+                                    class: "btn btn-sm btn-ghost",
+                                    r#type: "button",
+                                    disabled: i == 0,
+                                    onclick: move |_| draft.with_mut(|f| f.ingredients.swap(i, i - 1)),
+                                    "↑"
+                                }
+                                button {
+                                    class: "btn btn-sm btn-ghost",
+                                    r#type: "button",
+                                    disabled: i == last,
+                                    onclick: move |_| draft.with_mut(|f| f.ingredients.swap(i, i + 1)),
+                                    "↓"
+                                }
+                                button {
+                                    class: "btn btn-sm btn-ghost",
+                                    r#type: "button",
+                                    onclick: move |_| draft.with_mut(|f| {
+                                        f.ingredients.remove(i);
+                                    }),
+                                    "✕"
+                                }
+                            }
+                            (!line.trim().is_empty()).then(|| rsx!(ParsedIngredient { parsed: parsed }))
+                            FieldErrors { errors: field_errors }
+                        }
+                    )
+                })
+            }
+            button {
+                class: "btn btn-sm btn-outline self-start",
+                r#type: "button",
+                onclick: move |_| draft.with_mut(|f| f.add_ingredient(String::new())),
+                "Add ingredient"
+            }
+
+            h2 { class: "text-xl font-bold", "Method" }
+            ol { class: "flex flex-col gap-3",
+                form.steps.iter().enumerate().map(|(i, row)| {
+                    let step = &row.text;
+                    let field_errors = errors.get().get(&format!("steps.{i}")).to_vec();
+                    let last = form.steps.len() - 1;
+                    let number = i + 1;
+                    rsx!(
+                        li { key: "{row.id}", class: "flex flex-col gap-1",
+                            div { class: "flex gap-2 items-start",
+                                span { class: "font-bold w-6", "{number}." }
+                                textarea {
+                                    class: "textarea textarea-bordered flex-1",
+                                    value: "{step}",
+                                    oninput: move |evt| draft.with_mut(|f| f.steps[i].text = evt.value.clone()),
+                                }
+                                button {
+                                    class: "btn btn-sm btn-ghost",
+                                    r#type: "button",
+                                    disabled: i == 0,
+                                    onclick: move |_| draft.with_mut(|f| f.steps.swap(i, i - 1)),
+                                    "↑"
+                                }
+                                button {
+                                    class: "btn btn-sm btn-ghost",
+                                    r#type: "button",
+                                    disabled: i == last,
+                                    onclick: move |_| draft.with_mut(|f| f.steps.swap(i, i + 1)),
+                                    "↓"
+                                }
+                                button {
+                                    class: "btn btn-sm btn-ghost",
+                                    r#type: "button",
+                                    onclick: move |_| draft.with_mut(|f| {
+                                        f.steps.remove(i);
+                                    }),
+                                    "✕"
+                                }
+                            }
+                            FieldErrors { errors: field_errors }
+                        }
+                    )
+                })
+            }
+            button {
+                class: "btn btn-sm btn-outline self-start",
+                r#type: "button",
+                onclick: move |_| draft.with_mut(|f| f.add_step(String::new())),
+                "Add step"
+            }
+
+            server_error.get().as_ref().map(|e| rsx!(
+                div { class: "alert alert-error", "Failed to save recipe: {e}" }
+            ))
+            button {
+                class: "btn btn-primary self-end",
+                r#type: "button",
+                disabled: **saving,
+                onclick: save,
+                "{save_label}"
+            }
+        }
+    ))
+}
+
+pub fn NewRecipePage(cx: Scope) -> Element {
+    cx.render(rsx!(RecipeEditor {
+        mode: EditorMode::New
+    }))
+}
+
+#[component]
+pub fn EditRecipePage(cx: Scope, slug: String) -> Element {
+    let recipe = use_recipe(cx, slug)?;
+    cx.render(match recipe.result().value() {
+        QueryResult::Ok(QueryValue::Recipe(Some(detail))) => rsx!(RecipeEditor {
+            mode: EditorMode::Edit(detail.clone())
+        }),
+        QueryResult::Ok(_) => rsx!(ErrorAlert {
+            error: AppError::NotFound
+        }),
+        QueryResult::Err(QueryError::Server(error)) => rsx!(ErrorAlert {
+            error: error.clone()
+        }),
+        QueryResult::Loading(_) => rsx!(div { "loading" }),
+    })
+}
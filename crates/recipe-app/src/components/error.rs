@@ -0,0 +1,42 @@
+use dioxus::prelude::*;
+use dioxus_router::prelude::*;
+
+use crate::{AppError, Route};
+
+/// Explains why something failed, and where there is one, what to do next.
+#[component]
+pub fn ErrorAlert(cx: Scope, error: AppError) -> Element {
+    cx.render(match error {
+        AppError::NotFound => rsx!(
+            div { class: "alert alert-warning flex flex-wrap",
+                span { "That doesn't exist, or has been deleted." }
+                Link { to: Route::RecipesPage {}, class: "btn btn-sm", "Back to recipes" }
+            }
+        ),
+        AppError::Unauthorized { message } => rsx!(
+            div { class: "alert alert-warning flex flex-wrap",
+                span { "{message}" }
+                Link { to: Route::SignInPage {}, class: "btn btn-sm", "Sign in" }
+            }
+        ),
+        AppError::Validation { errors } => rsx!(
+            div { class: "alert alert-error flex flex-col items-start",
+                span { "Some of that isn't right:" }
+                ul { class: "list-disc list-inside",
+                    errors.iter().flat_map(|(field, messages)| {
+                        messages.iter().map(move |message| rsx!(
+                            li { key: "{field}{message}", "{field} {message}" }
+                        ))
+                    })
+                }
+            }
+        ),
+        AppError::UpstreamScrapeFailed { host, message } => rsx!(
+            div { class: "alert alert-error flex flex-col items-start",
+                span { "Couldn't get a recipe from {host}." }
+                span { class: "text-sm opacity-80", "{message}" }
+            }
+        ),
+        error => rsx!(div { class: "alert alert-error", "{error}" }),
+    })
+}
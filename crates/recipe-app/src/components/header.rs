@@ -1,9 +1,13 @@
 use dioxus::prelude::*;
 use dioxus_router::prelude::*;
 
-use crate::Route;
+use crate::{components::AccountMenu, hooks::SearchQuery, Route};
 
 pub fn Header(cx: Scope) -> Element {
+    let search = use_shared_state::<SearchQuery>(cx).unwrap();
+    let navigator = use_navigator(cx);
+    let on_recipes = matches!(use_route::<Route>(cx), Some(Route::RecipesPage {}));
+    let query = search.read().0.clone();
     cx.render(rsx!(
         header { class: "navbar bg-neutral text-neutral-content",
             div { class: "navbar-start",
@@ -26,24 +30,36 @@ pub fn Header(cx: Scope) -> Element {
                     ul {
                         class: "menu menu-sm dropdown-content mt-3 z-[1] p-2 shadow bg-base-100 rounded-box w-52",
                         tabindex: "0",
-                        li { a { class: "mr-5 hover:text-white", "Recipes" } }
-                        li { a { class: "mr-5 hover:text-white", "Meal plans" } }
+                        li { Link { to: Route::RecipesPage {}, "Recipes" } }
+                        li { Link { to: Route::IngredientsPage {}, "Ingredients" } }
+                        li { Link { to: Route::MealPlansPage {}, "Meal plans" } }
+                        li { Link { to: Route::HouseholdsPage {}, "Households" } }
                     }
                 }
-                a { class: "btn btn-ghost text-xl", "Recipe organiser" }
+                Link { to: Route::RecipesPage {}, class: "btn btn-ghost text-xl hidden sm:inline-flex", "Recipe organiser" }
             }
             div { class: "navbar-center hidden lg:flex",
                 ul { class: "menu menu-horizontal px-1",
                     li { Link { to: Route::RecipesPage {}, class: "mr-5 hover:text-white", "Recipes" } }
+                    li { Link { to: Route::IngredientsPage {}, class: "mr-5 hover:text-white", "Ingredients" } }
                     li { Link { to: Route::MealPlansPage {}, class: "mr-5 hover:text-white", "Meal plans" } }
+                    li { Link { to: Route::HouseholdsPage {}, class: "mr-5 hover:text-white", "Households" } }
                 }
             }
             div { class: "navbar-end",
                 div { class: "form-control", input {
-                    r#type: "text",
+                    r#type: "search",
                     placeholder: "Search",
-                    class: "input input-bordered w-24 md:w-auto"
+                    class: "input input-bordered w-24 md:w-auto",
+                    value: "{query}",
+                    oninput: move |evt| {
+                        search.write().0 = evt.value.clone();
+                        if !on_recipes {
+                            navigator.push(Route::RecipesPage {});
+                        }
+                    },
                 } }
+                AccountMenu {}
             }
         }
     ))
@@ -0,0 +1,380 @@
+use dioxus::prelude::*;
+use dioxus_query::prelude::*;
+use dioxus_router::prelude::*;
+
+use recipe_shared::{HouseholdDetail, HouseholdInput, HouseholdRole, ValidationErrors};
+
+use crate::{
+    components::{recipes::toggle, ErrorAlert},
+    hooks::{
+        use_household, use_households, use_mutations, use_optimistic, use_user, Changed,
+        QueryError, QueryValue,
+    },
+    server::{
+        create_household, remove_household_member, set_household_member, share_meal_plan,
+        share_recipe,
+    },
+    AppError, Route,
+};
+
+#[component]
+fn FieldErrors(cx: Scope, errors: Vec<String>) -> Element {
+    cx.render(rsx!(errors.iter().map(|e| rsx!(
+        p { class: "text-error text-sm", "{e}" }
+    ))))
+}
+
+fn NewHousehold(cx: Scope) -> Element {
+    let name = use_state(cx, String::new);
+    let errors = use_state(cx, ValidationErrors::default);
+    let server_error = use_state::<Option<String>>(cx, || None);
+    let navigator = use_navigator(cx);
+    let mutations = use_mutations(cx);
+
+    let create = move |_| {
+        let input = HouseholdInput {
+            name: name.trim().to_string(),
+        };
+        if let Err(e) = input.validate() {
+            errors.set(e);
+            return;
+        }
+        errors.set(ValidationErrors::default());
+        let navigator = navigator.clone();
+        let mutations = mutations.clone();
+        let server_error = server_error.to_owned();
+        let errors = errors.to_owned();
+        cx.spawn(async move {
+            let result = mutations
+                .run(create_household(input), |_| vec![Changed::Households])
+                .await;
+            match result {
+                Ok(household) => {
+                    navigator.push(Route::HouseholdPage { id: household.id });
+                }
+                Err(AppError::Validation { errors: e }) => errors.set(e),
+                Err(e) => server_error.set(Some(e.to_string())),
+            }
+        });
+    };
+
+    let name_value = name.get();
+    cx.render(rsx!(
+        div { class: "card bg-base-200",
+            div { class: "card-body gap-2",
+                h2 { class: "card-title", "New household" }
+                div { class: "flex items-end gap-4",
+                    label { class: "form-control flex-1",
+                        span { class: "label-text", "Name" }
+                        input {
+                            class: "input input-bordered input-sm",
+                            value: "{name_value}",
+                            oninput: move |evt| name.set(evt.value.clone()),
+                        }
+                    }
+                    button { class: "btn btn-primary btn-sm", r#type: "button", onclick: create, "Create" }
+                }
+                FieldErrors { errors: errors.get().get("name").to_vec() }
+                server_error.get().as_ref().map(|e| rsx!(
+                    div { class: "alert alert-error", "Failed to create household: {e}" }
+                ))
+            }
+        }
+    ))
+}
+
+pub fn HouseholdsPage(cx: Scope) -> Element {
+    let user = use_user(cx);
+    let households = use_households(cx)?;
+    let body = match households.result().value() {
+        QueryResult::Ok(QueryValue::Households(households)) => rsx!(
+            ul { class: "menu bg-base-100",
+                households.iter().map(|household| {
+                    let role = household.role.label();
+                    rsx!(
+                        li { key: "{household.id}",
+                            Link { to: Route::HouseholdPage { id: household.id },
+                                span { class: "font-bold", "{household.name}" }
+                                span { class: "badge badge-outline", "{role}" }
+                            }
+                        }
+                    )
+                })
+            }
+        ),
+        QueryResult::Err(QueryError::Server(error)) => rsx!(ErrorAlert {
+            error: error.clone()
+        }),
+        QueryResult::Ok(value) => rsx!(ErrorAlert {
+            error: value.unexpected()
+        }),
+        QueryResult::Loading(_) => rsx!(div { "loading" }),
+    };
+    cx.render(rsx!(
+        div { class: "max-w-4xl mx-auto p-4 flex flex-col gap-4",
+            h1 { class: "text-2xl font-bold", "Households" }
+            if user.is_some() {
+                rsx!(NewHousehold {} body)
+            } else {
+                rsx!(p {
+                    Link { to: Route::SignInPage {}, class: "link", "Sign in" }
+                    " to share recipes and meal plans with a household."
+                })
+            }
+        }
+    ))
+}
+
+#[component]
+pub fn HouseholdPage(cx: Scope, id: i64) -> Element {
+    let household = use_household(cx, *id)?;
+    cx.render(match household.result().value() {
+        QueryResult::Ok(QueryValue::Household(Some(detail))) => rsx!(HouseholdView {
+            detail: detail.clone()
+        }),
+        QueryResult::Ok(_) => rsx!(ErrorAlert {
+            error: AppError::NotFound
+        }),
+        QueryResult::Err(QueryError::Server(error)) => rsx!(ErrorAlert {
+            error: error.clone()
+        }),
+        QueryResult::Loading(_) => rsx!(div { "loading" }),
+    })
+}
+
+#[component]
+fn HouseholdView(cx: Scope, detail: HouseholdDetail) -> Element {
+    let household = &detail.household;
+    let user = use_user(cx);
+    let manage = household.role.can_manage();
+    let username = use_state(cx, String::new);
+    let role = use_state(cx, HouseholdRole::default);
+    let error = use_state::<Option<String>>(cx, || None);
+    let mutations = use_mutations(cx);
+    let navigator = use_navigator(cx);
+    let household_id = household.id;
+    let changed = move |_: &()| vec![Changed::Household(household_id)];
+
+    let set_member = move |username: String, role: HouseholdRole| {
+        let mutations = mutations.clone();
+        let error = error.to_owned();
+        cx.spawn(async move {
+            let mutation = set_household_member(household_id, username, role);
+            match mutations.run(mutation, changed).await {
+                Ok(()) => error.set(None),
+                Err(e) => error.set(Some(e.to_string())),
+            }
+        });
+    };
+    let add_member = {
+        let set_member = set_member.clone();
+        move |_| {
+            let name = username.trim().to_string();
+            if name.is_empty() {
+                return;
+            }
+            username.set(String::new());
+            set_member(name, **role);
+        }
+    };
+
+    let username_value = username.get();
+    cx.render(rsx!(
+        div { class: "max-w-4xl mx-auto p-4 flex flex-col gap-4",
+            h1 { class: "text-2xl font-bold", "{household.name}" }
+            h2 { class: "text-xl font-bold", "Members" }
+            table { class: "table table-sm",
+                tbody {
+                    detail.members.iter().map(|member| {
+                        let member_id = member.user.id;
+                        let member_username = member.user.username.clone();
+                        let is_self = user.as_ref().is_some_and(|u| u.id == member_id);
+                        let current = member.role;
+                        let set_member = set_member.clone();
+                        let mutations = mutations.clone();
+                        let navigator = navigator.clone();
+                        let error = error.to_owned();
+                        let remove_label = if is_self { "Leave" } else { "Remove" };
+                        rsx!(
+                            tr { key: "{member_id}",
+                                td { "{member.user.display_name}" }
+                                td { class: "opacity-70", "{member.user.username}" }
+                                td {
+                                    if manage {
+                                        rsx!(select {
+                                            class: "select select-bordered select-sm",
+                                            onchange: move |evt| {
+                                                if let Ok(role) = evt.value.parse() {
+                                                    set_member(member_username.clone(), role);
+                                                }
+                                            },
+                                            HouseholdRole::ALL.iter().map(|role| {
+                                                let label = role.label();
+                                                rsx!(
+                                                    option {
+                                                        key: "{role}",
+                                                        value: "{role}",
+                                                        selected: *role == current,
+                                                        "{label}"
+                                                    }
+                                                )
+                                            })
+                                        })
+                                    } else {
+                                        let label = current.label();
+                                        rsx!(span { class: "badge badge-outline", "{label}" })
+                                    }
+                                }
+                                td {
+                                    (manage || is_self).then(|| rsx!(button {
+                                        class: "btn btn-xs btn-ghost",
+                                        r#type: "button",
+                                        onclick: move |_| {
+                                            let mutations = mutations.clone();
+                                            let navigator = navigator.clone();
+                                            let error = error.clone();
+                                            cx.spawn(async move {
+                                                let mutation = remove_household_member(household_id, member_id);
+                                                match mutations.run(mutation, changed).await {
+                                                    Ok(()) if is_self => {
+                                                        navigator.push(Route::HouseholdsPage {});
+                                                    }
+                                                    Ok(()) => error.set(None),
+                                                    Err(e) => error.set(Some(e.to_string())),
+                                                }
+                                            });
+                                        },
+                                        "{remove_label}"
+                                    }))
+                                }
+                            }
+                        )
+                    })
+                }
+            }
+            manage.then(|| rsx!(
+                div { class: "flex items-end gap-2",
+                    label { class: "form-control flex-1",
+                        span { class: "label-text", "Username" }
+                        input {
+                            class: "input input-bordered input-sm",
+                            value: "{username_value}",
+                            oninput: move |evt| username.set(evt.value.clone()),
+                        }
+                    }
+                    select {
+                        class: "select select-bordered select-sm",
+                        onchange: move |evt| {
+                            if let Ok(r) = evt.value.parse() {
+                                role.set(r);
+                            }
+                        },
+                        HouseholdRole::ALL.iter().map(|r| {
+                            let label = r.label();
+                            rsx!(option { key: "{r}", value: "{r}", selected: *r == **role, "{label}" })
+                        })
+                    }
+                    button { class: "btn btn-sm", r#type: "button", onclick: add_member, "Add member" }
+                }
+            ))
+            error.get().as_ref().map(|e| rsx!(p { class: "text-error text-sm", "{e}" }))
+            h2 { class: "text-xl font-bold", "Recipes" }
+            ul { class: "list-disc list-inside",
+                detail.recipes.iter().map(|recipe| rsx!(
+                    li { key: "{recipe.id}",
+                        Link { to: Route::RecipePage { slug: recipe.slug.clone() }, class: "link", "{recipe.name}" }
+                    }
+                ))
+            }
+            h2 { class: "text-xl font-bold", "Meal plans" }
+            ul { class: "list-disc list-inside",
+                detail.meal_plans.iter().map(|plan| rsx!(
+                    li { key: "{plan.id}",
+                        Link { to: Route::MealPlanPage { slug: plan.slug.clone() }, class: "link", "{plan.name}" }
+                    }
+                ))
+            }
+        }
+    ))
+}
+
+/// Something which can be shared into a household.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum Shareable {
+    Recipe(i64),
+    MealPlan(i64),
+}
+
+/// Checkboxes for sharing a recipe or meal plan into the households of the
+/// current user. Households in which they're only a viewer are shown but
+/// can't be changed.
+#[component]
+pub(crate) fn ShareWithHouseholds(
+    cx: Scope,
+    item: Shareable,
+    household_ids: Vec<i64>,
+    changed: Changed,
+) -> Element {
+    let households = use_households(cx)?;
+    let error = use_state::<Option<String>>(cx, || None);
+    let mutations = use_mutations(cx);
+    // Ticked straight away, and unticked again if sharing fails.
+    let shared = use_optimistic(cx, household_ids);
+    let households = match households.result().value() {
+        QueryResult::Ok(QueryValue::Households(households)) => households.clone(),
+        _ => Vec::new(),
+    };
+    if households.is_empty() {
+        return None;
+    }
+    cx.render(rsx!(
+        div { class: "flex flex-wrap items-center gap-4",
+            span { class: "font-bold", "Shared with:" }
+            households.iter().map(|household| {
+                let household_id = household.id;
+                let checked = shared.contains(&household_id);
+                let mutations = mutations.clone();
+                let changed = changed.clone();
+                let shared = shared.to_owned();
+                let error = error.to_owned();
+                let item = *item;
+                rsx!(
+                    label { key: "{household_id}", class: "label cursor-pointer gap-2 p-0",
+                        input {
+                            r#type: "checkbox",
+                            class: "checkbox checkbox-sm",
+                            checked: checked,
+                            disabled: !household.role.can_edit(),
+                            onchange: move |_| {
+                                let mutations = mutations.clone();
+                                let changed = vec![changed.clone(), Changed::Household(household_id)];
+                                let shared = shared.clone();
+                                let error = error.clone();
+                                shared.with_mut(|ids| toggle(ids, household_id));
+                                cx.spawn(async move {
+                                    let result = match item {
+                                        Shareable::Recipe(id) => {
+                                            mutations.run(share_recipe(household_id, id, !checked), |_| changed).await
+                                        }
+                                        Shareable::MealPlan(id) => {
+                                            mutations.run(share_meal_plan(household_id, id, !checked), |_| changed).await
+                                        }
+                                    };
+                                    match result {
+                                        Ok(()) => error.set(None),
+                                        Err(e) => {
+                                            shared.with_mut(|ids| toggle(ids, household_id));
+                                            error.set(Some(e.to_string()));
+                                        }
+                                    }
+                                });
+                            },
+                        }
+                        span { "{household.name}" }
+                    }
+                )
+            })
+            error.get().as_ref().map(|e| rsx!(p { class: "text-error text-sm", "{e}" }))
+        }
+    ))
+}
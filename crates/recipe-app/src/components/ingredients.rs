@@ -0,0 +1,140 @@
+use std::collections::BTreeSet;
+
+use dioxus::prelude::*;
+use dioxus_query::prelude::*;
+
+use recipe_shared::{DietaryFlag, IngredientDiet};
+
+use crate::{
+    components::ErrorAlert,
+    hooks::{use_ingredient_diets, use_mutations, use_optimistic, Changed, QueryError, QueryValue},
+    server::set_ingredient_flags,
+};
+
+/// A row of the ingredient table, with the flags as currently ticked.
+#[component]
+fn IngredientFlags(cx: Scope, ingredient: IngredientDiet) -> Element {
+    let flags = use_optimistic(cx, &ingredient.flags);
+    let saving = use_state(cx, || false);
+    let error = use_state::<Option<String>>(cx, || None);
+    let mutations = use_mutations(cx);
+    let ingredient_id = ingredient.ingredient_id;
+
+    let confirm = move |_| {
+        let selected: Vec<DietaryFlag> = flags.iter().copied().collect();
+        let mutations = mutations.clone();
+        let saving = saving.to_owned();
+        let error = error.to_owned();
+        saving.set(true);
+        cx.spawn(async move {
+            // Every recipe's dietary information may have changed.
+            let result = mutations
+                .run(set_ingredient_flags(ingredient_id, selected), |_| {
+                    vec![Changed::IngredientFlags]
+                })
+                .await;
+            saving.set(false);
+            match result {
+                Ok(_) => error.set(None),
+                Err(e) => error.set(Some(e.to_string())),
+            }
+        });
+    };
+
+    let changed = *flags.get() != ingredient.flags;
+    let status = if ingredient.reviewed && !changed {
+        rsx!(span { class: "badge badge-success", "checked" })
+    } else {
+        rsx!(span { class: "badge badge-warning", "needs checking" })
+    };
+    cx.render(rsx!(
+        tr {
+            td { "{ingredient.name}" }
+            DietaryFlag::ALL.iter().map(|flag| {
+                let flag = *flag;
+                let checked = flags.contains(&flag);
+                let label = flag.label();
+                rsx!(
+                    td { key: "{flag}",
+                        input {
+                            r#type: "checkbox",
+                            class: "checkbox checkbox-sm",
+                            title: "{label}",
+                            checked: checked,
+                            onchange: move |_| flags.with_mut(|flags: &mut BTreeSet<DietaryFlag>| {
+                                if !flags.remove(&flag) {
+                                    flags.insert(flag);
+                                }
+                            }),
+                        }
+                    }
+                )
+            })
+            td { status }
+            td {
+                button {
+                    class: "btn btn-xs",
+                    r#type: "button",
+                    disabled: **saving,
+                    onclick: confirm,
+                    "Confirm"
+                }
+                error.get().as_ref().map(|e| rsx!(p { class: "text-error text-xs", "{e}" }))
+            }
+        }
+    ))
+}
+
+/// Every known ingredient with its allergens and dietary flags, so guessed
+/// flags can be checked and corrected.
+pub fn IngredientsPage(cx: Scope) -> Element {
+    let diets = use_ingredient_diets(cx)?;
+    let body = match diets.result().value() {
+        QueryResult::Ok(QueryValue::IngredientDiets(ingredients)) => {
+            let unreviewed = ingredients.iter().filter(|i| !i.reviewed).count();
+            rsx!(
+                (unreviewed > 0).then(|| rsx!(
+                    div { class: "alert alert-warning",
+                        "{unreviewed} ingredient(s) have flags guessed from their name which haven't been checked."
+                    }
+                ))
+                div { class: "overflow-x-auto",
+                    table { class: "table table-sm",
+                        thead {
+                            tr {
+                                th { "Ingredient" }
+                                DietaryFlag::ALL.iter().map(|flag| {
+                                    let label = flag.label();
+                                    rsx!(th { key: "{flag}", "{label}" })
+                                })
+                                th {}
+                                th {}
+                            }
+                        }
+                        tbody {
+                            ingredients.iter().map(|ingredient| rsx!(
+                                IngredientFlags {
+                                    key: "{ingredient.ingredient_id}",
+                                    ingredient: ingredient.clone(),
+                                }
+                            ))
+                        }
+                    }
+                }
+            )
+        }
+        QueryResult::Err(QueryError::Server(error)) => rsx!(ErrorAlert {
+            error: error.clone()
+        }),
+        QueryResult::Ok(value) => rsx!(ErrorAlert {
+            error: value.unexpected()
+        }),
+        QueryResult::Loading(_) => rsx!(div { "loading" }),
+    };
+    cx.render(rsx!(
+        div { class: "p-4 flex flex-col gap-4",
+            h1 { class: "text-2xl font-bold", "Ingredients" }
+            body
+        }
+    ))
+}
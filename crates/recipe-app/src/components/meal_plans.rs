@@ -1,7 +1,433 @@
+use chrono::{Duration, Local, NaiveDate};
 use dioxus::prelude::*;
+use dioxus_query::prelude::*;
+use dioxus_router::prelude::*;
+
+use recipe_shared::{
+    Diet, MealPlanDay, MealPlanDetail, MealPlanInput, NutritionSource, RecipeFilter,
+    ValidationErrors,
+};
+
+use crate::{
+    components::{
+        households::{ShareWithHouseholds, Shareable},
+        recipe::NutritionTable,
+        ErrorAlert,
+    },
+    hooks::{
+        use_meal_plan, use_meal_plans, use_mutations, use_optimistic, use_search,
+        use_shopping_list, Changed, QueryError, QueryValue,
+    },
+    server::{add_to_meal_plan, create_meal_plan, remove_from_meal_plan},
+    AppError, Route,
+};
+
+#[component]
+fn FieldErrors(cx: Scope, errors: Vec<String>) -> Element {
+    cx.render(rsx!(errors.iter().map(|e| rsx!(
+        p { class: "text-error text-sm", "{e}" }
+    ))))
+}
+
+/// Form for starting a new plan, defaulting to the coming week.
+fn NewMealPlan(cx: Scope) -> Element {
+    let today = Local::now().date_naive();
+    let name = use_state(cx, String::new);
+    let start_date = use_state(cx, || today.to_string());
+    let end_date = use_state(cx, || (today + Duration::days(6)).to_string());
+    let shared = use_state(cx, || false);
+    let errors = use_state(cx, ValidationErrors::default);
+    let server_error = use_state::<Option<String>>(cx, || None);
+    let navigator = use_navigator(cx);
+    let mutations = use_mutations(cx);
+
+    let create = move |_| {
+        let mut validation = ValidationErrors::default();
+        let mut date = |field: &str, s: &str| {
+            s.parse::<NaiveDate>()
+                .map_err(|_| validation.add(field, "must be a date"))
+                .ok()
+        };
+        let start = date("start_date", start_date.get());
+        let end = date("end_date", end_date.get());
+        let input = match (start, end) {
+            (Some(start_date), Some(end_date)) => {
+                let input = MealPlanInput {
+                    name: name.trim().to_string(),
+                    start_date,
+                    end_date,
+                    shared: **shared,
+                };
+                if let Err(e) = input.validate() {
+                    validation.merge(e);
+                }
+                Some(input)
+            }
+            _ => None,
+        };
+        let Some(input) = input.filter(|_| validation.is_empty()) else {
+            errors.set(validation);
+            return;
+        };
+        errors.set(ValidationErrors::default());
+        let navigator = navigator.clone();
+        let mutations = mutations.clone();
+        let server_error = server_error.to_owned();
+        let errors = errors.to_owned();
+        cx.spawn(async move {
+            let result = mutations
+                .run(create_meal_plan(input), |_| vec![Changed::MealPlans])
+                .await;
+            match result {
+                Ok(plan) => {
+                    navigator.push(Route::MealPlanPage { slug: plan.slug });
+                }
+                Err(AppError::Validation { errors: e }) => errors.set(e),
+                Err(e) => server_error.set(Some(e.to_string())),
+            }
+        });
+    };
+
+    let name_value = name.get();
+    let start_value = start_date.get();
+    let end_value = end_date.get();
+    cx.render(rsx!(
+        div { class: "card bg-base-200",
+            div { class: "card-body gap-2",
+                h2 { class: "card-title", "New plan" }
+                div { class: "flex flex-wrap items-end gap-4",
+                    label { class: "form-control",
+                        span { class: "label-text", "Name" }
+                        input {
+                            class: "input input-bordered input-sm",
+                            value: "{name_value}",
+                            oninput: move |evt| name.set(evt.value.clone()),
+                        }
+                    }
+                    label { class: "form-control",
+                        span { class: "label-text", "From" }
+                        input {
+                            class: "input input-bordered input-sm",
+                            r#type: "date",
+                            value: "{start_value}",
+                            oninput: move |evt| start_date.set(evt.value.clone()),
+                        }
+                    }
+                    label { class: "form-control",
+                        span { class: "label-text", "To" }
+                        input {
+                            class: "input input-bordered input-sm",
+                            r#type: "date",
+                            value: "{end_value}",
+                            oninput: move |evt| end_date.set(evt.value.clone()),
+                        }
+                    }
+                    label { class: "label cursor-pointer gap-2",
+                        input {
+                            r#type: "checkbox",
+                            class: "checkbox checkbox-sm",
+                            checked: **shared,
+                            onchange: move |_| shared.set(!**shared),
+                        }
+                        span { "Shared" }
+                    }
+                    button { class: "btn btn-primary btn-sm", r#type: "button", onclick: create, "Create" }
+                }
+                FieldErrors { errors: errors.get().get("name").to_vec() }
+                FieldErrors { errors: errors.get().get("start_date").to_vec() }
+                FieldErrors { errors: errors.get().get("end_date").to_vec() }
+                server_error.get().as_ref().map(|e| rsx!(
+                    div { class: "alert alert-error", "Failed to create plan: {e}" }
+                ))
+            }
+        }
+    ))
+}
 
 pub fn MealPlansPage(cx: Scope) -> Element {
+    let plans = use_meal_plans(cx)?;
+    let body = match plans.result().value() {
+        QueryResult::Ok(QueryValue::MealPlans(plans)) => rsx!(
+            ul { class: "menu bg-base-100",
+                plans.iter().map(|plan| {
+                    let start = plan.start_date.format("%a %e %b");
+                    let end = plan.end_date.format("%a %e %b");
+                    rsx!(
+                        li { key: "{plan.id}",
+                            Link { to: Route::MealPlanPage { slug: plan.slug.clone() },
+                                span { class: "font-bold", "{plan.name}" }
+                                span { class: "opacity-70", "{start} – {end}" }
+                            }
+                        }
+                    )
+                })
+            }
+        ),
+        QueryResult::Err(QueryError::Server(error)) => rsx!(ErrorAlert {
+            error: error.clone()
+        }),
+        QueryResult::Ok(value) => rsx!(ErrorAlert {
+            error: value.unexpected()
+        }),
+        QueryResult::Loading(_) => rsx!(div { "loading" }),
+    };
+    cx.render(rsx!(
+        div { class: "max-w-4xl mx-auto p-4 flex flex-col gap-4",
+            h1 { class: "text-2xl font-bold", "Meal plans" }
+            NewMealPlan {}
+            body
+        }
+    ))
+}
+
+#[component]
+pub fn MealPlanPage(cx: Scope, slug: String) -> Element {
+    let plan = use_meal_plan(cx, slug)?;
+    cx.render(match plan.result().value() {
+        QueryResult::Ok(QueryValue::MealPlan(Some(detail))) => rsx!(MealPlanView {
+            detail: detail.clone()
+        }),
+        QueryResult::Ok(_) => rsx!(ErrorAlert {
+            error: AppError::NotFound
+        }),
+        QueryResult::Err(QueryError::Server(error)) => rsx!(ErrorAlert {
+            error: error.clone()
+        }),
+        QueryResult::Loading(_) => rsx!(div { "loading" }),
+    })
+}
+
+#[component]
+fn MealPlanView(cx: Scope, detail: MealPlanDetail) -> Element {
+    let plan = &detail.plan;
+    let editable = detail.editable;
+    let diets = use_state::<Vec<Diet>>(cx, Vec::new);
+    let filter = RecipeFilter {
+        diets: diets.get().clone(),
+        ..Default::default()
+    };
+    let key = format!("{filter:?}");
+    cx.render(rsx!(
+        div { class: "max-w-5xl mx-auto p-4 flex flex-col gap-4",
+            div { class: "flex items-center justify-between",
+                h1 { class: "text-2xl font-bold", "{plan.name}" }
+                a {
+                    class: "btn btn-sm",
+                    href: "/api/v1/meal-plans/{plan.slug}/pdf",
+                    target: "_blank",
+                    "Print with shopping list"
+                }
+            }
+            editable.then(|| rsx!(ShareWithHouseholds {
+                item: Shareable::MealPlan(plan.id),
+                household_ids: detail.household_ids.clone(),
+                changed: Changed::MealPlan(plan.slug.clone()),
+            }))
+            ShoppingListPanel { slug: plan.slug.clone() }
+            div { class: "flex flex-wrap gap-4",
+                span { class: "font-bold", "Only show recipes suitable for:" }
+                Diet::ALL.iter().map(|diet| {
+                    let diet = *diet;
+                    let label = diet.label();
+                    let checked = diets.contains(&diet);
+                    rsx!(
+                        label { key: "{diet}", class: "label cursor-pointer gap-2 p-0",
+                            input {
+                                r#type: "checkbox",
+                                class: "checkbox checkbox-sm",
+                                checked: checked,
+                                onchange: move |_| diets.with_mut(|diets| {
+                                    match diets.iter().position(|d| *d == diet) {
+                                        Some(i) => {
+                                            diets.remove(i);
+                                        }
+                                        None => diets.push(diet),
+                                    }
+                                }),
+                            }
+                            span { "{label}" }
+                        }
+                    )
+                })
+            }
+            detail.days.iter().map(|day| rsx!(
+                PlanDay {
+                    key: "{day.date}-{key}",
+                    meal_plan_id: plan.id,
+                    slug: plan.slug.clone(),
+                    day: day.clone(),
+                    filter: filter.clone(),
+                    editable: editable,
+                }
+            ))
+        }
+    ))
+}
+
+/// Everything to buy for the plan, which follows along as recipes are added
+/// and removed.
+#[component]
+fn ShoppingListPanel(cx: Scope, slug: String) -> Element {
+    let list = use_shopping_list(cx, slug)?;
+    let body = match list.result().value() {
+        QueryResult::Ok(QueryValue::ShoppingList(Some(list))) if list.is_empty() => rsx!(
+            p { class: "opacity-70", "Add some recipes to see what to buy." }
+        ),
+        QueryResult::Ok(QueryValue::ShoppingList(Some(list))) => rsx!(
+            ul { class: "flex flex-col gap-1",
+                list.items.iter().enumerate().map(|(i, item)| {
+                    let line = item.to_line();
+                    let recipes = item.recipes.join(", ");
+                    rsx!(
+                        li { key: "{i}",
+                            span { "{line}" }
+                            span { class: "text-xs opacity-70 ml-2", "{recipes}" }
+                        }
+                    )
+                })
+            }
+        ),
+        QueryResult::Ok(_) => rsx!(ErrorAlert {
+            error: AppError::NotFound
+        }),
+        QueryResult::Err(QueryError::Server(error)) => rsx!(ErrorAlert {
+            error: error.clone()
+        }),
+        QueryResult::Loading(_) => rsx!(div { "loading" }),
+    };
+    cx.render(rsx!(
+        details { class: "collapse collapse-arrow bg-base-200",
+            summary { class: "collapse-title font-bold", "Shopping list" }
+            div { class: "collapse-content", body }
+        }
+    ))
+}
+
+/// The recipes planned for one day with their combined nutrition, and a
+/// picker to add another.
+#[component]
+fn PlanDay(
+    cx: Scope,
+    meal_plan_id: i64,
+    slug: String,
+    day: MealPlanDay,
+    filter: RecipeFilter,
+    editable: bool,
+) -> Element {
+    let search = use_search(cx, filter)?;
+    let selected = use_state::<Option<i64>>(cx, || None);
+    let error = use_state::<Option<String>>(cx, || None);
+    let mutations = use_mutations(cx);
+    // Removed recipes disappear straight away, and come back if removing
+    // fails.
+    let entries = use_optimistic(cx, &day.entries);
+    let meal_plan_id = *meal_plan_id;
+    let date = day.date;
+    let changed = vec![Changed::MealPlan(slug.clone())];
+
+    let add = {
+        let changed = changed.clone();
+        move |_| {
+            let Some(recipe_id) = **selected else {
+                return;
+            };
+            let mutations = mutations.clone();
+            let changed = changed.clone();
+            let error = error.to_owned();
+            cx.spawn(async move {
+                let mutation = add_to_meal_plan(meal_plan_id, recipe_id, date);
+                match mutations.run(mutation, |_| changed).await {
+                    Ok(()) => error.set(None),
+                    Err(e) => error.set(Some(e.to_string())),
+                }
+            });
+        }
+    };
+
+    let recipes = match search.result().value() {
+        QueryResult::Ok(QueryValue::Search(results)) => results.recipes.clone(),
+        _ => Vec::new(),
+    };
+    let heading = date.format("%A %e %B");
+    let estimated = day
+        .entries
+        .iter()
+        .any(|e| e.nutrition_source == NutritionSource::Estimated);
+    let nutrition = day.nutrition();
     cx.render(rsx!(
-        div { "Meal plans page" }
+        div { class: "card bg-base-200",
+            div { class: "card-body gap-2",
+                h2 { class: "card-title", "{heading}" }
+                ul {
+                    entries.iter().map(|entry| {
+                        let entry_id = entry.id;
+                        let mutations = mutations.clone();
+                        let changed = changed.clone();
+                        let entries = entries.to_owned();
+                        let error = error.to_owned();
+                        rsx!(
+                            li { key: "{entry_id}", class: "flex items-center gap-2",
+                                Link {
+                                    to: Route::RecipePage { slug: entry.recipe.slug.clone() },
+                                    class: "link",
+                                    "{entry.recipe.name}"
+                                }
+                                editable.then(|| rsx!(button {
+                                    class: "btn btn-xs btn-ghost",
+                                    r#type: "button",
+                                    onclick: move |_| {
+                                        let mutations = mutations.clone();
+                                        let changed = changed.clone();
+                                        let entries = entries.clone();
+                                        let error = error.clone();
+                                        let confirmed = day.entries.clone();
+                                        entries.with_mut(|entries| entries.retain(|e| e.id != entry_id));
+                                        cx.spawn(async move {
+                                            match mutations.run(remove_from_meal_plan(entry_id), |_| changed).await {
+                                                Ok(()) => error.set(None),
+                                                Err(e) => {
+                                                    entries.set(confirmed);
+                                                    error.set(Some(e.to_string()));
+                                                }
+                                            }
+                                        });
+                                    },
+                                    "✕"
+                                }))
+                            }
+                        )
+                    })
+                }
+                (!day.entries.is_empty()).then(|| rsx!(
+                    details { class: "collapse collapse-arrow bg-base-100",
+                        summary { class: "collapse-title text-sm", "Nutrition, one serving of each" }
+                        div { class: "collapse-content",
+                            NutritionTable { nutrition: nutrition, servings: None }
+                            estimated.then(|| rsx!(
+                                p { class: "text-xs opacity-70", "Includes values estimated from ingredients." }
+                            ))
+                        }
+                    }
+                ))
+                editable.then(|| rsx!(div { class: "flex gap-2",
+                    select {
+                        class: "select select-bordered select-sm flex-1",
+                        onchange: move |evt| selected.set(evt.value.parse().ok()),
+                        option { value: "", "Add a recipe…" }
+                        recipes.iter().map(|recipe| rsx!(
+                            option { key: "{recipe.id}", value: "{recipe.id}", "{recipe.name}" }
+                        ))
+                    }
+                    button {
+                        class: "btn btn-sm",
+                        r#type: "button",
+                        disabled: selected.is_none(),
+                        onclick: add,
+                        "Add"
+                    }
+                }))
+                error.get().as_ref().map(|e| rsx!(p { class: "text-error text-sm", "{e}" }))
+            }
+        }
     ))
 }
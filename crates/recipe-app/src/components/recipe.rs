@@ -0,0 +1,367 @@
+use dioxus::prelude::*;
+use dioxus_query::prelude::*;
+use dioxus_router::prelude::*;
+
+use recipe_export::Format;
+use recipe_shared::{
+    DietInfo, Nutrition, NutritionSource, RecipeDetail, RecipeNutrition, Tag, TagInput, TagKind,
+};
+
+use crate::{
+    components::{
+        households::{ShareWithHouseholds, Shareable},
+        ErrorAlert,
+    },
+    hooks::{use_mutations, use_optimistic, use_recipe, Changed, QueryError, QueryValue},
+    server::{tag_recipe, untag_recipe},
+    AppError, Route,
+};
+
+#[component]
+pub fn RecipePage(cx: Scope, slug: String) -> Element {
+    let recipe = use_recipe(cx, slug)?;
+    cx.render(match recipe.result().value() {
+        QueryResult::Ok(QueryValue::Recipe(Some(detail))) => rsx!(RecipeView {
+            detail: detail.clone()
+        }),
+        QueryResult::Ok(_) => rsx!(ErrorAlert {
+            error: AppError::NotFound
+        }),
+        QueryResult::Err(QueryError::Server(error)) => rsx!(ErrorAlert {
+            error: error.clone()
+        }),
+        QueryResult::Loading(_) => rsx!(div { "loading" }),
+    })
+}
+
+/// Links to download the recipe, or open it as a printable page.
+#[component]
+fn ExportMenu(cx: Scope, slug: String) -> Element {
+    cx.render(rsx!(
+        div { class: "dropdown dropdown-end",
+            label { tabindex: "0", class: "btn btn-sm", "Export" }
+            ul { tabindex: "0", class: "dropdown-content menu bg-base-200 rounded-box z-10 w-48",
+                Format::ALL.iter().map(|format| {
+                    let label = format.label();
+                    let target = if *format == Format::Html { "_blank" } else { "_self" };
+                    rsx!(
+                        li { key: "{format}",
+                            a { href: "/api/v1/recipes/{slug}/export/{format}", target: "{target}", "{label}" }
+                        }
+                    )
+                })
+                li {
+                    a { href: "/api/v1/recipes/{slug}/pdf", target: "_blank", "PDF recipe card" }
+                }
+            }
+        }
+    ))
+}
+
+#[component]
+fn RecipeView(cx: Scope, detail: RecipeDetail) -> Element {
+    let recipe = &detail.recipe;
+    let editable = detail.editable;
+    let minutes = |m: Option<i32>| {
+        m.map(|m| format!("{m} mins"))
+            .unwrap_or_else(|| "–".to_string())
+    };
+    let prep = minutes(recipe.prep_time_minutes);
+    let cook = minutes(recipe.cooking_time_minutes);
+    let servings = recipe
+        .servings
+        .map(|s| s.to_string())
+        .unwrap_or_else(|| "–".to_string());
+    cx.render(rsx!(
+        div { class: "max-w-4xl mx-auto p-4 flex flex-col gap-4",
+            div { class: "flex flex-wrap items-center justify-between gap-2",
+                h1 { class: "text-2xl md:text-3xl font-bold", "{recipe.name}" }
+                div { class: "flex gap-2",
+                    (!detail.steps.is_empty()).then(|| rsx!(
+                        Link {
+                            to: Route::CookingPage { slug: recipe.slug.clone() },
+                            class: "btn btn-sm btn-primary",
+                            "Cook"
+                        }
+                    ))
+                    ExportMenu { slug: recipe.slug.clone() }
+                    editable.then(|| rsx!(
+                        Link {
+                            to: Route::EditRecipePage { slug: recipe.slug.clone() },
+                            class: "btn btn-sm",
+                            "Edit"
+                        }
+                    ))
+                }
+            }
+            recipe.image_url.as_ref().map(|url| rsx!(figure {
+                img { class: "rounded-box w-full max-h-96 object-cover", src: "{url}", alt: "Recipe image" }
+            }))
+            div { class: "stats shadow",
+                div { class: "stat",
+                    div { class: "stat-title", "Prep" }
+                    div { class: "stat-value text-lg", "{prep}" }
+                }
+                div { class: "stat",
+                    div { class: "stat-title", "Cook" }
+                    div { class: "stat-value text-lg", "{cook}" }
+                }
+                div { class: "stat",
+                    div { class: "stat-title", "Serves" }
+                    div { class: "stat-value text-lg", "{servings}" }
+                }
+            }
+            editable.then(|| rsx!(ShareWithHouseholds {
+                item: Shareable::Recipe(recipe.id),
+                household_ids: detail.household_ids.clone(),
+                changed: Changed::Recipe(recipe.slug.clone()),
+            }))
+            RecipeTags {
+                recipe_id: recipe.id,
+                slug: recipe.slug.clone(),
+                tags: detail.tags.clone(),
+            }
+            DietPanel { diet: detail.diet.clone() }
+            NutritionPanel { nutrition: detail.nutrition.clone(), servings: recipe.servings }
+            recipe.notes.as_ref().map(|n| rsx!(p { "{n}" }))
+            recipe.source.as_ref().map(|s| rsx!(p { class: "text-sm opacity-70", "Source: {s}" }))
+            h2 { class: "text-xl font-bold", "Ingredients" }
+            ul { class: "list-disc list-inside",
+                detail.ingredients.iter().map(|ingredient| {
+                    let line = ingredient.to_line();
+                    rsx!(li { key: "{ingredient.ingredient_id}", "{line}" })
+                })
+            }
+            h2 { class: "text-xl font-bold", "Method" }
+            ol { class: "list-decimal list-inside flex flex-col gap-2",
+                detail.steps.iter().map(|step| {
+                    rsx!(li { key: "{step.step_number}", "{step.description}" })
+                })
+            }
+        }
+    ))
+}
+
+/// Which diets the recipe suits, what allergens it contains and anything
+/// that should be double checked before serving it.
+#[component]
+fn DietPanel(cx: Scope, diet: DietInfo) -> Element {
+    let suitable = diet.suitable_diets();
+    let conflicts: Vec<String> = diet
+        .conflicts()
+        .into_iter()
+        .map(|d| {
+            let reasons: Vec<String> = d
+                .excludes()
+                .iter()
+                .filter_map(|flag| diet.flags.get(flag))
+                .flatten()
+                .cloned()
+                .collect();
+            format!(
+                "Declared {} but contains {}.",
+                d.label().to_lowercase(),
+                reasons.join(", ")
+            )
+        })
+        .collect();
+    let unreviewed = diet.unreviewed.join(", ");
+    cx.render(rsx!(
+        div { class: "flex flex-col gap-2",
+            div { class: "flex flex-wrap gap-2",
+                suitable.iter().map(|d| {
+                    let label = d.label();
+                    rsx!(span { key: "{d}", class: "badge badge-success", "{label}" })
+                })
+            }
+            (!diet.flags.is_empty()).then(|| rsx!(
+                ul { class: "text-sm",
+                    diet.flags.iter().map(|(flag, ingredients)| {
+                        let label = flag.label();
+                        let ingredients = ingredients.join(", ");
+                        rsx!(li { key: "{flag}", span { class: "font-bold", "Contains {label}: " } "{ingredients}" })
+                    })
+                }
+            ))
+            conflicts.iter().map(|c| rsx!(div { class: "alert alert-error", "{c}" }))
+            (!diet.unreviewed.is_empty()).then(|| rsx!(
+                div { class: "alert alert-warning",
+                    span { "Dietary information for {unreviewed} was guessed and hasn't been checked. " }
+                    Link { to: Route::IngredientsPage {}, class: "link", "Check ingredients" }
+                }
+            ))
+        }
+    ))
+}
+
+/// Format a nutritional value for display, rounding energy to whole calories.
+pub(crate) fn nutrient_value(unit: &str, value: Option<f64>) -> String {
+    match value {
+        Some(v) if unit == "kcal" => format!("{v:.0} {unit}"),
+        Some(v) => format!("{v:.1} {unit}"),
+        None => "–".to_string(),
+    }
+}
+
+/// Table of the nutrition of one serving and of the whole recipe.
+#[component]
+pub(crate) fn NutritionTable(cx: Scope, nutrition: Nutrition, servings: Option<i32>) -> Element {
+    let total = servings.map(|s| nutrition.scale(s as f64));
+    let totals = total.map(|t| t.rows());
+    let rows: Vec<(&str, String, Option<String>)> = nutrition
+        .rows()
+        .into_iter()
+        .enumerate()
+        .map(|(i, (label, unit, value))| {
+            let total = totals.map(|t| nutrient_value(unit, t[i].2));
+            (label, nutrient_value(unit, value), total)
+        })
+        .collect();
+    cx.render(rsx!(
+        div { class: "overflow-x-auto",
+            table { class: "table table-xs w-auto",
+                thead {
+                    tr {
+                        th {}
+                        th { "Per serving" }
+                        total.is_some().then(|| rsx!(th { "Whole recipe" }))
+                    }
+                }
+                tbody {
+                    rows.into_iter().map(|(label, value, total)| rsx!(
+                        tr { key: "{label}",
+                            th { "{label}" }
+                            td { "{value}" }
+                            total.map(|t| rsx!(td { "{t}" }))
+                        }
+                    ))
+                }
+            }
+        }
+    ))
+}
+
+/// The nutrition of the recipe, noting whether it was declared or estimated
+/// and which ingredients the estimate is missing.
+#[component]
+fn NutritionPanel(cx: Scope, nutrition: RecipeNutrition, servings: Option<i32>) -> Element {
+    let (per_serving, source) = nutrition.per_serving();
+    if per_serving.is_empty() && nutrition.unestimated.is_empty() {
+        return None;
+    }
+    let partial = source == NutritionSource::Estimated && !nutrition.unestimated.is_empty();
+    let source = match source {
+        NutritionSource::Declared => "As declared by the recipe.",
+        NutritionSource::Estimated => "Estimated from the ingredients.",
+    };
+    let unestimated = nutrition.unestimated.join(", ");
+    cx.render(rsx!(
+        div { class: "flex flex-col gap-2",
+            h2 { class: "text-xl font-bold", "Nutrition" }
+            NutritionTable { nutrition: per_serving, servings: *servings }
+            p { class: "text-sm opacity-70", "{source}" }
+            partial.then(|| rsx!(
+                div { class: "alert alert-warning",
+                    "The estimate doesn't include {unestimated}."
+                }
+            ))
+        }
+    ))
+}
+
+/// The tags on a recipe, with controls to add and remove them.
+#[component]
+fn RecipeTags(cx: Scope, recipe_id: i64, slug: String, tags: Vec<Tag>) -> Element {
+    let kind = use_state(cx, TagKind::default);
+    let name = use_state(cx, String::new);
+    let error = use_state::<Option<String>>(cx, || None);
+    let mutations = use_mutations(cx);
+    // Removed tags disappear straight away, and come back if removing fails.
+    let shown = use_optimistic(cx, tags);
+    let recipe_id = *recipe_id;
+    let changed = vec![Changed::Recipe(slug.clone())];
+
+    let add = {
+        let changed = changed.clone();
+        move |_| {
+            let tag = TagInput::new(**kind, name.trim());
+            if tag.name.is_empty() {
+                return;
+            }
+            let mutations = mutations.clone();
+            let changed = changed.clone();
+            let name = name.to_owned();
+            let error = error.to_owned();
+            cx.spawn(async move {
+                match mutations.run(tag_recipe(recipe_id, tag), |_| changed).await {
+                    Ok(_) => {
+                        name.set(String::new());
+                        error.set(None);
+                    }
+                    Err(e) => error.set(Some(e.to_string())),
+                }
+            });
+        }
+    };
+
+    let name_value = name.get();
+    cx.render(rsx!(
+        div { class: "flex flex-wrap items-center gap-2",
+            shown.iter().map(|tag| {
+                let tag_id = tag.id;
+                let kind = tag.kind.as_str();
+                let mutations = mutations.clone();
+                let changed = changed.clone();
+                let shown = shown.to_owned();
+                let error = error.to_owned();
+                rsx!(
+                    span { key: "{tag_id}", class: "badge badge-outline gap-1", title: "{kind}",
+                        "{tag.name}"
+                        button {
+                            class: "opacity-60 hover:opacity-100",
+                            r#type: "button",
+                            onclick: move |_| {
+                                let mutations = mutations.clone();
+                                let changed = changed.clone();
+                                let shown = shown.clone();
+                                let error = error.clone();
+                                let confirmed = tags.clone();
+                                shown.with_mut(|tags| tags.retain(|tag| tag.id != tag_id));
+                                cx.spawn(async move {
+                                    match mutations.run(untag_recipe(recipe_id, tag_id), |_| changed).await {
+                                        Ok(()) => error.set(None),
+                                        Err(e) => {
+                                            shown.set(confirmed);
+                                            error.set(Some(e.to_string()));
+                                        }
+                                    }
+                                });
+                            },
+                            "✕"
+                        }
+                    }
+                )
+            })
+            select {
+                class: "select select-bordered select-xs",
+                onchange: move |evt| {
+                    if let Ok(k) = evt.value.parse() {
+                        kind.set(k);
+                    }
+                },
+                TagKind::ALL.iter().map(|k| {
+                    let value = k.as_str();
+                    rsx!(option { key: "{value}", value: "{value}", selected: *k == **kind, "{value}" })
+                })
+            }
+            input {
+                class: "input input-bordered input-xs",
+                placeholder: "Add a tag",
+                value: "{name_value}",
+                oninput: move |evt| name.set(evt.value.clone()),
+            }
+            button { class: "btn btn-xs", r#type: "button", onclick: add, "Add" }
+            error.get().as_ref().map(|e| rsx!(span { class: "text-error text-sm", "{e}" }))
+        }
+    ))
+}
@@ -1,37 +1,268 @@
 use dioxus::prelude::*;
 use dioxus_html_macro::html;
 use dioxus_query::prelude::*;
+use dioxus_router::prelude::*;
 
-use recipe_shared::Recipe;
+use recipe_shared::{
+    Diet, PageRequest, Recipe, RecipeFilter, RecipeSort, TagFacet, TagKind, RECIPE_PAGE_SIZE,
+};
 
 use crate::{
-    components::ScrapedRecipe,
-    hooks::{use_recipes, QueryValue},
+    components::{ErrorAlert, ScrapedRecipe},
+    hooks::{use_search, QueryError, QueryValue, SearchQuery},
     server::scrape_recipe,
+    AppError, Route,
 };
 
-pub fn RecipeGrid(cx: Scope) -> Element {
-    let recipes = use_recipes(cx);
-    cx.render(match recipes.result().value() {
-        QueryResult::Ok(QueryValue::Recipes(rs)) => rsx!(
-            div { class: "grid grid-cols-2 md:grid-cols-6 gap-4 p-4",
-                rs.iter().map(|recipe| {
-                    rsx!(RecipeCard { key: "{recipe.name}", recipe: recipe.clone() })
+/// The recipes matching a filter, alongside facets for narrowing it down.
+///
+/// Recipes are loaded a page at a time, with the next page loaded when the
+/// end of the grid scrolls into view.
+#[component]
+pub fn RecipeGrid<'a>(
+    cx: Scope<'a>,
+    filter: RecipeFilter,
+    on_toggle_tag: EventHandler<'a, i64>,
+    on_toggle_diet: EventHandler<'a, Diet>,
+) -> Element {
+    let pages = use_state(cx, || 1usize);
+    let first_page = RecipeFilter {
+        page: Some(PageRequest::nth(0)),
+        ..filter.clone()
+    };
+    let search = use_search(cx, &first_page)?;
+    cx.render(match search.result().value() {
+        QueryResult::Ok(QueryValue::Search(results)) => {
+            let empty = results.recipes.is_empty();
+            let more = **pages * RECIPE_PAGE_SIZE < results.total;
+            rsx!(
+                div { class: "flex flex-col md:flex-row gap-4 p-4",
+                    Facets {
+                        facets: results.facets.clone(),
+                        selected: filter.tag_ids.clone(),
+                        diets: filter.diets.clone(),
+                        on_toggle: move |id| on_toggle_tag.call(id),
+                        on_toggle_diet: move |diet| on_toggle_diet.call(diet),
+                    }
+                    div { class: "flex-1 flex flex-col gap-4",
+                        empty.then(|| rsx!(p { class: "opacity-70", "No recipes match." }))
+                        div { class: "grid grid-cols-1 sm:grid-cols-2 md:grid-cols-3 xl:grid-cols-5 gap-4",
+                            (0..**pages).map(|index| rsx!(
+                                RecipeGridPage {
+                                    key: "{index}",
+                                    filter: RecipeFilter {
+                                        page: Some(PageRequest::nth(index)),
+                                        ..filter.clone()
+                                    },
+                                }
+                            ))
+                        }
+                        more.then(|| rsx!(LoadMore { key: "{pages}", pages: pages.clone() }))
+                    }
+                }
+            )
+        }
+        QueryResult::Err(QueryError::Server(error)) => rsx!(ErrorAlert {
+            error: error.clone()
+        }),
+        QueryResult::Ok(value) => rsx!(ErrorAlert {
+            error: value.unexpected()
+        }),
+        QueryResult::Loading(_) => rsx!(div { "loading" }),
+    })
+}
+
+/// The cards of one page of recipes, each page cached under its own query.
+#[component]
+fn RecipeGridPage(cx: Scope, filter: RecipeFilter) -> Element {
+    let search = use_search(cx, filter)?;
+    cx.render(match search.result().value() {
+        QueryResult::Ok(QueryValue::Search(results)) => {
+            rsx!(results.recipes.iter().map(|recipe| {
+                rsx!(RecipeCard {
+                    key: "{recipe.id}",
+                    recipe: recipe.clone()
                 })
-            }
-        ),
-        QueryResult::Err(_) => rsx!(div { "error" }),
+            }))
+        }
+        QueryResult::Err(QueryError::Server(error)) => rsx!(ErrorAlert {
+            error: error.clone()
+        }),
+        QueryResult::Ok(value) => rsx!(ErrorAlert {
+            error: value.unexpected()
+        }),
         QueryResult::Loading(_) => rsx!(div { "loading" }),
     })
 }
 
+/// The id of the element which loads the next page when scrolled into view.
+const LOAD_MORE_ID: &str = "load-more-recipes";
+
+/// A button to load the next page of recipes, which is also pressed
+/// automatically once it scrolls into view.
+#[component]
+fn LoadMore(cx: Scope, pages: UseState<usize>) -> Element {
+    // Evaluating JavaScript isn't possible while rendering on the server.
+    #[cfg(not(feature = "ssr"))]
+    {
+        let create_eval = use_eval(cx);
+        use_future(cx, (), |_| {
+            let create_eval = create_eval.clone();
+            let pages = pages.clone();
+            async move {
+                let script = format!(
+                    r#"
+                    const observe = () => {{
+                        const sentinel = document.getElementById("{LOAD_MORE_ID}");
+                        if (!sentinel) {{
+                            requestAnimationFrame(observe);
+                            return;
+                        }}
+                        const observer = new IntersectionObserver((entries) => {{
+                            if (entries.some((entry) => entry.isIntersecting)) {{
+                                observer.disconnect();
+                                dioxus.send(true);
+                            }}
+                        }});
+                        observer.observe(sentinel);
+                    }};
+                    observe();
+                    "#
+                );
+                let Ok(eval) = create_eval(&script) else {
+                    return;
+                };
+                if eval.recv().await.is_ok() {
+                    pages.modify(|pages| pages + 1);
+                }
+            }
+        });
+    }
+    cx.render(rsx!(
+        div { id: "{LOAD_MORE_ID}", class: "flex justify-center",
+            button {
+                class: "btn btn-ghost",
+                r#type: "button",
+                onclick: move |_| pages.modify(|pages| pages + 1),
+                "Load more"
+            }
+        }
+    ))
+}
+
+/// Sidebar listing the diets along with the categories, cuisines and tags
+/// present in the current results, each of which can be toggled to narrow the
+/// results further. On narrow screens it's collapsed above the grid.
+#[component]
+fn Facets<'a>(
+    cx: Scope<'a>,
+    facets: Vec<TagFacet>,
+    selected: Vec<i64>,
+    diets: Vec<Diet>,
+    on_toggle: EventHandler<'a, i64>,
+    on_toggle_diet: EventHandler<'a, Diet>,
+) -> Element {
+    cx.render(rsx!(
+        details { class: "md:hidden collapse collapse-arrow bg-base-200",
+            summary { class: "collapse-title font-bold", "Filters" }
+            div { class: "collapse-content flex flex-col gap-4",
+                FacetList {
+                    facets: facets.clone(),
+                    selected: selected.clone(),
+                    diets: diets.clone(),
+                    on_toggle: move |id| on_toggle.call(id),
+                    on_toggle_diet: move |diet| on_toggle_diet.call(diet),
+                }
+            }
+        }
+        aside { class: "hidden md:flex w-56 flex-col gap-4",
+            FacetList {
+                facets: facets.clone(),
+                selected: selected.clone(),
+                diets: diets.clone(),
+                on_toggle: move |id| on_toggle.call(id),
+                on_toggle_diet: move |diet| on_toggle_diet.call(diet),
+            }
+        }
+    ))
+}
+
+/// The diets and tags which can be toggled, in the sidebar or the collapsed
+/// filters.
+#[component]
+fn FacetList<'a>(
+    cx: Scope<'a>,
+    facets: Vec<TagFacet>,
+    selected: Vec<i64>,
+    diets: Vec<Diet>,
+    on_toggle: EventHandler<'a, i64>,
+    on_toggle_diet: EventHandler<'a, Diet>,
+) -> Element {
+    cx.render(rsx!(
+        div {
+            h3 { class: "font-bold", "Diet" }
+            ul { class: "menu menu-sm p-0",
+                Diet::ALL.iter().map(|diet| {
+                    let diet = *diet;
+                    let label = diet.label();
+                    let checked = diets.contains(&diet);
+                    rsx!(
+                        li { key: "{diet}",
+                            label { class: "label cursor-pointer justify-start gap-2",
+                                input {
+                                    r#type: "checkbox",
+                                    class: "checkbox checkbox-sm",
+                                    checked: checked,
+                                    onchange: move |_| on_toggle_diet.call(diet),
+                                }
+                                span { class: "flex-1", "{label}" }
+                            }
+                        }
+                    )
+                })
+            }
+        }
+        TagKind::ALL.iter().map(|kind| {
+            let label = kind.label();
+            let facets: Vec<_> = facets.iter().filter(|f| f.tag.kind == *kind).collect();
+            (!facets.is_empty()).then(|| rsx!(
+                div { key: "{kind}",
+                    h3 { class: "font-bold", "{label}" }
+                    ul { class: "menu menu-sm p-0",
+                        facets.into_iter().map(|facet| {
+                            let id = facet.tag.id;
+                            let checked = selected.contains(&id);
+                            rsx!(
+                                li { key: "{id}",
+                                    label { class: "label cursor-pointer justify-start gap-2",
+                                        input {
+                                            r#type: "checkbox",
+                                            class: "checkbox checkbox-sm",
+                                            checked: checked,
+                                            onchange: move |_| on_toggle.call(id),
+                                        }
+                                        span { class: "flex-1", "{facet.tag.name}" }
+                                        span { class: "badge badge-sm", "{facet.count}" }
+                                    }
+                                }
+                            )
+                        })
+                    }
+                }
+            ))
+        })
+    ))
+}
+
 #[component]
 fn RecipeCard(cx: Scope, recipe: Recipe) -> Element {
     cx.render(rsx!(
-        div { class: "card shadow-xl bg-primary text-primary-content",
-            div { class: "card-body items-center text-center",
+        Link {
+            to: Route::RecipePage { slug: recipe.slug.clone() },
+            class: "card shadow-xl bg-primary text-primary-content",
+            div { class: "card-body items-center text-center p-4 sm:p-8",
                 recipe.image_url.as_ref().map(|url| rsx!(figure {
-                    img { src: "{url}", alt: "Recipe image" }
+                    img { class: "w-full h-48 sm:h-auto object-cover rounded-box", src: "{url}", alt: "Recipe image" }
                 })),
                 h4 { class: "card-title", "{cx.props.recipe.name}" }
                 recipe.notes.as_ref().map(|n| rsx!(p { "{n}" }))
@@ -63,21 +294,27 @@ fn NewRecipeButton(cx: Scope) -> Element {
 fn NewRecipeModal(cx: Scope) -> Element {
     let url = use_state(cx, || "".to_string());
     let recipe = use_state::<Option<recipe_scrape::ScrapedRecipe>>(cx, || None);
+    let error = use_state::<Option<AppError>>(cx, || None);
     let scrape_recipe = move |_| {
         let url = url.to_owned();
         let recipe = recipe.to_owned();
+        let error = error.to_owned();
+        error.set(None);
         cx.spawn({
             async move {
                 match scrape_recipe(url.to_string()).await {
                     Ok(r) => recipe.set(Some(r)),
-                    Err(err) => log::error!("Failed to scrape recipe: {:?}", err),
+                    Err(err) => {
+                        log::error!("Failed to scrape recipe: {:?}", err);
+                        error.set(Some(err.into()));
+                    }
                 }
             }
         });
     };
     cx.render(html!(
         <dialog id="new_recipe" class="modal">
-            <div class="modal-box w-9/12 max-w-5xl">
+            <div class="modal-box w-11/12 md:w-9/12 max-w-5xl">
                 <h3 class="font-bold text-lg">"Add a recipe"</h3>
                 <div>
                     <label "for"="recipe_url" class="py-4">"Recipe URL"</label>
@@ -90,7 +327,9 @@ fn NewRecipeModal(cx: Scope) -> Element {
                     />
                 </div>
                 <button "type"="submit" class="btn btn-primary" onclick={scrape_recipe}>"Scrape"</button>
-                {recipe.as_ref().map(|x| rsx!(ScrapedRecipe { recipe: x.clone() }))}
+                {rsx!(Link { to: Route::NewRecipePage {}, class: "btn btn-ghost", "Write one yourself" })}
+                {error.as_ref().map(|e| rsx!(ErrorAlert { error: e.clone() }))}
+                {recipe.as_ref().map(|x| rsx!(ScrapedRecipe { key: "{x.source}", recipe: x.clone() }))}
             </div>
             <form method="dialog" class="modal-backdrop">
                 <button>"close"</button>
@@ -99,12 +338,71 @@ fn NewRecipeModal(cx: Scope) -> Element {
     ))
 }
 
+/// Add `item` to `items` if it isn't there, otherwise remove it.
+pub(crate) fn toggle<T: PartialEq>(items: &mut Vec<T>, item: T) {
+    match items.iter().position(|x| *x == item) {
+        Some(i) => {
+            items.remove(i);
+        }
+        None => items.push(item),
+    }
+}
+
 pub fn RecipesPage(cx: Scope) -> Element {
-    cx.render(html!(
-        <div>
-            <RecipeGrid />
-            <NewRecipeModal />
-            <NewRecipeButton />
-        </div>
+    let search = use_shared_state::<SearchQuery>(cx).unwrap();
+    let tag_ids = use_state::<Vec<i64>>(cx, Vec::new);
+    let diets = use_state::<Vec<Diet>>(cx, Vec::new);
+    let sort = use_state(cx, RecipeSort::default);
+    let query = search.read().0.trim().to_string();
+    let filter = RecipeFilter {
+        query: Some(query).filter(|q| !q.is_empty()),
+        tag_ids: tag_ids.get().clone(),
+        diets: diets.get().clone(),
+        sort: **sort,
+        page: None,
+    };
+    // Queries are keyed when they're first used, so remount the grid whenever
+    // the filter changes.
+    let key = format!("{filter:?}");
+    let filtered = !tag_ids.is_empty() || !diets.is_empty();
+    cx.render(rsx!(
+        div {
+            div { class: "px-4 pt-4 flex flex-wrap items-center gap-4",
+                label { class: "flex items-center gap-2",
+                    span { class: "label-text", "Sort by" }
+                    select {
+                        class: "select select-bordered select-sm",
+                        onchange: move |evt| {
+                            if let Ok(s) = evt.value.parse() {
+                                sort.set(s);
+                            }
+                        },
+                        RecipeSort::ALL.iter().map(|s| {
+                            let label = s.label();
+                            rsx!(option { key: "{s}", value: "{s}", selected: *s == **sort, "{label}" })
+                        })
+                    }
+                }
+                filtered.then(|| rsx!(
+                    button {
+                        class: "btn btn-sm btn-ghost",
+                        r#type: "button",
+                        onclick: move |_| {
+                            tag_ids.set(Vec::new());
+                            diets.set(Vec::new());
+                        },
+                        "Clear filters"
+                    }
+                ))
+            }
+            RecipeGrid {
+                key: "{key}",
+                filter: filter,
+                on_toggle_tag: move |id| tag_ids.with_mut(|ids| toggle(ids, id)),
+                on_toggle_diet: move |diet| diets.with_mut(|diets| toggle(diets, diet)),
+            }
+            NewRecipeModal {}
+            NewRecipeButton {}
+        }
     ))
 }
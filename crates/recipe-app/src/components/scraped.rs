@@ -1,23 +1,287 @@
 use dioxus::prelude::*;
+use dioxus_query::prelude::*;
+use dioxus_router::prelude::*;
+
+use recipe_shared::{Ingredient, IngredientInput, RecipeInput, ValidationErrors};
+
+use crate::{
+    components::editor::ingredient_input,
+    hooks::{use_ingredients, use_mutations, Changed, QueryValue},
+    server::create_recipe,
+    AppError, Route,
+};
+
+/// A scraped ingredient line being reviewed before the recipe is saved.
+#[derive(Clone, Debug, PartialEq)]
+struct ReviewRow {
+    /// Keeps the row's element as the list changes.
+    id: usize,
+    raw: String,
+    /// The name of an existing ingredient to use instead of the parsed name.
+    matched: Option<String>,
+}
+
+impl ReviewRow {
+    fn parse(&self) -> recipe_scrape::ScrapedIngredient {
+        let mut parsed = recipe_scrape::ScrapedIngredient::parse_lenient(self.raw.trim());
+        if let Some(name) = &self.matched {
+            parsed.name = Some(name.clone());
+        }
+        parsed
+    }
+
+    fn resolve(&self) -> Result<IngredientInput, String> {
+        ingredient_input(self.parse())
+    }
+
+    /// Any problems the parser had with a line which still produced an ingredient.
+    fn diagnostic(&self) -> Option<String> {
+        if self.matched.is_some() {
+            return None;
+        }
+        self.parse().status.message().map(str::to_string)
+    }
+}
 
 #[component]
-fn ScrapedIngredient(cx: Scope, ingredient: recipe_scrape::ScrapedIngredient) -> Element {
+fn ReviewIngredient<'a>(
+    cx: Scope<'a>,
+    index: usize,
+    row: ReviewRow,
+    existing: Vec<Ingredient>,
+    on_change: EventHandler<'a, ReviewRow>,
+) -> Element {
+    let number = index + 1;
+    let resolved = row.resolve();
+    let is_existing = resolved.as_ref().is_ok_and(|ingredient| {
+        existing
+            .iter()
+            .any(|e| e.name.eq_ignore_ascii_case(&ingredient.name))
+    });
+    let row_class = if resolved.is_err() {
+        "bg-warning/20"
+    } else {
+        ""
+    };
+    let selected = row.matched.clone().unwrap_or_default();
+    let parsed = match &resolved {
+        Ok(ingredient) => {
+            let quantity = ingredient
+                .quantity
+                .map(|q| q.to_string())
+                .unwrap_or_default();
+            let unit = ingredient.unit.clone().unwrap_or_default();
+            let diagnostic = row.diagnostic();
+            rsx!(
+                div { class: "flex gap-2 text-sm",
+                    span { class: "badge badge-outline", "{quantity}" }
+                    span { class: "badge badge-outline", "{unit}" }
+                    span { class: "badge badge-primary", "{ingredient.name}" }
+                }
+                diagnostic.map(|d| rsx!(p { class: "text-warning text-xs", "{d}" }))
+            )
+        }
+        Err(e) => rsx!(span { class: "text-warning text-sm", "{e}" }),
+    };
+    let status = if resolved.is_err() {
+        rsx!(span { class: "badge badge-warning", "needs fixing" })
+    } else if is_existing {
+        rsx!(span { class: "badge badge-success", "existing" })
+    } else {
+        rsx!(span { class: "badge badge-info", "new" })
+    };
     cx.render(rsx!(
-        li {
-            span { "{ingredient}" }
+        tr { class: "{row_class}",
+            td { class: "w-6 opacity-60", "{number}" }
+            td {
+                input {
+                    class: "input input-bordered input-sm w-full",
+                    value: "{row.raw}",
+                    oninput: move |evt| on_change.call(ReviewRow {
+                        raw: evt.value.clone(),
+                        ..row.clone()
+                    }),
+                }
+            }
+            td { parsed }
+            td {
+                select {
+                    class: "select select-bordered select-sm",
+                    value: "{selected}",
+                    onchange: move |evt| on_change.call(ReviewRow {
+                        matched: Some(evt.value.clone()).filter(|v| !v.is_empty()),
+                        ..row.clone()
+                    }),
+                    option { value: "", "As parsed" }
+                    existing.iter().map(|ingredient| rsx!(
+                        option {
+                            key: "{ingredient.id}",
+                            value: "{ingredient.name}",
+                            selected: ingredient.name == selected,
+                            "{ingredient.name}"
+                        }
+                    ))
+                }
+            }
+            td { status }
         }
     ))
 }
 
+/// Review screen for a freshly scraped recipe.
+///
+/// Each ingredient line shows its raw text alongside what was parsed from it,
+/// and can be corrected or matched to an existing ingredient before saving.
 #[component]
 pub fn ScrapedRecipe(cx: Scope, recipe: recipe_scrape::ScrapedRecipe) -> Element {
+    let name = use_state(cx, || recipe.name.clone());
+    let rows = use_state(cx, || {
+        recipe
+            .ingredients
+            .iter()
+            .enumerate()
+            .map(|(id, ingredient)| ReviewRow {
+                id,
+                raw: ingredient.raw.clone(),
+                matched: None,
+            })
+            .collect::<Vec<_>>()
+    });
+    let errors = use_state(cx, ValidationErrors::default);
+    let server_error = use_state::<Option<String>>(cx, || None);
+    let saving = use_state(cx, || false);
+    let navigator = use_navigator(cx);
+    let mutations = use_mutations(cx);
+    let existing = match use_ingredients(cx).result().value() {
+        QueryResult::Ok(QueryValue::Ingredients(ingredients)) => ingredients.clone(),
+        _ => Vec::new(),
+    };
+
+    let save = move |_| {
+        let mut validation = ValidationErrors::default();
+        let mut ingredients = Vec::with_capacity(rows.len());
+        for (i, row) in rows.iter().enumerate() {
+            match row.resolve() {
+                Ok(ingredient) => ingredients.push(ingredient),
+                Err(e) => validation.add(&format!("ingredients.{i}"), e),
+            }
+        }
+        let input = RecipeInput {
+            name: name.trim().to_string(),
+            ..recipe.recipe_input(ingredients)
+        };
+        if let Err(e) = input.validate() {
+            validation.merge(e);
+        }
+        if !validation.is_empty() {
+            errors.set(validation);
+            return;
+        }
+        errors.set(ValidationErrors::default());
+        server_error.set(None);
+        saving.set(true);
+        let navigator = navigator.clone();
+        let mutations = mutations.clone();
+        let saving = saving.to_owned();
+        let server_error = server_error.to_owned();
+        let errors = errors.to_owned();
+        cx.spawn(async move {
+            let result = mutations
+                .run(create_recipe(input), |detail| {
+                    vec![
+                        Changed::Recipe(detail.recipe.slug.clone()),
+                        Changed::Ingredients,
+                    ]
+                })
+                .await;
+            saving.set(false);
+            match result {
+                Ok(detail) => {
+                    navigator.push(Route::RecipePage {
+                        slug: detail.recipe.slug,
+                    });
+                }
+                Err(AppError::Validation { errors: e }) => errors.set(e),
+                Err(e) => server_error.set(Some(e.to_string())),
+            }
+        });
+    };
+
+    let name_value = name.get();
+    let warning_count = recipe.warnings.len();
+    let failures = rows.iter().filter(|row| row.resolve().is_err()).count();
+    let save_label = if **saving { "Saving…" } else { "Save recipe" };
+    let scraped_tags = recipe.tags();
     cx.render(rsx!(
-        h1 { "{recipe.name}" }
-        h2 { "Ingredients" }
-        ul {
-            recipe.ingredients.iter().map(|ingredient| {
-                rsx!(ScrapedIngredient { ingredient: ingredient.clone() })
+        div { class: "flex flex-col gap-4 py-4",
+            div { class: "form-control",
+                label { class: "label", r#for: "scraped_name", "Name" }
+                input {
+                    id: "scraped_name",
+                    class: "input input-bordered",
+                    value: "{name_value}",
+                    oninput: move |evt| name.set(evt.value.clone()),
+                }
+                errors.get().get("name").iter().map(|e| rsx!(p { class: "text-error text-sm", "{e}" }))
+            }
+            (!scraped_tags.is_empty()).then(|| rsx!(
+                div { class: "flex flex-wrap gap-2",
+                    scraped_tags.iter().map(|tag| {
+                        let kind = tag.kind.as_str();
+                        rsx!(span { class: "badge badge-outline", title: "{kind}", "{tag.name}" })
+                    })
+                }
+            ))
+            (!recipe.warnings.is_empty()).then(|| rsx!(
+                details { class: "collapse collapse-arrow bg-base-200",
+                    summary { class: "collapse-title", "The scraper reported {warning_count} warning(s)" }
+                    ul { class: "collapse-content list-disc list-inside text-sm",
+                        recipe.warnings.iter().map(|w| rsx!(li { "{w}" }))
+                    }
+                }
+            ))
+            h2 { class: "text-xl font-bold", "Ingredients" }
+            (failures > 0).then(|| rsx!(
+                div { class: "alert alert-warning",
+                    "{failures} ingredient(s) couldn't be understood. Fix the text or match them to an existing ingredient."
+                }
+            ))
+            table { class: "table table-sm",
+                thead {
+                    tr {
+                        th {}
+                        th { "Scraped text" }
+                        th { "Parsed" }
+                        th { "Match existing" }
+                        th {}
+                    }
+                }
+                tbody {
+                    rows.iter().enumerate().map(|(i, row)| rsx!(
+                        ReviewIngredient {
+                            key: "{row.id}",
+                            index: i,
+                            row: row.clone(),
+                            existing: existing.clone(),
+                            on_change: move |row| rows.with_mut(|rows| rows[i] = row),
+                        }
+                    ))
+                }
+            }
+            errors.get().iter().filter(|(field, _)| field.starts_with("ingredients.")).map(|(field, messages)| {
+                let message = messages.join(", ");
+                rsx!(p { key: "{field}", class: "text-error text-sm", "{field}: {message}" })
             })
+            server_error.get().as_ref().map(|e| rsx!(
+                div { class: "alert alert-error", "Failed to save recipe: {e}" }
+            ))
+            button {
+                class: "btn btn-primary self-end",
+                r#type: "button",
+                disabled: **saving,
+                onclick: save,
+                "{save_label}"
+            }
         }
     ))
 }
@@ -0,0 +1,161 @@
+use std::fmt;
+
+use dioxus_fullstack::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use recipe_shared::ValidationErrors;
+
+/// Why a server function failed, carried to the client intact so that it can
+/// say something more useful than "error".
+///
+/// Server functions can only return [`ServerFnError`], so these are sent as
+/// JSON inside [`ServerFnError::ServerError`] and parsed back out with
+/// `AppError::from`. The server responds with [`AppError::status`].
+///
+/// This deliberately doesn't implement `std::error::Error`, which would make
+/// `?` turn it into a plain message.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum AppError {
+    NotFound,
+    /// The input was rejected, with the problems with each field.
+    Validation {
+        errors: ValidationErrors,
+    },
+    /// The change clashes with something which already exists.
+    Conflict {
+        message: String,
+    },
+    /// A recipe couldn't be scraped from a page on `host`.
+    UpstreamScrapeFailed {
+        host: String,
+        message: String,
+    },
+    /// Signing in is needed, or failed.
+    Unauthorized {
+        message: String,
+    },
+    /// The signed in user isn't allowed to do this.
+    Forbidden,
+    /// Anything else, which the user can't do much about.
+    Internal {
+        message: String,
+    },
+}
+
+impl AppError {
+    pub fn internal(message: impl fmt::Display) -> Self {
+        Self::Internal {
+            message: message.to_string(),
+        }
+    }
+
+    /// The HTTP status which best describes the error.
+    pub fn status(&self) -> u16 {
+        match self {
+            Self::NotFound => 404,
+            Self::Validation { .. } => 422,
+            Self::Conflict { .. } => 409,
+            Self::UpstreamScrapeFailed { .. } => 502,
+            Self::Unauthorized { .. } => 401,
+            Self::Forbidden => 403,
+            Self::Internal { .. } => 500,
+        }
+    }
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotFound => f.write_str("not found"),
+            Self::Validation { errors } => write!(f, "invalid input: {errors}"),
+            Self::Conflict { message }
+            | Self::Unauthorized { message }
+            | Self::Internal { message } => f.write_str(message),
+            Self::UpstreamScrapeFailed { host, message } => {
+                write!(f, "couldn't get a recipe from {host}: {message}")
+            }
+            Self::Forbidden => f.write_str("you aren't allowed to do that"),
+        }
+    }
+}
+
+impl From<ValidationErrors> for AppError {
+    fn from(errors: ValidationErrors) -> Self {
+        Self::Validation { errors }
+    }
+}
+
+impl From<AppError> for ServerFnError {
+    fn from(e: AppError) -> Self {
+        match serde_json::to_string(&e) {
+            Ok(json) => Self::ServerError(json),
+            Err(_) => Self::ServerError(e.to_string()),
+        }
+    }
+}
+
+impl From<ServerFnError> for AppError {
+    fn from(e: ServerFnError) -> Self {
+        match e {
+            ServerFnError::ServerError(message) => {
+                serde_json::from_str(&message).unwrap_or(Self::Internal { message })
+            }
+            ServerFnError::Request(message) => Self::Internal {
+                message: format!("couldn't reach the server: {message}"),
+            },
+            e => Self::internal(e),
+        }
+    }
+}
+
+#[cfg(feature = "ssr")]
+impl From<recipe_repository::Error> for AppError {
+    fn from(e: recipe_repository::Error) -> Self {
+        use recipe_repository::Error;
+
+        match e {
+            Error::NotFound => Self::NotFound,
+            Error::Validation(errors) => Self::Validation { errors },
+            Error::Unauthenticated => Self::Unauthorized {
+                message: "you must be signed in to do that".to_string(),
+            },
+            Error::Forbidden => Self::Forbidden,
+            e if e.is_conflict() => Self::Conflict {
+                message: "that already exists".to_string(),
+            },
+            e => {
+                tracing::error!(error = ?e, "server function failed");
+                Self::internal(e)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_through_server_fn_error() {
+        let mut errors = ValidationErrors::default();
+        errors.add("name", "must not be empty");
+        for error in [
+            AppError::NotFound,
+            AppError::Validation { errors },
+            AppError::UpstreamScrapeFailed {
+                host: "example.com".to_string(),
+                message: "not a recipe".to_string(),
+            },
+        ] {
+            assert_eq!(AppError::from(ServerFnError::from(error.clone())), error);
+        }
+    }
+
+    #[test]
+    fn other_errors_are_internal() {
+        let error = AppError::from(ServerFnError::ServerError("boom".to_string()));
+        assert_eq!(error, AppError::internal("boom"));
+        assert_eq!(error.status(), 500);
+    }
+}
@@ -1,40 +1,310 @@
+use std::future::Future;
+
 use dioxus::prelude::*;
 use dioxus_fullstack::prelude::*;
 use dioxus_query::prelude::*;
+use serde::{Deserialize, Serialize};
 
-use recipe_shared::Recipe;
+use recipe_shared::{
+    Household, HouseholdDetail, Ingredient, IngredientDiet, MealPlan, MealPlanDetail, RecipeDetail,
+    RecipeFilter, RecipeSearch, ShoppingList, User,
+};
 
-use crate::server;
+use crate::{server, AppError};
 
 pub fn use_init(cx: Scope) {
     use_init_query_client::<QueryValue, QueryError, QueryKeys>(cx);
 }
 
+/// The free text typed into the search box in the header.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SearchQuery(pub String);
+
+/// The first key of each query says what it loads; the rest are groups it
+/// belongs to, so that invalidating a group refreshes all of them.
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub enum QueryKeys {
+    /// Invalidates every query whose result depends on who is signed in.
+    Account,
+    CurrentUser,
+    /// Invalidates every query which lists recipes.
     Recipes,
+    /// One page of search results, as the filter includes the page.
+    Search(RecipeFilter),
+    Recipe(String),
+    Ingredients,
+    IngredientDiets,
+    MealPlans,
+    MealPlan(String),
+    /// Invalidates every shopping list, as they depend on recipes' ingredients.
+    ShoppingLists,
+    ShoppingList(String),
+    Households,
+    Household(i64),
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum QueryValue {
-    Recipes(Vec<Recipe>),
+    CurrentUser(Option<User>),
+    Search(RecipeSearch),
+    Recipe(Option<RecipeDetail>),
+    Ingredients(Vec<Ingredient>),
+    IngredientDiets(Vec<IngredientDiet>),
+    MealPlans(Vec<MealPlan>),
+    MealPlan(Option<MealPlanDetail>),
+    ShoppingList(Option<ShoppingList>),
+    Households(Vec<Household>),
+    Household(Option<HouseholdDetail>),
+}
+
+impl QueryValue {
+    /// The error to show when a query loads a different kind of value from
+    /// the one it's for, which is a bug rather than anything the user did.
+    pub fn unexpected(&self) -> AppError {
+        AppError::internal("the app loaded the wrong kind of data")
+    }
 }
 
 #[derive(Clone, Debug, PartialEq)]
 pub enum QueryError {
-    Server(ServerFnError),
+    Server(AppError),
+}
+
+impl From<ServerFnError> for QueryError {
+    fn from(e: ServerFnError) -> Self {
+        Self::Server(e.into())
+    }
+}
+
+/// Load the value for a query, going by its first key.
+async fn fetch(keys: Vec<QueryKeys>) -> Result<QueryValue, ServerFnError> {
+    match keys.into_iter().next() {
+        Some(QueryKeys::CurrentUser) => server::current_user().await.map(QueryValue::CurrentUser),
+        Some(QueryKeys::Search(filter)) => {
+            server::search_recipes(filter).await.map(QueryValue::Search)
+        }
+        Some(QueryKeys::Recipe(slug)) => server::recipe(slug).await.map(QueryValue::Recipe),
+        Some(QueryKeys::Ingredients) => server::ingredients().await.map(QueryValue::Ingredients),
+        Some(QueryKeys::IngredientDiets) => server::ingredient_diets()
+            .await
+            .map(QueryValue::IngredientDiets),
+        Some(QueryKeys::MealPlans) => server::meal_plans().await.map(QueryValue::MealPlans),
+        Some(QueryKeys::MealPlan(slug)) => server::meal_plan(slug).await.map(QueryValue::MealPlan),
+        Some(QueryKeys::ShoppingList(slug)) => server::shopping_list(slug)
+            .await
+            .map(QueryValue::ShoppingList),
+        Some(QueryKeys::Households) => server::households().await.map(QueryValue::Households),
+        Some(QueryKeys::Household(id)) => server::household(id).await.map(QueryValue::Household),
+        Some(key @ (QueryKeys::Account | QueryKeys::Recipes | QueryKeys::ShoppingLists)) => {
+            Err(AppError::internal(format!("{key:?} only groups other queries")).into())
+        }
+        None => Err(AppError::internal("the query has no keys").into()),
+    }
 }
 
-pub fn use_recipes(cx: Scope) -> &UseQuery<QueryValue, QueryError, QueryKeys> {
+fn use_fetch_query(
+    cx: Scope,
+    keys: Vec<QueryKeys>,
+) -> &UseQuery<QueryValue, QueryError, QueryKeys> {
     use_query(
         cx,
-        || vec![QueryKeys::Recipes],
-        |_| async {
-            server::recipes()
-                .await
-                .map(QueryValue::Recipes)
-                .map_err(QueryError::Server)
-                .into()
-        },
+        move || keys.clone(),
+        |keys| async move { fetch(keys).await.map_err(QueryError::from).into() },
+    )
+}
+
+/// A query whose first result is loaded while rendering on the server and
+/// sent along with the page, so that the first render shows the data rather
+/// than "loading". The component is suspended until that result is ready.
+fn use_prefetched_query(
+    cx: Scope,
+    keys: Vec<QueryKeys>,
+) -> Option<&UseQuery<QueryValue, QueryError, QueryKeys>> {
+    let prefetched = use_server_future(cx, (keys.clone(),), |(keys,)| async move {
+        fetch(keys).await.map_err(AppError::from)
+    })?;
+    let initial = prefetched.value().clone();
+    Some(use_query_config(cx, move || {
+        QueryConfig::new(keys, |keys| async move {
+            fetch(keys).await.map_err(QueryError::from).into()
+        })
+        .initial(move || initial.clone().map_err(QueryError::Server).into())
+    }))
+}
+
+pub fn use_current_user(cx: Scope) -> &UseQuery<QueryValue, QueryError, QueryKeys> {
+    use_fetch_query(cx, vec![QueryKeys::CurrentUser, QueryKeys::Account])
+}
+
+/// The signed in user, once known.
+pub fn use_user(cx: Scope) -> Option<User> {
+    match use_current_user(cx).result().value() {
+        QueryResult::Ok(QueryValue::CurrentUser(user)) => user.clone(),
+        _ => None,
+    }
+}
+
+pub fn use_search<'a>(
+    cx: Scope<'a>,
+    filter: &RecipeFilter,
+) -> Option<&'a UseQuery<QueryValue, QueryError, QueryKeys>> {
+    use_prefetched_query(
+        cx,
+        vec![
+            QueryKeys::Search(filter.clone()),
+            QueryKeys::Recipes,
+            QueryKeys::Account,
+        ],
+    )
+}
+
+pub fn use_recipe<'a>(
+    cx: Scope<'a>,
+    slug: &str,
+) -> Option<&'a UseQuery<QueryValue, QueryError, QueryKeys>> {
+    use_prefetched_query(
+        cx,
+        vec![
+            QueryKeys::Recipe(slug.to_string()),
+            QueryKeys::Recipes,
+            QueryKeys::Account,
+        ],
+    )
+}
+
+pub fn use_ingredients(cx: Scope) -> &UseQuery<QueryValue, QueryError, QueryKeys> {
+    use_fetch_query(cx, vec![QueryKeys::Ingredients])
+}
+
+pub fn use_ingredient_diets(cx: Scope) -> Option<&UseQuery<QueryValue, QueryError, QueryKeys>> {
+    use_prefetched_query(cx, vec![QueryKeys::IngredientDiets, QueryKeys::Ingredients])
+}
+
+pub fn use_meal_plans(cx: Scope) -> Option<&UseQuery<QueryValue, QueryError, QueryKeys>> {
+    use_prefetched_query(cx, vec![QueryKeys::MealPlans, QueryKeys::Account])
+}
+
+pub fn use_meal_plan<'a>(
+    cx: Scope<'a>,
+    slug: &str,
+) -> Option<&'a UseQuery<QueryValue, QueryError, QueryKeys>> {
+    use_prefetched_query(
+        cx,
+        vec![QueryKeys::MealPlan(slug.to_string()), QueryKeys::Account],
+    )
+}
+
+pub fn use_shopping_list<'a>(
+    cx: Scope<'a>,
+    slug: &str,
+) -> Option<&'a UseQuery<QueryValue, QueryError, QueryKeys>> {
+    use_prefetched_query(
+        cx,
+        vec![
+            QueryKeys::ShoppingList(slug.to_string()),
+            QueryKeys::ShoppingLists,
+            QueryKeys::Account,
+        ],
     )
 }
+
+pub fn use_households(cx: Scope) -> Option<&UseQuery<QueryValue, QueryError, QueryKeys>> {
+    use_prefetched_query(cx, vec![QueryKeys::Households, QueryKeys::Account])
+}
+
+pub fn use_household(cx: Scope, id: i64) -> Option<&UseQuery<QueryValue, QueryError, QueryKeys>> {
+    use_prefetched_query(cx, vec![QueryKeys::Household(id), QueryKeys::Account])
+}
+
+/// What a mutation changed, so that every query showing it can be refreshed.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Changed {
+    /// Someone signed in or out, which changes what they can see.
+    Account,
+    /// A recipe was created, edited, tagged or shared.
+    Recipe(String),
+    /// Ingredients were added, which happens when saving a recipe.
+    Ingredients,
+    /// The dietary flags of an ingredient, which decide which recipes suit
+    /// which diets.
+    IngredientFlags,
+    MealPlans,
+    /// A meal plan's recipes changed, or it was shared.
+    MealPlan(String),
+    Households,
+    /// A household's members changed.
+    Household(i64),
+}
+
+impl Changed {
+    /// The queries which may be showing what changed.
+    pub fn keys(&self) -> Vec<QueryKeys> {
+        match self {
+            Self::Account => vec![QueryKeys::Account],
+            Self::Recipe(slug) => vec![
+                QueryKeys::Recipes,
+                QueryKeys::Recipe(slug.clone()),
+                QueryKeys::ShoppingLists,
+            ],
+            Self::Ingredients => vec![QueryKeys::Ingredients],
+            Self::IngredientFlags => vec![QueryKeys::IngredientDiets, QueryKeys::Recipes],
+            Self::MealPlans => vec![QueryKeys::MealPlans],
+            Self::MealPlan(slug) => vec![
+                QueryKeys::MealPlans,
+                QueryKeys::MealPlan(slug.clone()),
+                QueryKeys::ShoppingList(slug.clone()),
+            ],
+            Self::Households => vec![QueryKeys::Households],
+            Self::Household(id) => vec![QueryKeys::Households, QueryKeys::Household(*id)],
+        }
+    }
+}
+
+/// Runs server functions which change data, then refreshes the queries
+/// showing what they changed.
+#[derive(Clone)]
+pub struct Mutations {
+    client: UseQueryClient<QueryValue, QueryError, QueryKeys>,
+}
+
+impl Mutations {
+    /// Run `mutation`, and if it succeeds refresh what `changed` says it
+    /// changed.
+    pub async fn run<T>(
+        &self,
+        mutation: impl Future<Output = Result<T, ServerFnError>>,
+        changed: impl FnOnce(&T) -> Vec<Changed>,
+    ) -> Result<T, AppError> {
+        let value = mutation.await?;
+        self.invalidate(&changed(&value));
+        Ok(value)
+    }
+
+    pub fn invalidate(&self, changed: &[Changed]) {
+        let keys = changed.iter().flat_map(Changed::keys).collect::<Vec<_>>();
+        self.client.invalidate_queries(&keys);
+    }
+}
+
+pub fn use_mutations(cx: Scope) -> &Mutations {
+    let client = use_query_client::<QueryValue, QueryError, QueryKeys>(cx);
+    cx.use_hook(|| Mutations {
+        client: client.clone(),
+    })
+}
+
+/// A copy of `confirmed` which a component can change straight away, before
+/// the server has confirmed a mutation, so that the page responds at once.
+/// It's replaced whenever `confirmed` changes, such as when the query is
+/// refreshed after the mutation; if the mutation fails, set it back.
+pub fn use_optimistic<'a, T>(cx: Scope<'a>, confirmed: &T) -> &'a UseState<T>
+where
+    T: Clone + PartialEq + 'static,
+{
+    let value = use_state(cx, || confirmed.clone());
+    use_effect(cx, (confirmed.clone(),), {
+        let value = value.to_owned();
+        move |(confirmed,)| async move { value.set(confirmed) }
+    });
+    value
+}
@@ -5,16 +5,23 @@ use dioxus_router::prelude::*;
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    components::{Header, MealPlansPage, RecipesPage},
-    hooks::use_init,
+    components::{
+        CookingPage, EditRecipePage, Header, HouseholdPage, HouseholdsPage, IngredientsPage,
+        MealPlanPage, MealPlansPage, NewRecipePage, RecipePage, RecipesPage, SignInPage,
+    },
+    hooks::{use_init, SearchQuery},
 };
 
 mod components;
+mod error;
 mod hooks;
 pub mod server;
 
+pub use error::AppError;
+
 pub fn Wrapper(cx: Scope) -> Element {
     use_init(cx);
+    use_shared_state_provider(cx, SearchQuery::default);
     cx.render(rsx!(
         Header {}
         Outlet::<Route> {}
@@ -27,6 +34,24 @@ pub enum Route {
     #[layout(Wrapper)]
         #[route("/")]
         RecipesPage {},
+        #[route("/recipes/new")]
+        NewRecipePage {},
+        #[route("/recipes/:slug")]
+        RecipePage { slug: String },
+        #[route("/recipes/:slug/edit")]
+        EditRecipePage { slug: String },
+        #[route("/recipes/:slug/cook")]
+        CookingPage { slug: String },
+        #[route("/ingredients")]
+        IngredientsPage {},
         #[route("/plans")]
         MealPlansPage {},
+        #[route("/plans/:slug")]
+        MealPlanPage { slug: String },
+        #[route("/households")]
+        HouseholdsPage {},
+        #[route("/households/:id")]
+        HouseholdPage { id: i64 },
+        #[route("/sign-in")]
+        SignInPage {},
 }
@@ -3,4 +3,4 @@ pub use functions::*;
 #[cfg(feature = "ssr")]
 mod state;
 #[cfg(feature = "ssr")]
-pub use state::AppState;
+pub use state::{AppState, CurrentUser, SessionChange, SESSION_COOKIE};
@@ -1,33 +1,353 @@
+use chrono::NaiveDate;
 use dioxus_fullstack::prelude::*;
 
 #[cfg(feature = "ssr")]
-use recipe_repository::Repository;
+use recipe_repository::{
+    BySlug, DieselRepository, DietRepository, HouseholdRepository, MealPlanRepository, Repository,
+    Search, TagRepository, UserRepository, WriteRepository,
+};
 use recipe_scrape::ScrapedRecipe;
-use recipe_shared::Recipe;
+use recipe_shared::{
+    Credentials, DietaryFlag, Household, HouseholdDetail, HouseholdInput, HouseholdRole,
+    Ingredient, IngredientDiet, MealPlan, MealPlanDetail, MealPlanInput, Recipe, RecipeDetail,
+    RecipeFilter, RecipeInput, RecipeSearch, ShoppingList, Tag, TagInput, User, UserInput,
+};
 
 #[cfg(feature = "ssr")]
-use super::AppState;
+use recipe_shared::ValidationErrors;
+
+#[cfg(feature = "ssr")]
+use crate::AppError;
+
+#[cfg(feature = "ssr")]
+use super::{AppState, CurrentUser, SessionChange};
+
+#[cfg(feature = "ssr")]
+fn state() -> Result<AppState, ServerFnError> {
+    tracing::debug!("loading state from server context");
+    AppState::extract().ok_or_else(|| AppError::internal("missing state").into())
+}
+
+/// The repository, acting on behalf of whoever made the request.
+#[cfg(feature = "ssr")]
+fn repo() -> Result<DieselRepository, ServerFnError> {
+    let user = CurrentUser::extract().0.map(|u| u.id.into());
+    Ok(state()?.repo.for_user(user))
+}
+
+/// Ask the router to set or clear the session cookie on the response.
+#[cfg(feature = "ssr")]
+fn change_session(change: SessionChange) -> Result<(), ServerFnError> {
+    server_context()
+        .insert(change)
+        .map_err(|_| AppError::internal("failed to update session").into())
+}
 
 #[server(Recipes)]
 pub async fn recipes() -> Result<Vec<Recipe>, ServerFnError> {
-    tracing::debug!("loading state from server context");
-    let state: AppState = server_context()
-        .get::<AppState>()
-        .ok_or_else(|| ServerFnError::ServerError("missing state".to_string()))?;
+    let repo = repo()?;
     tracing::debug!("loading recipes from DB");
-    Ok(state.repo.list().await?)
+    Ok(repo.list().await.map_err(AppError::from)?)
+}
+
+#[server(GetRecipe)]
+pub async fn recipe(slug: String) -> Result<Option<RecipeDetail>, ServerFnError> {
+    let repo = repo()?;
+    tracing::debug!(%slug, "loading recipe from DB");
+    Ok(repo.get_by_slug(&slug).await.map_err(AppError::from)?)
+}
+
+#[server(CreateRecipe)]
+pub async fn create_recipe(input: RecipeInput) -> Result<RecipeDetail, ServerFnError> {
+    let repo = repo()?;
+    Ok(repo.create(input).await.map_err(AppError::from)?)
+}
+
+#[server(UpdateRecipe)]
+pub async fn update_recipe(id: i64, input: RecipeInput) -> Result<RecipeDetail, ServerFnError> {
+    let repo = repo()?;
+    Ok(repo
+        .update(id.into(), input)
+        .await
+        .map_err(AppError::from)?)
+}
+
+#[server(SearchRecipes)]
+pub async fn search_recipes(filter: RecipeFilter) -> Result<RecipeSearch, ServerFnError> {
+    let repo = repo()?;
+    tracing::debug!(?filter, "searching recipes");
+    Ok(repo.search(filter).await.map_err(AppError::from)?)
+}
+
+#[server(TagRecipe)]
+pub async fn tag_recipe(recipe_id: i64, tag: TagInput) -> Result<Tag, ServerFnError> {
+    let repo = repo()?;
+    Ok(repo
+        .tag(recipe_id.into(), tag)
+        .await
+        .map_err(AppError::from)?)
+}
+
+#[server(UntagRecipe)]
+pub async fn untag_recipe(recipe_id: i64, tag_id: i64) -> Result<(), ServerFnError> {
+    let repo = repo()?;
+    Ok(repo
+        .untag(recipe_id.into(), tag_id.into())
+        .await
+        .map_err(AppError::from)?)
+}
+
+#[server(Ingredients)]
+pub async fn ingredients() -> Result<Vec<Ingredient>, ServerFnError> {
+    let repo = repo()?;
+    tracing::debug!("loading ingredients from DB");
+    Ok(repo.list().await.map_err(AppError::from)?)
+}
+
+#[server(IngredientDiets)]
+pub async fn ingredient_diets() -> Result<Vec<IngredientDiet>, ServerFnError> {
+    let repo = repo()?;
+    tracing::debug!("loading ingredient dietary flags from DB");
+    Ok(repo.list().await.map_err(AppError::from)?)
+}
+
+#[server(SetIngredientFlags)]
+pub async fn set_ingredient_flags(
+    ingredient_id: i64,
+    flags: Vec<DietaryFlag>,
+) -> Result<IngredientDiet, ServerFnError> {
+    let repo = repo()?;
+    Ok(repo
+        .set_ingredient_flags(ingredient_id.into(), flags.into_iter().collect())
+        .await
+        .map_err(AppError::from)?)
+}
+
+#[server(MealPlans)]
+pub async fn meal_plans() -> Result<Vec<MealPlan>, ServerFnError> {
+    let repo = repo()?;
+    tracing::debug!("loading meal plans from DB");
+    Ok(repo.list().await.map_err(AppError::from)?)
+}
+
+#[server(GetMealPlan)]
+pub async fn meal_plan(slug: String) -> Result<Option<MealPlanDetail>, ServerFnError> {
+    let repo = repo()?;
+    tracing::debug!(%slug, "loading meal plan from DB");
+    Ok(repo.get_by_slug(&slug).await.map_err(AppError::from)?)
+}
+
+#[server(CreateMealPlan)]
+pub async fn create_meal_plan(input: MealPlanInput) -> Result<MealPlan, ServerFnError> {
+    let repo = repo()?;
+    Ok(repo.create(input).await.map_err(AppError::from)?)
+}
+
+#[server(AddToMealPlan)]
+pub async fn add_to_meal_plan(
+    meal_plan_id: i64,
+    recipe_id: i64,
+    day: NaiveDate,
+) -> Result<(), ServerFnError> {
+    let repo = repo()?;
+    Ok(repo
+        .add_recipe(meal_plan_id.into(), recipe_id.into(), day)
+        .await
+        .map_err(AppError::from)?)
+}
+
+#[server(RemoveFromMealPlan)]
+pub async fn remove_from_meal_plan(entry_id: i64) -> Result<(), ServerFnError> {
+    let repo = repo()?;
+    Ok(repo
+        .remove_recipe(entry_id.into())
+        .await
+        .map_err(AppError::from)?)
+}
+
+/// Everything needed to cook a meal plan, or `None` if there's no such plan.
+#[server(GetShoppingList)]
+pub async fn shopping_list(slug: String) -> Result<Option<ShoppingList>, ServerFnError> {
+    let repo = repo()?;
+    tracing::debug!(%slug, "building shopping list");
+    let plan: Option<MealPlanDetail> = repo.get_by_slug(&slug).await.map_err(AppError::from)?;
+    let Some(plan) = plan else {
+        return Ok(None);
+    };
+    let mut recipes: Vec<RecipeDetail> = Vec::new();
+    for entry in plan.days.iter().flat_map(|day| &day.entries) {
+        if recipes.iter().any(|r| r.recipe.id == entry.recipe.id) {
+            continue;
+        }
+        let recipe: Option<RecipeDetail> = repo
+            .get_by_slug(&entry.recipe.slug)
+            .await
+            .map_err(AppError::from)?;
+        recipes.extend(recipe);
+    }
+    Ok(Some(ShoppingList::for_meal_plan(&plan, &recipes)))
+}
+
+#[server(Households)]
+pub async fn households() -> Result<Vec<Household>, ServerFnError> {
+    let repo = repo()?;
+    Ok(repo.list().await.map_err(AppError::from)?)
+}
+
+#[server(GetHousehold)]
+pub async fn household(id: i64) -> Result<Option<HouseholdDetail>, ServerFnError> {
+    let repo = repo()?;
+    Ok(repo.get(id.into()).await.map_err(AppError::from)?)
+}
+
+#[server(CreateHousehold)]
+pub async fn create_household(input: HouseholdInput) -> Result<Household, ServerFnError> {
+    let repo = repo()?;
+    Ok(repo.create(input).await.map_err(AppError::from)?)
+}
+
+#[server(SetHouseholdMember)]
+pub async fn set_household_member(
+    household_id: i64,
+    username: String,
+    role: HouseholdRole,
+) -> Result<(), ServerFnError> {
+    let repo = repo()?;
+    Ok(repo
+        .set_member(household_id.into(), &username, role)
+        .await
+        .map_err(AppError::from)?)
+}
+
+#[server(RemoveHouseholdMember)]
+pub async fn remove_household_member(household_id: i64, user_id: i64) -> Result<(), ServerFnError> {
+    let repo = repo()?;
+    Ok(repo
+        .remove_member(household_id.into(), user_id.into())
+        .await
+        .map_err(AppError::from)?)
+}
+
+#[server(ShareRecipe)]
+pub async fn share_recipe(
+    household_id: i64,
+    recipe_id: i64,
+    shared: bool,
+) -> Result<(), ServerFnError> {
+    let repo = repo()?;
+    Ok(repo
+        .share_recipe(household_id.into(), recipe_id.into(), shared)
+        .await
+        .map_err(AppError::from)?)
+}
+
+#[server(ShareMealPlan)]
+pub async fn share_meal_plan(
+    household_id: i64,
+    meal_plan_id: i64,
+    shared: bool,
+) -> Result<(), ServerFnError> {
+    let repo = repo()?;
+    Ok(repo
+        .share_meal_plan(household_id.into(), meal_plan_id.into(), shared)
+        .await
+        .map_err(AppError::from)?)
+}
+
+#[server(GetCurrentUser)]
+pub async fn current_user() -> Result<Option<User>, ServerFnError> {
+    Ok(CurrentUser::extract().0)
+}
+
+#[server(Register)]
+pub async fn register(input: UserInput) -> Result<User, ServerFnError> {
+    let repo = repo()?;
+    let user = repo.register(input).await.map_err(AppError::from)?;
+    let token = repo
+        .start_session(user.id.into())
+        .await
+        .map_err(AppError::from)?;
+    change_session(SessionChange::Start(token))?;
+    Ok(user)
+}
+
+#[server(SignIn)]
+pub async fn sign_in(credentials: Credentials) -> Result<User, ServerFnError> {
+    let repo = repo()?;
+    let Some(user) = repo
+        .authenticate(credentials)
+        .await
+        .map_err(AppError::from)?
+    else {
+        return Err(AppError::Unauthorized {
+            message: "incorrect username or password".to_string(),
+        }
+        .into());
+    };
+    let token = repo
+        .start_session(user.id.into())
+        .await
+        .map_err(AppError::from)?;
+    change_session(SessionChange::Start(token))?;
+    Ok(user)
+}
+
+#[server(SignOut)]
+pub async fn sign_out() -> Result<(), ServerFnError> {
+    change_session(SessionChange::End)
 }
 
 #[server(ScrapeRecipe)]
 pub async fn scrape_recipe(url: String) -> Result<ScrapedRecipe, ServerFnError> {
-    let scraper = recipe_scrape::RecipeScraper::new();
-    let url = url.parse()?;
-    let recipe = scraper.scrape(url).await?;
+    let invalid = |message: String| {
+        let mut errors = ValidationErrors::default();
+        errors.add("url", message);
+        AppError::from(errors)
+    };
+    let url: url::Url = url
+        .parse()
+        .map_err(|e| invalid(format!("isn't a valid URL: {e}")))?;
+    let host = recipe_scrape::RecipeScraper::web_page_host(&url)
+        .map_err(|e| invalid(e.to_string()))?
+        .to_string();
+    let recipe = state()?.scraper.scrape(url).await.map_err(|e| {
+        tracing::warn!(%host, error = %e, "failed to scrape recipe");
+        AppError::UpstreamScrapeFailed {
+            host,
+            message: e.to_string(),
+        }
+    })?;
     Ok(recipe)
 }
 
 #[cfg(feature = "ssr")]
 pub fn register_explicit() {
     let _ = Recipes::register_explicit();
+    let _ = GetRecipe::register_explicit();
+    let _ = CreateRecipe::register_explicit();
+    let _ = UpdateRecipe::register_explicit();
+    let _ = SearchRecipes::register_explicit();
+    let _ = TagRecipe::register_explicit();
+    let _ = UntagRecipe::register_explicit();
+    let _ = Ingredients::register_explicit();
+    let _ = IngredientDiets::register_explicit();
+    let _ = SetIngredientFlags::register_explicit();
+    let _ = MealPlans::register_explicit();
+    let _ = GetMealPlan::register_explicit();
+    let _ = CreateMealPlan::register_explicit();
+    let _ = AddToMealPlan::register_explicit();
+    let _ = RemoveFromMealPlan::register_explicit();
+    let _ = GetShoppingList::register_explicit();
+    let _ = Households::register_explicit();
+    let _ = GetHousehold::register_explicit();
+    let _ = CreateHousehold::register_explicit();
+    let _ = SetHouseholdMember::register_explicit();
+    let _ = RemoveHouseholdMember::register_explicit();
+    let _ = ShareRecipe::register_explicit();
+    let _ = ShareMealPlan::register_explicit();
+    let _ = GetCurrentUser::register_explicit();
+    let _ = Register::register_explicit();
+    let _ = SignIn::register_explicit();
+    let _ = SignOut::register_explicit();
     let _ = ScrapeRecipe::register_explicit();
 }
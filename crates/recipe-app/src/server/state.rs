@@ -1,12 +1,75 @@
+use std::sync::OnceLock;
+
+use dioxus_fullstack::prelude::*;
 use recipe_repository::DieselRepository;
+use recipe_scrape::RecipeScraper;
+use recipe_shared::User;
+
+/// The state and user for apps which call server functions in-process
+/// rather than through the router, so have nothing in the server context.
+static LOCAL: OnceLock<(AppState, User)> = OnceLock::new();
 
 #[derive(Debug, Clone)]
 pub struct AppState {
     pub repo: DieselRepository,
+    pub scraper: RecipeScraper,
 }
 
 impl AppState {
     pub fn new(repo: DieselRepository) -> Self {
-        Self { repo }
+        Self {
+            repo,
+            scraper: RecipeScraper::new(),
+        }
+    }
+
+    /// Scrape recipes with `scraper` rather than one with the default
+    /// options.
+    pub fn with_scraper(self, scraper: RecipeScraper) -> Self {
+        Self { scraper, ..self }
+    }
+
+    /// Use this state, acting as `user`, for server functions called outside
+    /// of a request, as the offline desktop app does. This can only be done
+    /// once.
+    pub fn install_local(self, user: User) {
+        if LOCAL.set((self, user)).is_err() {
+            tracing::warn!("local state was already installed");
+        }
+    }
+
+    /// The state from the server context, or the local state if there's
+    /// none.
+    pub fn extract() -> Option<Self> {
+        server_context()
+            .get::<AppState>()
+            .or_else(|| LOCAL.get().map(|(state, _)| state.clone()))
     }
 }
+
+/// The name of the cookie holding the session token.
+pub const SESSION_COOKIE: &str = "recipe_session";
+
+/// The signed in user, if any.
+///
+/// The router resolves this from the session cookie and stores it in the
+/// server context before running each server function.
+#[derive(Debug, Clone, Default)]
+pub struct CurrentUser(pub Option<User>);
+
+impl CurrentUser {
+    pub fn extract() -> Self {
+        server_context()
+            .get::<CurrentUser>()
+            .or_else(|| LOCAL.get().map(|(_, user)| CurrentUser(Some(user.clone()))))
+            .unwrap_or_default()
+    }
+}
+
+/// A change to the session cookie requested by a server function, which the
+/// router applies to the response.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SessionChange {
+    Start(String),
+    End,
+}
@@ -0,0 +1,18 @@
+DROP TABLE household_meal_plans;
+DROP TABLE household_recipes;
+DROP TABLE household_members;
+DROP TABLE households;
+DROP TABLE meal_plan_recipes;
+DROP TABLE meal_plans;
+DROP TABLE recipe_nutrition;
+DROP TABLE recipe_diets;
+DROP TABLE ingredient_flags;
+DROP TABLE recipe_tags;
+DROP TABLE tags;
+DROP TABLE recipe_ingredients;
+DROP TABLE ingredients;
+DROP TABLE measurements;
+DROP TABLE recipe_steps;
+DROP TABLE recipes;
+DROP TABLE sessions;
+DROP TABLE users;
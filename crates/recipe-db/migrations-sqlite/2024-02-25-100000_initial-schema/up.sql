@@ -0,0 +1,190 @@
+-- The schema of the Postgres migrations up to and including
+-- `2024-02-25-100000_slugs-in-rust`, for local databases on the desktop and
+-- mobile apps. Later changes are added to both sets of migrations.
+
+CREATE TABLE users (
+  id INTEGER PRIMARY KEY,
+  created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  username TEXT NOT NULL,
+  display_name TEXT NOT NULL,
+  password_hash TEXT NOT NULL
+);
+
+CREATE UNIQUE INDEX users_lower_username_uniq ON users (lower(username));
+
+CREATE TABLE sessions (
+  token TEXT PRIMARY KEY,
+  user_id BIGINT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+  created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  expires_at TIMESTAMP NOT NULL
+);
+
+CREATE INDEX sessions_user_id_idx ON sessions (user_id);
+
+CREATE TABLE recipes (
+  id INTEGER PRIMARY KEY,
+  created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  name TEXT NOT NULL,
+  slug TEXT NOT NULL UNIQUE,
+  source TEXT,
+  notes TEXT,
+  prep_time_minutes INTEGER,
+  cooking_time_minutes INTEGER,
+  image_url TEXT,
+  servings INTEGER,
+  owner_id BIGINT REFERENCES users(id) ON DELETE SET NULL,
+  shared BOOLEAN NOT NULL DEFAULT FALSE
+);
+
+CREATE INDEX recipes_owner_id_idx ON recipes (owner_id);
+
+CREATE TABLE recipe_steps (
+  id INTEGER PRIMARY KEY,
+  created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  recipe_id BIGINT NOT NULL REFERENCES recipes(id),
+  step_number INTEGER NOT NULL,
+  description TEXT NOT NULL
+);
+
+CREATE UNIQUE INDEX recipe_steps_recipe_id_step_number_uniq ON recipe_steps (recipe_id, step_number);
+
+CREATE TABLE measurements (
+  id INTEGER PRIMARY KEY,
+  created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  name TEXT NOT NULL,
+  slug TEXT NOT NULL UNIQUE,
+  abbreviation TEXT
+);
+
+CREATE UNIQUE INDEX measurements_lower_name_uniq ON measurements (lower(name));
+
+CREATE TABLE ingredients (
+  id INTEGER PRIMARY KEY,
+  created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  name TEXT NOT NULL,
+  slug TEXT NOT NULL UNIQUE,
+  default_measurement_id BIGINT NOT NULL REFERENCES measurements(id),
+  diet_reviewed BOOLEAN NOT NULL DEFAULT FALSE
+);
+
+CREATE UNIQUE INDEX ingredients_lower_name_uniq ON ingredients (lower(name));
+
+CREATE TABLE recipe_ingredients (
+  recipe_id BIGINT NOT NULL REFERENCES recipes(id),
+  ingredient_id BIGINT NOT NULL REFERENCES ingredients(id),
+  created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  quantity DOUBLE PRECISION,
+  measurement_id BIGINT REFERENCES measurements(id),
+  idx INTEGER NOT NULL,
+  notes TEXT,
+  PRIMARY KEY (recipe_id, ingredient_id)
+);
+
+CREATE TABLE tags (
+  id INTEGER PRIMARY KEY,
+  created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  name TEXT NOT NULL,
+  slug TEXT NOT NULL,
+  kind TEXT NOT NULL DEFAULT 'tag' CHECK (kind IN ('tag', 'category', 'cuisine')),
+  CONSTRAINT tags_kind_slug_unique UNIQUE (kind, slug)
+);
+
+CREATE TABLE recipe_tags (
+  recipe_id BIGINT NOT NULL REFERENCES recipes(id) ON DELETE CASCADE,
+  tag_id BIGINT NOT NULL REFERENCES tags(id) ON DELETE CASCADE,
+  created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  PRIMARY KEY (recipe_id, tag_id)
+);
+
+CREATE INDEX recipe_tags_tag_id_idx ON recipe_tags (tag_id);
+
+CREATE TABLE ingredient_flags (
+  ingredient_id BIGINT NOT NULL REFERENCES ingredients(id) ON DELETE CASCADE,
+  flag TEXT NOT NULL CHECK (flag IN (
+    'gluten', 'dairy', 'egg', 'nuts', 'peanuts', 'soy', 'sesame',
+    'fish', 'shellfish', 'meat', 'animal_product'
+  )),
+  created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  PRIMARY KEY (ingredient_id, flag)
+);
+
+CREATE INDEX ingredient_flags_flag_idx ON ingredient_flags (flag);
+
+CREATE TABLE recipe_diets (
+  recipe_id BIGINT NOT NULL REFERENCES recipes(id) ON DELETE CASCADE,
+  diet TEXT NOT NULL CHECK (diet IN (
+    'vegetarian', 'vegan', 'gluten_free', 'dairy_free', 'egg_free', 'nut_free'
+  )),
+  created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  PRIMARY KEY (recipe_id, diet)
+);
+
+CREATE TABLE recipe_nutrition (
+  recipe_id BIGINT PRIMARY KEY REFERENCES recipes(id) ON DELETE CASCADE,
+  created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  energy_kcal DOUBLE PRECISION,
+  fat_g DOUBLE PRECISION,
+  saturated_fat_g DOUBLE PRECISION,
+  carbohydrate_g DOUBLE PRECISION,
+  sugar_g DOUBLE PRECISION,
+  fibre_g DOUBLE PRECISION,
+  protein_g DOUBLE PRECISION,
+  salt_g DOUBLE PRECISION
+);
+
+CREATE TABLE meal_plans (
+  id INTEGER PRIMARY KEY,
+  name TEXT NOT NULL,
+  slug TEXT NOT NULL UNIQUE,
+  created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  start_date DATE NOT NULL,
+  end_date DATE NOT NULL,
+  owner_id BIGINT REFERENCES users(id) ON DELETE SET NULL,
+  shared BOOLEAN NOT NULL DEFAULT FALSE
+);
+
+CREATE INDEX meal_plans_owner_id_idx ON meal_plans (owner_id);
+
+CREATE TABLE meal_plan_recipes (
+  id INTEGER PRIMARY KEY,
+  created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  meal_plan_id BIGINT NOT NULL REFERENCES meal_plans(id),
+  recipe_id BIGINT NOT NULL REFERENCES recipes(id),
+  day DATE NOT NULL
+);
+
+CREATE INDEX meal_plan_recipes_plan_day_idx ON meal_plan_recipes (meal_plan_id, day);
+
+CREATE TABLE households (
+  id INTEGER PRIMARY KEY,
+  created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  name TEXT NOT NULL
+);
+
+CREATE TABLE household_members (
+  household_id BIGINT NOT NULL REFERENCES households(id) ON DELETE CASCADE,
+  user_id BIGINT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+  created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  role TEXT NOT NULL DEFAULT 'viewer' CHECK (role IN ('owner', 'editor', 'viewer')),
+  PRIMARY KEY (household_id, user_id)
+);
+
+CREATE INDEX household_members_user_id_idx ON household_members (user_id);
+
+CREATE TABLE household_recipes (
+  household_id BIGINT NOT NULL REFERENCES households(id) ON DELETE CASCADE,
+  recipe_id BIGINT NOT NULL REFERENCES recipes(id) ON DELETE CASCADE,
+  created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  PRIMARY KEY (household_id, recipe_id)
+);
+
+CREATE INDEX household_recipes_recipe_id_idx ON household_recipes (recipe_id);
+
+CREATE TABLE household_meal_plans (
+  household_id BIGINT NOT NULL REFERENCES households(id) ON DELETE CASCADE,
+  meal_plan_id BIGINT NOT NULL REFERENCES meal_plans(id) ON DELETE CASCADE,
+  created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  PRIMARY KEY (household_id, meal_plan_id)
+);
+
+CREATE INDEX household_meal_plans_meal_plan_id_idx ON household_meal_plans (meal_plan_id);
@@ -0,0 +1,14 @@
+DROP TABLE sync_cursors;
+DROP TABLE deletions;
+
+DROP INDEX meal_plans_owner_id_changed_at_idx;
+DROP INDEX meal_plans_sync_id_uniq;
+ALTER TABLE meal_plans DROP COLUMN changed_at;
+ALTER TABLE meal_plans DROP COLUMN updated_at;
+ALTER TABLE meal_plans DROP COLUMN sync_id;
+
+DROP INDEX recipes_owner_id_changed_at_idx;
+DROP INDEX recipes_sync_id_uniq;
+ALTER TABLE recipes DROP COLUMN changed_at;
+ALTER TABLE recipes DROP COLUMN updated_at;
+ALTER TABLE recipes DROP COLUMN sync_id;
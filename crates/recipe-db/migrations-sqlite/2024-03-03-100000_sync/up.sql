@@ -0,0 +1,47 @@
+-- See the Postgres migration of the same name. SQLite can only add columns
+-- with constant defaults, so they're filled in afterwards, and has no UUID
+-- function, so one is put together from random bytes.
+ALTER TABLE recipes ADD COLUMN sync_id TEXT NOT NULL DEFAULT '';
+ALTER TABLE recipes ADD COLUMN updated_at TIMESTAMP NOT NULL DEFAULT '1970-01-01 00:00:00';
+ALTER TABLE recipes ADD COLUMN changed_at TIMESTAMP NOT NULL DEFAULT '1970-01-01 00:00:00';
+UPDATE recipes SET
+  sync_id = lower(
+    hex(randomblob(4)) || '-' || hex(randomblob(2)) || '-4' || substr(hex(randomblob(2)), 2) || '-'
+    || substr('89ab', 1 + abs(random()) % 4, 1) || substr(hex(randomblob(2)), 2) || '-'
+    || hex(randomblob(6))
+  ),
+  updated_at = created_at,
+  changed_at = created_at;
+CREATE UNIQUE INDEX recipes_sync_id_uniq ON recipes (sync_id);
+CREATE INDEX recipes_owner_id_changed_at_idx ON recipes (owner_id, changed_at);
+
+ALTER TABLE meal_plans ADD COLUMN sync_id TEXT NOT NULL DEFAULT '';
+ALTER TABLE meal_plans ADD COLUMN updated_at TIMESTAMP NOT NULL DEFAULT '1970-01-01 00:00:00';
+ALTER TABLE meal_plans ADD COLUMN changed_at TIMESTAMP NOT NULL DEFAULT '1970-01-01 00:00:00';
+UPDATE meal_plans SET
+  sync_id = lower(
+    hex(randomblob(4)) || '-' || hex(randomblob(2)) || '-4' || substr(hex(randomblob(2)), 2) || '-'
+    || substr('89ab', 1 + abs(random()) % 4, 1) || substr(hex(randomblob(2)), 2) || '-'
+    || hex(randomblob(6))
+  ),
+  updated_at = created_at,
+  changed_at = created_at;
+CREATE UNIQUE INDEX meal_plans_sync_id_uniq ON meal_plans (sync_id);
+CREATE INDEX meal_plans_owner_id_changed_at_idx ON meal_plans (owner_id, changed_at);
+
+CREATE TABLE deletions (
+  kind TEXT NOT NULL CHECK (kind IN ('recipe', 'meal_plan')),
+  sync_id TEXT NOT NULL,
+  owner_id BIGINT REFERENCES users(id) ON DELETE CASCADE,
+  deleted_at TIMESTAMP NOT NULL,
+  changed_at TIMESTAMP NOT NULL,
+  PRIMARY KEY (kind, sync_id)
+);
+
+CREATE INDEX deletions_owner_id_changed_at_idx ON deletions (owner_id, changed_at);
+
+CREATE TABLE sync_cursors (
+  remote TEXT PRIMARY KEY,
+  pulled TIMESTAMP,
+  pushed TIMESTAMP
+);
@@ -0,0 +1,5 @@
+-- Sessions are found by a SHA-256 hash of their token, so that someone who
+-- can read the database can't use it to sign in. Existing sessions stored
+-- the token itself, so they're ended and their users sign in again.
+DELETE FROM sessions;
+ALTER TABLE sessions RENAME COLUMN token TO token_hash;
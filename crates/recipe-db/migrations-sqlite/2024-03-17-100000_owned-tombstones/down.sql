@@ -0,0 +1,17 @@
+CREATE TABLE deletions_old (
+  kind TEXT NOT NULL CHECK (kind IN ('recipe', 'meal_plan')),
+  sync_id TEXT NOT NULL,
+  owner_id BIGINT REFERENCES users(id) ON DELETE CASCADE,
+  deleted_at TIMESTAMP NOT NULL,
+  changed_at TIMESTAMP NOT NULL,
+  PRIMARY KEY (kind, sync_id)
+);
+
+-- Only the latest tombstone of each recipe or meal plan can be kept.
+INSERT OR REPLACE INTO deletions_old (kind, sync_id, owner_id, deleted_at, changed_at)
+  SELECT kind, sync_id, owner_id, deleted_at, changed_at FROM deletions
+  ORDER BY deleted_at;
+DROP TABLE deletions;
+ALTER TABLE deletions_old RENAME TO deletions;
+
+CREATE INDEX deletions_owner_id_changed_at_idx ON deletions (owner_id, changed_at);
@@ -0,0 +1,18 @@
+-- See the Postgres migration of the same name. SQLite can't change a
+-- table's primary key, so the table is copied into a new one.
+CREATE TABLE deletions_new (
+  kind TEXT NOT NULL CHECK (kind IN ('recipe', 'meal_plan')),
+  sync_id TEXT NOT NULL,
+  owner_id BIGINT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+  deleted_at TIMESTAMP NOT NULL,
+  changed_at TIMESTAMP NOT NULL,
+  PRIMARY KEY (owner_id, kind, sync_id)
+);
+
+INSERT INTO deletions_new (kind, sync_id, owner_id, deleted_at, changed_at)
+  SELECT kind, sync_id, owner_id, deleted_at, changed_at FROM deletions
+  WHERE owner_id IS NOT NULL;
+DROP TABLE deletions;
+ALTER TABLE deletions_new RENAME TO deletions;
+
+CREATE INDEX deletions_owner_id_changed_at_idx ON deletions (owner_id, changed_at);
@@ -0,0 +1,8 @@
+DROP INDEX recipe_steps_recipe_id_step_number_uniq;
+
+UPDATE recipe_ingredients SET quantity = 0 WHERE quantity IS NULL;
+ALTER TABLE recipe_ingredients
+ALTER COLUMN quantity SET NOT NULL;
+
+ALTER TABLE recipes
+DROP COLUMN servings;
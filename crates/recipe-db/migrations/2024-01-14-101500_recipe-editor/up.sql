@@ -0,0 +1,7 @@
+ALTER TABLE recipes
+ADD COLUMN servings INTEGER;
+
+ALTER TABLE recipe_ingredients
+ALTER COLUMN quantity DROP NOT NULL;
+
+CREATE UNIQUE INDEX recipe_steps_recipe_id_step_number_uniq ON recipe_steps (recipe_id, step_number);
@@ -0,0 +1,17 @@
+CREATE TABLE tags (
+  id BIGSERIAL PRIMARY KEY,
+  created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+  name TEXT NOT NULL,
+  slug TEXT NOT NULL GENERATED ALWAYS AS (slugify(name)) STORED,
+  kind TEXT NOT NULL DEFAULT 'tag' CHECK (kind IN ('tag', 'category', 'cuisine')),
+  CONSTRAINT tags_kind_slug_unique UNIQUE (kind, slug)
+);
+
+CREATE TABLE recipe_tags (
+  recipe_id BIGINT NOT NULL REFERENCES recipes(id) ON DELETE CASCADE,
+  tag_id BIGINT NOT NULL REFERENCES tags(id) ON DELETE CASCADE,
+  created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+  PRIMARY KEY (recipe_id, tag_id)
+);
+
+CREATE INDEX recipe_tags_tag_id_idx ON recipe_tags (tag_id);
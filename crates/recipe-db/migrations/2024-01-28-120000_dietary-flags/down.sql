@@ -0,0 +1,3 @@
+DROP TABLE recipe_diets;
+DROP TABLE ingredient_flags;
+ALTER TABLE ingredients DROP COLUMN diet_reviewed;
@@ -0,0 +1,22 @@
+ALTER TABLE ingredients ADD COLUMN diet_reviewed BOOLEAN NOT NULL DEFAULT FALSE;
+
+CREATE TABLE ingredient_flags (
+  ingredient_id BIGINT NOT NULL REFERENCES ingredients(id) ON DELETE CASCADE,
+  flag TEXT NOT NULL CHECK (flag IN (
+    'gluten', 'dairy', 'egg', 'nuts', 'peanuts', 'soy', 'sesame',
+    'fish', 'shellfish', 'meat', 'animal_product'
+  )),
+  created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+  PRIMARY KEY (ingredient_id, flag)
+);
+
+CREATE INDEX ingredient_flags_flag_idx ON ingredient_flags (flag);
+
+CREATE TABLE recipe_diets (
+  recipe_id BIGINT NOT NULL REFERENCES recipes(id) ON DELETE CASCADE,
+  diet TEXT NOT NULL CHECK (diet IN (
+    'vegetarian', 'vegan', 'gluten_free', 'dairy_free', 'egg_free', 'nut_free'
+  )),
+  created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+  PRIMARY KEY (recipe_id, diet)
+);
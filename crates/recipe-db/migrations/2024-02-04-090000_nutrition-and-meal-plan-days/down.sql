@@ -0,0 +1,9 @@
+DROP INDEX meal_plan_recipes_plan_day_idx;
+DELETE FROM meal_plan_recipes a
+  USING meal_plan_recipes b
+  WHERE a.id > b.id AND a.meal_plan_id = b.meal_plan_id AND a.recipe_id = b.recipe_id;
+ALTER TABLE meal_plan_recipes
+  ADD CONSTRAINT meal_plan_recipes_unique UNIQUE (meal_plan_id, recipe_id);
+ALTER TABLE meal_plan_recipes DROP COLUMN day;
+
+DROP TABLE recipe_nutrition;
@@ -0,0 +1,23 @@
+CREATE TABLE recipe_nutrition (
+  recipe_id BIGINT PRIMARY KEY REFERENCES recipes(id) ON DELETE CASCADE,
+  created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+  energy_kcal DOUBLE PRECISION,
+  fat_g DOUBLE PRECISION,
+  saturated_fat_g DOUBLE PRECISION,
+  carbohydrate_g DOUBLE PRECISION,
+  sugar_g DOUBLE PRECISION,
+  fibre_g DOUBLE PRECISION,
+  protein_g DOUBLE PRECISION,
+  salt_g DOUBLE PRECISION
+);
+
+-- Recipes in a meal plan are eaten on a particular day, and the same recipe
+-- may be eaten on several days.
+ALTER TABLE meal_plan_recipes ADD COLUMN day DATE;
+UPDATE meal_plan_recipes
+  SET day = meal_plans.start_date
+  FROM meal_plans
+  WHERE meal_plans.id = meal_plan_recipes.meal_plan_id;
+ALTER TABLE meal_plan_recipes ALTER COLUMN day SET NOT NULL;
+ALTER TABLE meal_plan_recipes DROP CONSTRAINT meal_plan_recipes_unique;
+CREATE INDEX meal_plan_recipes_plan_day_idx ON meal_plan_recipes (meal_plan_id, day);
@@ -0,0 +1,4 @@
+ALTER TABLE meal_plans DROP COLUMN owner_id, DROP COLUMN shared;
+ALTER TABLE recipes DROP COLUMN owner_id, DROP COLUMN shared;
+DROP TABLE sessions;
+DROP TABLE users;
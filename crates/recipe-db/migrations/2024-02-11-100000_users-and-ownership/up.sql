@@ -0,0 +1,32 @@
+CREATE TABLE users (
+  id BIGSERIAL PRIMARY KEY,
+  created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+  username TEXT NOT NULL,
+  display_name TEXT NOT NULL,
+  password_hash TEXT NOT NULL
+);
+
+CREATE UNIQUE INDEX users_lower_username_uniq ON users (lower(username));
+
+CREATE TABLE sessions (
+  token TEXT PRIMARY KEY,
+  user_id BIGINT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+  created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+  expires_at TIMESTAMP NOT NULL
+);
+
+CREATE INDEX sessions_user_id_idx ON sessions (user_id);
+
+-- Existing recipes and meal plans have no owner, and remain visible to and
+-- editable by everyone.
+ALTER TABLE recipes
+  ADD COLUMN owner_id BIGINT REFERENCES users(id) ON DELETE SET NULL,
+  ADD COLUMN shared BOOLEAN NOT NULL DEFAULT FALSE;
+
+CREATE INDEX recipes_owner_id_idx ON recipes (owner_id);
+
+ALTER TABLE meal_plans
+  ADD COLUMN owner_id BIGINT REFERENCES users(id) ON DELETE SET NULL,
+  ADD COLUMN shared BOOLEAN NOT NULL DEFAULT FALSE;
+
+CREATE INDEX meal_plans_owner_id_idx ON meal_plans (owner_id);
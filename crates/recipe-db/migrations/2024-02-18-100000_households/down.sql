@@ -0,0 +1,4 @@
+DROP TABLE household_meal_plans;
+DROP TABLE household_recipes;
+DROP TABLE household_members;
+DROP TABLE households;
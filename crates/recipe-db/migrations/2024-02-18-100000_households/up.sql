@@ -0,0 +1,35 @@
+CREATE TABLE households (
+  id BIGSERIAL PRIMARY KEY,
+  created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+  name TEXT NOT NULL
+);
+
+CREATE TABLE household_members (
+  household_id BIGINT NOT NULL REFERENCES households(id) ON DELETE CASCADE,
+  user_id BIGINT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+  created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+  role TEXT NOT NULL DEFAULT 'viewer' CHECK (role IN ('owner', 'editor', 'viewer')),
+  PRIMARY KEY (household_id, user_id)
+);
+
+CREATE INDEX household_members_user_id_idx ON household_members (user_id);
+
+-- Recipes and meal plans shared into a household can be seen by all of its
+-- members, and changed by its owners and editors.
+CREATE TABLE household_recipes (
+  household_id BIGINT NOT NULL REFERENCES households(id) ON DELETE CASCADE,
+  recipe_id BIGINT NOT NULL REFERENCES recipes(id) ON DELETE CASCADE,
+  created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+  PRIMARY KEY (household_id, recipe_id)
+);
+
+CREATE INDEX household_recipes_recipe_id_idx ON household_recipes (recipe_id);
+
+CREATE TABLE household_meal_plans (
+  household_id BIGINT NOT NULL REFERENCES households(id) ON DELETE CASCADE,
+  meal_plan_id BIGINT NOT NULL REFERENCES meal_plans(id) ON DELETE CASCADE,
+  created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+  PRIMARY KEY (household_id, meal_plan_id)
+);
+
+CREATE INDEX household_meal_plans_meal_plan_id_idx ON household_meal_plans (meal_plan_id);
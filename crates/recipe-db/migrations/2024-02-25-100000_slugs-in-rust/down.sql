@@ -0,0 +1,17 @@
+-- Generated columns can't be added back to existing columns, so the slugs are
+-- dropped and generated again.
+ALTER TABLE recipes DROP COLUMN slug;
+ALTER TABLE recipes ADD COLUMN slug TEXT NOT NULL GENERATED ALWAYS AS (slugify(name)) STORED UNIQUE;
+
+ALTER TABLE measurements DROP COLUMN slug;
+ALTER TABLE measurements ADD COLUMN slug TEXT NOT NULL GENERATED ALWAYS AS (slugify(name)) STORED UNIQUE;
+
+ALTER TABLE ingredients DROP COLUMN slug;
+ALTER TABLE ingredients ADD COLUMN slug TEXT NOT NULL GENERATED ALWAYS AS (slugify(name)) STORED UNIQUE;
+
+ALTER TABLE meal_plans DROP COLUMN slug;
+ALTER TABLE meal_plans ADD COLUMN slug TEXT NOT NULL GENERATED ALWAYS AS (slugify(name)) STORED UNIQUE;
+
+ALTER TABLE tags DROP COLUMN slug;
+ALTER TABLE tags ADD COLUMN slug TEXT NOT NULL GENERATED ALWAYS AS (slugify(name)) STORED;
+ALTER TABLE tags ADD CONSTRAINT tags_kind_slug_unique UNIQUE (kind, slug);
@@ -0,0 +1,8 @@
+-- Slugs are now generated by the app (see `recipe_db::slug`) so that SQLite,
+-- which has no equivalent of `slugify` and `unaccent`, can share the same
+-- rules. Existing slugs are kept as they are.
+ALTER TABLE recipes ALTER COLUMN slug DROP EXPRESSION;
+ALTER TABLE measurements ALTER COLUMN slug DROP EXPRESSION;
+ALTER TABLE ingredients ALTER COLUMN slug DROP EXPRESSION;
+ALTER TABLE meal_plans ALTER COLUMN slug DROP EXPRESSION;
+ALTER TABLE tags ALTER COLUMN slug DROP EXPRESSION;
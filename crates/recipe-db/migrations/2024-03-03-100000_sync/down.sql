@@ -0,0 +1,10 @@
+DROP TABLE sync_cursors;
+DROP TABLE deletions;
+
+ALTER TABLE meal_plans DROP COLUMN changed_at;
+ALTER TABLE meal_plans DROP COLUMN updated_at;
+ALTER TABLE meal_plans DROP COLUMN sync_id;
+
+ALTER TABLE recipes DROP COLUMN changed_at;
+ALTER TABLE recipes DROP COLUMN updated_at;
+ALTER TABLE recipes DROP COLUMN sync_id;
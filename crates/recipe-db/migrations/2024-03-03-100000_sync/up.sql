@@ -0,0 +1,42 @@
+-- Change tracking for offline sync. Recipes and meal plans are synced as
+-- whole documents, identified across devices by `sync_id`. `updated_at` is
+-- when the document was last edited, wherever that happened, and decides
+-- which copy wins a conflict; `changed_at` is when this database last wrote
+-- it, and is what sync cursors are compared against.
+ALTER TABLE recipes ADD COLUMN sync_id TEXT;
+UPDATE recipes SET sync_id = gen_random_uuid()::text;
+ALTER TABLE recipes ALTER COLUMN sync_id SET NOT NULL;
+ALTER TABLE recipes ADD CONSTRAINT recipes_sync_id_unique UNIQUE (sync_id);
+ALTER TABLE recipes ADD COLUMN updated_at TIMESTAMP NOT NULL DEFAULT now();
+ALTER TABLE recipes ADD COLUMN changed_at TIMESTAMP NOT NULL DEFAULT now();
+UPDATE recipes SET updated_at = created_at, changed_at = created_at;
+CREATE INDEX recipes_owner_id_changed_at_idx ON recipes (owner_id, changed_at);
+
+ALTER TABLE meal_plans ADD COLUMN sync_id TEXT;
+UPDATE meal_plans SET sync_id = gen_random_uuid()::text;
+ALTER TABLE meal_plans ALTER COLUMN sync_id SET NOT NULL;
+ALTER TABLE meal_plans ADD CONSTRAINT meal_plans_sync_id_unique UNIQUE (sync_id);
+ALTER TABLE meal_plans ADD COLUMN updated_at TIMESTAMP NOT NULL DEFAULT now();
+ALTER TABLE meal_plans ADD COLUMN changed_at TIMESTAMP NOT NULL DEFAULT now();
+UPDATE meal_plans SET updated_at = created_at, changed_at = created_at;
+CREATE INDEX meal_plans_owner_id_changed_at_idx ON meal_plans (owner_id, changed_at);
+
+-- Tombstones for deleted recipes and meal plans, so deletions reach other
+-- devices too.
+CREATE TABLE deletions (
+  kind TEXT NOT NULL CHECK (kind IN ('recipe', 'meal_plan')),
+  sync_id TEXT NOT NULL,
+  owner_id BIGINT REFERENCES users(id) ON DELETE CASCADE,
+  deleted_at TIMESTAMP NOT NULL,
+  changed_at TIMESTAMP NOT NULL,
+  PRIMARY KEY (kind, sync_id)
+);
+
+CREATE INDEX deletions_owner_id_changed_at_idx ON deletions (owner_id, changed_at);
+
+-- How far this database has synced with each server it syncs with.
+CREATE TABLE sync_cursors (
+  remote TEXT PRIMARY KEY,
+  pulled TIMESTAMP,
+  pushed TIMESTAMP
+);
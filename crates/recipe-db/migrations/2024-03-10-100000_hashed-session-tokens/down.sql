@@ -0,0 +1,2 @@
+DELETE FROM sessions;
+ALTER TABLE sessions RENAME COLUMN token_hash TO token;
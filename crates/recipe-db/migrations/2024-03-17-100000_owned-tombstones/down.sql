@@ -0,0 +1,7 @@
+-- Only the latest tombstone of each recipe or meal plan can be kept.
+DELETE FROM deletions a USING deletions b
+  WHERE a.kind = b.kind AND a.sync_id = b.sync_id
+  AND (a.deleted_at, a.owner_id) < (b.deleted_at, b.owner_id);
+ALTER TABLE deletions DROP CONSTRAINT deletions_pkey;
+ALTER TABLE deletions ALTER COLUMN owner_id DROP NOT NULL;
+ALTER TABLE deletions ADD PRIMARY KEY (kind, sync_id);
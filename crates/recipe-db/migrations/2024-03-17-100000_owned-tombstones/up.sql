@@ -0,0 +1,7 @@
+-- Tombstones belong to the owner of what was deleted, so that one user's
+-- deletions can't hide or remove another's recipes and meal plans with the
+-- same sync ID. Tombstones without an owner were never synced anyway.
+DELETE FROM deletions WHERE owner_id IS NULL;
+ALTER TABLE deletions DROP CONSTRAINT deletions_pkey;
+ALTER TABLE deletions ALTER COLUMN owner_id SET NOT NULL;
+ALTER TABLE deletions ADD PRIMARY KEY (owner_id, kind, sync_id);
@@ -0,0 +1,1256 @@
+//! Backing up the whole database to a portable archive, and restoring it into
+//! another database.
+//!
+//! An archive is a zip holding one JSON file per table, along with a manifest
+//! recording the format version and how many rows each table had. Rows keep
+//! their IDs in the archive but are given new ones when restored, so a backup
+//! can be restored into a database whose sequences have moved on, or which
+//! already holds data. Measurements, ingredients, tags and users which
+//! already exist are matched by slug or username and reused. Sessions and
+//! sync state aren't backed up, though recipes and meal plans keep their sync
+//! IDs.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    io::{Read, Seek, Write},
+};
+
+use anyhow::{anyhow, bail, Context, Result};
+use chrono::prelude::*;
+use diesel::prelude::*;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::{dsl::lower, models::Quantity, schema::*, SyncConnection};
+
+/// The version of the archive format written by [`Backup::write_archive`].
+///
+/// Tables added later are simply missing from older archives, so this only
+/// changes when the rows of an existing table change incompatibly.
+pub const FORMAT_VERSION: u32 = 1;
+
+const MANIFEST: &str = "manifest.json";
+
+/// Describes an archive, and is read before any of its tables.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Manifest {
+    pub version: u32,
+    pub created_at: NaiveDateTime,
+    /// The number of rows in each table.
+    pub tables: BTreeMap<String, usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Queryable, Selectable)]
+#[diesel(table_name = users)]
+pub struct UserRow {
+    pub id: i64,
+    pub created_at: NaiveDateTime,
+    pub username: String,
+    pub display_name: String,
+    pub password_hash: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Queryable, Selectable)]
+#[diesel(table_name = measurements)]
+pub struct MeasurementRow {
+    pub id: i64,
+    pub created_at: NaiveDateTime,
+    pub name: String,
+    /// Generated from the name, and used to match existing measurements.
+    pub slug: String,
+    pub abbreviation: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Queryable, Selectable)]
+#[diesel(table_name = ingredients)]
+pub struct IngredientRow {
+    pub id: i64,
+    pub created_at: NaiveDateTime,
+    pub name: String,
+    /// Generated from the name, and used to match existing ingredients.
+    pub slug: String,
+    pub default_measurement_id: i64,
+    pub diet_reviewed: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Queryable, Selectable)]
+#[diesel(table_name = ingredient_flags)]
+pub struct IngredientFlagRow {
+    pub ingredient_id: i64,
+    pub flag: String,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Queryable, Selectable)]
+#[diesel(table_name = tags)]
+pub struct TagRow {
+    pub id: i64,
+    pub created_at: NaiveDateTime,
+    pub name: String,
+    /// Generated from the name, and used with the kind to match existing
+    /// tags.
+    pub slug: String,
+    pub kind: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Queryable, Selectable)]
+#[diesel(table_name = households)]
+pub struct HouseholdRow {
+    pub id: i64,
+    pub created_at: NaiveDateTime,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Queryable, Selectable)]
+#[diesel(table_name = household_members)]
+pub struct HouseholdMemberRow {
+    pub household_id: i64,
+    pub user_id: i64,
+    pub created_at: NaiveDateTime,
+    pub role: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Queryable, Selectable)]
+#[diesel(table_name = recipes)]
+pub struct RecipeRow {
+    pub id: i64,
+    pub created_at: NaiveDateTime,
+    pub name: String,
+    pub slug: String,
+    pub source: Option<String>,
+    pub notes: Option<String>,
+    pub prep_time_minutes: Option<i32>,
+    pub cooking_time_minutes: Option<i32>,
+    pub image_url: Option<String>,
+    pub servings: Option<i32>,
+    pub owner_id: Option<i64>,
+    pub shared: bool,
+    /// Missing from archives written before recipes were synced, in which
+    /// case a new one is made up when restoring.
+    #[serde(default)]
+    pub sync_id: String,
+    /// Also missing from older archives, in which case the creation time is
+    /// used instead.
+    #[serde(default)]
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Queryable, Selectable)]
+#[diesel(table_name = recipe_ingredients)]
+pub struct RecipeIngredientRow {
+    pub recipe_id: i64,
+    pub ingredient_id: i64,
+    pub created_at: NaiveDateTime,
+    /// Written as a number, whichever backend the backup was taken from.
+    #[serde(with = "quantity")]
+    pub quantity: Option<Quantity>,
+    pub measurement_id: Option<i64>,
+    pub idx: i32,
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Queryable, Selectable)]
+#[diesel(table_name = recipe_steps)]
+pub struct RecipeStepRow {
+    pub id: i64,
+    pub created_at: NaiveDateTime,
+    pub recipe_id: i64,
+    pub step_number: i32,
+    pub description: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Queryable, Selectable)]
+#[diesel(table_name = recipe_tags)]
+pub struct RecipeTagRow {
+    pub recipe_id: i64,
+    pub tag_id: i64,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Queryable, Selectable)]
+#[diesel(table_name = recipe_diets)]
+pub struct RecipeDietRow {
+    pub recipe_id: i64,
+    pub diet: String,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Queryable, Selectable)]
+#[diesel(table_name = recipe_nutrition)]
+pub struct RecipeNutritionRow {
+    pub recipe_id: i64,
+    pub created_at: NaiveDateTime,
+    pub energy_kcal: Option<f64>,
+    pub fat_g: Option<f64>,
+    pub saturated_fat_g: Option<f64>,
+    pub carbohydrate_g: Option<f64>,
+    pub sugar_g: Option<f64>,
+    pub fibre_g: Option<f64>,
+    pub protein_g: Option<f64>,
+    pub salt_g: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Queryable, Selectable)]
+#[diesel(table_name = meal_plans)]
+pub struct MealPlanRow {
+    pub id: i64,
+    pub name: String,
+    pub slug: String,
+    pub created_at: NaiveDateTime,
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    pub owner_id: Option<i64>,
+    pub shared: bool,
+    #[serde(default)]
+    pub sync_id: String,
+    #[serde(default)]
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Queryable, Selectable)]
+#[diesel(table_name = meal_plan_recipes)]
+pub struct MealPlanRecipeRow {
+    pub id: i64,
+    pub created_at: NaiveDateTime,
+    pub meal_plan_id: i64,
+    pub recipe_id: i64,
+    pub day: NaiveDate,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Queryable, Selectable)]
+#[diesel(table_name = household_recipes)]
+pub struct HouseholdRecipeRow {
+    pub household_id: i64,
+    pub recipe_id: i64,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Queryable, Selectable)]
+#[diesel(table_name = household_meal_plans)]
+pub struct HouseholdMealPlanRow {
+    pub household_id: i64,
+    pub meal_plan_id: i64,
+    pub created_at: NaiveDateTime,
+}
+
+/// Every row of every table worth keeping.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Backup {
+    pub users: Vec<UserRow>,
+    pub measurements: Vec<MeasurementRow>,
+    pub ingredients: Vec<IngredientRow>,
+    pub ingredient_flags: Vec<IngredientFlagRow>,
+    pub tags: Vec<TagRow>,
+    pub households: Vec<HouseholdRow>,
+    pub household_members: Vec<HouseholdMemberRow>,
+    pub recipes: Vec<RecipeRow>,
+    pub recipe_ingredients: Vec<RecipeIngredientRow>,
+    pub recipe_steps: Vec<RecipeStepRow>,
+    pub recipe_tags: Vec<RecipeTagRow>,
+    pub recipe_diets: Vec<RecipeDietRow>,
+    pub recipe_nutrition: Vec<RecipeNutritionRow>,
+    pub meal_plans: Vec<MealPlanRow>,
+    pub meal_plan_recipes: Vec<MealPlanRecipeRow>,
+    pub household_recipes: Vec<HouseholdRecipeRow>,
+    pub household_meal_plans: Vec<HouseholdMealPlanRow>,
+}
+
+/// Generates `Backup::counts`, `Backup::write_tables` and
+/// `Backup::read_tables` so that every table is handled the same way.
+macro_rules! tables {
+    ($($table:ident),* $(,)?) => {
+        impl Backup {
+            /// The number of rows in each table.
+            pub fn counts(&self) -> BTreeMap<String, usize> {
+                BTreeMap::from([$((stringify!($table).to_string(), self.$table.len())),*])
+            }
+
+            fn write_tables<W: Write + Seek>(&self, zip: &mut zip::ZipWriter<W>) -> Result<()> {
+                $(write_entry(zip, stringify!($table), &self.$table)?;)*
+                Ok(())
+            }
+
+            fn read_tables<R: Read + Seek>(archive: &mut zip::ZipArchive<R>) -> Result<Self> {
+                Ok(Self {
+                    $($table: read_entry(archive, stringify!($table))?,)*
+                })
+            }
+        }
+    };
+}
+
+tables!(
+    users,
+    measurements,
+    ingredients,
+    ingredient_flags,
+    tags,
+    households,
+    household_members,
+    recipes,
+    recipe_ingredients,
+    recipe_steps,
+    recipe_tags,
+    recipe_diets,
+    recipe_nutrition,
+    meal_plans,
+    meal_plan_recipes,
+    household_recipes,
+    household_meal_plans,
+);
+
+fn write_entry<W: Write + Seek, T: Serialize>(
+    zip: &mut zip::ZipWriter<W>,
+    table: &str,
+    rows: &[T],
+) -> Result<()> {
+    let options = zip::write::FileOptions::default();
+    zip.start_file(format!("{table}.json"), options)?;
+    serde_json::to_writer(zip, rows).with_context(|| format!("writing {table}"))?;
+    Ok(())
+}
+
+/// Read a table from an archive, which is empty if the archive predates it.
+fn read_entry<R: Read + Seek, T: DeserializeOwned>(
+    archive: &mut zip::ZipArchive<R>,
+    table: &str,
+) -> Result<Vec<T>> {
+    let file = match archive.by_name(&format!("{table}.json")) {
+        Ok(file) => file,
+        Err(zip::result::ZipError::FileNotFound) => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+    serde_json::from_reader(file).with_context(|| format!("reading {table}"))
+}
+
+impl Backup {
+    /// Read every table.
+    pub fn dump(conn: &mut SyncConnection) -> QueryResult<Self> {
+        Ok(Self {
+            users: users::table
+                .order(users::id)
+                .select(UserRow::as_select())
+                .load(conn)?,
+            measurements: measurements::table
+                .order(measurements::id)
+                .select(MeasurementRow::as_select())
+                .load(conn)?,
+            ingredients: ingredients::table
+                .order(ingredients::id)
+                .select(IngredientRow::as_select())
+                .load(conn)?,
+            ingredient_flags: ingredient_flags::table
+                .order((ingredient_flags::ingredient_id, ingredient_flags::flag))
+                .select(IngredientFlagRow::as_select())
+                .load(conn)?,
+            tags: tags::table
+                .order(tags::id)
+                .select(TagRow::as_select())
+                .load(conn)?,
+            households: households::table
+                .order(households::id)
+                .select(HouseholdRow::as_select())
+                .load(conn)?,
+            household_members: household_members::table
+                .order((household_members::household_id, household_members::user_id))
+                .select(HouseholdMemberRow::as_select())
+                .load(conn)?,
+            recipes: recipes::table
+                .order(recipes::id)
+                .select(RecipeRow::as_select())
+                .load(conn)?,
+            recipe_ingredients: recipe_ingredients::table
+                .order((recipe_ingredients::recipe_id, recipe_ingredients::idx))
+                .select(RecipeIngredientRow::as_select())
+                .load(conn)?,
+            recipe_steps: recipe_steps::table
+                .order((recipe_steps::recipe_id, recipe_steps::step_number))
+                .select(RecipeStepRow::as_select())
+                .load(conn)?,
+            recipe_tags: recipe_tags::table
+                .order((recipe_tags::recipe_id, recipe_tags::tag_id))
+                .select(RecipeTagRow::as_select())
+                .load(conn)?,
+            recipe_diets: recipe_diets::table
+                .order((recipe_diets::recipe_id, recipe_diets::diet))
+                .select(RecipeDietRow::as_select())
+                .load(conn)?,
+            recipe_nutrition: recipe_nutrition::table
+                .order(recipe_nutrition::recipe_id)
+                .select(RecipeNutritionRow::as_select())
+                .load(conn)?,
+            meal_plans: meal_plans::table
+                .order(meal_plans::id)
+                .select(MealPlanRow::as_select())
+                .load(conn)?,
+            meal_plan_recipes: meal_plan_recipes::table
+                .order(meal_plan_recipes::id)
+                .select(MealPlanRecipeRow::as_select())
+                .load(conn)?,
+            household_recipes: household_recipes::table
+                .order((
+                    household_recipes::household_id,
+                    household_recipes::recipe_id,
+                ))
+                .select(HouseholdRecipeRow::as_select())
+                .load(conn)?,
+            household_meal_plans: household_meal_plans::table
+                .order((
+                    household_meal_plans::household_id,
+                    household_meal_plans::meal_plan_id,
+                ))
+                .select(HouseholdMealPlanRow::as_select())
+                .load(conn)?,
+        })
+    }
+
+    /// Write the backup as a zip archive.
+    pub fn write_archive<W: Write + Seek>(&self, writer: W) -> Result<()> {
+        let mut zip = zip::ZipWriter::new(writer);
+        let manifest = Manifest {
+            version: FORMAT_VERSION,
+            created_at: Utc::now().naive_utc(),
+            tables: self.counts(),
+        };
+        zip.start_file(MANIFEST, zip::write::FileOptions::default())?;
+        serde_json::to_writer_pretty(&mut zip, &manifest)?;
+        self.write_tables(&mut zip)?;
+        zip.finish()?;
+        Ok(())
+    }
+
+    /// Read a backup from a zip archive, checking that it was written by a
+    /// version of this format which can be read.
+    pub fn read_archive<R: Read + Seek>(reader: R) -> Result<(Manifest, Self)> {
+        let mut archive = zip::ZipArchive::new(reader).context("opening archive")?;
+        let manifest: Manifest = serde_json::from_reader(
+            archive
+                .by_name(MANIFEST)
+                .context("archive has no manifest")?,
+        )
+        .context("reading manifest")?;
+        if manifest.version > FORMAT_VERSION {
+            bail!(
+                "archive is version {}, but only versions up to {FORMAT_VERSION} can be restored",
+                manifest.version
+            );
+        }
+        let backup = Self::read_tables(&mut archive)?;
+        Ok((manifest, backup))
+    }
+
+    /// Restore the backup into a database, in a single transaction.
+    ///
+    /// Fails without changing anything if a recipe or meal plan with the same
+    /// slug as one in the backup already exists.
+    pub fn restore(&self, conn: &mut SyncConnection) -> Result<Restored> {
+        conn.transaction(|conn| Restorer::default().restore(self, conn))
+    }
+}
+
+/// How many rows of each table were restored.
+#[derive(Debug, Clone, Default)]
+pub struct Restored {
+    /// Rows added to each table.
+    pub inserted: BTreeMap<&'static str, usize>,
+    /// Rows which matched existing ones, and were reused instead.
+    pub matched: BTreeMap<&'static str, usize>,
+}
+
+/// The new IDs of rows restored so far, keyed by their IDs in the archive.
+#[derive(Default)]
+struct Restorer {
+    users: HashMap<i64, i64>,
+    measurements: HashMap<i64, i64>,
+    ingredients: HashMap<i64, i64>,
+    tags: HashMap<i64, i64>,
+    households: HashMap<i64, i64>,
+    recipes: HashMap<i64, i64>,
+    meal_plans: HashMap<i64, i64>,
+    restored: Restored,
+}
+
+/// The sync ID of a restored row, made up if the archive predates sync.
+fn sync_id(archived: &str) -> String {
+    match archived {
+        "" => crate::new_sync_id(),
+        id => id.to_string(),
+    }
+}
+
+/// Look up the new ID for a row referenced from another table.
+fn remap(ids: &HashMap<i64, i64>, id: i64, table: &str) -> Result<i64> {
+    ids.get(&id)
+        .copied()
+        .ok_or_else(|| anyhow!("archive refers to {table} {id}, which isn't in it"))
+}
+
+impl Restorer {
+    fn inserted(&mut self, table: &'static str, rows: usize) {
+        *self.restored.inserted.entry(table).or_default() += rows;
+    }
+
+    fn matched(&mut self, table: &'static str) {
+        *self.restored.matched.entry(table).or_default() += 1;
+    }
+
+    fn restore(mut self, backup: &Backup, conn: &mut SyncConnection) -> Result<Restored> {
+        let slugs: Vec<_> = backup.recipes.iter().map(|r| r.slug.as_str()).collect();
+        let existing: Vec<String> = recipes::table
+            .filter(recipes::slug.eq_any(&slugs))
+            .select(recipes::slug)
+            .load(conn)?;
+        if !existing.is_empty() {
+            bail!("recipes already exist: {}", existing.join(", "));
+        }
+        let slugs: Vec<_> = backup.meal_plans.iter().map(|p| p.slug.as_str()).collect();
+        let existing: Vec<String> = meal_plans::table
+            .filter(meal_plans::slug.eq_any(&slugs))
+            .select(meal_plans::slug)
+            .load(conn)?;
+        if !existing.is_empty() {
+            bail!("meal plans already exist: {}", existing.join(", "));
+        }
+
+        self.restore_users(backup, conn)?;
+        self.restore_ingredients(backup, conn)?;
+        self.restore_tags(backup, conn)?;
+        self.restore_households(backup, conn)?;
+        self.restore_recipes(backup, conn)?;
+        self.restore_meal_plans(backup, conn)?;
+        Ok(self.restored)
+    }
+
+    fn restore_users(&mut self, backup: &Backup, conn: &mut SyncConnection) -> Result<()> {
+        for user in &backup.users {
+            let existing = users::table
+                .filter(lower(users::username).eq(user.username.to_lowercase()))
+                .select(users::id)
+                .first::<i64>(conn)
+                .optional()?;
+            let id = match existing {
+                Some(id) => {
+                    self.matched("users");
+                    id
+                }
+                None => {
+                    self.inserted("users", 1);
+                    diesel::insert_into(users::table)
+                        .values((
+                            users::created_at.eq(user.created_at),
+                            users::username.eq(&user.username),
+                            users::display_name.eq(&user.display_name),
+                            users::password_hash.eq(&user.password_hash),
+                        ))
+                        .returning(users::id)
+                        .get_result(conn)?
+                }
+            };
+            self.users.insert(user.id, id);
+        }
+        Ok(())
+    }
+
+    fn restore_ingredients(&mut self, backup: &Backup, conn: &mut SyncConnection) -> Result<()> {
+        for measurement in &backup.measurements {
+            let existing = measurements::table
+                .filter(measurements::slug.eq(&measurement.slug))
+                .select(measurements::id)
+                .first::<i64>(conn)
+                .optional()?;
+            let id = match existing {
+                Some(id) => {
+                    self.matched("measurements");
+                    id
+                }
+                None => {
+                    self.inserted("measurements", 1);
+                    diesel::insert_into(measurements::table)
+                        .values((
+                            measurements::created_at.eq(measurement.created_at),
+                            measurements::name.eq(&measurement.name),
+                            measurements::slug.eq(&measurement.slug),
+                            measurements::abbreviation.eq(&measurement.abbreviation),
+                        ))
+                        .returning(measurements::id)
+                        .get_result(conn)?
+                }
+            };
+            self.measurements.insert(measurement.id, id);
+        }
+
+        for ingredient in &backup.ingredients {
+            let existing = ingredients::table
+                .filter(ingredients::slug.eq(&ingredient.slug))
+                .select(ingredients::id)
+                .first::<i64>(conn)
+                .optional()?;
+            let id = match existing {
+                Some(id) => {
+                    self.matched("ingredients");
+                    id
+                }
+                None => {
+                    let measurement = remap(
+                        &self.measurements,
+                        ingredient.default_measurement_id,
+                        "measurement",
+                    )?;
+                    self.inserted("ingredients", 1);
+                    diesel::insert_into(ingredients::table)
+                        .values((
+                            ingredients::created_at.eq(ingredient.created_at),
+                            ingredients::name.eq(&ingredient.name),
+                            ingredients::slug.eq(&ingredient.slug),
+                            ingredients::default_measurement_id.eq(measurement),
+                            ingredients::diet_reviewed.eq(ingredient.diet_reviewed),
+                        ))
+                        .returning(ingredients::id)
+                        .get_result(conn)?
+                }
+            };
+            self.ingredients.insert(ingredient.id, id);
+        }
+
+        for flag in &backup.ingredient_flags {
+            let ingredient = remap(&self.ingredients, flag.ingredient_id, "ingredient")?;
+            let rows = diesel::insert_into(ingredient_flags::table)
+                .values((
+                    ingredient_flags::ingredient_id.eq(ingredient),
+                    ingredient_flags::flag.eq(&flag.flag),
+                    ingredient_flags::created_at.eq(flag.created_at),
+                ))
+                .on_conflict_do_nothing()
+                .execute(conn)?;
+            self.inserted("ingredient_flags", rows);
+        }
+        Ok(())
+    }
+
+    fn restore_tags(&mut self, backup: &Backup, conn: &mut SyncConnection) -> Result<()> {
+        for tag in &backup.tags {
+            let existing = tags::table
+                .filter(tags::kind.eq(&tag.kind).and(tags::slug.eq(&tag.slug)))
+                .select(tags::id)
+                .first::<i64>(conn)
+                .optional()?;
+            let id = match existing {
+                Some(id) => {
+                    self.matched("tags");
+                    id
+                }
+                None => {
+                    self.inserted("tags", 1);
+                    diesel::insert_into(tags::table)
+                        .values((
+                            tags::created_at.eq(tag.created_at),
+                            tags::name.eq(&tag.name),
+                            tags::slug.eq(&tag.slug),
+                            tags::kind.eq(&tag.kind),
+                        ))
+                        .returning(tags::id)
+                        .get_result(conn)?
+                }
+            };
+            self.tags.insert(tag.id, id);
+        }
+        Ok(())
+    }
+
+    fn restore_households(&mut self, backup: &Backup, conn: &mut SyncConnection) -> Result<()> {
+        for household in &backup.households {
+            let id = diesel::insert_into(households::table)
+                .values((
+                    households::created_at.eq(household.created_at),
+                    households::name.eq(&household.name),
+                ))
+                .returning(households::id)
+                .get_result(conn)?;
+            self.households.insert(household.id, id);
+            self.inserted("households", 1);
+        }
+
+        for member in &backup.household_members {
+            let household = remap(&self.households, member.household_id, "household")?;
+            let user = remap(&self.users, member.user_id, "user")?;
+            let rows = diesel::insert_into(household_members::table)
+                .values((
+                    household_members::household_id.eq(household),
+                    household_members::user_id.eq(user),
+                    household_members::created_at.eq(member.created_at),
+                    household_members::role.eq(&member.role),
+                ))
+                .on_conflict_do_nothing()
+                .execute(conn)?;
+            self.inserted("household_members", rows);
+        }
+        Ok(())
+    }
+
+    fn restore_recipes(&mut self, backup: &Backup, conn: &mut SyncConnection) -> Result<()> {
+        let now = Utc::now().naive_utc();
+        for recipe in &backup.recipes {
+            let owner = recipe
+                .owner_id
+                .map(|id| remap(&self.users, id, "user"))
+                .transpose()?;
+            let id = diesel::insert_into(recipes::table)
+                .values((
+                    recipes::created_at.eq(recipe.created_at),
+                    recipes::name.eq(&recipe.name),
+                    recipes::slug.eq(&recipe.slug),
+                    recipes::source.eq(&recipe.source),
+                    recipes::notes.eq(&recipe.notes),
+                    recipes::prep_time_minutes.eq(recipe.prep_time_minutes),
+                    recipes::cooking_time_minutes.eq(recipe.cooking_time_minutes),
+                    recipes::image_url.eq(&recipe.image_url),
+                    recipes::servings.eq(recipe.servings),
+                    recipes::owner_id.eq(owner),
+                    recipes::shared.eq(recipe.shared),
+                    recipes::sync_id.eq(sync_id(&recipe.sync_id)),
+                    recipes::updated_at.eq(recipe.updated_at.max(recipe.created_at)),
+                    recipes::changed_at.eq(now),
+                ))
+                .returning(recipes::id)
+                .get_result(conn)?;
+            self.recipes.insert(recipe.id, id);
+            self.inserted("recipes", 1);
+        }
+
+        for ingredient in &backup.recipe_ingredients {
+            let measurement = ingredient
+                .measurement_id
+                .map(|id| remap(&self.measurements, id, "measurement"))
+                .transpose()?;
+            diesel::insert_into(recipe_ingredients::table)
+                .values((
+                    recipe_ingredients::recipe_id.eq(remap(
+                        &self.recipes,
+                        ingredient.recipe_id,
+                        "recipe",
+                    )?),
+                    recipe_ingredients::ingredient_id.eq(remap(
+                        &self.ingredients,
+                        ingredient.ingredient_id,
+                        "ingredient",
+                    )?),
+                    recipe_ingredients::created_at.eq(ingredient.created_at),
+                    recipe_ingredients::quantity.eq(&ingredient.quantity),
+                    recipe_ingredients::measurement_id.eq(measurement),
+                    recipe_ingredients::idx.eq(ingredient.idx),
+                    recipe_ingredients::notes.eq(&ingredient.notes),
+                ))
+                .execute(conn)?;
+            self.inserted("recipe_ingredients", 1);
+        }
+
+        for step in &backup.recipe_steps {
+            diesel::insert_into(recipe_steps::table)
+                .values((
+                    recipe_steps::created_at.eq(step.created_at),
+                    recipe_steps::recipe_id.eq(remap(&self.recipes, step.recipe_id, "recipe")?),
+                    recipe_steps::step_number.eq(step.step_number),
+                    recipe_steps::description.eq(&step.description),
+                ))
+                .execute(conn)?;
+            self.inserted("recipe_steps", 1);
+        }
+
+        for tag in &backup.recipe_tags {
+            let rows = diesel::insert_into(recipe_tags::table)
+                .values((
+                    recipe_tags::recipe_id.eq(remap(&self.recipes, tag.recipe_id, "recipe")?),
+                    recipe_tags::tag_id.eq(remap(&self.tags, tag.tag_id, "tag")?),
+                    recipe_tags::created_at.eq(tag.created_at),
+                ))
+                .on_conflict_do_nothing()
+                .execute(conn)?;
+            self.inserted("recipe_tags", rows);
+        }
+
+        for diet in &backup.recipe_diets {
+            diesel::insert_into(recipe_diets::table)
+                .values((
+                    recipe_diets::recipe_id.eq(remap(&self.recipes, diet.recipe_id, "recipe")?),
+                    recipe_diets::diet.eq(&diet.diet),
+                    recipe_diets::created_at.eq(diet.created_at),
+                ))
+                .execute(conn)?;
+            self.inserted("recipe_diets", 1);
+        }
+
+        for nutrition in &backup.recipe_nutrition {
+            diesel::insert_into(recipe_nutrition::table)
+                .values((
+                    recipe_nutrition::recipe_id.eq(remap(
+                        &self.recipes,
+                        nutrition.recipe_id,
+                        "recipe",
+                    )?),
+                    recipe_nutrition::created_at.eq(nutrition.created_at),
+                    recipe_nutrition::energy_kcal.eq(nutrition.energy_kcal),
+                    recipe_nutrition::fat_g.eq(nutrition.fat_g),
+                    recipe_nutrition::saturated_fat_g.eq(nutrition.saturated_fat_g),
+                    recipe_nutrition::carbohydrate_g.eq(nutrition.carbohydrate_g),
+                    recipe_nutrition::sugar_g.eq(nutrition.sugar_g),
+                    recipe_nutrition::fibre_g.eq(nutrition.fibre_g),
+                    recipe_nutrition::protein_g.eq(nutrition.protein_g),
+                    recipe_nutrition::salt_g.eq(nutrition.salt_g),
+                ))
+                .execute(conn)?;
+            self.inserted("recipe_nutrition", 1);
+        }
+
+        for shared in &backup.household_recipes {
+            diesel::insert_into(household_recipes::table)
+                .values((
+                    household_recipes::household_id.eq(remap(
+                        &self.households,
+                        shared.household_id,
+                        "household",
+                    )?),
+                    household_recipes::recipe_id.eq(remap(
+                        &self.recipes,
+                        shared.recipe_id,
+                        "recipe",
+                    )?),
+                    household_recipes::created_at.eq(shared.created_at),
+                ))
+                .execute(conn)?;
+            self.inserted("household_recipes", 1);
+        }
+        Ok(())
+    }
+
+    fn restore_meal_plans(&mut self, backup: &Backup, conn: &mut SyncConnection) -> Result<()> {
+        let now = Utc::now().naive_utc();
+        for plan in &backup.meal_plans {
+            let owner = plan
+                .owner_id
+                .map(|id| remap(&self.users, id, "user"))
+                .transpose()?;
+            let id = diesel::insert_into(meal_plans::table)
+                .values((
+                    meal_plans::name.eq(&plan.name),
+                    meal_plans::slug.eq(&plan.slug),
+                    meal_plans::created_at.eq(plan.created_at),
+                    meal_plans::start_date.eq(plan.start_date),
+                    meal_plans::end_date.eq(plan.end_date),
+                    meal_plans::owner_id.eq(owner),
+                    meal_plans::shared.eq(plan.shared),
+                    meal_plans::sync_id.eq(sync_id(&plan.sync_id)),
+                    meal_plans::updated_at.eq(plan.updated_at.max(plan.created_at)),
+                    meal_plans::changed_at.eq(now),
+                ))
+                .returning(meal_plans::id)
+                .get_result(conn)?;
+            self.meal_plans.insert(plan.id, id);
+            self.inserted("meal_plans", 1);
+        }
+
+        for entry in &backup.meal_plan_recipes {
+            diesel::insert_into(meal_plan_recipes::table)
+                .values((
+                    meal_plan_recipes::created_at.eq(entry.created_at),
+                    meal_plan_recipes::meal_plan_id.eq(remap(
+                        &self.meal_plans,
+                        entry.meal_plan_id,
+                        "meal plan",
+                    )?),
+                    meal_plan_recipes::recipe_id.eq(remap(
+                        &self.recipes,
+                        entry.recipe_id,
+                        "recipe",
+                    )?),
+                    meal_plan_recipes::day.eq(entry.day),
+                ))
+                .execute(conn)?;
+            self.inserted("meal_plan_recipes", 1);
+        }
+
+        for shared in &backup.household_meal_plans {
+            diesel::insert_into(household_meal_plans::table)
+                .values((
+                    household_meal_plans::household_id.eq(remap(
+                        &self.households,
+                        shared.household_id,
+                        "household",
+                    )?),
+                    household_meal_plans::meal_plan_id.eq(remap(
+                        &self.meal_plans,
+                        shared.meal_plan_id,
+                        "meal plan",
+                    )?),
+                    household_meal_plans::created_at.eq(shared.created_at),
+                ))
+                .execute(conn)?;
+            self.inserted("household_meal_plans", 1);
+        }
+        Ok(())
+    }
+}
+
+/// Quantities are exact decimals in Postgres but floats in SQLite, so they're
+/// archived as plain numbers to restore into either. Archives written before
+/// SQLite was supported hold them as strings.
+mod quantity {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use crate::models::{quantity_from_f64, quantity_to_f64, Quantity};
+
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Number {
+        Float(f64),
+        String(String),
+    }
+
+    pub fn serialize<S: Serializer>(
+        quantity: &Option<Quantity>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        quantity
+            .as_ref()
+            .and_then(quantity_to_f64)
+            .serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Quantity>, D::Error> {
+        let number = match Option::<Number>::deserialize(deserializer)? {
+            Some(Number::Float(n)) => n,
+            Some(Number::String(s)) => s.parse().map_err(serde::de::Error::custom)?,
+            None => return Ok(None),
+        };
+        quantity_from_f64(number)
+            .map(Some)
+            .ok_or_else(|| serde::de::Error::custom(format!("invalid quantity {number}")))
+    }
+}
+
+#[cfg(all(test, feature = "sqlite"))]
+mod tests {
+    use std::io::Cursor;
+
+    use diesel_migrations::MigrationHarness;
+
+    use super::*;
+    use crate::{
+        models::{quantity_from_f64, quantity_to_f64},
+        slug::slugify,
+    };
+
+    /// An ingredient of a recipe, with its quantity and unit.
+    type Line = (String, Option<f64>, String);
+
+    /// A new in-memory database, with the schema set up.
+    fn database() -> SyncConnection {
+        let mut conn = crate::connect(":memory:").unwrap();
+        conn.run_pending_migrations(crate::MIGRATIONS).unwrap();
+        conn
+    }
+
+    fn day(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 3, day).unwrap()
+    }
+
+    fn insert_user(conn: &mut SyncConnection, username: &str) -> i64 {
+        diesel::insert_into(users::table)
+            .values((
+                users::username.eq(username),
+                users::display_name.eq(username),
+                users::password_hash.eq("!"),
+            ))
+            .returning(users::id)
+            .get_result(conn)
+            .unwrap()
+    }
+
+    /// The ID of the measurement called `name`, added if it doesn't exist.
+    fn measurement(conn: &mut SyncConnection, name: &str) -> i64 {
+        let existing: Option<i64> = measurements::table
+            .filter(measurements::slug.eq(slugify(name)))
+            .select(measurements::id)
+            .first(conn)
+            .optional()
+            .unwrap();
+        existing.unwrap_or_else(|| {
+            diesel::insert_into(measurements::table)
+                .values((
+                    measurements::name.eq(name),
+                    measurements::slug.eq(slugify(name)),
+                ))
+                .returning(measurements::id)
+                .get_result(conn)
+                .unwrap()
+        })
+    }
+
+    /// The ID of the ingredient called `name`, added if it doesn't exist.
+    fn ingredient(conn: &mut SyncConnection, name: &str, measurement: i64) -> i64 {
+        let existing: Option<i64> = ingredients::table
+            .filter(ingredients::slug.eq(slugify(name)))
+            .select(ingredients::id)
+            .first(conn)
+            .optional()
+            .unwrap();
+        existing.unwrap_or_else(|| {
+            diesel::insert_into(ingredients::table)
+                .values((
+                    ingredients::name.eq(name),
+                    ingredients::slug.eq(slugify(name)),
+                    ingredients::default_measurement_id.eq(measurement),
+                ))
+                .returning(ingredients::id)
+                .get_result(conn)
+                .unwrap()
+        })
+    }
+
+    fn insert_recipe(
+        conn: &mut SyncConnection,
+        owner: i64,
+        name: &str,
+        lines: &[(&str, f64, &str)],
+        steps: &[&str],
+    ) -> i64 {
+        let id = diesel::insert_into(recipes::table)
+            .values((
+                recipes::name.eq(name),
+                recipes::slug.eq(slugify(name)),
+                recipes::owner_id.eq(owner),
+                recipes::sync_id.eq(crate::new_sync_id()),
+            ))
+            .returning(recipes::id)
+            .get_result(conn)
+            .unwrap();
+        for (idx, &(name, quantity, unit)) in lines.iter().enumerate() {
+            let measurement = measurement(conn, unit);
+            let ingredient = ingredient(conn, name, measurement);
+            diesel::insert_into(recipe_ingredients::table)
+                .values((
+                    recipe_ingredients::recipe_id.eq(id),
+                    recipe_ingredients::ingredient_id.eq(ingredient),
+                    recipe_ingredients::quantity.eq(quantity_from_f64(quantity)),
+                    recipe_ingredients::measurement_id.eq(measurement),
+                    recipe_ingredients::idx.eq(idx as i32),
+                ))
+                .execute(conn)
+                .unwrap();
+        }
+        for (number, &step) in steps.iter().enumerate() {
+            diesel::insert_into(recipe_steps::table)
+                .values((
+                    recipe_steps::recipe_id.eq(id),
+                    recipe_steps::step_number.eq(number as i32 + 1),
+                    recipe_steps::description.eq(step),
+                ))
+                .execute(conn)
+                .unwrap();
+        }
+        id
+    }
+
+    fn insert_plan(conn: &mut SyncConnection, owner: i64, name: &str, entries: &[(i64, u32)]) {
+        let id: i64 = diesel::insert_into(meal_plans::table)
+            .values((
+                meal_plans::name.eq(name),
+                meal_plans::slug.eq(slugify(name)),
+                meal_plans::start_date.eq(day(1)),
+                meal_plans::end_date.eq(day(14)),
+                meal_plans::owner_id.eq(owner),
+                meal_plans::sync_id.eq(crate::new_sync_id()),
+            ))
+            .returning(meal_plans::id)
+            .get_result(conn)
+            .unwrap();
+        for &(recipe, on) in entries {
+            diesel::insert_into(meal_plan_recipes::table)
+                .values((
+                    meal_plan_recipes::meal_plan_id.eq(id),
+                    meal_plan_recipes::recipe_id.eq(recipe),
+                    meal_plan_recipes::day.eq(day(on)),
+                ))
+                .execute(conn)
+                .unwrap();
+        }
+    }
+
+    /// A recipe's ingredients and steps, found by following the foreign keys
+    /// of its rows.
+    fn recipe_contents(conn: &mut SyncConnection, slug: &str) -> (Vec<Line>, Vec<String>) {
+        let id: i64 = recipes::table
+            .filter(recipes::slug.eq(slug))
+            .select(recipes::id)
+            .first(conn)
+            .unwrap();
+        let rows: Vec<(i64, Option<Quantity>, Option<i64>)> = recipe_ingredients::table
+            .filter(recipe_ingredients::recipe_id.eq(id))
+            .order(recipe_ingredients::idx)
+            .select((
+                recipe_ingredients::ingredient_id,
+                recipe_ingredients::quantity,
+                recipe_ingredients::measurement_id,
+            ))
+            .load(conn)
+            .unwrap();
+        let lines: Vec<Line> = rows
+            .into_iter()
+            .map(|(ingredient, quantity, measurement)| {
+                let name: String = ingredients::table
+                    .find(ingredient)
+                    .select(ingredients::name)
+                    .first(conn)
+                    .unwrap();
+                let unit: String = measurements::table
+                    .find(measurement.unwrap())
+                    .select(measurements::name)
+                    .first(conn)
+                    .unwrap();
+                (name, quantity.as_ref().and_then(quantity_to_f64), unit)
+            })
+            .collect();
+        let steps: Vec<String> = recipe_steps::table
+            .filter(recipe_steps::recipe_id.eq(id))
+            .order(recipe_steps::step_number)
+            .select(recipe_steps::description)
+            .load(conn)
+            .unwrap();
+        (lines, steps)
+    }
+
+    /// The slugs of the recipes in a meal plan, by day.
+    fn plan_contents(conn: &mut SyncConnection, slug: &str) -> Vec<(NaiveDate, String)> {
+        let id: i64 = meal_plans::table
+            .filter(meal_plans::slug.eq(slug))
+            .select(meal_plans::id)
+            .first(conn)
+            .unwrap();
+        let entries: Vec<(NaiveDate, i64)> = meal_plan_recipes::table
+            .filter(meal_plan_recipes::meal_plan_id.eq(id))
+            .order((meal_plan_recipes::day, meal_plan_recipes::id))
+            .select((meal_plan_recipes::day, meal_plan_recipes::recipe_id))
+            .load(conn)
+            .unwrap();
+        entries
+            .into_iter()
+            .map(|(on, recipe)| {
+                let slug: String = recipes::table
+                    .find(recipe)
+                    .select(recipes::slug)
+                    .first(conn)
+                    .unwrap();
+                (on, slug)
+            })
+            .collect()
+    }
+
+    fn line(name: &str, quantity: f64, unit: &str) -> Line {
+        (name.to_string(), Some(quantity), unit.to_string())
+    }
+
+    fn strings(strs: &[&str]) -> Vec<String> {
+        strs.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn restored_rows_keep_their_relationships() {
+        let mut source = database();
+        let alice = insert_user(&mut source, "alice");
+        let bread = insert_recipe(
+            &mut source,
+            alice,
+            "Bread",
+            &[
+                ("Flour", 500.0, "gram"),
+                ("Water", 350.0, "millilitre"),
+                ("Salt", 10.0, "gram"),
+            ],
+            &["Mix.", "Knead.", "Bake."],
+        );
+        let cake = insert_recipe(
+            &mut source,
+            alice,
+            "Cake",
+            &[("Flour", 200.0, "gram"), ("Sugar", 150.0, "gram")],
+            &["Cream.", "Bake."],
+        );
+        insert_plan(
+            &mut source,
+            alice,
+            "Baking week",
+            &[(bread, 4), (cake, 6), (bread, 8)],
+        );
+        let mut archive = Cursor::new(Vec::new());
+        Backup::dump(&mut source)
+            .unwrap()
+            .write_archive(&mut archive)
+            .unwrap();
+
+        // Every table already has rows, so the restored ones get different
+        // IDs from those in the archive, and sugar is matched rather than
+        // added.
+        let mut target = database();
+        let bob = insert_user(&mut target, "bob");
+        let toast = insert_recipe(
+            &mut target,
+            bob,
+            "Toast",
+            &[("Sugar", 1.0, "teaspoon"), ("Butter", 10.0, "gram")],
+            &["Toast.", "Spread."],
+        );
+        insert_plan(&mut target, bob, "Breakfasts", &[(toast, 4)]);
+
+        archive.set_position(0);
+        let (_, backup) = Backup::read_archive(archive).unwrap();
+        let restored = backup.restore(&mut target).unwrap();
+        assert_eq!(restored.inserted["recipes"], 2);
+        assert_eq!(restored.inserted["ingredients"], 3);
+        assert_eq!(restored.matched["ingredients"], 1);
+        assert_eq!(restored.matched["measurements"], 1);
+        let restored_bread: i64 = recipes::table
+            .filter(recipes::slug.eq("bread"))
+            .select(recipes::id)
+            .first(&mut target)
+            .unwrap();
+        assert_ne!(restored_bread, bread);
+
+        assert_eq!(
+            recipe_contents(&mut target, "bread"),
+            (
+                vec![
+                    line("Flour", 500.0, "gram"),
+                    line("Water", 350.0, "millilitre"),
+                    line("Salt", 10.0, "gram"),
+                ],
+                strings(&["Mix.", "Knead.", "Bake."]),
+            )
+        );
+        assert_eq!(
+            recipe_contents(&mut target, "cake"),
+            (
+                vec![line("Flour", 200.0, "gram"), line("Sugar", 150.0, "gram")],
+                strings(&["Cream.", "Bake."]),
+            )
+        );
+        assert_eq!(
+            recipe_contents(&mut target, "toast"),
+            (
+                vec![line("Sugar", 1.0, "teaspoon"), line("Butter", 10.0, "gram")],
+                strings(&["Toast.", "Spread."]),
+            )
+        );
+        assert_eq!(
+            plan_contents(&mut target, "baking-week"),
+            [
+                (day(4), "bread".to_string()),
+                (day(6), "cake".to_string()),
+                (day(8), "bread".to_string()),
+            ]
+        );
+        assert_eq!(
+            plan_contents(&mut target, "breakfasts"),
+            [(day(4), "toast".to_string())]
+        );
+    }
+}
@@ -0,0 +1,52 @@
+use std::{
+    env::args,
+    fs::File,
+    io::{BufReader, BufWriter},
+};
+
+use anyhow::{anyhow, Context, Result};
+
+use recipe_db::{backup::Backup, establish_connection};
+
+/// Back up the database at `DATABASE_URL` to an archive, or restore one into
+/// it, e.g. `recipe-backup dump recipes.zip` against one database and
+/// `recipe-backup restore recipes.zip` against another.
+fn main() -> Result<()> {
+    let mut args = args().skip(1);
+    let usage = || anyhow!("usage: recipe-backup <dump|restore> <archive.zip>");
+    let command = args.next().ok_or_else(usage)?;
+    let path = args.next().ok_or_else(usage)?;
+    let connection = &mut establish_connection();
+
+    match command.as_str() {
+        "dump" => {
+            let backup = Backup::dump(connection).context("reading database")?;
+            let file = File::create(&path).with_context(|| format!("creating {path}"))?;
+            backup.write_archive(BufWriter::new(file))?;
+            for (table, rows) in backup.counts() {
+                eprintln!("{table}: {rows}");
+            }
+            eprintln!("Backed up to {path}");
+        }
+        "restore" => {
+            let file = File::open(&path).with_context(|| format!("opening {path}"))?;
+            let (manifest, backup) = Backup::read_archive(BufReader::new(file))?;
+            eprintln!(
+                "Restoring a version {} backup from {}",
+                manifest.version, manifest.created_at
+            );
+            let restored = backup.restore(connection)?;
+            for (table, rows) in backup.counts() {
+                let inserted = restored.inserted.get(table.as_str()).unwrap_or(&0);
+                match restored.matched.get(table.as_str()) {
+                    Some(matched) => {
+                        eprintln!("{table}: {inserted} of {rows} added, {matched} already existed")
+                    }
+                    None => eprintln!("{table}: {inserted} of {rows} added"),
+                }
+            }
+        }
+        _ => return Err(usage()),
+    }
+    Ok(())
+}
@@ -1,10 +1,10 @@
 use anyhow::{Context, Result};
 use diesel::prelude::*;
 
-use recipe_db::*;
+use recipe_db::{slug::slugify, *};
 
 pub fn create_measurement(
-    connection: &mut PgConnection,
+    connection: &mut SyncConnection,
     measurement: models::NewMeasurement<'_>,
 ) -> Result<models::Measurement> {
     use schema::measurements;
@@ -18,7 +18,7 @@ pub fn create_measurement(
 }
 
 pub fn create_ingredient(
-    connection: &mut PgConnection,
+    connection: &mut SyncConnection,
     ingredient: models::NewIngredient<'_>,
 ) -> Result<models::Ingredient> {
     use schema::ingredients;
@@ -32,13 +32,13 @@ pub fn create_ingredient(
 }
 
 pub fn create_recipe(
-    connection: &mut PgConnection,
+    connection: &mut SyncConnection,
     recipe: models::NewRecipe<'_>,
     ingredients: Vec<models::NewRecipeIngredient<'_>>,
 ) -> Result<(models::Recipe, Vec<models::RecipeIngredient>)> {
     connection.transaction(|conn| {
         let recipe: models::Recipe = diesel::insert_into(schema::recipes::table)
-            .values(&recipe)
+            .values((&recipe, schema::recipes::sync_id.eq(new_sync_id())))
             .get_result(conn)
             .context("creating recipe")?;
 
@@ -60,11 +60,13 @@ fn main() -> Result<()> {
     use self::schema::recipes::dsl::*;
 
     let connection = &mut establish_connection();
+    let now = chrono::Utc::now().naive_utc();
 
     let grams = create_measurement(
         connection,
         models::NewMeasurement {
             name: "grams",
+            slug: slugify("grams"),
             abbreviation: Some("g"),
         },
     )?;
@@ -72,6 +74,7 @@ fn main() -> Result<()> {
         connection,
         models::NewMeasurement {
             name: "tablespoons",
+            slug: slugify("tablespoons"),
             abbreviation: Some("tbsp"),
         },
     )?;
@@ -79,6 +82,7 @@ fn main() -> Result<()> {
         connection,
         models::NewMeasurement {
             name: "count",
+            slug: slugify("count"),
             abbreviation: None,
         },
     )?;
@@ -87,6 +91,7 @@ fn main() -> Result<()> {
         connection,
         models::NewIngredient {
             name: "Onion",
+            slug: slugify("Onion"),
             default_measurement_id: count.id,
         },
     )?;
@@ -94,6 +99,7 @@ fn main() -> Result<()> {
         connection,
         models::NewIngredient {
             name: "Tomato Puree",
+            slug: slugify("Tomato Puree"),
             default_measurement_id: tablespoon.id,
         },
     )?;
@@ -101,6 +107,7 @@ fn main() -> Result<()> {
         connection,
         models::NewIngredient {
             name: "Garlic clove",
+            slug: slugify("Garlic clove"),
             default_measurement_id: count.id,
         },
     )?;
@@ -108,6 +115,7 @@ fn main() -> Result<()> {
         connection,
         models::NewIngredient {
             name: "Spaghetti",
+            slug: slugify("Spaghetti"),
             default_measurement_id: grams.id,
         },
     )?;
@@ -116,18 +124,23 @@ fn main() -> Result<()> {
         connection,
         models::NewRecipe {
             name: "Spaghetti with Tomato Sauce",
+            slug: slugify("Spaghetti with Tomato Sauce"),
             source: Some("Anna"),
             notes: Some("Delicious!"),
             prep_time_minutes: Some(15),
             cooking_time_minutes: Some(15),
             image_url: Some("https://images.immediate.co.uk/production/volatile/sites/30/2020/08/recipe-image-legacy-id-281737_11-ba026f3.jpg"),
+            servings: Some(2),
+            shared: true,
+            updated_at: now,
+            changed_at: now,
         },
         vec![
             models::NewRecipeIngredient {
                 ingredient_id: onion.id,
                 recipe_id: 0.into(),
                 measurement_id: None,
-                quantity: 1.into(),
+                quantity: Some(1.into()),
                 index: 0,
                 notes: None,
             },
@@ -135,7 +148,7 @@ fn main() -> Result<()> {
                 ingredient_id: garlic_clove.id,
                 recipe_id: 0.into(),
                 measurement_id: None,
-                quantity: 2.into(),
+                quantity: Some(2.into()),
                 index: 0,
                 notes: None,
             },
@@ -143,7 +156,7 @@ fn main() -> Result<()> {
                 ingredient_id: tomato_puree.id,
                 recipe_id: 0.into(),
                 measurement_id: None,
-                quantity: 1.into(),
+                quantity: Some(1.into()),
                 index: 0,
                 notes: None,
             },
@@ -151,7 +164,7 @@ fn main() -> Result<()> {
                 ingredient_id: spaghetti.id,
                 recipe_id: 0.into(),
                 measurement_id: None,
-                quantity: 500.into(),
+                quantity: Some(500.into()),
                 index: 1,
                 notes: None,
             },
@@ -177,12 +190,13 @@ fn main() -> Result<()> {
             .map(|recipe_ingredient| {
                 let ingredient = recipe_ingredient.ingredient(connection)?;
                 let measurement = recipe_ingredient.measurement(connection, &ingredient)?;
+                let quantity = recipe_ingredient.quantity.unwrap_or_default();
                 println!(
                     "- {}{} {}{}",
-                    recipe_ingredient.quantity,
+                    quantity,
                     measurement.abbreviation.as_deref().unwrap_or(""),
                     ingredient.name,
-                    if measurement.name == "count" && recipe_ingredient.quantity > 1.into() {
+                    if measurement.name == "count" && quantity > 1.into() {
                         "s"
                     } else {
                         ""
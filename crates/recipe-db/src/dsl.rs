@@ -1,9 +1,20 @@
-use diesel::{sql_function, sql_types::*};
+use diesel::{define_sql_function, sql_types::*};
 
-sql_function! {
-    /// Represents the Pg `LOWER` function used with text.
+define_sql_function! {
+    /// Represents the SQL `LOWER` function used with text.
     fn lower(x: Text) -> Text;
 }
 
 /// The return type of `lower(expr)`
-pub type Lower<Expr> = lower::HelperType<Expr>;
+pub type Lower<Expr> = lower<Expr>;
+
+define_sql_function! {
+    /// Represents the SQL `LOWER` function used with nullable text.
+    #[sql_name = "LOWER"]
+    fn lower_nullable(x: Nullable<Text>) -> Nullable<Text>;
+}
+
+define_sql_function! {
+    /// Represents the SQL `COALESCE` function used with nullable integers.
+    fn coalesce(x: Nullable<Integer>, y: Nullable<Integer>) -> Nullable<Integer>;
+}
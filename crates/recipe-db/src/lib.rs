@@ -1,39 +1,219 @@
-use std::env;
+use std::{collections::HashSet, env};
 
-use diesel::pg::PgConnection;
 use diesel::prelude::*;
-use diesel_async::{
-    pooled_connection::{
-        deadpool::{Pool, PoolBuilder},
-        AsyncDieselConnectionManager,
-    },
-    AsyncPgConnection,
+use diesel_async::pooled_connection::{
+    deadpool::{Pool, PoolBuilder},
+    AsyncDieselConnectionManager,
 };
 use dotenvy::dotenv;
+#[cfg(feature = "sqlite")]
+use futures_util::future::{BoxFuture, FutureExt};
 
+#[cfg(all(feature = "postgres", feature = "sqlite"))]
+compile_error!("only one of the `postgres` and `sqlite` features can be enabled");
+#[cfg(not(any(feature = "postgres", feature = "sqlite")))]
+compile_error!("one of the `postgres` or `sqlite` features must be enabled");
+
+#[cfg(feature = "backup")]
+pub mod backup;
 pub mod dsl;
+#[cfg(feature = "metrics")]
+pub mod metrics;
 pub mod models;
+#[cfg_attr(feature = "sqlite", path = "schema_sqlite.rs")]
 pub mod schema;
+pub mod slug;
 pub mod traits;
 
-pub fn establish_connection() -> PgConnection {
+/// A new random ID for a synced row, which stays the same in every database
+/// the row is synced to.
+pub fn new_sync_id() -> String {
+    uuid::Uuid::new_v4().to_string()
+}
+
+/// The database backend, chosen with the `postgres` (the default) or `sqlite`
+/// feature.
+#[cfg(feature = "postgres")]
+pub type DbBackend = diesel::pg::Pg;
+#[cfg(feature = "sqlite")]
+pub type DbBackend = diesel::sqlite::Sqlite;
+
+/// A blocking connection, as used by the command line tools.
+#[cfg(feature = "postgres")]
+pub type SyncConnection = diesel::pg::PgConnection;
+#[cfg(feature = "sqlite")]
+pub type SyncConnection = diesel::sqlite::SqliteConnection;
+
+/// An async connection, as used by the repository.
+#[cfg(feature = "postgres")]
+pub type DbConnection = diesel_async::AsyncPgConnection;
+/// An async connection, as used by the repository. SQLite connections are
+/// blocking, so queries run on a background thread.
+#[cfg(feature = "sqlite")]
+pub type DbConnection =
+    diesel_async::sync_connection_wrapper::SyncConnectionWrapper<SyncConnection>;
+
+/// Settings applied to every SQLite connection: foreign keys aren't enforced
+/// unless asked for, and waiting briefly for locks lets the pool's
+/// connections write from several threads.
+#[cfg(feature = "sqlite")]
+const SQLITE_PRAGMAS: &str = "PRAGMA foreign_keys = ON; PRAGMA busy_timeout = 5000;";
+
+/// The migrations for Postgres databases, embedded so that the server can
+/// apply them itself rather than needing the diesel CLI.
+#[cfg(feature = "postgres")]
+pub const MIGRATIONS: diesel_migrations::EmbeddedMigrations =
+    diesel_migrations::embed_migrations!("migrations");
+/// The migrations for SQLite databases, which are created on the device
+/// rather than by an administrator so have to be set up by the app itself.
+#[cfg(feature = "sqlite")]
+pub const MIGRATIONS: diesel_migrations::EmbeddedMigrations =
+    diesel_migrations::embed_migrations!("migrations-sqlite");
+
+/// The key of the Postgres advisory lock held while migrating, so that
+/// servers starting at the same time take turns rather than racing.
+#[cfg(feature = "postgres")]
+const MIGRATION_LOCK_KEY: i64 = 0x7265_6369_7065;
+
+pub fn establish_connection() -> SyncConnection {
     dotenv().ok();
 
     let database_url = env::var("DATABASE_URL").expect("DATABASE_URL must be set");
-    PgConnection::establish(&database_url)
-        .unwrap_or_else(|_| panic!("Error connecting to {}", database_url))
+    connect(&database_url).unwrap_or_else(|_| panic!("Error connecting to {}", database_url))
 }
 
-pub type DbPool = Pool<AsyncPgConnection>;
+#[cfg(feature = "postgres")]
+fn connect(database_url: &str) -> ConnectionResult<SyncConnection> {
+    SyncConnection::establish(database_url)
+}
+
+#[cfg(feature = "sqlite")]
+fn connect(database_url: &str) -> ConnectionResult<SyncConnection> {
+    use diesel::connection::SimpleConnection;
+
+    let mut connection = SyncConnection::establish(database_url)?;
+    connection
+        .batch_execute(SQLITE_PRAGMAS)
+        .map_err(ConnectionError::CouldntSetupConfiguration)?;
+    Ok(connection)
+}
+
+/// The async equivalent of [`connect`], used by the pool.
+#[cfg(feature = "sqlite")]
+fn connect_async(database_url: &str) -> BoxFuture<'_, ConnectionResult<DbConnection>> {
+    use diesel_async::{AsyncConnection, SimpleAsyncConnection};
 
-pub fn build_pool(database_url: &str) -> PoolBuilder<AsyncPgConnection> {
+    async move {
+        let mut connection = DbConnection::establish(database_url).await?;
+        connection
+            .batch_execute(SQLITE_PRAGMAS)
+            .await
+            .map_err(ConnectionError::CouldntSetupConfiguration)?;
+        Ok(connection)
+    }
+    .boxed()
+}
+
+/// Bring a database up to date, creating a SQLite database if it's new, and
+/// return the versions of the migrations which were run.
+pub fn run_migrations(database_url: &str) -> anyhow::Result<Vec<String>> {
+    use diesel_migrations::MigrationHarness;
+
+    let mut connection = connect(database_url)?;
+    with_migration_lock(&mut connection, |connection| {
+        let versions = connection
+            .run_pending_migrations(MIGRATIONS)
+            .map_err(|e| anyhow::anyhow!("migrating {database_url}: {e}"))?;
+        Ok(versions.iter().map(|v| v.to_string()).collect())
+    })
+}
+
+/// Run `f` while holding the migration lock, waiting for any other server
+/// which is migrating the database to finish first.
+#[cfg(feature = "postgres")]
+fn with_migration_lock<T>(
+    connection: &mut SyncConnection,
+    f: impl FnOnce(&mut SyncConnection) -> anyhow::Result<T>,
+) -> anyhow::Result<T> {
+    use diesel::sql_types::BigInt;
+
+    diesel::sql_query("SELECT pg_advisory_lock($1)")
+        .bind::<BigInt, _>(MIGRATION_LOCK_KEY)
+        .execute(connection)?;
+    let result = f(connection);
+    // The lock would be released when the connection closes anyway, so
+    // failing to unlock only matters if migrating succeeded.
+    let unlocked = diesel::sql_query("SELECT pg_advisory_unlock($1)")
+        .bind::<BigInt, _>(MIGRATION_LOCK_KEY)
+        .execute(connection);
+    let value = result?;
+    unlocked?;
+    Ok(value)
+}
+
+/// SQLite databases belong to a single app, and migrating takes a write lock
+/// anyway.
+#[cfg(feature = "sqlite")]
+fn with_migration_lock<T>(
+    connection: &mut SyncConnection,
+    f: impl FnOnce(&mut SyncConnection) -> anyhow::Result<T>,
+) -> anyhow::Result<T> {
+    f(connection)
+}
+
+/// The names of the embedded migrations which haven't been run on the
+/// database, oldest first.
+///
+/// This fails if no migrations have ever been run, since diesel's table
+/// recording them won't exist yet.
+pub async fn pending_migrations(connection: &mut DbConnection) -> QueryResult<Vec<String>> {
+    use diesel::migration::{Migration, MigrationSource};
+
+    #[derive(QueryableByName)]
+    struct Applied {
+        #[diesel(sql_type = diesel::sql_types::Text)]
+        version: String,
+    }
+
+    let query = diesel::sql_query("SELECT version FROM __diesel_schema_migrations");
+    let applied: HashSet<String> = diesel_async::RunQueryDsl::load::<Applied>(query, connection)
+        .await?
+        .into_iter()
+        .map(|a| a.version)
+        .collect();
+    let migrations = MigrationSource::<DbBackend>::migrations(&MIGRATIONS)
+        .map_err(diesel::result::Error::QueryBuilderError)?;
+    Ok(migrations
+        .iter()
+        .filter(|m| !applied.contains(&m.name().version().to_string()))
+        .map(|m| m.name().to_string())
+        .collect())
+}
+
+pub type DbPool = Pool<DbConnection>;
+
+#[cfg(feature = "postgres")]
+pub fn build_pool(database_url: &str) -> PoolBuilder<DbConnection> {
     let config = AsyncDieselConnectionManager::new(database_url);
     Pool::builder(config)
 }
 
+#[cfg(feature = "sqlite")]
+pub fn build_pool(database_url: &str) -> PoolBuilder<DbConnection> {
+    use diesel_async::pooled_connection::ManagerConfig;
+
+    let mut manager_config = ManagerConfig::default();
+    manager_config.custom_setup = Box::new(connect_async);
+    let config = AsyncDieselConnectionManager::new_with_config(database_url, manager_config);
+    Pool::builder(config)
+}
+
+/// A pool for the database at `DATABASE_URL`, which may be set in `.env`.
 pub fn create_pool() -> anyhow::Result<DbPool> {
+    use anyhow::Context;
+
     dotenv().ok();
-    let database_url = env::var("DATABASE_URL").expect("DATABASE_URL must be set");
+    let database_url = env::var("DATABASE_URL").context("DATABASE_URL must be set")?;
     Ok(build_pool(&database_url).build()?)
 }
 
@@ -42,6 +222,6 @@ pub mod prelude {
     pub use diesel_async::RunQueryDsl;
 
     pub use super::traits::*;
-    pub use super::DbPool;
+    pub use super::{DbConnection, DbPool};
     pub type PoolError = diesel_async::pooled_connection::deadpool::PoolError;
 }
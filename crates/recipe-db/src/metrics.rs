@@ -0,0 +1,72 @@
+//! Timings of every query, recorded in the `db_query_duration_seconds`
+//! histogram with the `metrics` crate.
+
+use std::time::Instant;
+
+use diesel::{
+    connection::{set_default_instrumentation, Instrumentation, InstrumentationEvent},
+    QueryResult,
+};
+
+/// Time queries on every connection opened from now on.
+pub fn install() -> QueryResult<()> {
+    set_default_instrumentation(|| Some(Box::new(QueryTimer::default())))
+}
+
+/// Times the queries made on one connection, which are made one at a time.
+#[derive(Debug, Default)]
+struct QueryTimer {
+    started: Option<Instant>,
+}
+
+impl Instrumentation for QueryTimer {
+    fn on_connection_event(&mut self, event: InstrumentationEvent<'_>) {
+        match event {
+            InstrumentationEvent::StartQuery { .. } => self.started = Some(Instant::now()),
+            InstrumentationEvent::FinishQuery { query, error, .. } => {
+                let Some(started) = self.started.take() else {
+                    return;
+                };
+                let statement = statement(&query.to_string());
+                let outcome = if error.is_some() { "error" } else { "ok" };
+                metrics::histogram!(
+                    "db_query_duration_seconds",
+                    "statement" => statement,
+                    "outcome" => outcome,
+                )
+                .record(started.elapsed());
+            }
+            _ => {}
+        }
+    }
+}
+
+/// The kind of statement `sql` is, which labels its timing. The query itself
+/// would make too many distinct labels.
+fn statement(sql: &str) -> &'static str {
+    let keyword = sql
+        .trim_start()
+        .split(|c: char| !c.is_ascii_alphabetic())
+        .next()
+        .unwrap_or_default();
+    ["select", "insert", "update", "delete"]
+        .into_iter()
+        .find(|kind| keyword.eq_ignore_ascii_case(kind))
+        .unwrap_or("other")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::statement;
+
+    #[test]
+    fn statements() {
+        assert_eq!(
+            statement("SELECT \"recipes\".\"id\" FROM \"recipes\""),
+            "select"
+        );
+        assert_eq!(statement("  insert into tags (name) values ($1)"), "insert");
+        assert_eq!(statement("WITH x AS (SELECT 1) SELECT * FROM x"), "other");
+        assert_eq!(statement(""), "other");
+    }
+}
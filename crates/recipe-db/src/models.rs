@@ -1,9 +1,41 @@
+mod deletion;
+mod household;
+mod household_meal_plan;
+mod household_member;
+mod household_recipe;
 mod ingredient;
+mod ingredient_flag;
+mod meal_plan;
+mod meal_plan_recipe;
 mod measurement;
 mod recipe;
+mod recipe_diet;
 mod recipe_ingredient;
+mod recipe_nutrition;
+mod recipe_step;
+mod recipe_tag;
+mod session;
+mod sync_cursor;
+mod tag;
+mod user;
 
+pub use deletion::*;
+pub use household::*;
+pub use household_meal_plan::*;
+pub use household_member::*;
+pub use household_recipe::*;
 pub use ingredient::*;
+pub use ingredient_flag::*;
+pub use meal_plan::*;
+pub use meal_plan_recipe::*;
 pub use measurement::*;
 pub use recipe::*;
+pub use recipe_diet::*;
 pub use recipe_ingredient::*;
+pub use recipe_nutrition::*;
+pub use recipe_step::*;
+pub use recipe_tag::*;
+pub use session::*;
+pub use sync_cursor::*;
+pub use tag::*;
+pub use user::*;
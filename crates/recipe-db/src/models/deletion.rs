@@ -0,0 +1,21 @@
+use chrono::prelude::*;
+use diesel::prelude::*;
+
+use crate::{models::UserId, schema::deletions};
+
+/// The kinds of synced rows which leave a tombstone when deleted.
+pub const DELETION_KINDS: [&str; 2] = ["recipe", "meal_plan"];
+
+/// A tombstone left behind by a deleted recipe or meal plan, so the deletion
+/// can be synced to other databases.
+#[derive(Clone, Debug, Queryable, Identifiable, Insertable, Selectable)]
+#[diesel(primary_key(owner_id, kind, sync_id))]
+pub struct Deletion {
+    /// One of `recipe` or `meal_plan`.
+    pub kind: String,
+    pub sync_id: String,
+    /// The owner of the deleted row, whose devices should hear about it.
+    pub owner_id: UserId,
+    pub deleted_at: NaiveDateTime,
+    pub changed_at: NaiveDateTime,
+}
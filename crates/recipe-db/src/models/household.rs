@@ -0,0 +1,47 @@
+use chrono::prelude::*;
+use derive_more::{Display, From, Into};
+use diesel::{backend::Backend, helper_types::*, prelude::*};
+use diesel_derive_newtype::DieselNewType;
+
+use crate::{
+    schema::households,
+    traits::{All, Findable},
+};
+
+#[derive(Clone, Copy, Debug, DieselNewType, Display, Eq, From, Hash, Into, PartialEq)]
+pub struct HouseholdId(i64);
+
+/// A group of users sharing recipes and meal plans.
+#[derive(Clone, Debug, Queryable, Identifiable, Selectable)]
+pub struct Household {
+    pub id: HouseholdId,
+    pub created_at: NaiveDateTime,
+    pub name: String,
+}
+
+impl<Db: Backend> All<Db> for Household {
+    type Output = Select<households::table, AsSelect<Household, Db>>;
+    fn all() -> Self::Output {
+        households::table.select(Self::as_select())
+    }
+}
+
+impl<'a> Findable<'a> for Household {
+    type Id = HouseholdId;
+    type FindById = Find<households::table, Self::Id>;
+    type FindByIds = Filter<households::table, EqAny<households::id, &'a [HouseholdId]>>;
+
+    fn by_id(id: Self::Id) -> Self::FindById {
+        households::table.find(id)
+    }
+
+    fn by_ids(ids: &'a [Self::Id]) -> Self::FindByIds {
+        households::table.filter(households::id.eq_any(ids))
+    }
+}
+
+#[derive(Debug, Insertable, AsChangeset)]
+#[diesel(table_name = households)]
+pub struct NewHousehold<'a> {
+    pub name: &'a str,
+}
@@ -0,0 +1,27 @@
+use chrono::prelude::*;
+use diesel::prelude::*;
+
+use crate::{
+    models::{Household, HouseholdId, MealPlan, MealPlanId},
+    schema::household_meal_plans,
+};
+
+/// A meal plan shared into a household.
+#[derive(Clone, Debug, Queryable, Identifiable, Associations, Selectable)]
+#[diesel(
+    primary_key(household_id, meal_plan_id),
+    belongs_to(Household),
+    belongs_to(MealPlan)
+)]
+pub struct HouseholdMealPlan {
+    pub household_id: HouseholdId,
+    pub meal_plan_id: MealPlanId,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = household_meal_plans)]
+pub struct NewHouseholdMealPlan {
+    pub household_id: HouseholdId,
+    pub meal_plan_id: MealPlanId,
+}
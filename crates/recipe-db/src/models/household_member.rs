@@ -0,0 +1,52 @@
+use chrono::prelude::*;
+use diesel::{helper_types::*, prelude::*};
+
+use crate::{
+    models::{Household, HouseholdId, User, UserId},
+    schema::household_members,
+};
+
+/// Every role a household member can have.
+pub const ROLES: [&str; 3] = ["owner", "editor", "viewer"];
+/// The roles which allow changing what has been shared into a household.
+pub const EDITOR_ROLES: [&str; 2] = ["owner", "editor"];
+
+pub type HouseholdsOf<'a> = Select<
+    Filter<
+        Filter<household_members::table, Eq<household_members::user_id, UserId>>,
+        EqAny<household_members::role, &'a [&'a str]>,
+    >,
+    household_members::household_id,
+>;
+
+#[derive(Clone, Debug, Queryable, Identifiable, Associations, Selectable)]
+#[diesel(
+    primary_key(household_id, user_id),
+    belongs_to(Household),
+    belongs_to(User)
+)]
+pub struct HouseholdMember {
+    pub household_id: HouseholdId,
+    pub user_id: UserId,
+    pub created_at: NaiveDateTime,
+    /// One of `owner`, `editor` or `viewer`.
+    pub role: String,
+}
+
+impl HouseholdMember {
+    /// The ids of the households in which `user` has one of `roles`.
+    pub fn households_of<'a>(user: UserId, roles: &'a [&'a str]) -> HouseholdsOf<'a> {
+        household_members::table
+            .filter(household_members::user_id.eq(user))
+            .filter(household_members::role.eq_any(roles))
+            .select(household_members::household_id)
+    }
+}
+
+#[derive(Debug, Insertable, AsChangeset)]
+#[diesel(table_name = household_members)]
+pub struct NewHouseholdMember<'a> {
+    pub household_id: HouseholdId,
+    pub user_id: UserId,
+    pub role: &'a str,
+}
@@ -0,0 +1,27 @@
+use chrono::prelude::*;
+use diesel::prelude::*;
+
+use crate::{
+    models::{Household, HouseholdId, Recipe, RecipeId},
+    schema::household_recipes,
+};
+
+/// A recipe shared into a household.
+#[derive(Clone, Debug, Queryable, Identifiable, Associations, Selectable)]
+#[diesel(
+    primary_key(household_id, recipe_id),
+    belongs_to(Household),
+    belongs_to(Recipe)
+)]
+pub struct HouseholdRecipe {
+    pub household_id: HouseholdId,
+    pub recipe_id: RecipeId,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = household_recipes)]
+pub struct NewHouseholdRecipe {
+    pub household_id: HouseholdId,
+    pub recipe_id: RecipeId,
+}
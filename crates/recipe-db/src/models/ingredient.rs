@@ -23,6 +23,8 @@ pub struct Ingredient {
     pub name: String,
     pub slug: String,
     pub default_measurement_id: MeasurementId,
+    /// Whether the dietary flags have been checked by a person.
+    pub diet_reviewed: bool,
 }
 
 impl<Db: Backend> All<Db> for Ingredient {
@@ -50,5 +52,7 @@ impl<'a> Findable<'a> for Ingredient {
 #[diesel(table_name = ingredients)]
 pub struct NewIngredient<'a> {
     pub name: &'a str,
+    /// The name's [`slugify`](crate::slug::slugify)d form.
+    pub slug: String,
     pub default_measurement_id: MeasurementId,
 }
@@ -0,0 +1,35 @@
+use chrono::prelude::*;
+use diesel::{
+    helper_types::{EqAny, Filter},
+    prelude::*,
+};
+
+use crate::{
+    models::{Ingredient, IngredientId},
+    schema::ingredient_flags,
+};
+
+type FilteredByIngredientId<'a> =
+    Filter<ingredient_flags::table, EqAny<ingredient_flags::ingredient_id, &'a [IngredientId]>>;
+
+/// A dietary flag, such as `gluten` or `meat`, carried by an ingredient.
+#[derive(Clone, Debug, Queryable, Identifiable, Associations, Selectable)]
+#[diesel(primary_key(ingredient_id, flag), belongs_to(Ingredient))]
+pub struct IngredientFlag {
+    pub ingredient_id: IngredientId,
+    pub flag: String,
+    pub created_at: NaiveDateTime,
+}
+
+impl IngredientFlag {
+    pub fn belonging_to_ingredients(ids: &[IngredientId]) -> FilteredByIngredientId {
+        ingredient_flags::table.filter(ingredient_flags::ingredient_id.eq_any(ids))
+    }
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = ingredient_flags)]
+pub struct NewIngredientFlag<'a> {
+    pub ingredient_id: IngredientId,
+    pub flag: &'a str,
+}
@@ -0,0 +1,145 @@
+use chrono::prelude::*;
+use derive_more::{Display, From, Into};
+use diesel::{backend::Backend, helper_types::*, prelude::*, sql_types};
+use diesel_derive_newtype::DieselNewType;
+
+use crate::{
+    models::{HouseholdMember, HouseholdsOf, UserId, EDITOR_ROLES, ROLES},
+    schema::{household_meal_plans, meal_plans},
+    traits::{All, Findable},
+    DbBackend,
+};
+
+#[derive(Clone, Copy, Debug, DieselNewType, Display, Eq, From, Hash, Into, PartialEq)]
+pub struct MealPlanId(i64);
+
+type FindBySlug<'a> = Filter<meal_plans::table, Eq<meal_plans::slug, &'a str>>;
+type FindBySyncId<'a> = Filter<meal_plans::table, Eq<meal_plans::sync_id, &'a str>>;
+type InHouseholdsOf = Select<
+    Filter<
+        household_meal_plans::table,
+        EqAny<household_meal_plans::household_id, HouseholdsOf<'static>>,
+    >,
+    household_meal_plans::meal_plan_id,
+>;
+/// A condition on meal plans depending on the user, like those on recipes.
+type Permission = Box<
+    dyn BoxableExpression<
+        meal_plans::table,
+        DbBackend,
+        SqlType = sql_types::Nullable<sql_types::Bool>,
+    >,
+>;
+
+#[derive(Clone, Debug, Queryable, Identifiable, Selectable)]
+pub struct MealPlan {
+    pub id: MealPlanId,
+    pub name: String,
+    pub slug: String,
+    pub created_at: NaiveDateTime,
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    pub owner_id: Option<UserId>,
+    pub shared: bool,
+    /// Identifies the plan across databases, like a recipe's `sync_id`.
+    pub sync_id: String,
+    pub updated_at: NaiveDateTime,
+    pub changed_at: NaiveDateTime,
+}
+
+impl MealPlan {
+    pub fn by_slug(slug: &str) -> FindBySlug {
+        meal_plans::table.filter(meal_plans::slug.eq(slug))
+    }
+
+    pub fn by_sync_id(sync_id: &str) -> FindBySyncId {
+        meal_plans::table.filter(meal_plans::sync_id.eq(sync_id))
+    }
+
+    /// Whether a plan can be seen by `user`, following the same rules as
+    /// recipes.
+    pub fn visible_to(user: Option<UserId>) -> Permission {
+        let public = meal_plans::owner_id
+            .is_null()
+            .or(meal_plans::shared)
+            .or(meal_plans::owner_id.eq(user));
+        match user {
+            Some(user) => {
+                Box::new(public.or(meal_plans::id.eq_any(Self::in_households_of(user, &ROLES))))
+            }
+            None => Box::new(public),
+        }
+    }
+
+    /// Whether a plan can be changed by `user`. Viewers of a household a plan
+    /// is shared into can see it but not change it.
+    pub fn editable_by(user: Option<UserId>) -> Permission {
+        let owned = meal_plans::owner_id
+            .is_null()
+            .or(meal_plans::owner_id.eq(user));
+        match user {
+            Some(user) => Box::new(
+                owned.or(meal_plans::id.eq_any(Self::in_households_of(user, &EDITOR_ROLES))),
+            ),
+            None => Box::new(owned),
+        }
+    }
+
+    /// Whether `user` owns a plan, so can decide who it's shared with. Plans
+    /// without an owner belong to everyone.
+    pub fn owned_by(user: Option<UserId>) -> Permission {
+        Box::new(
+            meal_plans::owner_id
+                .is_null()
+                .or(meal_plans::owner_id.eq(user)),
+        )
+    }
+
+    /// The in-memory equivalent of [`MealPlan::owned_by`].
+    pub fn is_owned_by(&self, user: UserId) -> bool {
+        self.owner_id.map_or(true, |owner| owner == user)
+    }
+
+    fn in_households_of(user: UserId, roles: &'static [&'static str]) -> InHouseholdsOf {
+        household_meal_plans::table
+            .filter(
+                household_meal_plans::household_id
+                    .eq_any(HouseholdMember::households_of(user, roles)),
+            )
+            .select(household_meal_plans::meal_plan_id)
+    }
+}
+
+impl<Db: Backend> All<Db> for MealPlan {
+    type Output = Select<meal_plans::table, AsSelect<MealPlan, Db>>;
+    fn all() -> Self::Output {
+        meal_plans::table.select(Self::as_select())
+    }
+}
+
+impl<'a> Findable<'a> for MealPlan {
+    type Id = MealPlanId;
+    type FindById = Find<meal_plans::table, Self::Id>;
+    type FindByIds = Filter<meal_plans::table, EqAny<meal_plans::id, &'a [MealPlanId]>>;
+
+    fn by_id(id: Self::Id) -> Self::FindById {
+        meal_plans::table.find(id)
+    }
+
+    fn by_ids(ids: &'a [Self::Id]) -> Self::FindByIds {
+        meal_plans::table.filter(meal_plans::id.eq_any(ids))
+    }
+}
+
+#[derive(Debug, Insertable, AsChangeset)]
+#[diesel(table_name = meal_plans)]
+pub struct NewMealPlan<'a> {
+    pub name: &'a str,
+    /// The name's [`slugify`](crate::slug::slugify)d form.
+    pub slug: String,
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    pub shared: bool,
+    pub updated_at: NaiveDateTime,
+    pub changed_at: NaiveDateTime,
+}
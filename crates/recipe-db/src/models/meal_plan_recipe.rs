@@ -0,0 +1,31 @@
+use chrono::prelude::*;
+use derive_more::{Display, From, Into};
+use diesel::prelude::*;
+use diesel_derive_newtype::DieselNewType;
+
+use crate::{
+    models::{MealPlan, MealPlanId, Recipe, RecipeId},
+    schema::meal_plan_recipes,
+};
+
+#[derive(Clone, Copy, Debug, DieselNewType, Display, Eq, From, Hash, Into, PartialEq)]
+pub struct MealPlanRecipeId(i64);
+
+/// A recipe to be eaten on a day of a meal plan.
+#[derive(Clone, Debug, Queryable, Identifiable, Associations, Selectable)]
+#[diesel(belongs_to(MealPlan), belongs_to(Recipe))]
+pub struct MealPlanRecipe {
+    pub id: MealPlanRecipeId,
+    pub created_at: NaiveDateTime,
+    pub meal_plan_id: MealPlanId,
+    pub recipe_id: RecipeId,
+    pub day: NaiveDate,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = meal_plan_recipes)]
+pub struct NewMealPlanRecipe {
+    pub meal_plan_id: MealPlanId,
+    pub recipe_id: RecipeId,
+    pub day: NaiveDate,
+}
@@ -49,5 +49,7 @@ impl<'a> Findable<'a> for Measurement {
 #[diesel(table_name = measurements)]
 pub struct NewMeasurement<'a> {
     pub name: &'a str,
+    /// The name's [`slugify`](crate::slug::slugify)d form.
+    pub slug: String,
     pub abbreviation: Option<&'a str>,
 }
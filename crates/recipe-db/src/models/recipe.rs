@@ -1,18 +1,20 @@
 use chrono::prelude::*;
 use derive_more::{Display, From, Into};
-use diesel::{backend::Backend, helper_types::*, prelude::*};
+use diesel::{backend::Backend, helper_types::*, prelude::*, sql_types};
 use diesel_derive_newtype::DieselNewType;
 
 use crate::{
-    models::IngredientId,
-    schema::{recipe_ingredients, recipes},
+    models::{HouseholdMember, HouseholdsOf, IngredientId, UserId, EDITOR_ROLES, ROLES},
+    schema::{household_recipes, recipe_ingredients, recipe_steps, recipes},
     traits::{All, Findable},
+    DbBackend,
 };
 
 #[derive(Clone, Copy, Debug, DieselNewType, Display, Eq, From, Hash, Into, PartialEq)]
 pub struct RecipeId(i64);
 
 type FindBySlug<'a> = Filter<recipes::table, Eq<recipes::slug, &'a str>>;
+type FindBySyncId<'a> = Filter<recipes::table, Eq<recipes::sync_id, &'a str>>;
 
 type RecipeIngredientsBelongingToIngredient<'a> =
     Filter<recipe_ingredients::table, EqAny<recipe_ingredients::ingredient_id, &'a [IngredientId]>>;
@@ -31,6 +33,21 @@ type IngredientsForRecipe = Filter<
     recipe_ingredients::table,
     diesel::helper_types::Eq<recipe_ingredients::recipe_id, RecipeId>,
 >;
+type InHouseholdsOf = Select<
+    Filter<household_recipes::table, EqAny<household_recipes::household_id, HouseholdsOf<'static>>>,
+    household_recipes::recipe_id,
+>;
+/// A condition on recipes depending on the user, boxed since it includes
+/// subqueries for signed in users only.
+type Permission = Box<
+    dyn BoxableExpression<
+        recipes::table,
+        DbBackend,
+        SqlType = sql_types::Nullable<sql_types::Bool>,
+    >,
+>;
+type StepsForRecipe =
+    Filter<recipe_steps::table, diesel::helper_types::Eq<recipe_steps::recipe_id, RecipeId>>;
 
 #[derive(Clone, Debug, Queryable, Identifiable, Selectable)]
 pub struct Recipe {
@@ -43,13 +60,74 @@ pub struct Recipe {
     pub prep_time_minutes: Option<i32>,
     pub cooking_time_minutes: Option<i32>,
     pub image_url: Option<String>,
+    pub servings: Option<i32>,
+    pub owner_id: Option<UserId>,
+    pub shared: bool,
+    /// Identifies the recipe across all the databases it's synced between.
+    pub sync_id: String,
+    /// When the recipe was last edited, in whichever database that happened.
+    pub updated_at: NaiveDateTime,
+    /// When this database last wrote the recipe, which sync cursors are
+    /// compared against.
+    pub changed_at: NaiveDateTime,
 }
 
 impl Recipe {
+    /// Whether a recipe can be seen by `user`: recipes without an owner and
+    /// shared recipes are visible to everyone, and recipes shared into a
+    /// household are visible to all of its members.
+    pub fn visible_to(user: Option<UserId>) -> Permission {
+        let public = recipes::owner_id
+            .is_null()
+            .or(recipes::shared)
+            .or(recipes::owner_id.eq(user));
+        match user {
+            Some(user) => {
+                Box::new(public.or(recipes::id.eq_any(Self::in_households_of(user, &ROLES))))
+            }
+            None => Box::new(public),
+        }
+    }
+
+    /// Whether a recipe can be changed by `user`: by its owner, or by owners
+    /// and editors of a household it's shared into.
+    pub fn editable_by(user: Option<UserId>) -> Permission {
+        let owned = recipes::owner_id.is_null().or(recipes::owner_id.eq(user));
+        match user {
+            Some(user) => {
+                Box::new(owned.or(recipes::id.eq_any(Self::in_households_of(user, &EDITOR_ROLES))))
+            }
+            None => Box::new(owned),
+        }
+    }
+
+    /// Whether `user` owns a recipe, so can decide who it's shared with.
+    /// Recipes without an owner belong to everyone.
+    pub fn owned_by(user: Option<UserId>) -> Permission {
+        Box::new(recipes::owner_id.is_null().or(recipes::owner_id.eq(user)))
+    }
+
+    /// The in-memory equivalent of [`Recipe::owned_by`].
+    pub fn is_owned_by(&self, user: UserId) -> bool {
+        self.owner_id.map_or(true, |owner| owner == user)
+    }
+
+    fn in_households_of(user: UserId, roles: &'static [&'static str]) -> InHouseholdsOf {
+        household_recipes::table
+            .filter(
+                household_recipes::household_id.eq_any(HouseholdMember::households_of(user, roles)),
+            )
+            .select(household_recipes::recipe_id)
+    }
+
     pub fn by_slug(slug: &str) -> FindBySlug {
         recipes::table.filter(recipes::slug.eq(slug))
     }
 
+    pub fn by_sync_id(sync_id: &str) -> FindBySyncId {
+        recipes::table.filter(recipes::sync_id.eq(sync_id))
+    }
+
     pub fn by_ingredient_ids(ids: &[IngredientId]) -> FilteredByIngredientId {
         let recipe_ingredient_recipe_ids = recipe_ingredients::table
             .filter(recipe_ingredients::ingredient_id.eq_any(ids))
@@ -66,6 +144,10 @@ impl Recipe {
     pub fn ingredients(&self) -> IngredientsForRecipe {
         recipe_ingredients::table.filter(recipe_ingredients::recipe_id.eq(self.id))
     }
+
+    pub fn steps(&self) -> StepsForRecipe {
+        recipe_steps::table.filter(recipe_steps::recipe_id.eq(self.id))
+    }
 }
 
 impl<Db: Backend> All<Db> for Recipe {
@@ -89,12 +171,18 @@ impl<'a> Findable<'a> for Recipe {
 }
 
 #[derive(Debug, Insertable, AsChangeset)]
-#[diesel(table_name = recipes)]
+#[diesel(table_name = recipes, treat_none_as_null = true)]
 pub struct NewRecipe<'a> {
     pub name: &'a str,
+    /// The name's [`slugify`](crate::slug::slugify)d form.
+    pub slug: String,
     pub source: Option<&'a str>,
     pub notes: Option<&'a str>,
     pub prep_time_minutes: Option<i32>,
     pub cooking_time_minutes: Option<i32>,
     pub image_url: Option<&'a str>,
+    pub servings: Option<i32>,
+    pub shared: bool,
+    pub updated_at: NaiveDateTime,
+    pub changed_at: NaiveDateTime,
 }
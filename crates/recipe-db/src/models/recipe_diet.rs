@@ -0,0 +1,23 @@
+use chrono::prelude::*;
+use diesel::prelude::*;
+
+use crate::{
+    models::{Recipe, RecipeId},
+    schema::recipe_diets,
+};
+
+/// A diet which a recipe has been declared suitable for.
+#[derive(Clone, Debug, Queryable, Identifiable, Associations, Selectable)]
+#[diesel(primary_key(recipe_id, diet), belongs_to(Recipe))]
+pub struct RecipeDiet {
+    pub recipe_id: RecipeId,
+    pub diet: String,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = recipe_diets)]
+pub struct NewRecipeDiet<'a> {
+    pub recipe_id: RecipeId,
+    pub diet: &'a str,
+}
@@ -5,14 +5,45 @@ use diesel::{
     prelude::*,
 };
 
-use bigdecimal::BigDecimal;
+#[cfg(feature = "postgres")]
+use bigdecimal::{BigDecimal, ToPrimitive};
 
 use crate::{
     models::{Ingredient, IngredientId, Measurement, MeasurementId, Recipe, RecipeId},
     schema::{ingredients, measurements, recipe_ingredients, recipes},
     traits::All,
+    SyncConnection,
 };
 
+/// The quantity of an ingredient in a recipe. Postgres stores these as exact
+/// decimals, but SQLite has no decimal type so they're floats there.
+#[cfg(feature = "postgres")]
+pub type Quantity = BigDecimal;
+#[cfg(feature = "sqlite")]
+pub type Quantity = f64;
+
+#[cfg(feature = "postgres")]
+pub fn quantity_from_f64(quantity: f64) -> Option<Quantity> {
+    // Going through the shortest string representation turns e.g. 0.1 into
+    // exactly 0.1 rather than the nearest float to it.
+    quantity.to_string().parse().ok()
+}
+
+#[cfg(feature = "sqlite")]
+pub fn quantity_from_f64(quantity: f64) -> Option<Quantity> {
+    quantity.is_finite().then_some(quantity)
+}
+
+#[cfg(feature = "postgres")]
+pub fn quantity_to_f64(quantity: &Quantity) -> Option<f64> {
+    quantity.to_f64()
+}
+
+#[cfg(feature = "sqlite")]
+pub fn quantity_to_f64(quantity: &Quantity) -> Option<f64> {
+    Some(*quantity)
+}
+
 type FilteredByRecipeId<'a> =
     Filter<recipe_ingredients::table, EqAny<recipe_ingredients::recipe_id, &'a [RecipeId]>>;
 type FilteredByIngredientId<'a> =
@@ -28,7 +59,7 @@ pub struct RecipeIngredient {
     pub recipe_id: RecipeId,
     pub ingredient_id: IngredientId,
     pub created_at: NaiveDateTime,
-    pub quantity: BigDecimal,
+    pub quantity: Option<Quantity>,
     pub measurement_id: Option<MeasurementId>,
     #[diesel(column_name = "idx")]
     pub index: i32,
@@ -44,11 +75,11 @@ impl RecipeIngredient {
         recipe_ingredients::table.filter(recipe_ingredients::ingredient_id.eq_any(ids))
     }
 
-    pub fn recipe(&self, conn: &mut PgConnection) -> QueryResult<Recipe> {
+    pub fn recipe(&self, conn: &mut SyncConnection) -> QueryResult<Recipe> {
         recipes::table.find(self.recipe_id).first::<Recipe>(conn)
     }
 
-    pub fn ingredient(&self, conn: &mut PgConnection) -> QueryResult<Ingredient> {
+    pub fn ingredient(&self, conn: &mut SyncConnection) -> QueryResult<Ingredient> {
         ingredients::table
             .find(self.ingredient_id)
             .first::<Ingredient>(conn)
@@ -56,7 +87,7 @@ impl RecipeIngredient {
 
     pub fn measurement(
         &self,
-        conn: &mut PgConnection,
+        conn: &mut SyncConnection,
         ingredient: &Ingredient,
     ) -> QueryResult<Measurement> {
         self.measurement_id
@@ -82,7 +113,7 @@ pub struct NewRecipeIngredient<'a> {
     pub recipe_id: RecipeId,
     pub ingredient_id: IngredientId,
     pub measurement_id: Option<MeasurementId>,
-    pub quantity: BigDecimal,
+    pub quantity: Option<Quantity>,
     #[diesel(column_name = "idx")]
     pub index: i32,
     pub notes: Option<&'a str>,
@@ -0,0 +1,41 @@
+use chrono::prelude::*;
+use diesel::prelude::*;
+
+use crate::{
+    models::{Recipe, RecipeId},
+    schema::recipe_nutrition,
+};
+
+/// Nutrition per serving of a recipe, as declared by its source.
+#[derive(Clone, Debug, Queryable, Identifiable, Associations, Selectable)]
+#[diesel(
+    table_name = recipe_nutrition,
+    primary_key(recipe_id),
+    belongs_to(Recipe)
+)]
+pub struct RecipeNutrition {
+    pub recipe_id: RecipeId,
+    pub created_at: NaiveDateTime,
+    pub energy_kcal: Option<f64>,
+    pub fat_g: Option<f64>,
+    pub saturated_fat_g: Option<f64>,
+    pub carbohydrate_g: Option<f64>,
+    pub sugar_g: Option<f64>,
+    pub fibre_g: Option<f64>,
+    pub protein_g: Option<f64>,
+    pub salt_g: Option<f64>,
+}
+
+#[derive(Debug, Insertable, AsChangeset)]
+#[diesel(table_name = recipe_nutrition, treat_none_as_null = true)]
+pub struct NewRecipeNutrition {
+    pub recipe_id: RecipeId,
+    pub energy_kcal: Option<f64>,
+    pub fat_g: Option<f64>,
+    pub saturated_fat_g: Option<f64>,
+    pub carbohydrate_g: Option<f64>,
+    pub sugar_g: Option<f64>,
+    pub fibre_g: Option<f64>,
+    pub protein_g: Option<f64>,
+    pub salt_g: Option<f64>,
+}
@@ -0,0 +1,51 @@
+use chrono::prelude::*;
+use derive_more::{Display, From, Into};
+use diesel::{
+    backend::Backend,
+    helper_types::{AsSelect, EqAny, Filter, Select},
+    prelude::*,
+};
+use diesel_derive_newtype::DieselNewType;
+
+use crate::{
+    models::{Recipe, RecipeId},
+    schema::recipe_steps,
+    traits::All,
+};
+
+type FilteredByRecipeId<'a> =
+    Filter<recipe_steps::table, EqAny<recipe_steps::recipe_id, &'a [RecipeId]>>;
+
+#[derive(Clone, Copy, Debug, DieselNewType, Display, Eq, From, Hash, Into, PartialEq)]
+pub struct RecipeStepId(i64);
+
+#[derive(Clone, Debug, Queryable, Identifiable, Associations, Selectable)]
+#[diesel(belongs_to(Recipe))]
+pub struct RecipeStep {
+    pub id: RecipeStepId,
+    pub created_at: NaiveDateTime,
+    pub recipe_id: RecipeId,
+    pub step_number: i32,
+    pub description: String,
+}
+
+impl RecipeStep {
+    pub fn belonging_to_recipes(ids: &[RecipeId]) -> FilteredByRecipeId {
+        recipe_steps::table.filter(recipe_steps::recipe_id.eq_any(ids))
+    }
+}
+
+impl<Db: Backend> All<Db> for RecipeStep {
+    type Output = Select<recipe_steps::table, AsSelect<RecipeStep, Db>>;
+    fn all() -> Self::Output {
+        recipe_steps::table.select(Self::as_select())
+    }
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = recipe_steps)]
+pub struct NewRecipeStep<'a> {
+    pub recipe_id: RecipeId,
+    pub step_number: i32,
+    pub description: &'a str,
+}
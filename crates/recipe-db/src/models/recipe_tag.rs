@@ -0,0 +1,34 @@
+use chrono::prelude::*;
+use diesel::{
+    helper_types::{EqAny, Filter},
+    prelude::*,
+};
+
+use crate::{
+    models::{Recipe, RecipeId, Tag, TagId},
+    schema::recipe_tags,
+};
+
+type FilteredByRecipeId<'a> =
+    Filter<recipe_tags::table, EqAny<recipe_tags::recipe_id, &'a [RecipeId]>>;
+
+#[derive(Clone, Debug, Queryable, Identifiable, Associations, Selectable)]
+#[diesel(primary_key(recipe_id, tag_id), belongs_to(Recipe), belongs_to(Tag))]
+pub struct RecipeTag {
+    pub recipe_id: RecipeId,
+    pub tag_id: TagId,
+    pub created_at: NaiveDateTime,
+}
+
+impl RecipeTag {
+    pub fn belonging_to_recipes(ids: &[RecipeId]) -> FilteredByRecipeId {
+        recipe_tags::table.filter(recipe_tags::recipe_id.eq_any(ids))
+    }
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = recipe_tags)]
+pub struct NewRecipeTag {
+    pub recipe_id: RecipeId,
+    pub tag_id: TagId,
+}
@@ -0,0 +1,27 @@
+use chrono::prelude::*;
+use diesel::prelude::*;
+
+use crate::{
+    models::{User, UserId},
+    schema::sessions,
+};
+
+/// A signed in browser, identified by the random token in its cookie.
+#[derive(Clone, Debug, Queryable, Identifiable, Associations, Selectable)]
+#[diesel(primary_key(token_hash), belongs_to(User))]
+pub struct Session {
+    /// The hex encoded SHA-256 hash of the token. The token itself isn't
+    /// stored.
+    pub token_hash: String,
+    pub user_id: UserId,
+    pub created_at: NaiveDateTime,
+    pub expires_at: NaiveDateTime,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = sessions)]
+pub struct NewSession<'a> {
+    pub token_hash: &'a str,
+    pub user_id: UserId,
+    pub expires_at: NaiveDateTime,
+}
@@ -0,0 +1,16 @@
+use chrono::prelude::*;
+use diesel::prelude::*;
+
+use crate::schema::sync_cursors;
+
+/// How far this database has synced with a remote server.
+#[derive(Clone, Debug, Queryable, Identifiable, Insertable, AsChangeset, Selectable)]
+#[diesel(primary_key(remote), treat_none_as_null = true)]
+pub struct SyncCursor {
+    /// The URL of the server.
+    pub remote: String,
+    /// The cursor the server returned from the last sync.
+    pub pulled: Option<NaiveDateTime>,
+    /// When changes in this database were last sent to the server.
+    pub pushed: Option<NaiveDateTime>,
+}
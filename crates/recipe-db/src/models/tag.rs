@@ -0,0 +1,56 @@
+use chrono::prelude::*;
+use derive_more::{Display, From, Into};
+use diesel::{
+    backend::Backend,
+    helper_types::{AsSelect, EqAny, Filter, Find, Select},
+    prelude::*,
+};
+use diesel_derive_newtype::DieselNewType;
+
+use crate::{
+    schema::tags,
+    traits::{All, Findable},
+};
+
+#[derive(Clone, Copy, Debug, DieselNewType, Display, Eq, From, Hash, Into, PartialEq)]
+pub struct TagId(i64);
+
+#[derive(Clone, Debug, Queryable, Identifiable, Selectable)]
+pub struct Tag {
+    pub id: TagId,
+    pub created_at: NaiveDateTime,
+    pub name: String,
+    pub slug: String,
+    /// One of `tag`, `category` or `cuisine`.
+    pub kind: String,
+}
+
+impl<Db: Backend> All<Db> for Tag {
+    type Output = Select<tags::table, AsSelect<Tag, Db>>;
+    fn all() -> Self::Output {
+        tags::table.select(Self::as_select())
+    }
+}
+
+impl<'a> Findable<'a> for Tag {
+    type Id = TagId;
+    type FindById = Find<tags::table, Self::Id>;
+    type FindByIds = Filter<tags::table, EqAny<tags::id, &'a [TagId]>>;
+
+    fn by_id(id: Self::Id) -> Self::FindById {
+        tags::table.find(id)
+    }
+
+    fn by_ids(ids: &'a [Self::Id]) -> Self::FindByIds {
+        tags::table.filter(tags::id.eq_any(ids))
+    }
+}
+
+#[derive(Debug, Insertable, AsChangeset)]
+#[diesel(table_name = tags)]
+pub struct NewTag<'a> {
+    pub name: &'a str,
+    /// The name's [`slugify`](crate::slug::slugify)d form.
+    pub slug: String,
+    pub kind: &'a str,
+}
@@ -0,0 +1,60 @@
+use chrono::prelude::*;
+use derive_more::{Display, From, Into};
+use diesel::{backend::Backend, helper_types::*, prelude::*};
+use diesel_derive_newtype::DieselNewType;
+
+use crate::{
+    dsl::{lower, Lower},
+    schema::users,
+    traits::{All, Findable},
+};
+
+#[derive(Clone, Copy, Debug, DieselNewType, Display, Eq, From, Hash, Into, PartialEq)]
+pub struct UserId(i64);
+
+type FindByUsername<'a> = Filter<users::table, Eq<Lower<users::username>, String>>;
+
+#[derive(Clone, Debug, Queryable, Identifiable, Selectable)]
+pub struct User {
+    pub id: UserId,
+    pub created_at: NaiveDateTime,
+    pub username: String,
+    pub display_name: String,
+    pub password_hash: String,
+}
+
+impl User {
+    /// Usernames are unique regardless of case.
+    pub fn by_username(username: &str) -> FindByUsername {
+        users::table.filter(lower(users::username).eq(username.to_lowercase()))
+    }
+}
+
+impl<Db: Backend> All<Db> for User {
+    type Output = Select<users::table, AsSelect<User, Db>>;
+    fn all() -> Self::Output {
+        users::table.select(Self::as_select())
+    }
+}
+
+impl<'a> Findable<'a> for User {
+    type Id = UserId;
+    type FindById = Find<users::table, Self::Id>;
+    type FindByIds = Filter<users::table, EqAny<users::id, &'a [UserId]>>;
+
+    fn by_id(id: Self::Id) -> Self::FindById {
+        users::table.find(id)
+    }
+
+    fn by_ids(ids: &'a [Self::Id]) -> Self::FindByIds {
+        users::table.filter(users::id.eq_any(ids))
+    }
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = users)]
+pub struct NewUser<'a> {
+    pub username: &'a str,
+    pub display_name: &'a str,
+    pub password_hash: &'a str,
+}
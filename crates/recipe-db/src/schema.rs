@@ -1,5 +1,56 @@
 // @generated automatically by Diesel CLI.
 
+diesel::table! {
+    ingredient_flags (ingredient_id, flag) {
+        ingredient_id -> Int8,
+        flag -> Text,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    deletions (owner_id, kind, sync_id) {
+        kind -> Text,
+        sync_id -> Text,
+        owner_id -> Int8,
+        deleted_at -> Timestamp,
+        changed_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    household_meal_plans (household_id, meal_plan_id) {
+        household_id -> Int8,
+        meal_plan_id -> Int8,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    household_members (household_id, user_id) {
+        household_id -> Int8,
+        user_id -> Int8,
+        created_at -> Timestamp,
+        role -> Text,
+    }
+}
+
+diesel::table! {
+    household_recipes (household_id, recipe_id) {
+        household_id -> Int8,
+        recipe_id -> Int8,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    households (id) {
+        id -> Int8,
+        created_at -> Timestamp,
+        name -> Text,
+    }
+}
+
 diesel::table! {
     ingredients (id) {
         id -> Int8,
@@ -7,6 +58,7 @@ diesel::table! {
         name -> Text,
         slug -> Text,
         default_measurement_id -> Int8,
+        diet_reviewed -> Bool,
     }
 }
 
@@ -16,6 +68,7 @@ diesel::table! {
         created_at -> Timestamp,
         meal_plan_id -> Int8,
         recipe_id -> Int8,
+        day -> Date,
     }
 }
 
@@ -27,6 +80,11 @@ diesel::table! {
         created_at -> Timestamp,
         start_date -> Date,
         end_date -> Date,
+        owner_id -> Nullable<Int8>,
+        shared -> Bool,
+        sync_id -> Text,
+        updated_at -> Timestamp,
+        changed_at -> Timestamp,
     }
 }
 
@@ -40,18 +98,41 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    recipe_diets (recipe_id, diet) {
+        recipe_id -> Int8,
+        diet -> Text,
+        created_at -> Timestamp,
+    }
+}
+
 diesel::table! {
     recipe_ingredients (recipe_id, ingredient_id) {
         recipe_id -> Int8,
         ingredient_id -> Int8,
         created_at -> Timestamp,
-        quantity -> Numeric,
+        quantity -> Nullable<Numeric>,
         measurement_id -> Nullable<Int8>,
         idx -> Int4,
         notes -> Nullable<Text>,
     }
 }
 
+diesel::table! {
+    recipe_nutrition (recipe_id) {
+        recipe_id -> Int8,
+        created_at -> Timestamp,
+        energy_kcal -> Nullable<Float8>,
+        fat_g -> Nullable<Float8>,
+        saturated_fat_g -> Nullable<Float8>,
+        carbohydrate_g -> Nullable<Float8>,
+        sugar_g -> Nullable<Float8>,
+        fibre_g -> Nullable<Float8>,
+        protein_g -> Nullable<Float8>,
+        salt_g -> Nullable<Float8>,
+    }
+}
+
 diesel::table! {
     recipe_steps (id) {
         id -> Int8,
@@ -62,6 +143,14 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    recipe_tags (recipe_id, tag_id) {
+        recipe_id -> Int8,
+        tag_id -> Int8,
+        created_at -> Timestamp,
+    }
+}
+
 diesel::table! {
     recipes (id) {
         id -> Int8,
@@ -73,23 +162,94 @@ diesel::table! {
         prep_time_minutes -> Nullable<Int4>,
         cooking_time_minutes -> Nullable<Int4>,
         image_url -> Nullable<Text>,
+        servings -> Nullable<Int4>,
+        owner_id -> Nullable<Int8>,
+        shared -> Bool,
+        sync_id -> Text,
+        updated_at -> Timestamp,
+        changed_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    sessions (token_hash) {
+        token_hash -> Text,
+        user_id -> Int8,
+        created_at -> Timestamp,
+        expires_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    sync_cursors (remote) {
+        remote -> Text,
+        pulled -> Nullable<Timestamp>,
+        pushed -> Nullable<Timestamp>,
+    }
+}
+
+diesel::table! {
+    tags (id) {
+        id -> Int8,
+        created_at -> Timestamp,
+        name -> Text,
+        slug -> Text,
+        kind -> Text,
+    }
+}
+
+diesel::table! {
+    users (id) {
+        id -> Int8,
+        created_at -> Timestamp,
+        username -> Text,
+        display_name -> Text,
+        password_hash -> Text,
     }
 }
 
+diesel::joinable!(deletions -> users (owner_id));
+diesel::joinable!(household_meal_plans -> households (household_id));
+diesel::joinable!(household_meal_plans -> meal_plans (meal_plan_id));
+diesel::joinable!(household_members -> households (household_id));
+diesel::joinable!(household_members -> users (user_id));
+diesel::joinable!(household_recipes -> households (household_id));
+diesel::joinable!(household_recipes -> recipes (recipe_id));
+diesel::joinable!(ingredient_flags -> ingredients (ingredient_id));
 diesel::joinable!(ingredients -> measurements (default_measurement_id));
 diesel::joinable!(meal_plan_recipes -> meal_plans (meal_plan_id));
 diesel::joinable!(meal_plan_recipes -> recipes (recipe_id));
+diesel::joinable!(meal_plans -> users (owner_id));
+diesel::joinable!(recipe_diets -> recipes (recipe_id));
 diesel::joinable!(recipe_ingredients -> ingredients (ingredient_id));
 diesel::joinable!(recipe_ingredients -> measurements (measurement_id));
 diesel::joinable!(recipe_ingredients -> recipes (recipe_id));
+diesel::joinable!(recipe_nutrition -> recipes (recipe_id));
 diesel::joinable!(recipe_steps -> recipes (recipe_id));
+diesel::joinable!(recipe_tags -> recipes (recipe_id));
+diesel::joinable!(recipe_tags -> tags (tag_id));
+diesel::joinable!(recipes -> users (owner_id));
+diesel::joinable!(sessions -> users (user_id));
 
 diesel::allow_tables_to_appear_in_same_query!(
+    deletions,
+    household_meal_plans,
+    household_members,
+    household_recipes,
+    households,
+    ingredient_flags,
     ingredients,
     meal_plan_recipes,
     meal_plans,
     measurements,
+    recipe_diets,
     recipe_ingredients,
+    recipe_nutrition,
     recipe_steps,
+    recipe_tags,
     recipes,
+    sessions,
+    sync_cursors,
+    tags,
+    users,
 );
@@ -0,0 +1,89 @@
+use unicode_normalization::{char::is_combining_mark, UnicodeNormalization};
+
+/// Turn a name into the slug used in URLs, e.g. `Crème Brûlée` into
+/// `creme-brulee`.
+///
+/// This matches the `slugify` function which Postgres used to generate slugs
+/// with, so slugs stay the same whichever backend a recipe was saved with:
+/// German umlauts are spelled out, other accents are dropped, quotes are
+/// removed and anything else which isn't a letter, digit, `-` or `_` becomes
+/// a single `-`.
+pub fn slugify(name: &str) -> String {
+    let mut unaccented = String::with_capacity(name.len());
+    for c in name.chars() {
+        match c {
+            'Ä' | 'ä' => unaccented.push_str("ae"),
+            'Ö' | 'ö' => unaccented.push_str("oe"),
+            'Ü' | 'ü' => unaccented.push_str("ue"),
+            c => match ligature(c) {
+                Some(s) => unaccented.push_str(s),
+                None => unaccented.extend(c.nfd().filter(|c| !is_combining_mark(*c))),
+            },
+        }
+    }
+
+    let mut slug = String::with_capacity(unaccented.len());
+    let mut replacing = false;
+    for c in unaccented.to_lowercase().chars() {
+        match c {
+            '\'' | '"' => {}
+            'a'..='z' | '0'..='9' | '-' | '_' => {
+                slug.push(c);
+                replacing = false;
+            }
+            _ if replacing => {}
+            _ => {
+                slug.push('-');
+                replacing = true;
+            }
+        }
+    }
+    let slug = slug.trim_end_matches('-');
+    slug.strip_prefix('-').unwrap_or(slug).to_string()
+}
+
+/// Letters which `unaccent` replaces with something other than their base
+/// letter, since they don't decompose.
+fn ligature(c: char) -> Option<&'static str> {
+    Some(match c {
+        'ß' => "ss",
+        'Æ' | 'æ' => "ae",
+        'Œ' | 'œ' => "oe",
+        'Ø' | 'ø' => "o",
+        'Ł' | 'ł' => "l",
+        'Đ' | 'đ' => "d",
+        'Þ' | 'þ' => "th",
+        'ı' => "i",
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::slugify;
+
+    #[test]
+    fn punctuation() {
+        assert_eq!(slugify("Spaghetti Bolognese"), "spaghetti-bolognese");
+        assert_eq!(slugify("Mum's \"best\" chilli"), "mums-best-chilli");
+        assert_eq!(slugify("Fish & chips!"), "fish-chips");
+        assert_eq!(slugify("  salt_and-pepper  "), "salt_and-pepper");
+        assert_eq!(slugify("tbsp."), "tbsp");
+        assert_eq!(slugify("salt - pepper"), "salt---pepper");
+    }
+
+    #[test]
+    fn accents() {
+        assert_eq!(slugify("Crème Brûlée"), "creme-brulee");
+        assert_eq!(slugify("Käsespätzle"), "kaesespaetzle");
+        assert_eq!(slugify("Smørrebrød"), "smorrebrod");
+        assert_eq!(slugify("Weißwurst"), "weisswurst");
+    }
+
+    #[test]
+    fn leading_dashes() {
+        // Like the Postgres version, only one leading dash is removed.
+        assert_eq!(slugify("--x"), "-x");
+        assert_eq!(slugify("(x)"), "x");
+    }
+}
@@ -0,0 +1,42 @@
+use std::{env, fs, path::PathBuf};
+
+use anyhow::{Context, Result};
+
+use recipe_app::server::AppState;
+use recipe_repository::{DieselRepository, UserRepository};
+
+/// Open (creating if need be) the database in the user's data directory, or
+/// at `RECIPE_DATABASE` if it's set, and have server functions use it.
+///
+/// Everything is owned by a single local user named after the account
+/// running the app, so there's no need to sign in. If a server to sync with
+/// is configured, syncing starts in the background.
+pub fn init() -> Result<()> {
+    let path = match env::var_os("RECIPE_DATABASE") {
+        Some(path) => PathBuf::from(path),
+        None => {
+            let dir = dirs::data_dir()
+                .context("no data directory to keep recipes in")?
+                .join("recipes");
+            fs::create_dir_all(&dir).with_context(|| format!("creating {}", dir.display()))?;
+            dir.join("recipes.db")
+        }
+    };
+    let database_url = path.to_string_lossy();
+    log::info!("using local database at {database_url}");
+    recipe_db::run_migrations(&database_url)?;
+
+    let pool = recipe_db::build_pool(&database_url)
+        .build()
+        .context("creating db pool")?;
+    let repo = DieselRepository::new(pool);
+    let username = env::var("USER")
+        .or_else(|_| env::var("USERNAME"))
+        .unwrap_or_else(|_| "me".to_string());
+    let user = tokio::runtime::Runtime::new()?
+        .block_on(repo.local_user(&username))
+        .context("creating local user")?;
+    crate::sync::spawn(repo.for_user(Some(user.id.into())))?;
+    AppState::new(repo).install_local(user);
+    Ok(())
+}
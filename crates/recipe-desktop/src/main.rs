@@ -4,9 +4,17 @@ use log::LevelFilter;
 
 use recipe_app::Route;
 
+#[cfg(feature = "local")]
+mod local;
+#[cfg(feature = "local")]
+mod sync;
+
 fn main() {
-    server_fn::set_server_url("http://127.0.0.1:8080");
     dioxus_logger::init(LevelFilter::Debug).expect("failed to init logger");
+    #[cfg(feature = "local")]
+    local::init().expect("failed to open local database");
+    #[cfg(not(feature = "local"))]
+    server_fn::set_server_url("http://127.0.0.1:8080");
     let config = Config::new()
         .with_custom_head(r#"<link rel="stylesheet" href="public/tailwind.css">"#.to_string())
         .with_window(WindowBuilder::new().with_title("Recipes"));
@@ -0,0 +1,148 @@
+use std::{env, thread, time::Duration};
+
+use anyhow::{Context, Result};
+use reqwest::{Client, StatusCode};
+
+use recipe_repository::{DieselRepository, SyncRepository};
+use recipe_shared::{Credentials, SyncCursor, SyncRequest, SyncResponse};
+
+/// How often to sync when `RECIPE_SYNC_INTERVAL` isn't set, in seconds.
+const DEFAULT_INTERVAL: u64 = 300;
+
+/// Where to sync to and who as, from the environment.
+struct Remote {
+    url: String,
+    credentials: Credentials,
+    interval: Duration,
+}
+
+impl Remote {
+    /// The server set in `RECIPE_SYNC_URL`, if any, along with the
+    /// `RECIPE_SYNC_USERNAME` and `RECIPE_SYNC_PASSWORD` to sign in with.
+    fn from_env() -> Result<Option<Self>> {
+        let Ok(url) = env::var("RECIPE_SYNC_URL") else {
+            return Ok(None);
+        };
+        let credentials = Credentials {
+            username: env::var("RECIPE_SYNC_USERNAME")
+                .context("RECIPE_SYNC_USERNAME must be set to sync")?,
+            password: env::var("RECIPE_SYNC_PASSWORD")
+                .context("RECIPE_SYNC_PASSWORD must be set to sync")?,
+        };
+        let interval = match env::var("RECIPE_SYNC_INTERVAL") {
+            Ok(seconds) => seconds
+                .parse()
+                .context("RECIPE_SYNC_INTERVAL must be a number of seconds")?,
+            Err(_) => DEFAULT_INTERVAL,
+        };
+        Ok(Some(Self {
+            url: url.trim_end_matches('/').to_string(),
+            credentials,
+            interval: Duration::from_secs(interval),
+        }))
+    }
+
+    fn endpoint(&self, path: &str) -> String {
+        format!("{}/api/v1/{path}", self.url)
+    }
+}
+
+/// Keep the local database in sync with the server in `RECIPE_SYNC_URL` on
+/// a background thread, if one is set. `repo` must act as the local user.
+pub fn spawn(repo: DieselRepository) -> Result<()> {
+    let Some(remote) = Remote::from_env()? else {
+        return Ok(());
+    };
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()?;
+    thread::Builder::new()
+        .name("sync".to_string())
+        .spawn(move || runtime.block_on(run(repo, remote)))?;
+    Ok(())
+}
+
+async fn run(repo: DieselRepository, remote: Remote) {
+    let client = Client::new();
+    let mut token = None;
+    loop {
+        match sync(&client, &repo, &remote, &mut token).await {
+            Ok(()) => log::debug!("synced with {}", remote.url),
+            Err(e) => log::warn!("syncing with {} failed: {e:#}", remote.url),
+        }
+        tokio::time::sleep(remote.interval).await;
+    }
+}
+
+/// Send the changes made here since the last sync and apply those made on
+/// the server, signing in first if need be.
+async fn sync(
+    client: &Client,
+    repo: &DieselRepository,
+    remote: &Remote,
+    token: &mut Option<String>,
+) -> Result<()> {
+    let bearer = match token.clone() {
+        Some(bearer) => bearer,
+        None => token.insert(sign_in(client, remote).await?).clone(),
+    };
+    let cursor = repo.sync_cursor(&remote.url).await?;
+    let (changes, pushed) = repo.changes_since(cursor.pushed).await?;
+    let request = SyncRequest {
+        since: cursor.pulled,
+        changes,
+    };
+    let response = client
+        .post(remote.endpoint("sync"))
+        .bearer_auth(bearer)
+        .json(&request)
+        .send()
+        .await?;
+    if response.status() == StatusCode::UNAUTHORIZED {
+        // The session expired, so sign in again next time.
+        *token = None;
+    }
+    let response: SyncResponse = response.error_for_status()?.json().await?;
+    for rejection in &response.rejected {
+        log::warn!(
+            "{} rejected {} {}: {}",
+            remote.url,
+            rejection.kind,
+            rejection.sync_id,
+            rejection.reason
+        );
+    }
+    for rejection in repo.apply_changes(response.changes).await? {
+        log::warn!(
+            "couldn't apply {} {} from {}: {}",
+            rejection.kind,
+            rejection.sync_id,
+            remote.url,
+            rejection.reason
+        );
+    }
+    let cursor = SyncCursor {
+        pulled: Some(response.cursor),
+        pushed: Some(pushed),
+    };
+    repo.set_sync_cursor(&remote.url, cursor).await?;
+    Ok(())
+}
+
+async fn sign_in(client: &Client, remote: &Remote) -> Result<String> {
+    #[derive(serde::Deserialize)]
+    struct Session {
+        token: String,
+    }
+
+    let session: Session = client
+        .post(remote.endpoint("sessions"))
+        .json(&remote.credentials)
+        .send()
+        .await?
+        .error_for_status()
+        .context("signing in")?
+        .json()
+        .await?;
+    Ok(session.token)
+}
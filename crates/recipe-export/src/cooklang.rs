@@ -0,0 +1,340 @@
+use std::fmt::Write;
+
+use recipe_scrape::Unit;
+use recipe_shared::{RecipeDetail, RecipeIngredient, TagKind};
+
+/// Render a recipe as a [Cooklang](https://cooklang.org/docs/spec/) file,
+/// which keeps well in version control.
+///
+/// Each ingredient is marked up where the method first mentions it by name.
+/// Ingredients the method never names are listed as hidden ingredients at the
+/// start of it, so importing the file gives back the same recipe.
+pub fn to_cooklang(detail: &RecipeDetail) -> String {
+    let recipe = &detail.recipe;
+    let mut out = String::from("---\n");
+    // Writing to a `String` can't fail, so the results are ignored throughout.
+    let _ = writeln!(out, "title: {}", one_line(&recipe.name));
+    if let Some(source) = &recipe.source {
+        let _ = writeln!(out, "source: {}", one_line(source));
+    }
+    if let Some(servings) = recipe.servings {
+        let _ = writeln!(out, "servings: {servings}");
+    }
+    if let Some(minutes) = recipe.prep_time_minutes {
+        let _ = writeln!(out, "prep time: {minutes} minutes");
+    }
+    if let Some(minutes) = recipe.cooking_time_minutes {
+        let _ = writeln!(out, "cook time: {minutes} minutes");
+    }
+    if let Some(url) = &recipe.image_url {
+        let _ = writeln!(out, "image: {}", one_line(url));
+    }
+    for (key, kind) in [
+        ("course", TagKind::Category),
+        ("cuisine", TagKind::Cuisine),
+        ("tags", TagKind::Tag),
+    ] {
+        let names: Vec<_> = detail
+            .tags
+            .iter()
+            .filter(|tag| tag.kind == kind)
+            .map(|tag| tag.name.as_str())
+            .collect();
+        if !names.is_empty() {
+            let _ = writeln!(out, "{key}: [{}]", names.join(", "));
+        }
+    }
+    let diets: Vec<_> = detail
+        .diet
+        .declared
+        .iter()
+        .filter_map(|diet| diet.schema_org())
+        .collect();
+    if !diets.is_empty() {
+        let _ = writeln!(out, "diet: [{}]", diets.join(", "));
+    }
+    out.push_str("---\n");
+
+    if let Some(notes) = &recipe.notes {
+        let lines: Vec<_> = notes
+            .lines()
+            .map(str::trim)
+            .filter(|l| !l.is_empty())
+            .collect();
+        if !lines.is_empty() {
+            out.push('\n');
+            for line in lines {
+                let _ = writeln!(out, "> {line}");
+            }
+        }
+    }
+
+    for step in method(detail) {
+        let _ = write!(out, "\n{step}\n");
+    }
+    out
+}
+
+/// A mention of an ingredient in a step, to be replaced with its markup.
+struct Mention {
+    start: usize,
+    end: usize,
+    markup: String,
+}
+
+/// The steps with their ingredients marked up.
+fn method(detail: &RecipeDetail) -> Vec<String> {
+    let steps: Vec<_> = detail
+        .steps
+        .iter()
+        .map(|step| one_line(&step.description))
+        .filter(|step| !step.is_empty())
+        .collect();
+    let mut mentions: Vec<Vec<Mention>> = steps.iter().map(|_| Vec::new()).collect();
+    let mut hidden = vec![false; detail.ingredients.len()];
+
+    // Longer names are placed first so that "plain flour" isn't taken by
+    // "flour" when a recipe has both.
+    let mut order: Vec<_> = (0..detail.ingredients.len()).collect();
+    order.sort_by_key(|&i| std::cmp::Reverse(detail.ingredients[i].name.len()));
+    for i in order {
+        let ingredient = &detail.ingredients[i];
+        let found = steps.iter().enumerate().find_map(|(step, text)| {
+            mention(text, &ingredient.name, &mentions[step]).map(|start| (step, start))
+        });
+        match found {
+            Some((step, start)) => mentions[step].push(Mention {
+                start,
+                end: start + ingredient.name.len(),
+                markup: markup(ingredient, false),
+            }),
+            None => hidden[i] = true,
+        }
+    }
+
+    let mut method: Vec<String> = steps
+        .iter()
+        .zip(mentions)
+        .map(|(text, mut mentions)| {
+            mentions.sort_by_key(|m| m.start);
+            let mut out = String::with_capacity(text.len());
+            let mut last = 0;
+            for mention in mentions {
+                out.push_str(&text[last..mention.start]);
+                out.push_str(&mention.markup);
+                last = mention.end;
+            }
+            out.push_str(&text[last..]);
+            out
+        })
+        .collect();
+
+    let hidden: Vec<_> = detail
+        .ingredients
+        .iter()
+        .zip(hidden)
+        .filter(|(_, hidden)| *hidden)
+        .map(|(ingredient, _)| markup(ingredient, true))
+        .collect();
+    if !hidden.is_empty() {
+        let hidden = hidden.join(" ");
+        match method.first_mut() {
+            Some(first) => *first = format!("{hidden} {first}"),
+            None => method.push(hidden),
+        }
+    }
+    method
+}
+
+/// Find where `name` is mentioned as a whole word in `text`, outside of any
+/// mention already found.
+fn mention(text: &str, name: &str, taken: &[Mention]) -> Option<usize> {
+    if name.is_empty() {
+        return None;
+    }
+    let is_word = |c: char| c.is_alphanumeric() || c == '_';
+    text.match_indices(name).map(|(i, _)| i).find(|&start| {
+        let end = start + name.len();
+        let before = text[..start].chars().next_back();
+        let after = text[end..].chars().next();
+        !before.is_some_and(is_word)
+            && !after.is_some_and(is_word)
+            && !taken.iter().any(|m| start < m.end && m.start < end)
+    })
+}
+
+/// The Cooklang markup for an ingredient, e.g. `@plain flour{125%g}(sifted)`.
+fn markup(ingredient: &RecipeIngredient, hidden: bool) -> String {
+    let mut amount = ingredient
+        .quantity
+        .map(|q| q.to_string())
+        .unwrap_or_default();
+    if let Some(unit) = ingredient.unit.as_deref().filter(|u| !u.is_empty()) {
+        let unit = unit
+            .parse::<Unit>()
+            .map_or_else(|_| unit.to_string(), |u| u.to_string());
+        let _ = write!(amount, "%{unit}");
+    }
+    let modifier = if hidden { "-" } else { "" };
+    let mut markup = format!("@{modifier}{}{{{amount}}}", ingredient.name);
+    if let Some(notes) = ingredient.notes.as_deref().map(one_line) {
+        if !notes.is_empty() {
+            let _ = write!(markup, "({notes})");
+        }
+    }
+    markup
+}
+
+/// Join the lines of `text` into one, as Cooklang steps and metadata are
+/// line based.
+fn one_line(text: &str) -> String {
+    text.lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use url::Url;
+
+    use recipe_scrape::import::parse_cooklang;
+    use recipe_shared::{IngredientInput, RecipeInput};
+
+    use super::to_cooklang;
+    use crate::testing::detail;
+
+    fn import(text: &str) -> RecipeInput {
+        let fallback: Url = "file:///recipes/recipe.cook".parse().unwrap();
+        parse_cooklang("recipe", text, &fallback)
+            .unwrap()
+            .to_input()
+            .unwrap()
+    }
+
+    /// Import a recipe, export it and import it again, checking that nothing
+    /// changed along the way and that exporting again gives the same file.
+    fn round_trip(text: &str) -> (RecipeInput, String) {
+        let first = import(text);
+        let exported = to_cooklang(&detail(first.clone()));
+        let second = import(&exported);
+        assert_eq!(
+            first, second,
+            "re-importing changed the recipe:\n{exported}"
+        );
+        assert_eq!(exported, to_cooklang(&detail(second)));
+        (first, exported)
+    }
+
+    #[test]
+    fn pancakes() {
+        let (recipe, exported) = round_trip(
+            "\
+---
+title: Easy pancakes
+servings: 4
+prep time: 10 mins
+tags: [breakfast, quick]
+diet: [https://schema.org/VegetarianDiet]
+---
+
+> Best eaten straight away.
+
+Crack the @eggs{3} into a blender, then add the @plain flour{125%g}, @milk{250%ml} \
+and a pinch of @salt.
+
+Blitz until smooth, then rest for ~{20%minutes}.
+
+Heat a #large frying pan{} and fry ladlefuls of batter, adding more @milk{50%ml} \
+if the batter thickens.
+",
+        );
+        assert_eq!(recipe.ingredients.len(), 4);
+        assert_eq!(
+            exported,
+            "\
+---
+title: Easy pancakes
+source: file:///recipes/recipe.cook
+servings: 4
+prep time: 10 minutes
+tags: [breakfast, quick]
+diet: [https://schema.org/VegetarianDiet]
+---
+
+> Best eaten straight away.
+
+Crack the @eggs{3} into a blender, then add the @plain flour{125%g}, @milk{300%ml} \
+and a pinch of @salt{}.
+
+Blitz until smooth, then rest for 20 minutes.
+
+Heat a large frying pan and fry ladlefuls of batter, adding more milk if the batter thickens.
+"
+        );
+    }
+
+    #[test]
+    fn units_and_notes() {
+        let (recipe, _) = round_trip(
+            "\
+>> source: https://example.com/dal
+>> cook time: 1 hour 10 minutes
+>> cuisine: Indian
+>> diet: [https://schema.org/VeganDiet]
+
+Rinse the @red lentils{0.25%kg}(well rinsed) until the water runs clear.
+Simmer with @water{1%l} and @turmeric{1/2%tsp}.
+
+Finish with @lemon juice{2%tbsp}.
+",
+        );
+        assert_eq!(recipe.cooking_time_minutes, Some(70));
+        assert_eq!(recipe.steps.len(), 2);
+        let lentils = &recipe.ingredients[0];
+        assert_eq!(lentils.quantity, Some(0.25));
+        assert_eq!(lentils.notes.as_deref(), Some("well rinsed"));
+        assert_eq!(recipe.ingredients[2].quantity, Some(0.5));
+    }
+
+    #[test]
+    fn unmentioned_ingredients_are_hidden() {
+        let mut input = RecipeInput {
+            name: "Cheese on toast".to_string(),
+            steps: vec![
+                "Toast the bread.".to_string(),
+                "Top with the grated cheese and grill until bubbling.".to_string(),
+            ],
+            ..Default::default()
+        };
+        for (name, quantity) in [("sourdough", 2.0), ("cheddar", 50.0), ("cheese", 25.0)] {
+            input.ingredients.push(IngredientInput {
+                name: name.to_string(),
+                quantity: Some(quantity),
+                unit: (name != "sourdough").then(|| "g".to_string()),
+                notes: None,
+            });
+        }
+        let exported = to_cooklang(&detail(input.clone()));
+        assert!(exported.contains(
+            "\n@-sourdough{2} @-cheddar{50%g} Toast the bread.\n\nTop with the grated @cheese{25%g} and"
+        ));
+
+        let reimported = import(&exported);
+        assert_eq!(reimported.steps, input.steps);
+        let names: Vec<_> = reimported
+            .ingredients
+            .iter()
+            .map(|i| (i.name.as_str(), i.quantity))
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                ("sourdough", Some(2.0)),
+                ("cheddar", Some(50.0)),
+                ("cheese", Some(25.0)),
+            ]
+        );
+    }
+}
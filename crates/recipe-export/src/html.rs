@@ -0,0 +1,215 @@
+use std::fmt::Write;
+
+use recipe_shared::{RecipeDetail, TagKind};
+
+use crate::{amount, summary};
+
+/// Styles for the page, with the print rules dropping everything that's only
+/// useful on screen.
+const STYLE: &str = "
+body { font-family: Georgia, serif; max-width: 42rem; margin: 2rem auto; padding: 0 1rem; line-height: 1.5; color: #111; }
+h1 { margin-bottom: 0.25rem; }
+img { max-width: 100%; max-height: 18rem; object-fit: cover; }
+.summary, .tags, .source { color: #555; }
+.columns { display: grid; grid-template-columns: 1fr 2fr; gap: 2rem; }
+ol li { margin-bottom: 0.5rem; }
+table { border-collapse: collapse; }
+td { padding: 0.1rem 1rem 0.1rem 0; }
+td.amount { text-align: right; }
+@media print {
+  body { margin: 0; max-width: none; font-size: 11pt; }
+  img { display: none; }
+  a { color: inherit; text-decoration: none; }
+  h2 { break-after: avoid; }
+  li, tr { break-inside: avoid; }
+}
+";
+
+/// Render a recipe as a standalone HTML page laid out for printing.
+pub fn to_html(detail: &RecipeDetail) -> String {
+    let recipe = &detail.recipe;
+    let name = escape(&recipe.name);
+    let mut out = String::new();
+    // Writing to a `String` can't fail, so the results are ignored throughout.
+    let _ = write!(
+        out,
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+         <title>{name}</title>\n<style>{STYLE}</style>\n</head>\n<body>\n<h1>{name}</h1>\n"
+    );
+    let summary = summary(recipe);
+    if !summary.is_empty() {
+        let _ = writeln!(
+            out,
+            "<p class=\"summary\">{}</p>",
+            escape(&summary.join(" · "))
+        );
+    }
+    for kind in TagKind::ALL {
+        let names: Vec<_> = detail
+            .tags
+            .iter()
+            .filter(|tag| tag.kind == kind)
+            .map(|tag| escape(&tag.name))
+            .collect();
+        if !names.is_empty() {
+            let _ = writeln!(
+                out,
+                "<p class=\"tags\"><strong>{}:</strong> {}</p>",
+                kind.label(),
+                names.join(", ")
+            );
+        }
+    }
+    let diets: Vec<_> = detail
+        .diet
+        .suitable_diets()
+        .iter()
+        .map(|diet| diet.label())
+        .collect();
+    if !diets.is_empty() {
+        let _ = writeln!(
+            out,
+            "<p class=\"tags\"><strong>Suitable for:</strong> {}</p>",
+            diets.join(", ")
+        );
+    }
+    if let Some(url) = &recipe.image_url {
+        let _ = writeln!(out, "<img src=\"{}\" alt=\"{name}\">", escape(url));
+    }
+
+    out.push_str("<div class=\"columns\">\n<section>\n<h2>Ingredients</h2>\n<ul>\n");
+    for ingredient in &detail.ingredients {
+        let _ = writeln!(out, "<li>{}</li>", escape(&ingredient.to_line()));
+    }
+    out.push_str("</ul>\n</section>\n<section>\n<h2>Method</h2>\n<ol>\n");
+    for step in &detail.steps {
+        let _ = writeln!(out, "<li>{}</li>", escape(&step.description));
+    }
+    out.push_str("</ol>\n</section>\n</div>\n");
+
+    if let Some(notes) = recipe.notes.as_deref().filter(|n| !n.trim().is_empty()) {
+        out.push_str("<h2>Notes</h2>\n");
+        for paragraph in notes.split("\n\n").filter(|p| !p.trim().is_empty()) {
+            let _ = writeln!(out, "<p>{}</p>", escape(paragraph.trim()));
+        }
+    }
+
+    let (nutrition, _) = detail.nutrition.per_serving();
+    if !nutrition.is_empty() {
+        out.push_str("<h2>Nutrition per serving</h2>\n<table>\n");
+        for (label, unit, value) in nutrition.rows() {
+            if let Some(value) = value {
+                let _ = writeln!(
+                    out,
+                    "<tr><td>{label}</td><td class=\"amount\">{} {unit}</td></tr>",
+                    amount(value)
+                );
+            }
+        }
+        out.push_str("</table>\n");
+    }
+
+    if let Some(source) = &recipe.source {
+        let source = escape(source);
+        if source.starts_with("http://") || source.starts_with("https://") {
+            let _ = writeln!(
+                out,
+                "<p class=\"source\">Source: <a href=\"{source}\">{source}</a></p>"
+            );
+        } else {
+            let _ = writeln!(out, "<p class=\"source\">Source: {source}</p>");
+        }
+    }
+    out.push_str("</body>\n</html>\n");
+    out
+}
+
+/// Escape text for use in HTML content and quoted attribute values.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::to_html;
+    use crate::testing::flapjacks;
+
+    #[test]
+    fn full_recipe() {
+        let html = to_html(&flapjacks());
+        assert!(html.contains("<title>Flapjacks</title>"));
+        let (_, body) = html.split_once("</head>\n").unwrap();
+        assert_eq!(
+            body,
+            r#"<body>
+<h1>Flapjacks</h1>
+<p class="summary">Serves 12 · Prep 10 mins · Cook 25 mins</p>
+<p class="tags"><strong>Categories:</strong> Baking</p>
+<p class="tags"><strong>Tags:</strong> quick</p>
+<p class="tags"><strong>Suitable for:</strong> Vegetarian, Egg free, Nut free</p>
+<div class="columns">
+<section>
+<h2>Ingredients</h2>
+<ul>
+<li>250 g oats</li>
+<li>125 g butter</li>
+<li>3 tbsp golden syrup, warmed</li>
+</ul>
+</section>
+<section>
+<h2>Method</h2>
+<ol>
+<li>Melt the butter and syrup.</li>
+<li>Stir in the oats and bake for 25 minutes.</li>
+</ol>
+</section>
+</div>
+<h2>Notes</h2>
+<p>Keeps for a week.</p>
+<h2>Nutrition per serving</h2>
+<table>
+<tr><td>Energy</td><td class="amount">200 kcal</td></tr>
+<tr><td>Fat</td><td class="amount">9.5 g</td></tr>
+<tr><td>Salt</td><td class="amount">0.5 g</td></tr>
+</table>
+<p class="source">Source: <a href="https://example.com/flapjacks">https://example.com/flapjacks</a></p>
+</body>
+</html>
+"#
+        );
+    }
+
+    #[test]
+    fn text_is_escaped() {
+        let mut detail = flapjacks();
+        detail.recipe.name = "<script>alert('hi')</script>".to_string();
+        detail.recipe.image_url =
+            Some("https://example.com/a.jpg\" onerror=\"alert(1)".to_string());
+        detail.recipe.source = Some("<b>Granny</b> & co".to_string());
+        detail.steps[0].description = "Melt <em>everything</em>.".to_string();
+        let html = to_html(&detail);
+        assert!(!html.contains("<script>"));
+        assert!(!html.contains("<b>"));
+        assert!(!html.contains("<em>"));
+        assert!(!html.contains("\" onerror"));
+        let name = "&lt;script&gt;alert(&#39;hi&#39;)&lt;/script&gt;";
+        assert!(html.contains(&format!("<title>{name}</title>")));
+        assert!(html.contains(&format!("<h1>{name}</h1>")));
+        assert!(html.contains(&format!(
+            "<img src=\"https://example.com/a.jpg&quot; onerror=&quot;alert(1)\" alt=\"{name}\">"
+        )));
+        assert!(html.contains("<li>Melt &lt;em&gt;everything&lt;/em&gt;.</li>"));
+        assert!(html.contains("<p class=\"source\">Source: &lt;b&gt;Granny&lt;/b&gt; &amp; co</p>"));
+    }
+}
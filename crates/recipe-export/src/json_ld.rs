@@ -0,0 +1,186 @@
+use serde_json::{json, Map, Value};
+
+use recipe_shared::{RecipeDetail, TagKind};
+
+use crate::amount;
+
+/// Render a recipe as a schema.org `Recipe` in JSON-LD.
+pub fn to_json_ld(detail: &RecipeDetail) -> String {
+    serde_json::to_string_pretty(&to_value(detail)).expect("JSON values always serialize")
+}
+
+fn to_value(detail: &RecipeDetail) -> Value {
+    let recipe = &detail.recipe;
+    let mut object = Map::new();
+    object.insert("@context".into(), json!("https://schema.org"));
+    object.insert("@type".into(), json!("Recipe"));
+    object.insert("name".into(), json!(recipe.name));
+    object.insert(
+        "datePublished".into(),
+        json!(recipe.created_at.date().to_string()),
+    );
+    if let Some(notes) = &recipe.notes {
+        object.insert("description".into(), json!(notes));
+    }
+    if let Some(url) = &recipe.image_url {
+        object.insert("image".into(), json!(url));
+    }
+    if let Some(source) = &recipe.source {
+        object.insert("isBasedOn".into(), json!(source));
+    }
+    if let Some(servings) = recipe.servings {
+        object.insert("recipeYield".into(), json!(servings.to_string()));
+    }
+    let times = [
+        ("prepTime", recipe.prep_time_minutes),
+        ("cookTime", recipe.cooking_time_minutes),
+        (
+            "totalTime",
+            match (recipe.prep_time_minutes, recipe.cooking_time_minutes) {
+                (Some(prep), Some(cook)) => Some(prep + cook),
+                (prep, cook) => prep.or(cook),
+            },
+        ),
+    ];
+    for (key, minutes) in times {
+        if let Some(minutes) = minutes {
+            object.insert(key.into(), json!(format!("PT{minutes}M")));
+        }
+    }
+
+    let tags = |kind: TagKind| -> Vec<&str> {
+        detail
+            .tags
+            .iter()
+            .filter(|tag| tag.kind == kind)
+            .map(|tag| tag.name.as_str())
+            .collect()
+    };
+    for (key, kind) in [
+        ("recipeCategory", TagKind::Category),
+        ("recipeCuisine", TagKind::Cuisine),
+    ] {
+        let names = tags(kind);
+        if !names.is_empty() {
+            object.insert(key.into(), json!(names));
+        }
+    }
+    let keywords = tags(TagKind::Tag);
+    if !keywords.is_empty() {
+        object.insert("keywords".into(), json!(keywords.join(", ")));
+    }
+    let diets: Vec<_> = detail
+        .diet
+        .suitable_diets()
+        .iter()
+        .filter_map(|diet| diet.schema_org())
+        .collect();
+    if !diets.is_empty() {
+        object.insert("suitableForDiet".into(), json!(diets));
+    }
+
+    let ingredients: Vec<_> = detail.ingredients.iter().map(|i| i.to_line()).collect();
+    object.insert("recipeIngredient".into(), json!(ingredients));
+    let steps: Vec<_> = detail
+        .steps
+        .iter()
+        .enumerate()
+        .map(|(i, step)| {
+            json!({
+                "@type": "HowToStep",
+                "position": i + 1,
+                "text": step.description,
+            })
+        })
+        .collect();
+    object.insert("recipeInstructions".into(), json!(steps));
+
+    let (nutrition, _) = detail.nutrition.per_serving();
+    if !nutrition.is_empty() {
+        let mut values = Map::new();
+        values.insert("@type".into(), json!("NutritionInformation"));
+        let properties = [
+            ("calories", nutrition.energy_kcal, "kcal"),
+            ("fatContent", nutrition.fat_g, "g"),
+            ("saturatedFatContent", nutrition.saturated_fat_g, "g"),
+            ("carbohydrateContent", nutrition.carbohydrate_g, "g"),
+            ("sugarContent", nutrition.sugar_g, "g"),
+            ("fiberContent", nutrition.fibre_g, "g"),
+            ("proteinContent", nutrition.protein_g, "g"),
+            // schema.org only knows about sodium, of which salt is roughly two
+            // and a half times the weight.
+            (
+                "sodiumContent",
+                nutrition.salt_g.map(|salt| salt / 2.5),
+                "g",
+            ),
+        ];
+        for (key, value, unit) in properties {
+            if let Some(value) = value {
+                values.insert(key.into(), json!(format!("{} {unit}", amount(value))));
+            }
+        }
+        object.insert("nutrition".into(), Value::Object(values));
+    }
+
+    Value::Object(object)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Value};
+
+    use super::to_json_ld;
+    use crate::testing::flapjacks;
+
+    #[test]
+    fn full_recipe() {
+        let value: Value = serde_json::from_str(&to_json_ld(&flapjacks())).unwrap();
+        assert_eq!(
+            value,
+            json!({
+                "@context": "https://schema.org",
+                "@type": "Recipe",
+                "name": "Flapjacks",
+                "datePublished": "2024-01-01",
+                "description": "Keeps for a week.",
+                "isBasedOn": "https://example.com/flapjacks",
+                "recipeYield": "12",
+                "prepTime": "PT10M",
+                "cookTime": "PT25M",
+                "totalTime": "PT35M",
+                "recipeCategory": ["Baking"],
+                "keywords": "quick",
+                "suitableForDiet": ["https://schema.org/VegetarianDiet"],
+                "recipeIngredient": ["250 g oats", "125 g butter", "3 tbsp golden syrup, warmed"],
+                "recipeInstructions": [
+                    {
+                        "@type": "HowToStep",
+                        "position": 1,
+                        "text": "Melt the butter and syrup.",
+                    },
+                    {
+                        "@type": "HowToStep",
+                        "position": 2,
+                        "text": "Stir in the oats and bake for 25 minutes.",
+                    },
+                ],
+                "nutrition": {
+                    "@type": "NutritionInformation",
+                    "calories": "200 kcal",
+                    "fatContent": "9.5 g",
+                    "sodiumContent": "0.2 g",
+                },
+            })
+        );
+    }
+
+    #[test]
+    fn total_time_is_either_time_alone() {
+        let mut detail = flapjacks();
+        detail.recipe.prep_time_minutes = None;
+        let value: Value = serde_json::from_str(&to_json_ld(&detail)).unwrap();
+        assert_eq!(value.get("prepTime"), None);
+        assert_eq!(value["totalTime"], "PT25M");
+    }
+}
@@ -0,0 +1,138 @@
+//! Rendering stored recipes into formats for sharing, archiving and
+//! printing.
+
+use std::{fmt, str::FromStr};
+
+use recipe_shared::{Recipe, RecipeDetail};
+
+mod cooklang;
+mod html;
+mod json_ld;
+mod markdown;
+#[cfg(feature = "pdf")]
+pub mod pdf;
+#[cfg(test)]
+mod testing;
+
+pub use cooklang::to_cooklang;
+pub use html::to_html;
+pub use json_ld::to_json_ld;
+pub use markdown::to_markdown;
+
+/// A format a recipe can be exported to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Format {
+    Markdown,
+    /// A schema.org `Recipe`, as understood by other recipe managers and
+    /// search engines.
+    JsonLd,
+    /// A standalone page styled for printing.
+    Html,
+    /// A Cooklang `.cook` file, with the ingredients marked up in the method.
+    Cooklang,
+}
+
+impl Format {
+    pub const ALL: [Self; 4] = [Self::Markdown, Self::JsonLd, Self::Html, Self::Cooklang];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Markdown => "markdown",
+            Self::JsonLd => "json-ld",
+            Self::Html => "html",
+            Self::Cooklang => "cooklang",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Markdown => "Markdown",
+            Self::JsonLd => "JSON-LD",
+            Self::Html => "Printable page",
+            Self::Cooklang => "Cooklang",
+        }
+    }
+
+    /// The extension of files in this format, without the leading dot.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Markdown => "md",
+            Self::JsonLd => "jsonld",
+            Self::Html => "html",
+            Self::Cooklang => "cook",
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            Self::Markdown => "text/markdown; charset=utf-8",
+            Self::JsonLd => "application/ld+json",
+            Self::Html => "text/html; charset=utf-8",
+            Self::Cooklang => "text/plain; charset=utf-8",
+        }
+    }
+
+    /// The name to save an exported recipe under.
+    pub fn file_name(&self, recipe: &Recipe) -> String {
+        format!("{}.{}", recipe.slug, self.extension())
+    }
+
+    pub fn render(&self, recipe: &RecipeDetail) -> String {
+        match self {
+            Self::Markdown => to_markdown(recipe),
+            Self::JsonLd => to_json_ld(recipe),
+            Self::Html => to_html(recipe),
+            Self::Cooklang => to_cooklang(recipe),
+        }
+    }
+}
+
+impl fmt::Display for Format {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|format| format.as_str() == s)
+            .ok_or_else(|| format!("unknown export format {s}"))
+    }
+}
+
+/// Format a number of minutes for people, e.g. "1 hr 15 mins".
+fn duration(minutes: i32) -> String {
+    let plural = |n: i32| if n == 1 { "" } else { "s" };
+    let (hours, minutes) = (minutes / 60, minutes % 60);
+    match (hours, minutes) {
+        (0, m) => format!("{m} min{}", plural(m)),
+        (h, 0) => format!("{h} hr{}", plural(h)),
+        (h, m) => format!("{h} hr{} {m} min{}", plural(h), plural(m)),
+    }
+}
+
+/// The servings and times of a recipe, e.g. "Serves 4 · Prep 15 mins".
+fn summary(recipe: &Recipe) -> Vec<String> {
+    [
+        recipe.servings.map(|s| format!("Serves {s}")),
+        recipe
+            .prep_time_minutes
+            .map(|m| format!("Prep {}", duration(m))),
+        recipe
+            .cooking_time_minutes
+            .map(|m| format!("Cook {}", duration(m))),
+    ]
+    .into_iter()
+    .flatten()
+    .collect()
+}
+
+/// Format a nutritional value, dropping needless decimal places.
+fn amount(value: f64) -> String {
+    let rounded = (value * 10.0).round() / 10.0;
+    rounded.to_string()
+}
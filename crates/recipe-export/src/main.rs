@@ -0,0 +1,55 @@
+use std::{
+    env::{self, args},
+    io::{stdout, Write},
+};
+
+use anyhow::{anyhow, Context, Result};
+
+use recipe_export::Format;
+use recipe_repository::{BySlug, DieselRepository, UserRepository};
+use recipe_shared::{Credentials, RecipeDetail};
+
+/// Print a stored recipe in the given format, e.g.
+/// `RECIPE_PASSWORD=... recipe-export alice pdf spaghetti-with-tomato-sauce > spaghetti.pdf`.
+///
+/// Any recipe the given user can see can be exported, including their own and
+/// those shared with their households.
+#[tokio::main]
+async fn main() -> Result<()> {
+    let mut args = args().skip(1);
+    let usage = || {
+        let mut formats: Vec<_> = Format::ALL.iter().map(Format::as_str).collect();
+        formats.push("pdf");
+        anyhow!(
+            "usage: recipe-export <username> <{}> <slug>",
+            formats.join("|")
+        )
+    };
+    let username = args.next().ok_or_else(usage)?;
+    // PDFs are binary, so they're rendered separately from the text formats.
+    let format = match args.next().ok_or_else(usage)?.as_str() {
+        "pdf" => None,
+        other => Some(other.parse::<Format>().map_err(|e| anyhow!(e))?),
+    };
+    let slug = args.next().ok_or_else(usage)?;
+    let password = env::var("RECIPE_PASSWORD").context("RECIPE_PASSWORD must be set")?;
+
+    let pool = recipe_db::create_pool().context("creating db pool")?;
+    let repo = DieselRepository::new(pool);
+    let user = repo
+        .authenticate(Credentials { username, password })
+        .await
+        .context("signing in")?
+        .ok_or_else(|| anyhow!("wrong username or password"))?;
+    let repo = repo.for_user(Some(user.id.into()));
+    let recipe = BySlug::<RecipeDetail>::get_by_slug(&repo, &slug)
+        .await
+        .context("loading recipe")?
+        .ok_or_else(|| anyhow!("no recipe with slug {slug}"))?;
+    let output = match format {
+        Some(format) => format.render(&recipe).into_bytes(),
+        None => recipe_export::pdf::recipe_card(&recipe),
+    };
+    stdout().write_all(&output).context("writing recipe")?;
+    Ok(())
+}
@@ -0,0 +1,148 @@
+use std::fmt::Write;
+
+use recipe_shared::{RecipeDetail, TagKind};
+
+use crate::{amount, summary};
+
+/// Render a recipe as a Markdown document.
+pub fn to_markdown(detail: &RecipeDetail) -> String {
+    let recipe = &detail.recipe;
+    let mut out = String::new();
+    // Writing to a `String` can't fail, so the results are ignored throughout.
+    let _ = writeln!(out, "# {}\n", recipe.name);
+    if let Some(url) = &recipe.image_url {
+        let _ = writeln!(out, "![{}]({url})\n", recipe.name);
+    }
+    let summary = summary(recipe);
+    if !summary.is_empty() {
+        let _ = writeln!(out, "{}\n", summary.join(" · "));
+    }
+    for kind in TagKind::ALL {
+        let names: Vec<_> = detail
+            .tags
+            .iter()
+            .filter(|tag| tag.kind == kind)
+            .map(|tag| tag.name.as_str())
+            .collect();
+        if !names.is_empty() {
+            let _ = writeln!(out, "**{}:** {}\n", kind.label(), names.join(", "));
+        }
+    }
+    let diets: Vec<_> = detail
+        .diet
+        .suitable_diets()
+        .iter()
+        .map(|diet| diet.label())
+        .collect();
+    if !diets.is_empty() {
+        let _ = writeln!(out, "**Suitable for:** {}\n", diets.join(", "));
+    }
+
+    if !detail.ingredients.is_empty() {
+        out.push_str("## Ingredients\n\n");
+        for ingredient in &detail.ingredients {
+            let _ = writeln!(out, "- {}", ingredient.to_line());
+        }
+        out.push('\n');
+    }
+
+    if !detail.steps.is_empty() {
+        out.push_str("## Method\n\n");
+        for (i, step) in detail.steps.iter().enumerate() {
+            let _ = writeln!(out, "{}. {}", i + 1, step.description);
+        }
+        out.push('\n');
+    }
+
+    if let Some(notes) = recipe.notes.as_deref().filter(|n| !n.trim().is_empty()) {
+        let _ = writeln!(out, "## Notes\n\n{}\n", notes.trim());
+    }
+
+    let (nutrition, _) = detail.nutrition.per_serving();
+    if !nutrition.is_empty() {
+        out.push_str("## Nutrition per serving\n\n| Nutrient | Amount |\n| --- | ---: |\n");
+        for (label, unit, value) in nutrition.rows() {
+            if let Some(value) = value {
+                let _ = writeln!(out, "| {label} | {} {unit} |", amount(value));
+            }
+        }
+        out.push('\n');
+    }
+
+    if let Some(source) = &recipe.source {
+        let _ = writeln!(out, "Source: {source}");
+    }
+    out.trim_end().to_string() + "\n"
+}
+
+#[cfg(test)]
+mod tests {
+    use recipe_shared::RecipeInput;
+
+    use super::to_markdown;
+    use crate::testing::{detail, flapjacks};
+
+    #[test]
+    fn full_recipe() {
+        assert_eq!(
+            to_markdown(&flapjacks()),
+            "\
+# Flapjacks
+
+Serves 12 · Prep 10 mins · Cook 25 mins
+
+**Categories:** Baking
+
+**Tags:** quick
+
+**Suitable for:** Vegetarian, Egg free, Nut free
+
+## Ingredients
+
+- 250 g oats
+- 125 g butter
+- 3 tbsp golden syrup, warmed
+
+## Method
+
+1. Melt the butter and syrup.
+2. Stir in the oats and bake for 25 minutes.
+
+## Notes
+
+Keeps for a week.
+
+## Nutrition per serving
+
+| Nutrient | Amount |
+| --- | ---: |
+| Energy | 200 kcal |
+| Fat | 9.5 g |
+| Salt | 0.5 g |
+
+Source: https://example.com/flapjacks
+"
+        );
+    }
+
+    #[test]
+    fn empty_sections_are_left_out() {
+        let toast = detail(RecipeInput {
+            name: "Toast".to_string(),
+            steps: vec!["Toast the bread.".to_string()],
+            ..Default::default()
+        });
+        assert_eq!(
+            to_markdown(&toast),
+            "\
+# Toast
+
+**Suitable for:** Vegetarian, Vegan, Gluten free, Dairy free, Egg free, Nut free
+
+## Method
+
+1. Toast the bread.
+"
+        );
+    }
+}
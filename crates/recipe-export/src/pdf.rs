@@ -0,0 +1,256 @@
+//! Printable PDF documents, rendered with the standard PDF fonts so no font
+//! files or external services are needed.
+
+use recipe_shared::{MealPlanDetail, RecipeDetail, ShoppingList, TagKind};
+
+use crate::{amount, summary};
+
+mod layout;
+
+use layout::{Layout, Style, A4, A4_LANDSCAPE, MARGIN};
+
+/// The space left after each section, in millimetres.
+const SECTION_GAP: f32 = 5.0;
+/// The indent of list items, leaving room for their bullet or number.
+const LIST_INDENT: f32 = 7.0;
+
+/// Render a single recipe as a card, for printing and sticking on the wall.
+pub fn recipe_card(detail: &RecipeDetail) -> Vec<u8> {
+    let mut layout = Layout::new(A4);
+    add_recipe(&mut layout, detail);
+    layout.render(&detail.recipe.name)
+}
+
+/// Render several recipes as a book, with each recipe starting on a new page
+/// after a table of contents.
+pub fn cookbook(title: &str, recipes: &[RecipeDetail]) -> Vec<u8> {
+    let mut body = Layout::new(A4);
+    let mut starts = Vec::with_capacity(recipes.len());
+    for detail in recipes {
+        body.page_break();
+        starts.push(body.page_count());
+        add_recipe(&mut body, detail);
+    }
+    // The contents take the same number of pages whatever the page numbers
+    // in them, so lay them out once to learn how many pages they push the
+    // recipes back by.
+    let offset = contents(title, recipes, &starts, 0).page_count();
+    let mut book = contents(title, recipes, &starts, offset);
+    book.append(body);
+    book.render(title)
+}
+
+fn contents(title: &str, recipes: &[RecipeDetail], starts: &[usize], offset: usize) -> Layout {
+    let mut layout = Layout::new(A4);
+    layout.text(Style::Title, title);
+    layout.gap(SECTION_GAP);
+    layout.text(Style::Heading, "Contents");
+    layout.gap(SECTION_GAP / 2.0);
+    for (detail, start) in recipes.iter().zip(starts) {
+        let page = (start + offset).to_string();
+        layout.row(Style::Body, &detail.recipe.name, &page);
+    }
+    layout
+}
+
+/// Render a meal plan as a landscape grid of each week, followed by the
+/// shopping list for every recipe in it.
+pub fn meal_plan(detail: &MealPlanDetail, shopping_list: &ShoppingList) -> Vec<u8> {
+    let plan = &detail.plan;
+    let mut layout = Layout::new(A4_LANDSCAPE);
+    for (i, week) in detail.days.chunks(7).enumerate() {
+        if i > 0 {
+            layout.new_page(A4_LANDSCAPE);
+        }
+        layout.text(Style::Title, &plan.name);
+        let dates = format!(
+            "{} to {}",
+            week[0].date.format("%a %-d %B %Y"),
+            week[week.len() - 1].date.format("%a %-d %B %Y")
+        );
+        layout.text(Style::Small, &dates);
+        layout.gap(SECTION_GAP);
+        let top = layout.y();
+        let column = layout.width() / 7.0;
+        for (j, day) in week.iter().enumerate() {
+            let x = MARGIN + j as f32 * column;
+            let width = column - 2.0;
+            let heading = day.date.format("%a %-d %b").to_string();
+            let mut y = layout.boxed(x, top, width, Style::Subheading, &heading);
+            y += 1.0;
+            if day.entries.is_empty() {
+                layout.boxed(x, y, width, Style::Small, "Nothing planned");
+            }
+            for entry in &day.entries {
+                y = layout.boxed(x, y, width, Style::Body, &entry.recipe.name) + 1.5;
+            }
+        }
+    }
+
+    layout.new_page(A4);
+    layout.text(Style::Title, "Shopping list");
+    layout.text(Style::Small, &plan.name);
+    layout.gap(SECTION_GAP);
+    if shopping_list.is_empty() {
+        layout.text(Style::Body, "No recipes have been planned yet.");
+    }
+    for item in &shopping_list.items {
+        layout.indented(LIST_INDENT, Some("[ ]"), Style::Body, &item.to_line());
+        let recipes = format!("for {}", item.recipes.join(", "));
+        layout.indented(LIST_INDENT, None, Style::Small, &recipes);
+        layout.gap(1.0);
+    }
+    layout.render(&plan.name)
+}
+
+fn add_recipe(layout: &mut Layout, detail: &RecipeDetail) {
+    let recipe = &detail.recipe;
+    layout.text(Style::Title, &recipe.name);
+    let summary = summary(recipe);
+    if !summary.is_empty() {
+        layout.text(Style::Body, &summary.join(" - "));
+    }
+    for kind in TagKind::ALL {
+        let names: Vec<_> = detail
+            .tags
+            .iter()
+            .filter(|tag| tag.kind == kind)
+            .map(|tag| tag.name.as_str())
+            .collect();
+        if !names.is_empty() {
+            let line = format!("{}: {}", kind.label(), names.join(", "));
+            layout.text(Style::Small, &line);
+        }
+    }
+    let diets: Vec<_> = detail
+        .diet
+        .suitable_diets()
+        .iter()
+        .map(|diet| diet.label())
+        .collect();
+    if !diets.is_empty() {
+        let line = format!("Suitable for: {}", diets.join(", "));
+        layout.text(Style::Small, &line);
+    }
+    layout.gap(SECTION_GAP);
+
+    if !detail.ingredients.is_empty() {
+        heading(layout, "Ingredients");
+        for ingredient in &detail.ingredients {
+            layout.indented(LIST_INDENT, Some("-"), Style::Body, &ingredient.to_line());
+        }
+        layout.gap(SECTION_GAP);
+    }
+
+    if !detail.steps.is_empty() {
+        heading(layout, "Method");
+        for (i, step) in detail.steps.iter().enumerate() {
+            let number = format!("{}.", i + 1);
+            layout.indented(LIST_INDENT, Some(&number), Style::Body, &step.description);
+            layout.gap(1.5);
+        }
+        layout.gap(SECTION_GAP);
+    }
+
+    if let Some(notes) = recipe.notes.as_deref().filter(|n| !n.trim().is_empty()) {
+        heading(layout, "Notes");
+        for paragraph in notes.split("\n\n").filter(|p| !p.trim().is_empty()) {
+            layout.text(Style::Body, paragraph.trim());
+            layout.gap(1.5);
+        }
+        layout.gap(SECTION_GAP);
+    }
+
+    let (nutrition, _) = detail.nutrition.per_serving();
+    if !nutrition.is_empty() {
+        heading(layout, "Nutrition per serving");
+        for (label, unit, value) in nutrition.rows() {
+            if let Some(value) = value {
+                let value = format!("{} {unit}", amount(value));
+                layout.row(Style::Body, label, &value);
+            }
+        }
+        layout.gap(SECTION_GAP);
+    }
+
+    if let Some(source) = &recipe.source {
+        layout.text(Style::Small, &format!("Source: {source}"));
+    }
+}
+
+/// Add a section heading, moving to the next page first if it would
+/// otherwise be left alone at the bottom of this one.
+fn heading(layout: &mut Layout, text: &str) {
+    layout.keep(Style::Heading.line_height() + 3.0 * Style::Body.line_height());
+    layout.text(Style::Heading, text);
+    layout.gap(1.0);
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+    use recipe_shared::{MealPlan, MealPlanEntry, NutritionSource, RecipeDetail};
+
+    use super::*;
+    use crate::testing::flapjacks;
+
+    fn assert_pdf(bytes: &[u8]) {
+        assert!(bytes.starts_with(b"%PDF-"));
+        assert!(bytes.len() > 1000);
+    }
+
+    /// A recipe too long for one page, with a word too long for one line.
+    fn long_recipe() -> RecipeDetail {
+        let mut detail = flapjacks();
+        detail.recipe.name = format!("Very {}long flapjacks", "very ".repeat(30));
+        let ingredient = detail.ingredients[0].clone();
+        detail.ingredients = vec![ingredient; 80];
+        detail.steps[0].description = "a".repeat(500);
+        for _ in 0..3 {
+            let steps = detail.steps.clone();
+            detail.steps.extend(steps);
+        }
+        detail
+    }
+
+    #[test]
+    fn recipes_render() {
+        assert_pdf(&recipe_card(&flapjacks()));
+        assert_pdf(&recipe_card(&long_recipe()));
+        assert_pdf(&cookbook("Baking", &[flapjacks(), long_recipe()]));
+        assert_pdf(&cookbook("Nothing", &[]));
+    }
+
+    #[test]
+    fn meal_plans_render() {
+        let recipe = flapjacks();
+        let start = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+        // Longer than a week, so the grid goes over two pages.
+        let plan = MealPlan {
+            id: 1,
+            name: "Baking fortnight".to_string(),
+            slug: "baking-fortnight".to_string(),
+            start_date: start,
+            end_date: start + chrono::Duration::days(9),
+            owner_id: None,
+            shared: false,
+        };
+        let entries = plan
+            .dates()
+            .into_iter()
+            .step_by(2)
+            .enumerate()
+            .map(|(i, day)| MealPlanEntry {
+                id: i as i64,
+                day,
+                recipe: recipe.recipe.clone(),
+                nutrition: Default::default(),
+                nutrition_source: NutritionSource::Estimated,
+            })
+            .collect();
+        let detail = MealPlanDetail::new(plan, entries);
+        let shopping_list = ShoppingList::for_meal_plan(&detail, &[recipe]);
+        assert_pdf(&meal_plan(&detail, &shopping_list));
+        assert_pdf(&meal_plan(&detail, &ShoppingList::default()));
+    }
+}
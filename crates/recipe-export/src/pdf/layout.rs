@@ -0,0 +1,307 @@
+//! A minimal text layout engine targeting the standard PDF fonts.
+//!
+//! The standard fonts don't need embedding, which keeps documents small and
+//! avoids shipping font files, but printpdf can't measure them. Widths are
+//! instead estimated from rough Helvetica character widths, which is close
+//! enough for wrapping text with a little slack.
+
+use printpdf::{BuiltinFont, Mm, PdfDocument};
+
+/// The size of a portrait A4 page, in millimetres.
+pub const A4: (f32, f32) = (210.0, 297.0);
+/// The size of a landscape A4 page, in millimetres.
+pub const A4_LANDSCAPE: (f32, f32) = (297.0, 210.0);
+pub const MARGIN: f32 = 15.0;
+
+const MM_PER_PT: f32 = 0.3528;
+
+#[derive(Debug, Clone, Copy)]
+pub enum Style {
+    Title,
+    Heading,
+    Subheading,
+    Body,
+    Small,
+}
+
+impl Style {
+    fn size(&self) -> f32 {
+        match self {
+            Self::Title => 20.0,
+            Self::Heading => 14.0,
+            Self::Subheading => 10.0,
+            Self::Body => 10.0,
+            Self::Small => 8.0,
+        }
+    }
+
+    fn bold(&self) -> bool {
+        matches!(self, Self::Title | Self::Heading | Self::Subheading)
+    }
+
+    /// The height of a line of text in this style, in millimetres.
+    pub fn line_height(&self) -> f32 {
+        self.size() * 1.35 * MM_PER_PT
+    }
+
+    /// The estimated width of `text` in this style, in millimetres.
+    pub fn width(&self, text: &str) -> f32 {
+        let ems: f32 = text
+            .chars()
+            .map(|c| match c {
+                'i' | 'j' | 'l' | 'I' | '.' | ',' | ':' | ';' | '\'' | '!' | '|' => 0.28,
+                'f' | 't' | 'r' | ' ' | '(' | ')' | '-' => 0.36,
+                'm' | 'w' | 'M' | 'W' => 0.85,
+                c if c.is_uppercase() => 0.68,
+                _ => 0.56,
+            })
+            .sum();
+        let bold = if self.bold() { 1.06 } else { 1.0 };
+        ems * self.size() * MM_PER_PT * bold
+    }
+
+    /// Break `text` into lines no wider than `width` millimetres. Words
+    /// longer than a whole line are left to overflow.
+    pub fn wrap(&self, text: &str, width: f32) -> Vec<String> {
+        let mut lines = Vec::new();
+        let mut line = String::new();
+        for word in text.split_whitespace() {
+            let candidate = if line.is_empty() {
+                word.to_string()
+            } else {
+                format!("{line} {word}")
+            };
+            if !line.is_empty() && self.width(&candidate) > width {
+                lines.push(std::mem::replace(&mut line, word.to_string()));
+            } else {
+                line = candidate;
+            }
+        }
+        if !line.is_empty() {
+            lines.push(line);
+        }
+        lines
+    }
+}
+
+/// A line of text placed on a page. `y` is the distance of the baseline from
+/// the top of the page.
+#[derive(Debug)]
+struct Run {
+    x: f32,
+    y: f32,
+    style: Style,
+    text: String,
+}
+
+#[derive(Debug)]
+pub struct Page {
+    width: f32,
+    height: f32,
+    runs: Vec<Run>,
+}
+
+/// Text laid out from the top of the page down, starting new pages as each
+/// fills up.
+#[derive(Debug)]
+pub struct Layout {
+    pages: Vec<Page>,
+    /// How far down the current page the next line goes.
+    y: f32,
+}
+
+impl Layout {
+    pub fn new(size: (f32, f32)) -> Self {
+        let mut layout = Self {
+            pages: Vec::new(),
+            y: 0.0,
+        };
+        layout.new_page(size);
+        layout
+    }
+
+    /// Start a new page of the given size.
+    pub fn new_page(&mut self, (width, height): (f32, f32)) {
+        self.pages.push(Page {
+            width,
+            height,
+            runs: Vec::new(),
+        });
+        self.y = MARGIN;
+    }
+
+    /// Start a new page unless the current one is still empty.
+    pub fn page_break(&mut self) {
+        if !self.page().runs.is_empty() {
+            self.new_page(self.size());
+        }
+    }
+
+    pub fn page_count(&self) -> usize {
+        self.pages.len()
+    }
+
+    /// The size of the current page.
+    pub fn size(&self) -> (f32, f32) {
+        (self.page().width, self.page().height)
+    }
+
+    /// The width available for text between the margins.
+    pub fn width(&self) -> f32 {
+        self.page().width - 2.0 * MARGIN
+    }
+
+    /// How far down the current page the next line goes.
+    pub fn y(&self) -> f32 {
+        self.y
+    }
+
+    pub fn gap(&mut self, height: f32) {
+        self.y += height;
+    }
+
+    /// Start a new page unless there's `height` millimetres left on this one.
+    pub fn keep(&mut self, height: f32) {
+        let (_, page_height) = self.size();
+        if self.y + height > page_height - MARGIN {
+            self.new_page(self.size());
+        }
+    }
+
+    /// Add a paragraph across the full width of the page.
+    pub fn text(&mut self, style: Style, text: &str) {
+        self.indented(0.0, None, style, text);
+    }
+
+    /// Add a paragraph starting `indent` millimetres in from the margin,
+    /// with `marker` (such as a bullet or step number) in the gutter before
+    /// its first line.
+    pub fn indented(&mut self, indent: f32, marker: Option<&str>, style: Style, text: &str) {
+        let lines = style.wrap(&sanitize(text), self.width() - indent);
+        for (i, line) in lines.into_iter().enumerate() {
+            self.keep(style.line_height());
+            let baseline = self.y + style.size() * MM_PER_PT;
+            if let Some(marker) = marker.filter(|_| i == 0) {
+                self.place(MARGIN, baseline, style, marker);
+            }
+            self.place(MARGIN + indent, baseline, style, &line);
+            self.y += style.line_height();
+        }
+    }
+
+    /// Add a line with `right` aligned to the right margin, as in a table of
+    /// contents. `left` is cut short if the two would overlap.
+    pub fn row(&mut self, style: Style, left: &str, right: &str) {
+        self.keep(style.line_height());
+        let right = sanitize(right);
+        let right_width = style.width(&right);
+        let mut left = sanitize(left);
+        while !left.is_empty() && style.width(&left) + right_width + 5.0 > self.width() {
+            left.pop();
+        }
+        let baseline = self.y + style.size() * MM_PER_PT;
+        self.place(MARGIN, baseline, style, &left);
+        let x = MARGIN + self.width() - right_width;
+        self.place(x, baseline, style, &right);
+        self.y += style.line_height();
+    }
+
+    /// Wrap `text` into a box `width` millimetres wide at `x` and `y` on the
+    /// current page, without moving on or starting new pages. Returns where
+    /// the next line of the box would go; lines running off the page are
+    /// dropped.
+    pub fn boxed(&mut self, x: f32, y: f32, width: f32, style: Style, text: &str) -> f32 {
+        let bottom = self.page().height - MARGIN;
+        let mut y = y;
+        for line in style.wrap(&sanitize(text), width) {
+            if y + style.line_height() > bottom {
+                break;
+            }
+            self.place(x, y + style.size() * MM_PER_PT, style, &line);
+            y += style.line_height();
+        }
+        y
+    }
+
+    /// Add the pages of `other` after these ones.
+    pub fn append(&mut self, other: Layout) {
+        self.pages.extend(other.pages);
+        self.y = other.y;
+    }
+
+    /// Render the pages, numbering them in the footer if there's more than
+    /// one.
+    pub fn render(self, title: &str) -> Vec<u8> {
+        let first = &self.pages[0];
+        let (doc, page, layer) =
+            PdfDocument::new(title, Mm(first.width), Mm(first.height), "Layer 1");
+        // Adding the standard fonts only fails if they're embedded more than
+        // once, so these can't fail.
+        let regular = doc
+            .add_builtin_font(BuiltinFont::Helvetica)
+            .expect("standard font can be added");
+        let bold = doc
+            .add_builtin_font(BuiltinFont::HelveticaBold)
+            .expect("standard font can be added");
+        let total = self.pages.len();
+        for (i, content) in self.pages.iter().enumerate() {
+            let (page, layer) = if i == 0 {
+                (page, layer)
+            } else {
+                doc.add_page(Mm(content.width), Mm(content.height), "Layer 1")
+            };
+            let layer = doc.get_page(page).get_layer(layer);
+            let footer = (total > 1).then(|| {
+                let text = format!("{} / {}", i + 1, total);
+                Run {
+                    x: (content.width - Style::Small.width(&text)) / 2.0,
+                    y: content.height - MARGIN / 2.0,
+                    style: Style::Small,
+                    text,
+                }
+            });
+            for run in content.runs.iter().chain(&footer) {
+                let font = if run.style.bold() { &bold } else { &regular };
+                layer.use_text(
+                    run.text.clone(),
+                    run.style.size(),
+                    Mm(run.x),
+                    Mm(content.height - run.y),
+                    font,
+                );
+            }
+        }
+        doc.save_to_bytes()
+            .expect("writing a PDF to memory doesn't fail")
+    }
+
+    fn page(&self) -> &Page {
+        self.pages.last().expect("layouts always have a page")
+    }
+
+    fn place(&mut self, x: f32, y: f32, style: Style, text: &str) {
+        let text = text.to_string();
+        self.pages
+            .last_mut()
+            .expect("layouts always have a page")
+            .runs
+            .push(Run { x, y, style, text });
+    }
+}
+
+/// Replace characters the standard fonts can't show. They only cover
+/// Latin-1, so common typographic punctuation is swapped for plain
+/// equivalents and anything else outside it is dropped.
+fn sanitize(text: &str) -> String {
+    text.chars()
+        .filter_map(|c| match c {
+            '\u{2018}' | '\u{2019}' => Some('\''),
+            '\u{201c}' | '\u{201d}' => Some('"'),
+            '\u{2013}' | '\u{2014}' => Some('-'),
+            '\u{2022}' => Some('\u{b7}'),
+            '\t' | '\n' | '\r' => Some(' '),
+            c if (c as u32) < 0x100 && !c.is_control() => Some(c),
+            _ => None,
+        })
+        .collect()
+}
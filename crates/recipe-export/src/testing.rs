@@ -0,0 +1,114 @@
+//! Recipes for the exporters' tests.
+
+use chrono::NaiveDate;
+
+use recipe_shared::{
+    DietInfo, DietaryFlag, IngredientInput, Nutrition, Recipe, RecipeDetail, RecipeIngredient,
+    RecipeInput, RecipeStep, Tag, TagInput, TagKind,
+};
+
+/// Store a recipe as the repository would, without any database.
+pub(crate) fn detail(input: RecipeInput) -> RecipeDetail {
+    RecipeDetail {
+        recipe: Recipe {
+            id: 1,
+            created_at: NaiveDate::from_ymd_opt(2024, 1, 1)
+                .unwrap()
+                .and_hms_opt(0, 0, 0)
+                .unwrap(),
+            name: input.name,
+            slug: "recipe".to_string(),
+            source: input.source,
+            notes: input.notes,
+            prep_time_minutes: input.prep_time_minutes,
+            cooking_time_minutes: input.cooking_time_minutes,
+            image_url: input.image_url,
+            servings: input.servings,
+            owner_id: None,
+            shared: false,
+        },
+        ingredients: input
+            .ingredients
+            .into_iter()
+            .enumerate()
+            .map(|(i, ingredient)| RecipeIngredient {
+                ingredient_id: i as i64,
+                name: ingredient.name,
+                quantity: ingredient.quantity,
+                unit: ingredient.unit,
+                notes: ingredient.notes,
+            })
+            .collect(),
+        steps: input
+            .steps
+            .into_iter()
+            .enumerate()
+            .map(|(i, description)| RecipeStep {
+                step_number: i as i32 + 1,
+                description,
+            })
+            .collect(),
+        tags: input
+            .tags
+            .into_iter()
+            .enumerate()
+            .map(|(i, tag)| Tag {
+                id: i as i64,
+                slug: tag.name.to_lowercase(),
+                name: tag.name,
+                kind: tag.kind,
+            })
+            .collect(),
+        diet: DietInfo {
+            declared: input.diets,
+            ..Default::default()
+        },
+        nutrition: Default::default(),
+        household_ids: Vec::new(),
+        editable: false,
+    }
+}
+
+/// A recipe using every part of the exports.
+pub(crate) fn flapjacks() -> RecipeDetail {
+    let ingredient = |quantity: f64, unit: &str, name: &str, notes: Option<&str>| IngredientInput {
+        name: name.to_string(),
+        quantity: Some(quantity),
+        unit: Some(unit.to_string()),
+        notes: notes.map(str::to_string),
+    };
+    let mut detail = detail(RecipeInput {
+        name: "Flapjacks".to_string(),
+        source: Some("https://example.com/flapjacks".to_string()),
+        notes: Some("Keeps for a week.".to_string()),
+        prep_time_minutes: Some(10),
+        cooking_time_minutes: Some(25),
+        servings: Some(12),
+        ingredients: vec![
+            ingredient(250.0, "g", "oats", None),
+            ingredient(125.0, "g", "butter", None),
+            ingredient(3.0, "tbsp", "golden syrup", Some("warmed")),
+        ],
+        steps: vec![
+            "Melt the butter and syrup.".to_string(),
+            "Stir in the oats and bake for 25 minutes.".to_string(),
+        ],
+        tags: vec![
+            TagInput::new(TagKind::Category, "Baking"),
+            TagInput::new(TagKind::Tag, "quick"),
+        ],
+        ..Default::default()
+    });
+    detail.diet.flags = [
+        (DietaryFlag::Gluten, vec!["oats".to_string()]),
+        (DietaryFlag::Dairy, vec!["butter".to_string()]),
+    ]
+    .into();
+    detail.nutrition.declared = Some(Nutrition {
+        energy_kcal: Some(200.0),
+        fat_g: Some(9.5),
+        salt_g: Some(0.5),
+        ..Default::default()
+    });
+    detail
+}
@@ -1,16 +1,35 @@
-use dioxus::prelude::*;
+use dioxus_desktop::{Config, WindowBuilder};
+use dioxus_fullstack::prelude::*;
 use mobile_entry_point::mobile_entry_point;
 
+use recipe_app::Route;
+
+/// The recipe server to talk to, set with `RECIPE_SERVER_URL` when building
+/// since there's no environment to read it from on a phone.
+const SERVER_URL: &str = match option_env!("RECIPE_SERVER_URL") {
+    Some(url) => url,
+    None => "http://127.0.0.1:8080",
+};
+
+/// Scale the page to the device rather than laying it out for a desktop
+/// sized window and shrinking it.
+const HEAD: &str = r#"
+<meta name="viewport" content="width=device-width, initial-scale=1, viewport-fit=cover">
+<link rel="stylesheet" href="public/tailwind.css">
+"#;
+
 #[mobile_entry_point]
 fn main() {
-    simple_logger::SimpleLogger::new().init().unwrap();
-    dioxus_desktop::launch(app);
-}
-
-fn app(cx: Scope) -> Element {
-    cx.render(rsx! {
-        div {
-            "hello world!"
-        }
-    })
+    simple_logger::SimpleLogger::new()
+        .with_level(log::LevelFilter::Info)
+        .init()
+        .unwrap();
+    log::info!("using recipe server at {SERVER_URL}");
+    server_fn::set_server_url(SERVER_URL);
+    let config = Config::new()
+        .with_custom_head(HEAD.to_string())
+        .with_window(WindowBuilder::new().with_title("Recipes"));
+    LaunchBuilder::<FullstackRouterConfig<Route>>::router()
+        .desktop_cfg(config)
+        .launch()
 }
@@ -0,0 +1,212 @@
+use std::collections::{BTreeSet, HashMap};
+
+use recipe_db::prelude::*;
+
+use async_trait::async_trait;
+use diesel::prelude::*;
+use diesel_async::{scoped_futures::ScopedFutureExt, AsyncConnection};
+
+use recipe_db::{
+    models::{
+        Ingredient as DbIngredient, IngredientFlag, IngredientId, NewIngredientFlag, NewRecipeDiet,
+        RecipeId,
+    },
+    schema::{ingredient_flags, ingredients, recipe_diets},
+};
+use recipe_shared::{Diet, DietInfo, DietaryFlag, IngredientDiet};
+
+use crate::{DieselRepository, DietRepository, Error, Repository, Result};
+
+#[async_trait]
+impl Repository<IngredientDiet> for DieselRepository {
+    type Id = IngredientId;
+    async fn get(&self, id: Self::Id) -> Result<Option<IngredientDiet>> {
+        let mut conn = self.pool.get().await?;
+        let ingredient: Option<DbIngredient> =
+            DbIngredient::by_id(id).first(&mut conn).await.optional()?;
+        match ingredient {
+            Some(ingredient) => Ok(ingredient_diets(&mut conn, vec![ingredient]).await?.pop()),
+            None => Ok(None),
+        }
+    }
+
+    async fn list(&self) -> Result<Vec<IngredientDiet>> {
+        let mut conn = self.pool.get().await?;
+        let ingredients: Vec<DbIngredient> = DbIngredient::all()
+            .order(ingredients::name)
+            .load(&mut conn)
+            .await?;
+        ingredient_diets(&mut conn, ingredients).await
+    }
+}
+
+#[async_trait]
+impl DietRepository for DieselRepository {
+    type IngredientId = IngredientId;
+
+    async fn set_ingredient_flags(
+        &self,
+        id: Self::IngredientId,
+        flags: BTreeSet<DietaryFlag>,
+    ) -> Result<IngredientDiet> {
+        self.require_user()?;
+        let mut conn = self.pool.get().await?;
+        let ingredient = conn
+            .transaction::<_, Error, _>(|conn| {
+                async move {
+                    let ingredient: DbIngredient = diesel::update(DbIngredient::by_id(id))
+                        .set(ingredients::diet_reviewed.eq(true))
+                        .returning(DbIngredient::as_returning())
+                        .get_result(conn)
+                        .await
+                        .optional()?
+                        .ok_or(Error::NotFound)?;
+                    diesel::delete(
+                        ingredient_flags::table.filter(ingredient_flags::ingredient_id.eq(id)),
+                    )
+                    .execute(conn)
+                    .await?;
+                    insert_flags(conn, id, &flags).await?;
+                    Ok(ingredient)
+                }
+                .scope_boxed()
+            })
+            .await?;
+        ingredient_diets(&mut conn, vec![ingredient])
+            .await?
+            .pop()
+            .ok_or(Error::NotFound)
+    }
+}
+
+async fn insert_flags(
+    conn: &mut DbConnection,
+    ingredient_id: IngredientId,
+    flags: &BTreeSet<DietaryFlag>,
+) -> Result<()> {
+    let rows: Vec<_> = flags
+        .iter()
+        .map(|flag| NewIngredientFlag {
+            ingredient_id,
+            flag: flag.as_str(),
+        })
+        .collect();
+    if !rows.is_empty() {
+        diesel::insert_into(ingredient_flags::table)
+            .values(&rows)
+            .on_conflict_do_nothing()
+            .execute(conn)
+            .await?;
+    }
+    Ok(())
+}
+
+/// Record the flags guessed from an ingredient's name, unless a person has
+/// already reviewed them.
+pub(crate) async fn detect_ingredient_flags(
+    conn: &mut DbConnection,
+    ingredient_id: IngredientId,
+    name: &str,
+    reviewed: bool,
+) -> Result<()> {
+    if reviewed {
+        return Ok(());
+    }
+    insert_flags(conn, ingredient_id, &DietaryFlag::detect(name)).await
+}
+
+/// Fetch the flags recorded for each of the given ingredients.
+pub(crate) async fn load_ingredient_flags(
+    conn: &mut DbConnection,
+    ids: &[IngredientId],
+) -> Result<HashMap<IngredientId, BTreeSet<DietaryFlag>>> {
+    let rows: Vec<IngredientFlag> = IngredientFlag::belonging_to_ingredients(ids)
+        .load(conn)
+        .await?;
+    let mut by_ingredient: HashMap<IngredientId, BTreeSet<DietaryFlag>> = HashMap::new();
+    for row in rows {
+        // The database constrains `flag` to the known values.
+        if let Ok(flag) = row.flag.parse() {
+            by_ingredient
+                .entry(row.ingredient_id)
+                .or_default()
+                .insert(flag);
+        }
+    }
+    Ok(by_ingredient)
+}
+
+async fn ingredient_diets(
+    conn: &mut DbConnection,
+    ingredients: Vec<DbIngredient>,
+) -> Result<Vec<IngredientDiet>> {
+    let ids: Vec<IngredientId> = ingredients.iter().map(|i| i.id).collect();
+    let mut flags = load_ingredient_flags(conn, &ids).await?;
+    Ok(ingredients
+        .into_iter()
+        .map(|ingredient| IngredientDiet {
+            ingredient_id: ingredient.id.into(),
+            flags: flags.remove(&ingredient.id).unwrap_or_default(),
+            name: ingredient.name,
+            reviewed: ingredient.diet_reviewed,
+        })
+        .collect())
+}
+
+/// Derive the dietary information of a recipe from its ingredients.
+pub(crate) async fn load_diet_info(
+    conn: &mut DbConnection,
+    recipe_id: RecipeId,
+    ingredients: &[DbIngredient],
+) -> Result<DietInfo> {
+    let ids: Vec<IngredientId> = ingredients.iter().map(|i| i.id).collect();
+    let flags = load_ingredient_flags(conn, &ids).await?;
+    let mut info = DietInfo::default();
+    for ingredient in ingredients {
+        for flag in flags.get(&ingredient.id).into_iter().flatten() {
+            info.flags
+                .entry(*flag)
+                .or_default()
+                .push(ingredient.name.clone());
+        }
+        if !ingredient.diet_reviewed {
+            info.unreviewed.push(ingredient.name.clone());
+        }
+    }
+    info.declared = recipe_diets::table
+        .filter(recipe_diets::recipe_id.eq(recipe_id))
+        .select(recipe_diets::diet)
+        .load::<String>(conn)
+        .await?
+        .iter()
+        .filter_map(|diet| diet.parse().ok())
+        .collect();
+    info.declared.sort();
+    Ok(info)
+}
+
+/// Replace the diets a recipe is declared suitable for.
+pub(crate) async fn set_recipe_diets(
+    conn: &mut DbConnection,
+    recipe_id: RecipeId,
+    diets: &[Diet],
+) -> Result<()> {
+    diesel::delete(recipe_diets::table.filter(recipe_diets::recipe_id.eq(recipe_id)))
+        .execute(conn)
+        .await?;
+    let rows: Vec<_> = diets
+        .iter()
+        .map(|diet| NewRecipeDiet {
+            recipe_id,
+            diet: diet.as_str(),
+        })
+        .collect();
+    if !rows.is_empty() {
+        diesel::insert_into(recipe_diets::table)
+            .values(&rows)
+            .on_conflict_do_nothing()
+            .execute(conn)
+            .await?;
+    }
+    Ok(())
+}
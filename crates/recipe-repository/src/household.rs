@@ -0,0 +1,594 @@
+use recipe_db::prelude::*;
+
+use async_trait::async_trait;
+use diesel::prelude::*;
+use diesel_async::{scoped_futures::ScopedFutureExt, AsyncConnection};
+
+use recipe_db::{
+    models::{
+        Household as DbHousehold, HouseholdId, HouseholdMember as DbHouseholdMember,
+        MealPlan as DbMealPlan, MealPlanId, NewHousehold, NewHouseholdMealPlan, NewHouseholdMember,
+        NewHouseholdRecipe, Recipe as DbRecipe, RecipeId, User as DbUser, UserId, ROLES,
+    },
+    schema::{
+        household_meal_plans, household_members, household_recipes, households, meal_plans,
+        recipes, users,
+    },
+};
+use recipe_shared::{
+    Household as SharedHousehold, HouseholdDetail, HouseholdInput,
+    HouseholdMember as SharedHouseholdMember, HouseholdRole, ValidationErrors,
+};
+
+use crate::{
+    meal_plan::MealPlan, recipe::ensure_owned, recipe::Recipe, user::User, DieselRepository, Error,
+    HouseholdRepository, Repository, Result, WriteRepository,
+};
+
+fn role(role: &str) -> HouseholdRole {
+    // The database constrains `role` to the known values.
+    role.parse().unwrap_or_default()
+}
+
+fn household(db_household: DbHousehold, role: HouseholdRole) -> SharedHousehold {
+    SharedHousehold {
+        id: db_household.id.into(),
+        name: db_household.name,
+        role,
+    }
+}
+
+#[async_trait]
+impl Repository<SharedHousehold> for DieselRepository {
+    type Id = HouseholdId;
+    async fn get(&self, id: Self::Id) -> Result<Option<SharedHousehold>> {
+        let Some(user) = self.user else {
+            return Ok(None);
+        };
+        let mut conn = self.pool.get().await?;
+        let row: Option<(DbHousehold, String)> = households::table
+            .inner_join(household_members::table)
+            .filter(households::id.eq(id))
+            .filter(household_members::user_id.eq(user))
+            .select((DbHousehold::as_select(), household_members::role))
+            .first(&mut conn)
+            .await
+            .optional()?;
+        Ok(row.map(|(h, r)| household(h, role(&r))))
+    }
+
+    async fn list(&self) -> Result<Vec<SharedHousehold>> {
+        let Some(user) = self.user else {
+            return Ok(Vec::new());
+        };
+        let mut conn = self.pool.get().await?;
+        let rows: Vec<(DbHousehold, String)> = households::table
+            .inner_join(household_members::table)
+            .filter(household_members::user_id.eq(user))
+            .select((DbHousehold::as_select(), household_members::role))
+            .order(households::name)
+            .load(&mut conn)
+            .await?;
+        Ok(rows
+            .into_iter()
+            .map(|(h, r)| household(h, role(&r)))
+            .collect())
+    }
+}
+
+#[async_trait]
+impl Repository<HouseholdDetail> for DieselRepository {
+    type Id = HouseholdId;
+    async fn get(&self, id: Self::Id) -> Result<Option<HouseholdDetail>> {
+        match Repository::<SharedHousehold>::get(self, id).await? {
+            Some(household) => {
+                let mut conn = self.pool.get().await?;
+                Ok(Some(load_household_detail(&mut conn, household).await?))
+            }
+            None => Ok(None),
+        }
+    }
+
+    async fn list(&self) -> Result<Vec<HouseholdDetail>> {
+        let households = Repository::<SharedHousehold>::list(self).await?;
+        let mut conn = self.pool.get().await?;
+        let mut details = Vec::with_capacity(households.len());
+        for household in households {
+            details.push(load_household_detail(&mut conn, household).await?);
+        }
+        Ok(details)
+    }
+}
+
+#[async_trait]
+impl WriteRepository<SharedHousehold> for DieselRepository {
+    type Input = HouseholdInput;
+
+    /// Create a household with the current user as its owner.
+    async fn create(&self, input: Self::Input) -> Result<SharedHousehold> {
+        let user = self.require_user()?;
+        input.validate()?;
+        let mut conn = self.pool.get().await?;
+        let created = conn
+            .transaction::<_, Error, _>(|conn| {
+                async move {
+                    let created: DbHousehold = diesel::insert_into(households::table)
+                        .values(&NewHousehold {
+                            name: input.name.trim(),
+                        })
+                        .returning(DbHousehold::as_returning())
+                        .get_result(conn)
+                        .await?;
+                    diesel::insert_into(household_members::table)
+                        .values(&NewHouseholdMember {
+                            household_id: created.id,
+                            user_id: user,
+                            role: HouseholdRole::Owner.as_str(),
+                        })
+                        .execute(conn)
+                        .await?;
+                    Ok(created)
+                }
+                .scope_boxed()
+            })
+            .await?;
+        Ok(household(created, HouseholdRole::Owner))
+    }
+
+    async fn update(&self, id: Self::Id, input: Self::Input) -> Result<SharedHousehold> {
+        let user = self.require_user()?;
+        input.validate()?;
+        let mut conn = self.pool.get().await?;
+        let role = require_role(&mut conn, id, user, HouseholdRole::can_manage).await?;
+        let updated: DbHousehold = diesel::update(DbHousehold::by_id(id))
+            .set(&NewHousehold {
+                name: input.name.trim(),
+            })
+            .returning(DbHousehold::as_returning())
+            .get_result(&mut conn)
+            .await?;
+        Ok(household(updated, role))
+    }
+}
+
+#[async_trait]
+impl HouseholdRepository for DieselRepository {
+    type HouseholdId = HouseholdId;
+    type UserId = UserId;
+    type RecipeId = RecipeId;
+    type MealPlanId = MealPlanId;
+
+    async fn set_member(
+        &self,
+        household_id: Self::HouseholdId,
+        username: &str,
+        new_role: HouseholdRole,
+    ) -> Result<()> {
+        let user = self.require_user()?;
+        let mut conn = self.pool.get().await?;
+        require_role(&mut conn, household_id, user, HouseholdRole::can_manage).await?;
+        let member: Option<DbUser> = DbUser::by_username(username.trim())
+            .select(DbUser::as_select())
+            .first(&mut conn)
+            .await
+            .optional()?;
+        let Some(member) = member else {
+            let mut errors = ValidationErrors::default();
+            errors.add("username", "there is no user with that username");
+            return Err(errors.into());
+        };
+        if new_role != HouseholdRole::Owner {
+            ensure_other_owner(&mut conn, household_id, member.id).await?;
+        }
+        let new_member = NewHouseholdMember {
+            household_id,
+            user_id: member.id,
+            role: new_role.as_str(),
+        };
+        diesel::insert_into(household_members::table)
+            .values(&new_member)
+            .on_conflict((household_members::household_id, household_members::user_id))
+            .do_update()
+            .set(household_members::role.eq(new_role.as_str()))
+            .execute(&mut conn)
+            .await?;
+        Ok(())
+    }
+
+    /// Remove a member from a household. Owners can remove anyone, and
+    /// everyone else can only leave.
+    async fn remove_member(
+        &self,
+        household_id: Self::HouseholdId,
+        user_id: Self::UserId,
+    ) -> Result<()> {
+        let user = self.require_user()?;
+        let mut conn = self.pool.get().await?;
+        if user_id != user {
+            require_role(&mut conn, household_id, user, HouseholdRole::can_manage).await?;
+        }
+        ensure_other_owner(&mut conn, household_id, user_id).await?;
+        diesel::delete(household_members::table.find((household_id, user_id)))
+            .execute(&mut conn)
+            .await?;
+        Ok(())
+    }
+
+    async fn share_recipe(
+        &self,
+        household_id: Self::HouseholdId,
+        recipe_id: Self::RecipeId,
+        shared: bool,
+    ) -> Result<()> {
+        let user = self.require_user()?;
+        let mut conn = self.pool.get().await?;
+        require_role(&mut conn, household_id, user, HouseholdRole::can_edit).await?;
+        // Only owners can share what they own into a household, so that
+        // editing something shared into one household doesn't let it be
+        // passed on to others.
+        if shared {
+            ensure_owned(&mut conn, user, recipe_id).await?;
+            diesel::insert_into(household_recipes::table)
+                .values(&NewHouseholdRecipe {
+                    household_id,
+                    recipe_id,
+                })
+                .on_conflict_do_nothing()
+                .execute(&mut conn)
+                .await?;
+        } else {
+            diesel::delete(household_recipes::table.find((household_id, recipe_id)))
+                .execute(&mut conn)
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn share_meal_plan(
+        &self,
+        household_id: Self::HouseholdId,
+        meal_plan_id: Self::MealPlanId,
+        shared: bool,
+    ) -> Result<()> {
+        let user = self.require_user()?;
+        let mut conn = self.pool.get().await?;
+        require_role(&mut conn, household_id, user, HouseholdRole::can_edit).await?;
+        if shared {
+            let plan: DbMealPlan = DbMealPlan::by_id(meal_plan_id)
+                .filter(DbMealPlan::visible_to(Some(user)))
+                .first(&mut conn)
+                .await
+                .optional()?
+                .ok_or(Error::NotFound)?;
+            if !plan.is_owned_by(user) {
+                return Err(Error::Forbidden);
+            }
+            diesel::insert_into(household_meal_plans::table)
+                .values(&NewHouseholdMealPlan {
+                    household_id,
+                    meal_plan_id,
+                })
+                .on_conflict_do_nothing()
+                .execute(&mut conn)
+                .await?;
+        } else {
+            diesel::delete(household_meal_plans::table.find((household_id, meal_plan_id)))
+                .execute(&mut conn)
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+/// Check that `user` belongs to a household with a role passing `allowed`,
+/// returning their role.
+async fn require_role(
+    conn: &mut DbConnection,
+    household_id: HouseholdId,
+    user: UserId,
+    allowed: fn(&HouseholdRole) -> bool,
+) -> Result<HouseholdRole> {
+    let member: Option<String> = household_members::table
+        .find((household_id, user))
+        .select(household_members::role)
+        .first(conn)
+        .await
+        .optional()?;
+    match member.map(|r| role(&r)) {
+        Some(role) if allowed(&role) => Ok(role),
+        Some(_) => Err(Error::Forbidden),
+        None => Err(Error::NotFound),
+    }
+}
+
+/// Check that a household would still have an owner without `user_id` as one.
+async fn ensure_other_owner(
+    conn: &mut DbConnection,
+    household_id: HouseholdId,
+    user_id: UserId,
+) -> Result<()> {
+    let other_owners: i64 = household_members::table
+        .filter(household_members::household_id.eq(household_id))
+        .filter(household_members::user_id.ne(user_id))
+        .filter(household_members::role.eq(HouseholdRole::Owner.as_str()))
+        .count()
+        .get_result(conn)
+        .await?;
+    let is_owner: Option<String> = household_members::table
+        .find((household_id, user_id))
+        .select(household_members::role)
+        .first(conn)
+        .await
+        .optional()?;
+    if other_owners == 0 && is_owner.as_deref() == Some(HouseholdRole::Owner.as_str()) {
+        let mut errors = ValidationErrors::default();
+        errors.add("role", "a household must keep at least one owner");
+        return Err(errors.into());
+    }
+    Ok(())
+}
+
+/// Fetch the members of a household and everything shared into it.
+async fn load_household_detail(
+    conn: &mut DbConnection,
+    household: SharedHousehold,
+) -> Result<HouseholdDetail> {
+    let id = HouseholdId::from(household.id);
+    let members: Vec<(DbHouseholdMember, DbUser)> = household_members::table
+        .inner_join(users::table)
+        .filter(household_members::household_id.eq(id))
+        .select((DbHouseholdMember::as_select(), DbUser::as_select()))
+        .order(users::username)
+        .load(conn)
+        .await?;
+    let shared_recipes: Vec<DbRecipe> = household_recipes::table
+        .inner_join(recipes::table)
+        .filter(household_recipes::household_id.eq(id))
+        .select(DbRecipe::as_select())
+        .order(recipes::name)
+        .load(conn)
+        .await?;
+    let shared_plans: Vec<DbMealPlan> = household_meal_plans::table
+        .inner_join(meal_plans::table)
+        .filter(household_meal_plans::household_id.eq(id))
+        .select(DbMealPlan::as_select())
+        .order(meal_plans::start_date.desc())
+        .load(conn)
+        .await?;
+    Ok(HouseholdDetail {
+        household,
+        members: members
+            .into_iter()
+            .map(|(member, user)| SharedHouseholdMember {
+                user: User::from(user).0,
+                role: role(&member.role),
+            })
+            .collect(),
+        recipes: shared_recipes
+            .into_iter()
+            .map(|r| Recipe::from(r).0)
+            .collect(),
+        meal_plans: shared_plans
+            .into_iter()
+            .map(|p| MealPlan::from(p).0)
+            .collect(),
+    })
+}
+
+/// Whether `user` can change a recipe, and the ids of their households it
+/// is shared into.
+pub(crate) async fn recipe_access(
+    conn: &mut DbConnection,
+    user: Option<UserId>,
+    recipe_id: RecipeId,
+) -> Result<(bool, Vec<i64>)> {
+    let editable: Option<RecipeId> = DbRecipe::by_id(recipe_id)
+        .filter(DbRecipe::editable_by(user))
+        .select(recipes::id)
+        .first(conn)
+        .await
+        .optional()?;
+    let household_ids: Vec<HouseholdId> = match user {
+        Some(user) => {
+            household_recipes::table
+                .filter(household_recipes::recipe_id.eq(recipe_id))
+                .filter(
+                    household_recipes::household_id
+                        .eq_any(DbHouseholdMember::households_of(user, &ROLES)),
+                )
+                .select(household_recipes::household_id)
+                .load(conn)
+                .await?
+        }
+        None => Vec::new(),
+    };
+    Ok((
+        user.is_some() && editable.is_some(),
+        household_ids.into_iter().map(Into::into).collect(),
+    ))
+}
+
+/// Whether `user` can change a meal plan, and the ids of their households it
+/// is shared into.
+pub(crate) async fn meal_plan_access(
+    conn: &mut DbConnection,
+    user: Option<UserId>,
+    meal_plan_id: MealPlanId,
+) -> Result<(bool, Vec<i64>)> {
+    let editable: Option<MealPlanId> = DbMealPlan::by_id(meal_plan_id)
+        .filter(DbMealPlan::editable_by(user))
+        .select(meal_plans::id)
+        .first(conn)
+        .await
+        .optional()?;
+    let household_ids: Vec<HouseholdId> = match user {
+        Some(user) => {
+            household_meal_plans::table
+                .filter(household_meal_plans::meal_plan_id.eq(meal_plan_id))
+                .filter(
+                    household_meal_plans::household_id
+                        .eq_any(DbHouseholdMember::households_of(user, &ROLES)),
+                )
+                .select(household_meal_plans::household_id)
+                .load(conn)
+                .await?
+        }
+        None => Vec::new(),
+    };
+    Ok((
+        user.is_some() && editable.is_some(),
+        household_ids.into_iter().map(Into::into).collect(),
+    ))
+}
+
+#[cfg(all(test, feature = "sqlite"))]
+mod tests {
+    use chrono::NaiveDate;
+
+    use recipe_shared::{
+        Household, HouseholdInput, HouseholdRole, MealPlan, MealPlanInput, RecipeDetail,
+        RecipeInput,
+    };
+
+    use crate::{testing, BySlug, DieselRepository, Error, HouseholdRepository, WriteRepository};
+
+    /// A database where alice owns a household with bob as a viewer and
+    /// carol as an editor, and dave isn't a member.
+    async fn household(name: &str) -> (i64, [DieselRepository; 4]) {
+        let repo = testing::database(&format!("household-{name}")).await;
+        let alice = testing::as_user(&repo, "alice").await;
+        let bob = testing::as_user(&repo, "bob").await;
+        let carol = testing::as_user(&repo, "carol").await;
+        let dave = testing::as_user(&repo, "dave").await;
+        let home: Household = alice
+            .create(HouseholdInput {
+                name: "Home".to_string(),
+            })
+            .await
+            .unwrap();
+        let id = home.id.into();
+        alice
+            .set_member(id, "bob", HouseholdRole::Viewer)
+            .await
+            .unwrap();
+        alice
+            .set_member(id, "carol", HouseholdRole::Editor)
+            .await
+            .unwrap();
+        (home.id, [alice, bob, carol, dave])
+    }
+
+    fn recipe(name: &str) -> RecipeInput {
+        RecipeInput {
+            name: name.to_string(),
+            steps: vec!["Mix everything.".to_string()],
+            ..Default::default()
+        }
+    }
+
+    async fn get(repo: &DieselRepository, slug: &str) -> Option<RecipeDetail> {
+        repo.get_by_slug(slug).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn roles_decide_what_members_can_do() {
+        let (home, [alice, bob, carol, dave]) = household("roles").await;
+        let pesto: RecipeDetail = alice.create(recipe("Pesto")).await.unwrap();
+        let id = pesto.recipe.id.into();
+        alice.share_recipe(home.into(), id, true).await.unwrap();
+
+        // Viewers can read but not edit.
+        assert!(get(&bob, "pesto").await.is_some());
+        let edited: Result<RecipeDetail, _> = bob.update(id, recipe("Pesto")).await;
+        assert!(matches!(edited, Err(Error::NotFound)));
+
+        // Editors can edit.
+        let mut input = recipe("Pesto");
+        input.steps.push("Serve with pasta.".to_string());
+        let edited: RecipeDetail = carol.update(id, input).await.unwrap();
+        assert_eq!(edited.steps.len(), 2);
+
+        // Everyone else sees nothing.
+        assert!(get(&dave, "pesto").await.is_none());
+        assert!(get(&alice.for_user(None), "pesto").await.is_none());
+    }
+
+    #[tokio::test]
+    async fn only_owners_make_recipes_public() {
+        let (home, [alice, _, carol, dave]) = household("public-recipes").await;
+        let pesto: RecipeDetail = alice.create(recipe("Pesto")).await.unwrap();
+        let id = pesto.recipe.id.into();
+        alice.share_recipe(home.into(), id, true).await.unwrap();
+
+        let input = RecipeInput {
+            shared: true,
+            ..recipe("Pesto")
+        };
+        let edited: RecipeDetail = carol.update(id, input.clone()).await.unwrap();
+        assert!(!edited.recipe.shared);
+        assert!(get(&dave, "pesto").await.is_none());
+
+        let edited: RecipeDetail = alice.update(id, input).await.unwrap();
+        assert!(edited.recipe.shared);
+        assert!(get(&dave, "pesto").await.is_some());
+    }
+
+    #[tokio::test]
+    async fn only_owners_share_into_households() {
+        let (home, [alice, _, carol, dave]) = household("share").await;
+        let pesto: RecipeDetail = alice.create(recipe("Pesto")).await.unwrap();
+        let recipe_id = pesto.recipe.id.into();
+        alice
+            .share_recipe(home.into(), recipe_id, true)
+            .await
+            .unwrap();
+        let start = NaiveDate::from_ymd_opt(2024, 3, 4).unwrap();
+        let plan: MealPlan = alice
+            .create(MealPlanInput {
+                name: "First week of March".to_string(),
+                start_date: start,
+                end_date: start + chrono::Duration::days(6),
+                shared: false,
+            })
+            .await
+            .unwrap();
+        let plan_id = plan.id.into();
+        alice
+            .share_meal_plan(home.into(), plan_id, true)
+            .await
+            .unwrap();
+
+        // Carol can edit both through alice's household, but can't pass them
+        // on to a household of her own.
+        let other: Household = carol
+            .create(HouseholdInput {
+                name: "Flat".to_string(),
+            })
+            .await
+            .unwrap();
+        carol
+            .set_member(other.id.into(), "dave", HouseholdRole::Viewer)
+            .await
+            .unwrap();
+        let shared = carol.share_recipe(other.id.into(), recipe_id, true).await;
+        assert!(matches!(shared, Err(Error::Forbidden)));
+        let shared = carol.share_meal_plan(other.id.into(), plan_id, true).await;
+        assert!(matches!(shared, Err(Error::Forbidden)));
+        assert!(get(&dave, "pesto").await.is_none());
+
+        // Nor make the plan public.
+        let edited: MealPlan = carol
+            .update(
+                plan_id,
+                MealPlanInput {
+                    name: "First week of March".to_string(),
+                    start_date: start,
+                    end_date: start + chrono::Duration::days(6),
+                    shared: true,
+                },
+            )
+            .await
+            .unwrap();
+        assert!(!edited.shared);
+    }
+}
@@ -0,0 +1,48 @@
+use recipe_db::prelude::*;
+
+use async_trait::async_trait;
+use diesel::prelude::*;
+use futures_util::TryStreamExt;
+
+use recipe_db::{
+    models::{Ingredient as DbIngredient, IngredientId},
+    schema::ingredients,
+};
+use recipe_shared::Ingredient as SharedIngredient;
+
+use crate::{DieselRepository, Repository, Result};
+
+struct Ingredient(SharedIngredient);
+
+impl From<DbIngredient> for Ingredient {
+    fn from(db_ingredient: DbIngredient) -> Self {
+        Self(SharedIngredient {
+            id: db_ingredient.id.into(),
+            name: db_ingredient.name,
+            slug: db_ingredient.slug,
+        })
+    }
+}
+
+#[async_trait]
+impl Repository<SharedIngredient> for DieselRepository {
+    type Id = IngredientId;
+    async fn get(&self, id: Self::Id) -> Result<Option<SharedIngredient>> {
+        let mut conn = self.pool.get().await?;
+        let ingredient: Option<DbIngredient> =
+            DbIngredient::by_id(id).first(&mut conn).await.optional()?;
+        Ok(ingredient.map(|i| Ingredient::from(i).0))
+    }
+
+    async fn list(&self) -> Result<Vec<SharedIngredient>> {
+        let mut conn = self.pool.get().await?;
+        let ingredients = DbIngredient::all()
+            .order(ingredients::name)
+            .load_stream(&mut conn)
+            .await?;
+        Ok(ingredients
+            .map_ok(|i| Ingredient::from(i).0)
+            .try_collect()
+            .await?)
+    }
+}
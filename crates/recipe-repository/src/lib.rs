@@ -1,8 +1,29 @@
+use std::collections::BTreeSet;
+
 use async_trait::async_trait;
+use chrono::{NaiveDate, NaiveDateTime};
 
+use recipe_db::models::UserId;
 use recipe_db::prelude::*;
+use recipe_db::slug::slugify;
+use recipe_shared::{
+    Changeset, Credentials, DietaryFlag, HouseholdRole, IngredientDiet, SyncCursor, SyncRejection,
+    SyncRequest, SyncResponse, TagInput, User, UserInput, ValidationErrors,
+};
 
+mod diet;
+mod household;
+mod ingredient;
+mod meal_plan;
+mod measurement;
+mod nutrition;
 mod recipe;
+mod search;
+mod sync;
+mod tag;
+#[cfg(all(test, feature = "sqlite"))]
+mod testing;
+mod user;
 
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
@@ -10,6 +31,32 @@ pub enum Error {
     Pool(#[from] PoolError),
     #[error("database error")]
     Database(#[from] DieselError),
+    #[error("password hashing error")]
+    PasswordHash(#[from] argon2::password_hash::Error),
+    #[error("invalid input: {0}")]
+    Validation(#[from] ValidationErrors),
+    #[error("not found")]
+    NotFound,
+    #[error("you must be signed in to do that")]
+    Unauthenticated,
+    #[error("you aren't allowed to do that")]
+    Forbidden,
+}
+
+impl Error {
+    /// Whether the change clashed with something which already exists, such
+    /// as a second recipe with the same name.
+    pub fn is_conflict(&self) -> bool {
+        use diesel::result::DatabaseErrorKind;
+
+        matches!(
+            self,
+            Self::Database(DieselError::DatabaseError(
+                DatabaseErrorKind::UniqueViolation,
+                _
+            ))
+        )
+    }
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
@@ -21,21 +68,218 @@ pub trait Repository<T> {
     async fn list(&self) -> Result<Vec<T>>;
 }
 
+/// Lookup of entities by their human readable slug.
+#[async_trait]
+pub trait BySlug<T> {
+    async fn get_by_slug(&self, slug: &str) -> Result<Option<T>>;
+}
+
+/// Creation and modification of entities from some input type.
+#[async_trait]
+pub trait WriteRepository<T>: Repository<T> {
+    type Input;
+    async fn create(&self, input: Self::Input) -> Result<T>;
+    async fn update(&self, id: Self::Id, input: Self::Input) -> Result<T>;
+}
+
+/// Deletion of entities, leaving a tombstone behind so that other databases
+/// they were synced to can delete them too.
+#[async_trait]
+pub trait DeleteRepository<T>: Repository<T> {
+    async fn delete(&self, id: Self::Id) -> Result<()>;
+}
+
+/// Attaching and detaching tags on recipes.
+#[async_trait]
+pub trait TagRepository<T> {
+    type RecipeId;
+    type TagId;
+    /// Attach a tag to a recipe, creating the tag if it doesn't exist yet.
+    async fn tag(&self, recipe_id: Self::RecipeId, tag: TagInput) -> Result<T>;
+    async fn untag(&self, recipe_id: Self::RecipeId, tag_id: Self::TagId) -> Result<()>;
+}
+
+/// Recording the dietary flags of ingredients.
+#[async_trait]
+pub trait DietRepository {
+    type IngredientId;
+    /// Replace the flags of an ingredient, marking them as reviewed.
+    async fn set_ingredient_flags(
+        &self,
+        id: Self::IngredientId,
+        flags: BTreeSet<DietaryFlag>,
+    ) -> Result<IngredientDiet>;
+}
+
+/// Scheduling recipes on the days of meal plans.
+#[async_trait]
+pub trait MealPlanRepository {
+    type MealPlanId;
+    type RecipeId;
+    type EntryId;
+    async fn add_recipe(
+        &self,
+        meal_plan_id: Self::MealPlanId,
+        recipe_id: Self::RecipeId,
+        day: NaiveDate,
+    ) -> Result<()>;
+    async fn remove_recipe(&self, id: Self::EntryId) -> Result<()>;
+}
+
+/// Registering users and managing their sessions.
+#[async_trait]
+pub trait UserRepository {
+    type UserId;
+    async fn register(&self, input: UserInput) -> Result<User>;
+    /// Check a user's password, returning the user if it's correct.
+    async fn authenticate(&self, credentials: Credentials) -> Result<Option<User>>;
+    /// Start a new session for a user, returning its token.
+    async fn start_session(&self, user_id: Self::UserId) -> Result<String>;
+    /// Find the user a session belongs to, unless it has expired.
+    async fn session_user(&self, token: &str) -> Result<Option<User>>;
+    async fn end_session(&self, token: &str) -> Result<()>;
+    /// Find or create the user who owns everything in a local database, for
+    /// apps which run on a single device without signing in. They can't sign
+    /// in to a server with this account since it has no password.
+    async fn local_user(&self, username: &str) -> Result<User>;
+}
+
+/// Managing the members of households and what is shared into them.
+#[async_trait]
+pub trait HouseholdRepository {
+    type HouseholdId;
+    type UserId;
+    type RecipeId;
+    type MealPlanId;
+    /// Add a user to a household by their username, or change their role if
+    /// they're already a member.
+    async fn set_member(
+        &self,
+        household_id: Self::HouseholdId,
+        username: &str,
+        role: HouseholdRole,
+    ) -> Result<()>;
+    async fn remove_member(
+        &self,
+        household_id: Self::HouseholdId,
+        user_id: Self::UserId,
+    ) -> Result<()>;
+    async fn share_recipe(
+        &self,
+        household_id: Self::HouseholdId,
+        recipe_id: Self::RecipeId,
+        shared: bool,
+    ) -> Result<()>;
+    async fn share_meal_plan(
+        &self,
+        household_id: Self::HouseholdId,
+        meal_plan_id: Self::MealPlanId,
+        shared: bool,
+    ) -> Result<()>;
+}
+
+/// Exchanging changes with other databases, so recipes and meal plans can be
+/// edited offline. Only what the current user owns is synced.
+#[async_trait]
+pub trait SyncRepository {
+    /// Everything which changed after `since`, along with the cursor to pass
+    /// next time to get what changes after this.
+    async fn changes_since(
+        &self,
+        since: Option<NaiveDateTime>,
+    ) -> Result<(Changeset, NaiveDateTime)>;
+    /// Apply changes made in another database, keeping whichever copy of each
+    /// recipe and meal plan was edited last. Applying the same changes again
+    /// does nothing. Changes which can't be applied, such as those to someone
+    /// else's recipes, are skipped and returned.
+    async fn apply_changes(&self, changes: Changeset) -> Result<Vec<SyncRejection>>;
+    /// The server's side of a sync: apply a client's changes, and return
+    /// those made here since the client's cursor.
+    async fn sync(&self, request: SyncRequest) -> Result<SyncResponse>;
+    /// How far this database has synced with `remote`.
+    async fn sync_cursor(&self, remote: &str) -> Result<SyncCursor>;
+    async fn set_sync_cursor(&self, remote: &str, cursor: SyncCursor) -> Result<()>;
+}
+
+/// Querying entities with some filter type `F`.
+#[async_trait]
+pub trait Search<F> {
+    type Output;
+    async fn search(&self, filter: F) -> Result<Self::Output>;
+}
+
+/// A snapshot of the connection pool.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PoolStatus {
+    /// The most connections the pool will open.
+    pub max_size: usize,
+    /// The connections currently open.
+    pub size: usize,
+    /// The open connections which aren't in use.
+    pub available: usize,
+    /// The requests waiting for a connection.
+    pub waiting: usize,
+}
+
 #[derive(Clone)]
 pub struct DieselRepository {
     pool: DbPool,
+    /// The user on whose behalf queries are made, which limits what can be
+    /// seen and changed.
+    user: Option<UserId>,
 }
 
 impl std::fmt::Debug for DieselRepository {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("DieselRepository")
             .field("pool", &"<pool>")
+            .field("user", &self.user)
             .finish()
     }
 }
 
 impl DieselRepository {
     pub fn new(pool: recipe_db::DbPool) -> Self {
-        Self { pool }
+        Self { pool, user: None }
+    }
+
+    /// A repository acting on behalf of `user`, or anonymously if `None`.
+    pub fn for_user(&self, user: Option<UserId>) -> Self {
+        Self {
+            pool: self.pool.clone(),
+            user,
+        }
+    }
+
+    /// The current user, for operations which aren't allowed anonymously.
+    fn require_user(&self) -> Result<UserId> {
+        self.user.ok_or(Error::Unauthenticated)
+    }
+
+    /// How many connections the pool has and how many are in use.
+    pub fn pool_status(&self) -> PoolStatus {
+        let status = self.pool.status();
+        PoolStatus {
+            max_size: status.max_size,
+            size: status.size,
+            available: status.available,
+            waiting: status.waiting,
+        }
+    }
+
+    /// The names of the migrations which haven't been run on the database.
+    pub async fn pending_migrations(&self) -> Result<Vec<String>> {
+        let mut conn = self.pool.get().await?;
+        Ok(recipe_db::pending_migrations(&mut conn).await?)
+    }
+}
+
+/// The slug to save a recipe or plan with. Slugs are unique across every
+/// user, so if another one already has it, the start of its sync ID is added
+/// to tell them apart.
+fn distinct_slug(slug: String, sync_id: &str, taken: bool) -> String {
+    match taken {
+        true => slugify(&format!("{slug}-{}", sync_id.get(..8).unwrap_or(sync_id))),
+        false => slug,
     }
 }
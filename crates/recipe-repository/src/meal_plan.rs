@@ -0,0 +1,341 @@
+use std::collections::HashMap;
+
+use recipe_db::prelude::*;
+
+use async_trait::async_trait;
+use chrono::prelude::*;
+use diesel::prelude::*;
+use diesel_async::{scoped_futures::ScopedFutureExt, AsyncConnection};
+use futures_util::TryStreamExt;
+
+use recipe_db::{
+    models::{
+        MealPlan as DbMealPlan, MealPlanId, MealPlanRecipe, MealPlanRecipeId, NewMealPlan,
+        NewMealPlanRecipe, Recipe as DbRecipe, RecipeId, UserId,
+    },
+    new_sync_id,
+    schema::{meal_plan_recipes, meal_plans, recipes},
+    slug::slugify,
+};
+use recipe_shared::{
+    MealPlan as SharedMealPlan, MealPlanDetail, MealPlanEntry, MealPlanInput, SyncKind,
+    ValidationErrors,
+};
+
+use crate::{
+    distinct_slug,
+    household::meal_plan_access,
+    recipe::{load_detail, Recipe},
+    sync::record_deletion,
+    BySlug, DeleteRepository, DieselRepository, Error, MealPlanRepository, Repository, Result,
+    WriteRepository,
+};
+
+pub(crate) struct MealPlan(pub(crate) SharedMealPlan);
+
+impl From<DbMealPlan> for MealPlan {
+    fn from(db_plan: DbMealPlan) -> Self {
+        Self(SharedMealPlan {
+            id: db_plan.id.into(),
+            name: db_plan.name,
+            slug: db_plan.slug,
+            start_date: db_plan.start_date,
+            end_date: db_plan.end_date,
+            owner_id: db_plan.owner_id.map(Into::into),
+            shared: db_plan.shared,
+        })
+    }
+}
+
+#[async_trait]
+impl Repository<SharedMealPlan> for DieselRepository {
+    type Id = MealPlanId;
+    async fn get(&self, id: Self::Id) -> Result<Option<SharedMealPlan>> {
+        let mut conn = self.pool.get().await?;
+        let plan: Option<DbMealPlan> = DbMealPlan::by_id(id)
+            .filter(DbMealPlan::visible_to(self.user))
+            .first(&mut conn)
+            .await
+            .optional()?;
+        Ok(plan.map(|p| MealPlan::from(p).0))
+    }
+
+    async fn list(&self) -> Result<Vec<SharedMealPlan>> {
+        let mut conn = self.pool.get().await?;
+        let plans = DbMealPlan::all()
+            .filter(DbMealPlan::visible_to(self.user))
+            .order(meal_plans::start_date.desc())
+            .load_stream(&mut conn)
+            .await?;
+        Ok(plans.map_ok(|p| MealPlan::from(p).0).try_collect().await?)
+    }
+}
+
+#[async_trait]
+impl WriteRepository<SharedMealPlan> for DieselRepository {
+    type Input = MealPlanInput;
+
+    async fn create(&self, input: Self::Input) -> Result<SharedMealPlan> {
+        let user = self.require_user()?;
+        input.validate()?;
+        let mut conn = self.pool.get().await?;
+        let sync_id = new_sync_id();
+        let mut values = new_meal_plan(&input, Utc::now().naive_utc());
+        values.slug = meal_plan_slug(&mut conn, values.slug, &sync_id).await?;
+        let plan: DbMealPlan = diesel::insert_into(meal_plans::table)
+            .values((
+                &values,
+                meal_plans::owner_id.eq(user),
+                meal_plans::sync_id.eq(sync_id),
+            ))
+            .returning(DbMealPlan::as_returning())
+            .get_result(&mut conn)
+            .await?;
+        Ok(MealPlan::from(plan).0)
+    }
+
+    async fn update(&self, id: Self::Id, input: Self::Input) -> Result<SharedMealPlan> {
+        let user = self.require_user()?;
+        input.validate()?;
+        let mut conn = self.pool.get().await?;
+        let existing: DbMealPlan = DbMealPlan::by_id(id)
+            .filter(DbMealPlan::editable_by(Some(user)))
+            .first(&mut conn)
+            .await
+            .optional()?
+            .ok_or(Error::NotFound)?;
+        let mut values = new_meal_plan(&input, Utc::now().naive_utc());
+        // Household editors can change a plan, but only its owner can make it
+        // public.
+        if !existing.is_owned_by(user) {
+            values.shared = existing.shared;
+        }
+        values.slug = meal_plan_slug(&mut conn, values.slug, &existing.sync_id).await?;
+        let plan: DbMealPlan = diesel::update(meal_plans::table.find(id))
+            .set(&values)
+            .returning(DbMealPlan::as_returning())
+            .get_result(&mut conn)
+            .await?;
+        Ok(MealPlan::from(plan).0)
+    }
+}
+
+#[async_trait]
+impl DeleteRepository<SharedMealPlan> for DieselRepository {
+    async fn delete(&self, id: Self::Id) -> Result<()> {
+        let user = self.require_user()?;
+        let mut conn = self.pool.get().await?;
+        conn.transaction::<_, Error, _>(|conn| {
+            async move {
+                let plan: DbMealPlan = DbMealPlan::by_id(id)
+                    .filter(DbMealPlan::editable_by(Some(user)))
+                    .first(conn)
+                    .await
+                    .optional()?
+                    .ok_or(Error::NotFound)?;
+                delete_meal_plan(conn, &plan).await?;
+                let deleted_at = Utc::now().naive_utc();
+                let (sync_id, owner) = (&plan.sync_id, plan.owner_id);
+                record_deletion(conn, SyncKind::MealPlan, sync_id, owner, deleted_at).await
+            }
+            .scope_boxed()
+        })
+        .await
+    }
+}
+
+#[async_trait]
+impl BySlug<MealPlanDetail> for DieselRepository {
+    async fn get_by_slug(&self, slug: &str) -> Result<Option<MealPlanDetail>> {
+        let mut conn = self.pool.get().await?;
+        let plan: Option<DbMealPlan> = DbMealPlan::by_slug(slug)
+            .filter(DbMealPlan::visible_to(self.user))
+            .first(&mut conn)
+            .await
+            .optional()?;
+        match plan {
+            Some(plan) => Ok(Some(
+                load_meal_plan_detail(&mut conn, self.user, plan).await?,
+            )),
+            None => Ok(None),
+        }
+    }
+}
+
+#[async_trait]
+impl MealPlanRepository for DieselRepository {
+    type MealPlanId = MealPlanId;
+    type RecipeId = RecipeId;
+    type EntryId = MealPlanRecipeId;
+
+    async fn add_recipe(
+        &self,
+        meal_plan_id: Self::MealPlanId,
+        recipe_id: Self::RecipeId,
+        day: NaiveDate,
+    ) -> Result<()> {
+        let user = self.require_user()?;
+        let mut conn = self.pool.get().await?;
+        let plan: DbMealPlan = DbMealPlan::by_id(meal_plan_id)
+            .filter(DbMealPlan::editable_by(Some(user)))
+            .first(&mut conn)
+            .await
+            .optional()?
+            .ok_or(Error::NotFound)?;
+        if day < plan.start_date || day > plan.end_date {
+            let mut errors = ValidationErrors::default();
+            errors.add("day", "must be within the dates of the meal plan");
+            return Err(errors.into());
+        }
+        // Only recipes the user can see may be planned.
+        DbRecipe::by_id(recipe_id)
+            .filter(DbRecipe::visible_to(Some(user)))
+            .select(recipes::id)
+            .first::<RecipeId>(&mut conn)
+            .await
+            .optional()?
+            .ok_or(Error::NotFound)?;
+        diesel::insert_into(meal_plan_recipes::table)
+            .values(&NewMealPlanRecipe {
+                meal_plan_id,
+                recipe_id,
+                day,
+            })
+            .execute(&mut conn)
+            .await?;
+        mark_meal_plans_changed(&mut conn, &[meal_plan_id]).await
+    }
+
+    async fn remove_recipe(&self, id: Self::EntryId) -> Result<()> {
+        let user = self.require_user()?;
+        let mut conn = self.pool.get().await?;
+        let plan_ids: Vec<MealPlanId> = meal_plan_recipes::table
+            .find(id)
+            .select(meal_plan_recipes::meal_plan_id)
+            .load(&mut conn)
+            .await?;
+        diesel::delete(
+            meal_plan_recipes::table.find(id).filter(
+                meal_plan_recipes::meal_plan_id.eq_any(
+                    meal_plans::table
+                        .filter(DbMealPlan::editable_by(Some(user)))
+                        .select(meal_plans::id),
+                ),
+            ),
+        )
+        .execute(&mut conn)
+        .await?;
+        mark_meal_plans_changed(&mut conn, &plan_ids).await
+    }
+}
+
+/// The plan's own columns from `input`, as edited at `updated_at`.
+pub(crate) fn new_meal_plan(input: &MealPlanInput, updated_at: NaiveDateTime) -> NewMealPlan<'_> {
+    let name = input.name.trim();
+    NewMealPlan {
+        name,
+        slug: slugify(name),
+        start_date: input.start_date,
+        end_date: input.end_date,
+        shared: input.shared,
+        updated_at,
+        changed_at: Utc::now().naive_utc(),
+    }
+}
+
+/// The slug to save the plan with sync ID `sync_id` with, adding to `slug`
+/// if another plan already has it.
+pub(crate) async fn meal_plan_slug(
+    conn: &mut DbConnection,
+    slug: String,
+    sync_id: &str,
+) -> Result<String> {
+    let taken = meal_plans::table
+        .filter(meal_plans::slug.eq(&slug))
+        .filter(meal_plans::sync_id.ne(sync_id))
+        .select(meal_plans::id)
+        .first::<MealPlanId>(conn)
+        .await
+        .optional()?
+        .is_some();
+    Ok(distinct_slug(slug, sync_id, taken))
+}
+
+/// Delete a meal plan along with its entries.
+pub(crate) async fn delete_meal_plan(conn: &mut DbConnection, plan: &DbMealPlan) -> Result<()> {
+    diesel::delete(MealPlanRecipe::belonging_to(plan))
+        .execute(conn)
+        .await?;
+    // Household shares are deleted along with it.
+    diesel::delete(meal_plans::table.find(plan.id))
+        .execute(conn)
+        .await?;
+    Ok(())
+}
+
+/// Record that the entries of some meal plans changed, so they're synced.
+pub(crate) async fn mark_meal_plans_changed(
+    conn: &mut DbConnection,
+    plan_ids: &[MealPlanId],
+) -> Result<()> {
+    if plan_ids.is_empty() {
+        return Ok(());
+    }
+    let now = Utc::now().naive_utc();
+    diesel::update(DbMealPlan::by_ids(plan_ids))
+        .set((
+            meal_plans::updated_at.eq(now),
+            meal_plans::changed_at.eq(now),
+        ))
+        .execute(conn)
+        .await?;
+    Ok(())
+}
+
+/// Fetch the recipes of a meal plan which `user` can see, along with the
+/// nutrition of each.
+async fn load_meal_plan_detail(
+    conn: &mut DbConnection,
+    user: Option<UserId>,
+    plan: DbMealPlan,
+) -> Result<MealPlanDetail> {
+    let rows: Vec<(MealPlanRecipe, DbRecipe)> = MealPlanRecipe::belonging_to(&plan)
+        .inner_join(recipes::table)
+        .filter(
+            meal_plan_recipes::recipe_id.eq_any(
+                recipes::table
+                    .filter(DbRecipe::visible_to(user))
+                    .select(recipes::id),
+            ),
+        )
+        .order((meal_plan_recipes::day, meal_plan_recipes::id))
+        .load(conn)
+        .await?;
+
+    let mut nutrition = HashMap::new();
+    let mut entries = Vec::with_capacity(rows.len());
+    for (entry, recipe) in rows {
+        let (per_serving, source) = match nutrition.get(&recipe.id) {
+            Some(n) => *n,
+            None => {
+                let detail = load_detail(conn, user, recipe.clone()).await?;
+                let n = detail.nutrition.per_serving();
+                nutrition.insert(recipe.id, n);
+                n
+            }
+        };
+        entries.push(MealPlanEntry {
+            id: entry.id.into(),
+            day: entry.day,
+            recipe: Recipe::from(recipe).0,
+            nutrition: per_serving,
+            nutrition_source: source,
+        });
+    }
+    let (editable, household_ids) = meal_plan_access(conn, user, plan.id).await?;
+    Ok(MealPlanDetail {
+        household_ids,
+        editable,
+        ..MealPlanDetail::new(MealPlan::from(plan).0, entries)
+    })
+}
@@ -0,0 +1,49 @@
+use recipe_db::prelude::*;
+
+use async_trait::async_trait;
+use diesel::prelude::*;
+use futures_util::TryStreamExt;
+
+use recipe_db::{
+    models::{Measurement as DbMeasurement, MeasurementId},
+    schema::measurements,
+};
+use recipe_shared::Measurement as SharedMeasurement;
+
+use crate::{DieselRepository, Repository, Result};
+
+struct Measurement(SharedMeasurement);
+
+impl From<DbMeasurement> for Measurement {
+    fn from(db_measurement: DbMeasurement) -> Self {
+        Self(SharedMeasurement {
+            id: db_measurement.id.into(),
+            name: db_measurement.name,
+            slug: db_measurement.slug,
+            abbreviation: db_measurement.abbreviation,
+        })
+    }
+}
+
+#[async_trait]
+impl Repository<SharedMeasurement> for DieselRepository {
+    type Id = MeasurementId;
+    async fn get(&self, id: Self::Id) -> Result<Option<SharedMeasurement>> {
+        let mut conn = self.pool.get().await?;
+        let measurement: Option<DbMeasurement> =
+            DbMeasurement::by_id(id).first(&mut conn).await.optional()?;
+        Ok(measurement.map(|m| Measurement::from(m).0))
+    }
+
+    async fn list(&self) -> Result<Vec<SharedMeasurement>> {
+        let mut conn = self.pool.get().await?;
+        let measurements = DbMeasurement::all()
+            .order(measurements::name)
+            .load_stream(&mut conn)
+            .await?;
+        Ok(measurements
+            .map_ok(|m| Measurement::from(m).0)
+            .try_collect()
+            .await?)
+    }
+}
@@ -0,0 +1,284 @@
+use std::{collections::HashMap, sync::OnceLock};
+
+use recipe_db::prelude::*;
+
+use diesel::prelude::*;
+
+use recipe_db::{
+    models::{NewRecipeNutrition, RecipeId, RecipeNutrition as DbRecipeNutrition},
+    schema::recipe_nutrition,
+};
+use recipe_scrape::Unit;
+use recipe_shared::{Nutrition, RecipeIngredient, RecipeNutrition};
+
+use crate::Result;
+
+/// Nutrient values per 100g, bundled so estimates work without any network
+/// access.
+const NUTRIENT_DATA: &str = include_str!("../data/nutrients.csv");
+
+#[derive(Debug, Clone, Copy)]
+struct Nutrients {
+    per_100g: Nutrition,
+    grams_each: Option<f64>,
+    grams_per_ml: Option<f64>,
+}
+
+fn nutrient_table() -> &'static HashMap<String, Nutrients> {
+    static TABLE: OnceLock<HashMap<String, Nutrients>> = OnceLock::new();
+    TABLE.get_or_init(|| {
+        NUTRIENT_DATA
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .skip(1)
+            .filter_map(|line| {
+                let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+                let value = |i: usize| fields.get(i).and_then(|f| f.parse::<f64>().ok());
+                let nutrients = Nutrients {
+                    per_100g: Nutrition {
+                        energy_kcal: value(1),
+                        fat_g: value(2),
+                        saturated_fat_g: value(3),
+                        carbohydrate_g: value(4),
+                        sugar_g: value(5),
+                        fibre_g: value(6),
+                        protein_g: value(7),
+                        salt_g: value(8),
+                    },
+                    grams_each: value(9),
+                    grams_per_ml: value(10),
+                };
+                Some((fields.first()?.to_string(), nutrients))
+            })
+            .collect()
+    })
+}
+
+/// Find the nutrient data for an ingredient, ignoring plurals and falling
+/// back to less specific names, e.g. "unsalted butter" uses "butter".
+fn lookup(name: &str) -> Option<&'static Nutrients> {
+    let table = nutrient_table();
+    let name = name.trim().to_lowercase();
+    let words: Vec<&str> = name.split_whitespace().collect();
+    (0..words.len()).find_map(|start| {
+        let candidate = words[start..].join(" ");
+        let singular = candidate
+            .strip_suffix("oes")
+            .map(|c| format!("{c}o"))
+            .or_else(|| candidate.strip_suffix('s').map(str::to_string));
+        table
+            .get(&candidate)
+            .or_else(|| singular.and_then(|s| table.get(&s)))
+    })
+}
+
+/// Rough weights of the informal units the ingredient parser understands.
+fn informal_grams(unit: &str) -> Option<f64> {
+    match unit.trim_end_matches(" of") {
+        "pinch" => Some(0.4),
+        "handful" => Some(30.0),
+        "small pack" => Some(20.0),
+        "large pack" => Some(40.0),
+        _ => None,
+    }
+}
+
+/// Estimate the weight of an ingredient in grams.
+fn grams(ingredient: &RecipeIngredient, nutrients: &Nutrients) -> Option<f64> {
+    let quantity = ingredient.quantity?;
+    let Some(unit) = &ingredient.unit else {
+        return nutrients.grams_each.map(|g| g * quantity);
+    };
+    let unit: Unit = unit.parse().ok()?;
+    unit.grams(quantity)
+        .or_else(|| {
+            unit.millilitres(quantity)
+                .map(|ml| ml * nutrients.grams_per_ml.unwrap_or(1.0))
+        })
+        .or_else(|| match &unit {
+            Unit::Other(other) => informal_grams(other).map(|g| g * quantity),
+            _ => None,
+        })
+}
+
+/// Estimate the nutrition of one serving from the ingredients, returning
+/// the names of any ingredients which couldn't be included.
+///
+/// Ingredients without a quantity ("salt, to taste") are assumed to be
+/// negligible and are skipped.
+pub(crate) fn estimate(
+    ingredients: &[RecipeIngredient],
+    servings: Option<i32>,
+) -> (Nutrition, Vec<String>) {
+    let mut total = Nutrition::default();
+    let mut unestimated = Vec::new();
+    for ingredient in ingredients.iter().filter(|i| i.quantity.is_some()) {
+        let estimate = lookup(&ingredient.name).and_then(|nutrients| {
+            Some(
+                nutrients
+                    .per_100g
+                    .scale(grams(ingredient, nutrients)? / 100.0),
+            )
+        });
+        match estimate {
+            Some(nutrition) => total = total + nutrition,
+            None => unestimated.push(ingredient.name.clone()),
+        }
+    }
+    let servings = servings.filter(|s| *s > 0).unwrap_or(1);
+    (total.scale(1.0 / servings as f64), unestimated)
+}
+
+/// Combine the declared nutrition of a recipe, if any, with an estimate from
+/// its ingredients.
+pub(crate) async fn load_nutrition(
+    conn: &mut DbConnection,
+    recipe_id: RecipeId,
+    ingredients: &[RecipeIngredient],
+    servings: Option<i32>,
+) -> Result<RecipeNutrition> {
+    let declared: Option<DbRecipeNutrition> = recipe_nutrition::table
+        .find(recipe_id)
+        .select(DbRecipeNutrition::as_select())
+        .first(conn)
+        .await
+        .optional()?;
+    let (estimated, unestimated) = estimate(ingredients, servings);
+    Ok(RecipeNutrition {
+        declared: declared.map(|n| Nutrition {
+            energy_kcal: n.energy_kcal,
+            fat_g: n.fat_g,
+            saturated_fat_g: n.saturated_fat_g,
+            carbohydrate_g: n.carbohydrate_g,
+            sugar_g: n.sugar_g,
+            fibre_g: n.fibre_g,
+            protein_g: n.protein_g,
+            salt_g: n.salt_g,
+        }),
+        estimated,
+        unestimated,
+    })
+}
+
+/// Replace the declared nutrition of a recipe, removing it if `nutrition` is
+/// empty so the estimate is used instead.
+pub(crate) async fn set_recipe_nutrition(
+    conn: &mut DbConnection,
+    recipe_id: RecipeId,
+    nutrition: Option<&Nutrition>,
+) -> Result<()> {
+    let Some(n) = nutrition.filter(|n| !n.is_empty()) else {
+        diesel::delete(recipe_nutrition::table.find(recipe_id))
+            .execute(conn)
+            .await?;
+        return Ok(());
+    };
+    let values = NewRecipeNutrition {
+        recipe_id,
+        energy_kcal: n.energy_kcal,
+        fat_g: n.fat_g,
+        saturated_fat_g: n.saturated_fat_g,
+        carbohydrate_g: n.carbohydrate_g,
+        sugar_g: n.sugar_g,
+        fibre_g: n.fibre_g,
+        protein_g: n.protein_g,
+        salt_g: n.salt_g,
+    };
+    diesel::insert_into(recipe_nutrition::table)
+        .values(&values)
+        .on_conflict(recipe_nutrition::recipe_id)
+        .do_update()
+        .set(&values)
+        .execute(conn)
+        .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ingredient(quantity: Option<f64>, unit: Option<&str>, name: &str) -> RecipeIngredient {
+        RecipeIngredient {
+            ingredient_id: 0,
+            name: name.to_string(),
+            quantity,
+            unit: unit.map(str::to_string),
+            notes: None,
+        }
+    }
+
+    /// The estimated energy of one serving of a single ingredient.
+    fn kcal(quantity: f64, unit: Option<&str>, name: &str) -> f64 {
+        let (nutrition, unestimated) = estimate(&[ingredient(Some(quantity), unit, name)], None);
+        assert!(unestimated.is_empty(), "{name} wasn't estimated");
+        nutrition.energy_kcal.unwrap()
+    }
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() < 1e-6,
+            "expected {expected}, got {actual}"
+        );
+    }
+
+    #[test]
+    fn units_are_converted_to_grams() {
+        // Flour has 364 kcal per 100g.
+        assert_close(kcal(200.0, Some("g"), "flour"), 728.0);
+        assert_close(kcal(1.0, Some("kg"), "flour"), 3640.0);
+        assert_close(kcal(1.0, Some("lb"), "flour"), 364.0 * 4.535_923_7);
+        // Volumes are weighed by the ingredient's density, so 100ml of milk
+        // is 103g, and a tablespoon of honey is 15ml at 1.42g/ml.
+        assert_close(kcal(100.0, Some("ml"), "milk"), 64.0 * 1.03);
+        assert_close(kcal(2.0, Some("tbsp"), "honey"), 304.0 * 0.426);
+        // Counted ingredients use the weight of one.
+        assert_close(kcal(2.0, None, "eggs"), 143.0);
+        assert_close(kcal(3.0, None, "tomatoes"), 18.0 * 2.55);
+        assert_close(kcal(1.0, Some("handful"), "basil"), 23.0 * 0.3);
+        assert_close(kcal(2.0, Some("pinch of"), "salt"), 0.0);
+    }
+
+    #[test]
+    fn less_specific_names_are_used() {
+        assert_close(kcal(100.0, Some("g"), "unsalted butter"), 737.0);
+        assert_close(kcal(100.0, Some("g"), "Mature Cheddar"), 416.0);
+    }
+
+    #[test]
+    fn unknown_ingredients_are_listed() {
+        let ingredients = [
+            ingredient(Some(100.0), Some("g"), "flour"),
+            ingredient(Some(1.0), None, "dragon fruit"),
+            // Known, but neither counted nor weighed.
+            ingredient(Some(2.0), None, "flour"),
+            ingredient(Some(2.0), Some("sprigs"), "basil"),
+            // Too little to matter.
+            ingredient(None, None, "black pepper"),
+        ];
+        let (nutrition, unestimated) = estimate(&ingredients, None);
+        assert_close(nutrition.energy_kcal.unwrap(), 364.0);
+        assert_eq!(unestimated, ["dragon fruit", "flour", "basil"]);
+
+        let (nutrition, unestimated) = estimate(&ingredients[1..2], None);
+        assert!(nutrition.is_empty());
+        assert_eq!(unestimated, ["dragon fruit"]);
+    }
+
+    #[test]
+    fn estimates_are_per_serving() {
+        let ingredients = [
+            ingredient(Some(400.0), Some("g"), "flour"),
+            ingredient(Some(4.0), None, "eggs"),
+        ];
+        let whole = 364.0 * 4.0 + 143.0 * 2.0;
+        let per_serving = |servings| estimate(&ingredients, servings).0.energy_kcal.unwrap();
+        assert_close(per_serving(Some(4)), whole / 4.0);
+        assert_close(per_serving(Some(1)), whole);
+        // Without a sensible number of servings, the whole recipe is one.
+        assert_close(per_serving(None), whole);
+        assert_close(per_serving(Some(0)), whole);
+        assert_close(per_serving(Some(-2)), whole);
+    }
+}
@@ -1,15 +1,48 @@
+use std::collections::HashMap;
+
 use recipe_db::prelude::*;
 
 use async_trait::async_trait;
+use chrono::prelude::*;
 use diesel::prelude::*;
+use diesel_async::{scoped_futures::ScopedFutureExt, AsyncConnection};
 use futures_util::TryStreamExt;
 
-use recipe_db::models::{Recipe as DbRecipe, RecipeId};
-use recipe_shared::Recipe as SharedRecipe;
+use recipe_db::{
+    dsl::{lower, lower_nullable},
+    models::{
+        quantity_from_f64, quantity_to_f64, Ingredient, IngredientId, MealPlanId, Measurement,
+        MeasurementId, NewIngredient, NewMeasurement, NewRecipe, NewRecipeIngredient,
+        NewRecipeStep, Recipe as DbRecipe, RecipeId, RecipeIngredient as DbRecipeIngredient,
+        RecipeStep as DbRecipeStep, UserId,
+    },
+    new_sync_id,
+    schema::{
+        ingredients, meal_plan_recipes, measurements, recipe_ingredients, recipe_steps, recipes,
+    },
+    slug::slugify,
+};
+use recipe_scrape::Unit;
+use recipe_shared::{
+    Recipe as SharedRecipe, RecipeDetail, RecipeIngredient as SharedRecipeIngredient, RecipeInput,
+    RecipeStep as SharedRecipeStep, SyncKind, ValidationErrors,
+};
+
+use crate::{
+    diet::{detect_ingredient_flags, load_diet_info, set_recipe_diets},
+    distinct_slug,
+    household::recipe_access,
+    meal_plan::mark_meal_plans_changed,
+    nutrition::{load_nutrition, set_recipe_nutrition},
+    sync::record_deletion,
+    tag::{load_recipe_tags, set_recipe_tags},
+    BySlug, DeleteRepository, DieselRepository, Error, Repository, Result, WriteRepository,
+};
 
-use crate::{DieselRepository, Repository, Result};
+/// The name of the measurement used for ingredients without a unit.
+const COUNT_MEASUREMENT: &str = "count";
 
-struct Recipe(SharedRecipe);
+pub(crate) struct Recipe(pub(crate) SharedRecipe);
 
 impl From<DbRecipe> for Recipe {
     fn from(db_recipe: DbRecipe) -> Self {
@@ -23,6 +56,9 @@ impl From<DbRecipe> for Recipe {
             prep_time_minutes: db_recipe.prep_time_minutes,
             cooking_time_minutes: db_recipe.cooking_time_minutes,
             image_url: db_recipe.image_url,
+            servings: db_recipe.servings,
+            owner_id: db_recipe.owner_id.map(Into::into),
+            shared: db_recipe.shared,
         })
     }
 }
@@ -32,13 +68,675 @@ impl Repository<SharedRecipe> for DieselRepository {
     type Id = RecipeId;
     async fn get(&self, id: Self::Id) -> Result<Option<SharedRecipe>> {
         let mut conn = self.pool.get().await?;
-        let recipe: Option<DbRecipe> = DbRecipe::by_id(id).first(&mut conn).await.optional()?;
+        let recipe: Option<DbRecipe> = DbRecipe::by_id(id)
+            .filter(DbRecipe::visible_to(self.user))
+            .first(&mut conn)
+            .await
+            .optional()?;
         Ok(recipe.map(|r| Recipe::from(r).0))
     }
 
     async fn list(&self) -> Result<Vec<SharedRecipe>> {
         let mut conn = self.pool.get().await?;
-        let recipes = DbRecipe::all().load_stream(&mut conn).await?;
+        let recipes = DbRecipe::all()
+            .filter(DbRecipe::visible_to(self.user))
+            .load_stream(&mut conn)
+            .await?;
         Ok(recipes.map_ok(|r| Recipe::from(r).0).try_collect().await?)
     }
 }
+
+#[async_trait]
+impl Repository<RecipeDetail> for DieselRepository {
+    type Id = RecipeId;
+    async fn get(&self, id: Self::Id) -> Result<Option<RecipeDetail>> {
+        let mut conn = self.pool.get().await?;
+        let recipe: Option<DbRecipe> = DbRecipe::by_id(id)
+            .filter(DbRecipe::visible_to(self.user))
+            .first(&mut conn)
+            .await
+            .optional()?;
+        match recipe {
+            Some(recipe) => Ok(Some(load_detail(&mut conn, self.user, recipe).await?)),
+            None => Ok(None),
+        }
+    }
+
+    async fn list(&self) -> Result<Vec<RecipeDetail>> {
+        let mut conn = self.pool.get().await?;
+        let recipes: Vec<DbRecipe> = DbRecipe::all()
+            .filter(DbRecipe::visible_to(self.user))
+            .load(&mut conn)
+            .await?;
+        let mut details = Vec::with_capacity(recipes.len());
+        for recipe in recipes {
+            details.push(load_detail(&mut conn, self.user, recipe).await?);
+        }
+        Ok(details)
+    }
+}
+
+#[async_trait]
+impl BySlug<RecipeDetail> for DieselRepository {
+    async fn get_by_slug(&self, slug: &str) -> Result<Option<RecipeDetail>> {
+        let mut conn = self.pool.get().await?;
+        let recipe: Option<DbRecipe> = DbRecipe::by_slug(slug)
+            .filter(DbRecipe::visible_to(self.user))
+            .first(&mut conn)
+            .await
+            .optional()?;
+        match recipe {
+            Some(recipe) => Ok(Some(load_detail(&mut conn, self.user, recipe).await?)),
+            None => Ok(None),
+        }
+    }
+}
+
+#[async_trait]
+impl WriteRepository<RecipeDetail> for DieselRepository {
+    type Input = RecipeInput;
+
+    async fn create(&self, input: Self::Input) -> Result<RecipeDetail> {
+        let user = self.require_user()?;
+        input.validate()?;
+        let mut conn = self.pool.get().await?;
+        let recipe = conn
+            .transaction::<_, Error, _>(|conn| {
+                async move {
+                    let sync_id = new_sync_id();
+                    let mut values = new_recipe(&input, Utc::now().naive_utc());
+                    values.slug = recipe_slug(conn, values.slug, &sync_id).await?;
+                    let recipe: DbRecipe = diesel::insert_into(recipes::table)
+                        .values((
+                            &values,
+                            recipes::owner_id.eq(user),
+                            recipes::sync_id.eq(sync_id),
+                        ))
+                        .returning(DbRecipe::as_returning())
+                        .get_result(conn)
+                        .await?;
+                    set_recipe_contents(conn, recipe.id, &input).await?;
+                    Ok(recipe)
+                }
+                .scope_boxed()
+            })
+            .await?;
+        load_detail(&mut conn, self.user, recipe).await
+    }
+
+    async fn update(&self, id: Self::Id, input: Self::Input) -> Result<RecipeDetail> {
+        let user = self.require_user()?;
+        input.validate()?;
+        let mut conn = self.pool.get().await?;
+        let recipe = conn
+            .transaction::<_, Error, _>(|conn| {
+                async move {
+                    let existing: DbRecipe = DbRecipe::by_id(id)
+                        .filter(DbRecipe::editable_by(Some(user)))
+                        .first(conn)
+                        .await
+                        .optional()?
+                        .ok_or(Error::NotFound)?;
+                    let mut values = new_recipe(&input, Utc::now().naive_utc());
+                    // Household editors can change a recipe, but only its
+                    // owner can make it public.
+                    if !existing.is_owned_by(user) {
+                        values.shared = existing.shared;
+                    }
+                    values.slug = recipe_slug(conn, values.slug, &existing.sync_id).await?;
+                    let recipe: DbRecipe = diesel::update(recipes::table.find(id))
+                        .set(&values)
+                        .returning(DbRecipe::as_returning())
+                        .get_result(conn)
+                        .await?;
+                    set_recipe_contents(conn, id, &input).await?;
+                    Ok(recipe)
+                }
+                .scope_boxed()
+            })
+            .await?;
+        load_detail(&mut conn, self.user, recipe).await
+    }
+}
+
+#[async_trait]
+impl DeleteRepository<RecipeDetail> for DieselRepository {
+    async fn delete(&self, id: Self::Id) -> Result<()> {
+        let user = self.require_user()?;
+        let mut conn = self.pool.get().await?;
+        conn.transaction::<_, Error, _>(|conn| {
+            async move {
+                let recipe: DbRecipe = DbRecipe::by_id(id)
+                    .filter(DbRecipe::editable_by(Some(user)))
+                    .first(conn)
+                    .await
+                    .optional()?
+                    .ok_or(Error::NotFound)?;
+                delete_recipe(conn, &recipe).await?;
+                let deleted_at = Utc::now().naive_utc();
+                let (sync_id, owner) = (&recipe.sync_id, recipe.owner_id);
+                record_deletion(conn, SyncKind::Recipe, sync_id, owner, deleted_at).await
+            }
+            .scope_boxed()
+        })
+        .await
+    }
+}
+
+fn non_empty(s: &Option<String>) -> Option<&str> {
+    s.as_deref().map(str::trim).filter(|s| !s.is_empty())
+}
+
+/// The recipe's own columns from `input`, as edited at `updated_at`.
+pub(crate) fn new_recipe(input: &RecipeInput, updated_at: NaiveDateTime) -> NewRecipe<'_> {
+    let name = input.name.trim();
+    NewRecipe {
+        name,
+        slug: slugify(name),
+        source: non_empty(&input.source),
+        notes: non_empty(&input.notes),
+        prep_time_minutes: input.prep_time_minutes,
+        cooking_time_minutes: input.cooking_time_minutes,
+        image_url: non_empty(&input.image_url),
+        servings: input.servings,
+        shared: input.shared,
+        updated_at,
+        changed_at: Utc::now().naive_utc(),
+    }
+}
+
+/// Replace everything belonging to a recipe with what's in `input`.
+pub(crate) async fn set_recipe_contents(
+    conn: &mut DbConnection,
+    recipe_id: RecipeId,
+    input: &RecipeInput,
+) -> Result<()> {
+    diesel::delete(recipe_ingredients::table.filter(recipe_ingredients::recipe_id.eq(recipe_id)))
+        .execute(conn)
+        .await?;
+    diesel::delete(recipe_steps::table.filter(recipe_steps::recipe_id.eq(recipe_id)))
+        .execute(conn)
+        .await?;
+    insert_recipe_contents(conn, recipe_id, input).await?;
+    set_recipe_tags(conn, recipe_id, &input.tags).await?;
+    set_recipe_diets(conn, recipe_id, &input.diets).await?;
+    set_recipe_nutrition(conn, recipe_id, input.nutrition.as_ref()).await
+}
+
+/// Delete a recipe along with everything belonging to it, taking it off any
+/// meal plans it was planned on.
+pub(crate) async fn delete_recipe(conn: &mut DbConnection, recipe: &DbRecipe) -> Result<()> {
+    let plan_ids: Vec<MealPlanId> = meal_plan_recipes::table
+        .filter(meal_plan_recipes::recipe_id.eq(recipe.id))
+        .select(meal_plan_recipes::meal_plan_id)
+        .distinct()
+        .load(conn)
+        .await?;
+    diesel::delete(meal_plan_recipes::table.filter(meal_plan_recipes::recipe_id.eq(recipe.id)))
+        .execute(conn)
+        .await?;
+    mark_meal_plans_changed(conn, &plan_ids).await?;
+    diesel::delete(recipe_ingredients::table.filter(recipe_ingredients::recipe_id.eq(recipe.id)))
+        .execute(conn)
+        .await?;
+    diesel::delete(recipe_steps::table.filter(recipe_steps::recipe_id.eq(recipe.id)))
+        .execute(conn)
+        .await?;
+    // Tags, diets, nutrition and household shares are deleted along with it.
+    diesel::delete(recipes::table.find(recipe.id))
+        .execute(conn)
+        .await?;
+    Ok(())
+}
+
+/// Record that something belonging to a recipe changed, so it's synced.
+pub(crate) async fn mark_recipe_changed(
+    conn: &mut DbConnection,
+    recipe_id: RecipeId,
+) -> Result<()> {
+    let now = Utc::now().naive_utc();
+    diesel::update(recipes::table.find(recipe_id))
+        .set((recipes::updated_at.eq(now), recipes::changed_at.eq(now)))
+        .execute(conn)
+        .await?;
+    Ok(())
+}
+
+/// Fetch the ingredients (with their measurements), steps, tags, dietary
+/// information and nutrition of a recipe, along with what `user` may do with
+/// it.
+pub(crate) async fn load_detail(
+    conn: &mut DbConnection,
+    user: Option<UserId>,
+    recipe: DbRecipe,
+) -> Result<RecipeDetail> {
+    let rows: Vec<(DbRecipeIngredient, Ingredient, Option<Measurement>)> =
+        recipe_ingredients::table
+            .inner_join(ingredients::table)
+            .left_join(
+                measurements::table
+                    .on(recipe_ingredients::measurement_id.eq(measurements::id.nullable())),
+            )
+            .filter(recipe_ingredients::recipe_id.eq(recipe.id))
+            .order(recipe_ingredients::idx)
+            .load(conn)
+            .await?;
+
+    // Recipe ingredients without an explicit measurement use the ingredient's default.
+    let default_ids: Vec<MeasurementId> = rows
+        .iter()
+        .filter(|(_, _, measurement)| measurement.is_none())
+        .map(|(_, ingredient, _)| ingredient.default_measurement_id)
+        .collect();
+    let defaults: HashMap<MeasurementId, Measurement> = if default_ids.is_empty() {
+        HashMap::new()
+    } else {
+        Measurement::by_ids(&default_ids)
+            .load::<Measurement>(conn)
+            .await?
+            .into_iter()
+            .map(|m| (m.id, m))
+            .collect()
+    };
+
+    let used: Vec<Ingredient> = rows.iter().map(|(_, i, _)| i.clone()).collect();
+    let diet = load_diet_info(conn, recipe.id, &used).await?;
+
+    let ingredients: Vec<SharedRecipeIngredient> = rows
+        .into_iter()
+        .map(|(recipe_ingredient, ingredient, measurement)| {
+            let measurement =
+                measurement.or_else(|| defaults.get(&ingredient.default_measurement_id).cloned());
+            SharedRecipeIngredient {
+                ingredient_id: ingredient.id.into(),
+                name: ingredient.name,
+                quantity: recipe_ingredient
+                    .quantity
+                    .as_ref()
+                    .and_then(quantity_to_f64),
+                unit: measurement.and_then(|m| m.abbreviation),
+                notes: recipe_ingredient.notes,
+            }
+        })
+        .collect();
+
+    let steps = recipe
+        .steps()
+        .order(recipe_steps::step_number)
+        .load::<DbRecipeStep>(conn)
+        .await?
+        .into_iter()
+        .map(|step| SharedRecipeStep {
+            step_number: step.step_number,
+            description: step.description,
+        })
+        .collect();
+
+    let nutrition = load_nutrition(conn, recipe.id, &ingredients, recipe.servings).await?;
+
+    let tags = load_recipe_tags(conn, &[recipe.id])
+        .await?
+        .remove(&recipe.id)
+        .unwrap_or_default();
+
+    let (editable, household_ids) = recipe_access(conn, user, recipe.id).await?;
+
+    Ok(RecipeDetail {
+        recipe: Recipe::from(recipe).0,
+        ingredients,
+        steps,
+        tags,
+        diet,
+        nutrition,
+        household_ids,
+        editable,
+    })
+}
+
+/// The slug to save the recipe with sync ID `sync_id` with, adding to
+/// `slug` if another recipe already has it.
+pub(crate) async fn recipe_slug(
+    conn: &mut DbConnection,
+    slug: String,
+    sync_id: &str,
+) -> Result<String> {
+    let taken = recipes::table
+        .filter(recipes::slug.eq(&slug))
+        .filter(recipes::sync_id.ne(sync_id))
+        .select(recipes::id)
+        .first::<RecipeId>(conn)
+        .await
+        .optional()?
+        .is_some();
+    Ok(distinct_slug(slug, sync_id, taken))
+}
+
+/// Insert the ingredients and steps from `input`, creating any
+/// ingredients and measurements which don't exist yet.
+async fn insert_recipe_contents(
+    conn: &mut DbConnection,
+    recipe_id: RecipeId,
+    input: &RecipeInput,
+) -> Result<()> {
+    // Names which slugify to the same value are the same ingredient, which a
+    // recipe can only list once.
+    let mut slugs = HashMap::new();
+    for (index, ingredient) in input.ingredients.iter().enumerate() {
+        let name = ingredient.name.trim();
+        if let Some(first) = slugs.insert(slugify(name), index) {
+            return Err(invalid_ingredient(
+                index,
+                format!("is the same ingredient as ingredient {}", first + 1),
+            ));
+        }
+        let measurement_id = upsert_measurement(conn, non_empty(&ingredient.unit)).await?;
+        let ingredient_id = upsert_ingredient(conn, name, measurement_id)
+            .await
+            .map_err(|e| ingredient_clash(index, e))?;
+        let recipe_ingredient = NewRecipeIngredient {
+            recipe_id,
+            ingredient_id,
+            measurement_id: Some(measurement_id),
+            quantity: ingredient.quantity.and_then(quantity_from_f64),
+            index: index as i32,
+            notes: non_empty(&ingredient.notes),
+        };
+        diesel::insert_into(recipe_ingredients::table)
+            .values(&recipe_ingredient)
+            .execute(conn)
+            .await
+            .map_err(|e| ingredient_clash(index, e.into()))?;
+    }
+
+    let steps: Vec<_> = input
+        .steps
+        .iter()
+        .enumerate()
+        .map(|(i, description)| NewRecipeStep {
+            recipe_id,
+            step_number: i as i32 + 1,
+            description: description.trim(),
+        })
+        .collect();
+    if !steps.is_empty() {
+        diesel::insert_into(recipe_steps::table)
+            .values(&steps)
+            .execute(conn)
+            .await?;
+    }
+    Ok(())
+}
+
+/// A validation error for the ingredient at `index` in the input.
+fn invalid_ingredient(index: usize, message: impl Into<String>) -> Error {
+    let mut errors = ValidationErrors::default();
+    errors.add(&format!("ingredients.{index}"), message);
+    errors.into()
+}
+
+/// `e`, or a validation error for the ingredient at `index` if it clashed with
+/// another ingredient.
+fn ingredient_clash(index: usize, e: Error) -> Error {
+    match e.is_conflict() {
+        true => invalid_ingredient(index, "clashes with another ingredient's name"),
+        false => e,
+    }
+}
+
+/// The measurement for `unit`, reusing one whose name or abbreviation
+/// matches it in any case, so that "tablespoon", "Tbsp" and "tbsp" share a
+/// measurement.
+async fn upsert_measurement(conn: &mut DbConnection, unit: Option<&str>) -> Result<MeasurementId> {
+    let Some(unit) = unit else {
+        return insert_measurement(conn, COUNT_MEASUREMENT, None).await;
+    };
+    let canonical = canonical_unit(unit);
+    let spellings = [canonical.to_lowercase(), unit.to_lowercase()];
+    let existing: Option<MeasurementId> = measurements::table
+        .filter(
+            lower(measurements::name)
+                .eq_any(spellings.clone())
+                .or(lower_nullable(measurements::abbreviation).eq_any(spellings)),
+        )
+        .select(measurements::id)
+        .order(measurements::id)
+        .first(conn)
+        .await
+        .optional()?;
+    match existing {
+        Some(id) => Ok(id),
+        None => insert_measurement(conn, &canonical, Some(&canonical)).await,
+    }
+}
+
+async fn insert_measurement(
+    conn: &mut DbConnection,
+    name: &str,
+    abbreviation: Option<&str>,
+) -> Result<MeasurementId> {
+    let measurement = NewMeasurement {
+        name,
+        slug: slugify(name),
+        abbreviation,
+    };
+    Ok(diesel::insert_into(measurements::table)
+        .values(&measurement)
+        .on_conflict(measurements::slug)
+        .do_update()
+        .set(measurements::name.eq(measurements::name))
+        .returning(measurements::id)
+        .get_result(conn)
+        .await?)
+}
+
+/// The usual abbreviation for units the scraper knows, e.g. "tbsp" for
+/// "tablespoons". Other units are left as they are.
+fn canonical_unit(unit: &str) -> String {
+    match unit.to_lowercase().parse() {
+        Ok(Unit::Other(_)) | Err(_) => unit.to_string(),
+        Ok(known) => known.to_string(),
+    }
+}
+
+async fn upsert_ingredient(
+    conn: &mut DbConnection,
+    name: &str,
+    default_measurement_id: MeasurementId,
+) -> Result<IngredientId> {
+    let (id, reviewed) = diesel::insert_into(ingredients::table)
+        .values(&NewIngredient {
+            name,
+            slug: slugify(name),
+            default_measurement_id,
+        })
+        .on_conflict(ingredients::slug)
+        .do_update()
+        .set(ingredients::name.eq(ingredients::name))
+        .returning((ingredients::id, ingredients::diet_reviewed))
+        .get_result(conn)
+        .await?;
+    detect_ingredient_flags(conn, id, name, reviewed).await?;
+    Ok(id)
+}
+
+/// Check that `user` owns a recipe, so can share it. Recipes they can't see
+/// are treated as missing.
+pub(crate) async fn ensure_owned(
+    conn: &mut DbConnection,
+    user: UserId,
+    recipe_id: RecipeId,
+) -> Result<()> {
+    let recipe: DbRecipe = DbRecipe::by_id(recipe_id)
+        .filter(DbRecipe::visible_to(Some(user)))
+        .first(conn)
+        .await
+        .optional()?
+        .ok_or(Error::NotFound)?;
+    if recipe.is_owned_by(user) {
+        Ok(())
+    } else {
+        Err(Error::Forbidden)
+    }
+}
+
+/// Check that `user` can change a recipe, treating recipes they can't change
+/// as missing.
+pub(crate) async fn ensure_editable(
+    conn: &mut DbConnection,
+    user: UserId,
+    recipe_id: RecipeId,
+) -> Result<()> {
+    let editable: Option<RecipeId> = DbRecipe::by_id(recipe_id)
+        .filter(DbRecipe::editable_by(Some(user)))
+        .select(recipes::id)
+        .first(conn)
+        .await
+        .optional()?;
+    editable.map(|_| ()).ok_or(Error::NotFound)
+}
+
+#[cfg(all(test, feature = "sqlite"))]
+mod tests {
+    use recipe_shared::{IngredientInput, MealPlan, MealPlanInput, RecipeDetail, RecipeInput};
+
+    use crate::{testing, BySlug, Error, Result, WriteRepository};
+
+    #[tokio::test]
+    async fn spellings_of_a_unit_share_a_measurement() {
+        let repo = testing::database("recipe-measurements").await;
+        let repo = testing::as_user(&repo, "alice").await;
+        let ingredient = |name: &str, unit: &str| IngredientInput {
+            name: name.to_string(),
+            quantity: Some(1.0),
+            unit: Some(unit.to_string()),
+            notes: None,
+        };
+        let recipe: RecipeDetail = repo
+            .create(RecipeInput {
+                name: "Dressing".to_string(),
+                ingredients: vec![
+                    ingredient("olive oil", "tablespoons"),
+                    ingredient("vinegar", "Tbsp"),
+                    ingredient("mustard", "tbsp"),
+                    ingredient("parsley", "Handful"),
+                    ingredient("basil", "handful"),
+                ],
+                steps: vec!["Whisk.".to_string()],
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        let units: Vec<_> = recipe
+            .ingredients
+            .iter()
+            .map(|i| i.unit.as_deref().unwrap())
+            .collect();
+        assert_eq!(units, ["tbsp", "tbsp", "tbsp", "Handful", "Handful"]);
+    }
+
+    #[tokio::test]
+    async fn abbreviations_match_in_any_case() {
+        let repo = testing::database("recipe-abbreviations").await;
+        let mut conn = repo.pool.get().await.unwrap();
+        super::insert_measurement(&mut conn, "tablespoon", Some("Tbsp"))
+            .await
+            .unwrap();
+        drop(conn);
+        let repo = testing::as_user(&repo, "alice").await;
+        let ingredient = |name: &str, unit: &str| IngredientInput {
+            name: name.to_string(),
+            quantity: Some(1.0),
+            unit: Some(unit.to_string()),
+            notes: None,
+        };
+        let recipe: RecipeDetail = repo
+            .create(RecipeInput {
+                name: "Cake".to_string(),
+                ingredients: vec![
+                    ingredient("flour", "g"),
+                    ingredient("sugar", "G"),
+                    ingredient("milk", "TBSP"),
+                    ingredient("oil", "tbsp"),
+                ],
+                steps: vec!["Bake.".to_string()],
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        let units: Vec<_> = recipe
+            .ingredients
+            .iter()
+            .map(|i| i.unit.as_deref().unwrap())
+            .collect();
+        assert_eq!(units, ["g", "g", "Tbsp", "Tbsp"]);
+    }
+
+    #[tokio::test]
+    async fn ingredients_with_the_same_slug_are_invalid() {
+        let repo = testing::database("recipe-ingredient-slugs").await;
+        let repo = testing::as_user(&repo, "alice").await;
+        let ingredient = |name: &str| IngredientInput {
+            name: name.to_string(),
+            ..Default::default()
+        };
+        let result: Result<RecipeDetail> = repo
+            .create(RecipeInput {
+                name: "Salad".to_string(),
+                ingredients: vec![
+                    ingredient("olive oil"),
+                    ingredient("lettuce"),
+                    ingredient("Olive-Oil"),
+                ],
+                steps: vec!["Toss.".to_string()],
+                ..Default::default()
+            })
+            .await;
+        match result {
+            Err(Error::Validation(errors)) => {
+                assert_eq!(
+                    errors.get("ingredients.2"),
+                    ["is the same ingredient as ingredient 1"]
+                );
+            }
+            other => panic!("expected a validation error, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn users_can_use_the_same_names() {
+        let repo = testing::database("recipe-same-names").await;
+        let alice = testing::as_user(&repo, "alice").await;
+        let bob = testing::as_user(&repo, "bob").await;
+        let pancakes = || RecipeInput {
+            name: "Pancakes".to_string(),
+            steps: vec!["Fry.".to_string()],
+            ..Default::default()
+        };
+        let alices: RecipeDetail = alice.create(pancakes()).await.unwrap();
+        let bobs: RecipeDetail = bob.create(pancakes()).await.unwrap();
+        assert_eq!(alices.recipe.slug, "pancakes");
+        assert_ne!(bobs.recipe.slug, alices.recipe.slug);
+        for (repo, recipe) in [(&alice, &alices), (&bob, &bobs)] {
+            let found: RecipeDetail = repo
+                .get_by_slug(&recipe.recipe.slug)
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(found.recipe.id, recipe.recipe.id);
+        }
+        // Saving it again keeps its slug.
+        let id = bobs.recipe.id.into();
+        let updated: RecipeDetail = bob.update(id, pancakes()).await.unwrap();
+        assert_eq!(updated.recipe.slug, bobs.recipe.slug);
+
+        let week = || MealPlanInput {
+            name: "This week".to_string(),
+            start_date: chrono::NaiveDate::from_ymd_opt(2024, 3, 4).unwrap(),
+            end_date: chrono::NaiveDate::from_ymd_opt(2024, 3, 10).unwrap(),
+            shared: false,
+        };
+        let alices: MealPlan = alice.create(week()).await.unwrap();
+        let bobs: MealPlan = bob.create(week()).await.unwrap();
+        assert_ne!(bobs.slug, alices.slug);
+    }
+}
@@ -0,0 +1,173 @@
+use std::collections::BTreeMap;
+
+use recipe_db::prelude::*;
+
+use async_trait::async_trait;
+use diesel::{dsl::not, prelude::*};
+
+use recipe_db::{
+    dsl::{coalesce, lower},
+    models::{Recipe as DbRecipe, RecipeId, TagId, UserId},
+    schema::{ingredient_flags, recipe_ingredients, recipe_tags, recipes},
+    DbBackend,
+};
+use recipe_shared::{RecipeFilter, RecipeSearch, RecipeSort, Tag, TagFacet};
+
+use crate::{recipe::Recipe, tag::load_recipe_tags, DieselRepository, Result, Search};
+
+#[async_trait]
+impl Search<RecipeFilter> for DieselRepository {
+    type Output = RecipeSearch;
+
+    async fn search(&self, filter: RecipeFilter) -> Result<RecipeSearch> {
+        let mut conn = self.pool.get().await?;
+
+        // Facets and the total are counted across every match, while only the
+        // requested page of recipes is loaded.
+        let ids: Vec<RecipeId> = filtered(
+            recipes::table.select(recipes::id).into_boxed(),
+            self.user,
+            &filter,
+        )
+        .load(&mut conn)
+        .await?;
+        let mut counts: BTreeMap<Tag, usize> = BTreeMap::new();
+        if !ids.is_empty() {
+            for tag in load_recipe_tags(&mut conn, &ids)
+                .await?
+                .into_values()
+                .flatten()
+            {
+                *counts.entry(tag).or_default() += 1;
+            }
+        }
+
+        let mut query = filtered(
+            recipes::table.select(DbRecipe::as_select()).into_boxed(),
+            self.user,
+            &filter,
+        );
+        query = match filter.sort {
+            RecipeSort::Name => query.order(recipes::name),
+            RecipeSort::Newest => query.order(recipes::created_at.desc()),
+            RecipeSort::TotalTime => {
+                // Either time alone is better than nothing when the other is missing.
+                let prep = recipes::prep_time_minutes;
+                let cooking = recipes::cooking_time_minutes;
+                let total = coalesce(prep + cooking, coalesce(prep, cooking));
+                query.order((total.asc().nulls_last(), recipes::name))
+            }
+        };
+        // Break ties so pages don't overlap.
+        query = query.then_order_by(recipes::id);
+        if let Some(page) = filter.page {
+            query = query
+                .offset(i64::try_from(page.offset).unwrap_or(i64::MAX))
+                .limit(i64::try_from(page.limit).unwrap_or(i64::MAX));
+        }
+        let matches: Vec<DbRecipe> = query.load(&mut conn).await?;
+
+        Ok(RecipeSearch {
+            recipes: matches.into_iter().map(|r| Recipe::from(r).0).collect(),
+            facets: counts
+                .into_iter()
+                .map(|(tag, count)| TagFacet { tag, count })
+                .collect(),
+            total: ids.len(),
+        })
+    }
+}
+
+/// Narrow `query` down to the recipes `user` can see which match `filter`.
+fn filtered<'a, ST: 'a>(
+    mut query: recipes::BoxedQuery<'a, DbBackend, ST>,
+    user: Option<UserId>,
+    filter: &RecipeFilter,
+) -> recipes::BoxedQuery<'a, DbBackend, ST> {
+    query = query.filter(DbRecipe::visible_to(user));
+    if let Some(text) = filter.query.as_deref().map(str::trim) {
+        if !text.is_empty() {
+            let pattern = format!("%{}%", escape_like(&text.to_lowercase()));
+            // Postgres escapes with a backslash by default but SQLite has no
+            // escape character unless given one.
+            query = query.filter(lower(recipes::name).like(pattern).escape('\\'));
+        }
+    }
+    // A recipe must carry every selected tag to match.
+    for &tag_id in &filter.tag_ids {
+        query = query.filter(
+            recipes::id.eq_any(
+                recipe_tags::table
+                    .filter(recipe_tags::tag_id.eq(TagId::from(tag_id)))
+                    .select(recipe_tags::recipe_id),
+            ),
+        );
+    }
+    // ...and must not use any ingredient flagged as unsuitable for a
+    // selected diet.
+    let excluded: Vec<&'static str> = filter
+        .diets
+        .iter()
+        .flat_map(|diet| diet.excludes())
+        .map(|flag| flag.as_str())
+        .collect();
+    if !excluded.is_empty() {
+        query = query.filter(not(recipes::id.eq_any(
+            recipe_ingredients::table
+                .inner_join(
+                    ingredient_flags::table
+                        .on(ingredient_flags::ingredient_id.eq(recipe_ingredients::ingredient_id)),
+                )
+                .filter(ingredient_flags::flag.eq_any(excluded))
+                .select(recipe_ingredients::recipe_id),
+        )));
+    }
+    query
+}
+
+/// Escape the wildcard characters of a `LIKE` pattern.
+fn escape_like(s: &str) -> String {
+    s.replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
+
+#[cfg(all(test, feature = "sqlite"))]
+mod tests {
+    use recipe_shared::{RecipeDetail, RecipeFilter, RecipeInput};
+
+    use crate::{testing, Search, WriteRepository};
+
+    #[tokio::test]
+    async fn wildcards_are_matched_literally() {
+        let repo = testing::database("search-wildcards").await;
+        let repo = testing::as_user(&repo, "alice").await;
+        for name in ["100% rye", "Rye bread", "Pain_au_levain", "Back\\slash"] {
+            let _: RecipeDetail = repo
+                .create(RecipeInput {
+                    name: name.to_string(),
+                    steps: vec!["Bake.".to_string()],
+                    ..Default::default()
+                })
+                .await
+                .unwrap();
+        }
+        for (query, expected) in [
+            ("%", vec!["100% rye"]),
+            ("0% r", vec!["100% rye"]),
+            ("_", vec!["Pain_au_levain"]),
+            ("\\", vec!["Back\\slash"]),
+            ("rye", vec!["100% rye", "Rye bread"]),
+        ] {
+            let found = repo
+                .search(RecipeFilter {
+                    query: Some(query.to_string()),
+                    ..Default::default()
+                })
+                .await
+                .unwrap();
+            let names: Vec<_> = found.recipes.iter().map(|r| r.name.as_str()).collect();
+            assert_eq!(names, expected, "{query}");
+        }
+    }
+}
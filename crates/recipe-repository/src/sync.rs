@@ -0,0 +1,759 @@
+use recipe_db::prelude::*;
+
+use async_trait::async_trait;
+use chrono::{prelude::*, Duration};
+use diesel::prelude::*;
+use diesel_async::{scoped_futures::ScopedFutureExt, AsyncConnection};
+
+use recipe_db::{
+    models::{
+        Deletion, MealPlan as DbMealPlan, MealPlanRecipe, NewMealPlanRecipe, Recipe as DbRecipe,
+        RecipeId, SyncCursor as DbSyncCursor, UserId,
+    },
+    schema::{deletions, meal_plan_recipes, meal_plans, recipes, sync_cursors},
+};
+use recipe_shared::{
+    Changeset, MealPlanInput, SyncCursor, SyncDeletion, SyncKind, SyncMealPlan, SyncMealPlanEntry,
+    SyncRecipe, SyncRejection, SyncRequest, SyncResponse, ValidationErrors,
+};
+
+use crate::{
+    meal_plan::{delete_meal_plan, meal_plan_slug, new_meal_plan},
+    recipe::{delete_recipe, load_detail, new_recipe, recipe_slug, set_recipe_contents},
+    DieselRepository, Error, Result, SyncRepository,
+};
+
+/// How far before the start of a query its cursor is set, so that writes
+/// which were still being committed while it ran are picked up next time.
+/// Anything fetched twice because of this is ignored when applied again.
+const CURSOR_OVERLAP_SECONDS: i64 = 5;
+
+#[async_trait]
+impl SyncRepository for DieselRepository {
+    async fn changes_since(
+        &self,
+        since: Option<NaiveDateTime>,
+    ) -> Result<(Changeset, NaiveDateTime)> {
+        let user = self.require_user()?;
+        let cursor = Utc::now().naive_utc() - Duration::seconds(CURSOR_OVERLAP_SECONDS);
+        let mut conn = self.pool.get().await?;
+
+        let mut query = recipes::table
+            .filter(recipes::owner_id.eq(user))
+            .select(DbRecipe::as_select())
+            .order(recipes::id)
+            .into_boxed();
+        if let Some(since) = since {
+            query = query.filter(recipes::changed_at.gt(since));
+        }
+        let mut changes = Changeset::default();
+        for recipe in query.load::<DbRecipe>(&mut conn).await? {
+            let (sync_id, updated_at) = (recipe.sync_id.clone(), recipe.updated_at);
+            let detail = load_detail(&mut conn, Some(user), recipe).await?;
+            changes.recipes.push(SyncRecipe {
+                sync_id,
+                updated_at,
+                recipe: detail.to_input(),
+            });
+        }
+
+        let mut query = meal_plans::table
+            .filter(meal_plans::owner_id.eq(user))
+            .select(DbMealPlan::as_select())
+            .order(meal_plans::id)
+            .into_boxed();
+        if let Some(since) = since {
+            query = query.filter(meal_plans::changed_at.gt(since));
+        }
+        for plan in query.load::<DbMealPlan>(&mut conn).await? {
+            let entries = MealPlanRecipe::belonging_to(&plan)
+                .inner_join(recipes::table)
+                .order((meal_plan_recipes::day, meal_plan_recipes::id))
+                .select((meal_plan_recipes::day, recipes::sync_id))
+                .load::<(NaiveDate, String)>(&mut conn)
+                .await?
+                .into_iter()
+                .map(|(day, recipe)| SyncMealPlanEntry { day, recipe })
+                .collect();
+            changes.meal_plans.push(SyncMealPlan {
+                sync_id: plan.sync_id,
+                updated_at: plan.updated_at,
+                meal_plan: MealPlanInput {
+                    name: plan.name,
+                    start_date: plan.start_date,
+                    end_date: plan.end_date,
+                    shared: plan.shared,
+                },
+                entries,
+            });
+        }
+
+        let mut query = deletions::table
+            .filter(deletions::owner_id.eq(user))
+            .select(Deletion::as_select())
+            .into_boxed();
+        if let Some(since) = since {
+            query = query.filter(deletions::changed_at.gt(since));
+        }
+        changes.deletions = query
+            .load::<Deletion>(&mut conn)
+            .await?
+            .into_iter()
+            // The database constrains `kind` to the known values.
+            .filter_map(|deletion| {
+                Some(SyncDeletion {
+                    kind: deletion.kind.parse().ok()?,
+                    sync_id: deletion.sync_id,
+                    deleted_at: deletion.deleted_at,
+                })
+            })
+            .collect();
+        Ok((changes, cursor))
+    }
+
+    async fn apply_changes(&self, changes: Changeset) -> Result<Vec<SyncRejection>> {
+        let user = self.require_user()?;
+        let mut conn = self.pool.get().await?;
+        conn.transaction::<_, Error, _>(|conn| {
+            async move {
+                // Each change is applied in a savepoint of its own, so that
+                // one which is rejected can be undone without the others.
+                let mut rejected = Vec::new();
+                for deletion in &changes.deletions {
+                    let result = conn
+                        .transaction(|conn| apply_deletion(conn, user, deletion).scope_boxed())
+                        .await;
+                    rejected.extend(rejection(deletion.kind, &deletion.sync_id, result)?);
+                }
+                // Recipes come first, since meal plans refer to them.
+                for recipe in &changes.recipes {
+                    let result = conn
+                        .transaction(|conn| apply_recipe(conn, user, recipe).scope_boxed())
+                        .await;
+                    rejected.extend(rejection(SyncKind::Recipe, &recipe.sync_id, result)?);
+                }
+                for plan in &changes.meal_plans {
+                    let result = conn
+                        .transaction(|conn| apply_meal_plan(conn, user, plan).scope_boxed())
+                        .await;
+                    rejected.extend(rejection(SyncKind::MealPlan, &plan.sync_id, result)?);
+                }
+                Ok(rejected)
+            }
+            .scope_boxed()
+        })
+        .await
+    }
+
+    async fn sync(&self, request: SyncRequest) -> Result<SyncResponse> {
+        // Changes here are fetched before the client's are applied, so that
+        // they aren't sent straight back to it.
+        let (changes, cursor) = self.changes_since(request.since).await?;
+        let rejected = self.apply_changes(request.changes).await?;
+        Ok(SyncResponse {
+            cursor,
+            changes,
+            rejected,
+        })
+    }
+
+    async fn sync_cursor(&self, remote: &str) -> Result<SyncCursor> {
+        let mut conn = self.pool.get().await?;
+        let cursor: Option<DbSyncCursor> = sync_cursors::table
+            .find(remote)
+            .first(&mut conn)
+            .await
+            .optional()?;
+        Ok(cursor
+            .map(|c| SyncCursor {
+                pulled: c.pulled,
+                pushed: c.pushed,
+            })
+            .unwrap_or_default())
+    }
+
+    async fn set_sync_cursor(&self, remote: &str, cursor: SyncCursor) -> Result<()> {
+        let mut conn = self.pool.get().await?;
+        let row = DbSyncCursor {
+            remote: remote.to_string(),
+            pulled: cursor.pulled,
+            pushed: cursor.pushed,
+        };
+        diesel::insert_into(sync_cursors::table)
+            .values(&row)
+            .on_conflict(sync_cursors::remote)
+            .do_update()
+            .set(&row)
+            .execute(&mut conn)
+            .await?;
+        Ok(())
+    }
+}
+
+/// Why a change from another database wasn't applied, if it was rejected
+/// rather than failing in a way which stops every change applying.
+fn rejection(kind: SyncKind, sync_id: &str, result: Result<()>) -> Result<Option<SyncRejection>> {
+    match result {
+        Ok(()) => Ok(None),
+        Err(e @ (Error::Forbidden | Error::Validation(_))) => Ok(Some(SyncRejection {
+            kind,
+            sync_id: sync_id.to_string(),
+            reason: e.to_string(),
+        })),
+        Err(e) if e.is_conflict() => Ok(Some(SyncRejection {
+            kind,
+            sync_id: sync_id.to_string(),
+            reason: "it clashes with something which already exists".to_string(),
+        })),
+        Err(e) => Err(e),
+    }
+}
+
+/// When a change from another database was made, going by its clock. Times
+/// after now are taken to be now, so that a device whose clock is ahead
+/// can't keep winning conflicts with edits made since.
+fn clamped(at: NaiveDateTime) -> NaiveDateTime {
+    at.min(Utc::now().naive_utc())
+}
+
+/// Record that a recipe or meal plan was deleted at `deleted_at`, unless a
+/// later deletion of it is already recorded. Rows without an owner aren't
+/// synced, so don't need one.
+pub(crate) async fn record_deletion(
+    conn: &mut DbConnection,
+    kind: SyncKind,
+    sync_id: &str,
+    owner_id: Option<UserId>,
+    deleted_at: NaiveDateTime,
+) -> Result<()> {
+    let Some(owner_id) = owner_id else {
+        return Ok(());
+    };
+    if tombstone(conn, kind, sync_id, owner_id).await? >= Some(deleted_at) {
+        return Ok(());
+    }
+    let deletion = Deletion {
+        kind: kind.to_string(),
+        sync_id: sync_id.to_string(),
+        owner_id,
+        deleted_at,
+        changed_at: Utc::now().naive_utc(),
+    };
+    diesel::insert_into(deletions::table)
+        .values(&deletion)
+        .on_conflict((deletions::owner_id, deletions::kind, deletions::sync_id))
+        .do_update()
+        .set((
+            deletions::deleted_at.eq(deletion.deleted_at),
+            deletions::changed_at.eq(deletion.changed_at),
+        ))
+        .execute(conn)
+        .await?;
+    Ok(())
+}
+
+/// When `owner` deleted a recipe or meal plan, if they have.
+async fn tombstone(
+    conn: &mut DbConnection,
+    kind: SyncKind,
+    sync_id: &str,
+    owner: UserId,
+) -> Result<Option<NaiveDateTime>> {
+    Ok(deletions::table
+        .find((owner, kind.as_str(), sync_id))
+        .select(deletions::deleted_at)
+        .first(conn)
+        .await
+        .optional()?)
+}
+
+/// Forget a deletion, once whatever was deleted has been restored by a later
+/// edit.
+async fn remove_tombstone(
+    conn: &mut DbConnection,
+    kind: SyncKind,
+    sync_id: &str,
+    owner: UserId,
+) -> Result<()> {
+    diesel::delete(deletions::table.find((owner, kind.as_str(), sync_id)))
+        .execute(conn)
+        .await?;
+    Ok(())
+}
+
+fn validate_sync_id(sync_id: &str) -> Result<()> {
+    if sync_id.trim().is_empty() {
+        let mut errors = ValidationErrors::default();
+        errors.add("sync_id", "sync_id is required");
+        return Err(errors.into());
+    }
+    Ok(())
+}
+
+async fn apply_deletion(
+    conn: &mut DbConnection,
+    user: UserId,
+    deletion: &SyncDeletion,
+) -> Result<()> {
+    let sync_id = deletion.sync_id.as_str();
+    let deleted_at = clamped(deletion.deleted_at);
+    match deletion.kind {
+        SyncKind::Recipe => {
+            let recipe: Option<DbRecipe> =
+                DbRecipe::by_sync_id(sync_id).first(conn).await.optional()?;
+            if let Some(recipe) = recipe {
+                if recipe.owner_id != Some(user) {
+                    return Err(Error::Forbidden);
+                }
+                // Edits made after the deletion win, and will restore the
+                // recipe in the other database when they're synced back.
+                if recipe.updated_at > deleted_at {
+                    return Ok(());
+                }
+                delete_recipe(conn, &recipe).await?;
+            }
+        }
+        SyncKind::MealPlan => {
+            let plan: Option<DbMealPlan> = DbMealPlan::by_sync_id(sync_id)
+                .first(conn)
+                .await
+                .optional()?;
+            if let Some(plan) = plan {
+                if plan.owner_id != Some(user) {
+                    return Err(Error::Forbidden);
+                }
+                if plan.updated_at > deleted_at {
+                    return Ok(());
+                }
+                delete_meal_plan(conn, &plan).await?;
+            }
+        }
+    }
+    record_deletion(conn, deletion.kind, sync_id, Some(user), deleted_at).await
+}
+
+async fn apply_recipe(conn: &mut DbConnection, user: UserId, synced: &SyncRecipe) -> Result<()> {
+    validate_sync_id(&synced.sync_id)?;
+    synced.recipe.validate()?;
+    let sync_id = synced.sync_id.as_str();
+    let updated_at = clamped(synced.updated_at);
+    let deleted_at = tombstone(conn, SyncKind::Recipe, sync_id, user).await?;
+    if deleted_at >= Some(updated_at) {
+        return Ok(());
+    }
+    let existing: Option<DbRecipe> = DbRecipe::by_sync_id(sync_id).first(conn).await.optional()?;
+    if let Some(existing) = &existing {
+        if existing.owner_id != Some(user) {
+            return Err(Error::Forbidden);
+        }
+        // On a tie the copy here is kept, so applying changes twice is
+        // harmless.
+        if existing.updated_at >= updated_at {
+            return Ok(());
+        }
+    }
+
+    let mut recipe = new_recipe(&synced.recipe, updated_at);
+    recipe.slug = recipe_slug(conn, recipe.slug, sync_id).await?;
+    let id = match existing {
+        Some(existing) => {
+            diesel::update(recipes::table.find(existing.id))
+                .set(&recipe)
+                .execute(conn)
+                .await?;
+            existing.id
+        }
+        None => {
+            diesel::insert_into(recipes::table)
+                .values((
+                    &recipe,
+                    recipes::owner_id.eq(user),
+                    recipes::sync_id.eq(sync_id),
+                ))
+                .returning(recipes::id)
+                .get_result(conn)
+                .await?
+        }
+    };
+    set_recipe_contents(conn, id, &synced.recipe).await?;
+    if deleted_at.is_some() {
+        remove_tombstone(conn, SyncKind::Recipe, sync_id, user).await?;
+    }
+    Ok(())
+}
+
+async fn apply_meal_plan(
+    conn: &mut DbConnection,
+    user: UserId,
+    synced: &SyncMealPlan,
+) -> Result<()> {
+    validate_sync_id(&synced.sync_id)?;
+    synced.meal_plan.validate()?;
+    let sync_id = synced.sync_id.as_str();
+    let updated_at = clamped(synced.updated_at);
+    let deleted_at = tombstone(conn, SyncKind::MealPlan, sync_id, user).await?;
+    if deleted_at >= Some(updated_at) {
+        return Ok(());
+    }
+    let existing: Option<DbMealPlan> = DbMealPlan::by_sync_id(sync_id)
+        .first(conn)
+        .await
+        .optional()?;
+    if let Some(existing) = &existing {
+        if existing.owner_id != Some(user) {
+            return Err(Error::Forbidden);
+        }
+        if existing.updated_at >= updated_at {
+            return Ok(());
+        }
+    }
+
+    let mut plan = new_meal_plan(&synced.meal_plan, updated_at);
+    plan.slug = meal_plan_slug(conn, plan.slug, sync_id).await?;
+    let plan: DbMealPlan = match existing {
+        Some(existing) => {
+            diesel::update(meal_plans::table.find(existing.id))
+                .set(&plan)
+                .returning(DbMealPlan::as_returning())
+                .get_result(conn)
+                .await?
+        }
+        None => {
+            diesel::insert_into(meal_plans::table)
+                .values((
+                    &plan,
+                    meal_plans::owner_id.eq(user),
+                    meal_plans::sync_id.eq(sync_id),
+                ))
+                .returning(DbMealPlan::as_returning())
+                .get_result(conn)
+                .await?
+        }
+    };
+
+    diesel::delete(MealPlanRecipe::belonging_to(&plan))
+        .execute(conn)
+        .await?;
+    for entry in &synced.entries {
+        if entry.day < plan.start_date || entry.day > plan.end_date {
+            continue;
+        }
+        // Recipes which weren't synced here, such as those shared with the
+        // user by someone else, are left out.
+        let recipe_id: Option<RecipeId> = DbRecipe::by_sync_id(&entry.recipe)
+            .filter(DbRecipe::visible_to(Some(user)))
+            .select(recipes::id)
+            .first(conn)
+            .await
+            .optional()?;
+        if let Some(recipe_id) = recipe_id {
+            diesel::insert_into(meal_plan_recipes::table)
+                .values(&NewMealPlanRecipe {
+                    meal_plan_id: plan.id,
+                    recipe_id,
+                    day: entry.day,
+                })
+                .execute(conn)
+                .await?;
+        }
+    }
+    if deleted_at.is_some() {
+        remove_tombstone(conn, SyncKind::MealPlan, sync_id, user).await?;
+    }
+    Ok(())
+}
+
+#[cfg(all(test, feature = "sqlite"))]
+mod tests {
+    use chrono::prelude::*;
+
+    use recipe_shared::{
+        Changeset, IngredientInput, MealPlan, MealPlanDetail, MealPlanInput, RecipeDetail,
+        RecipeInput, SyncCursor, SyncDeletion, SyncKind, SyncRecipe, SyncRequest,
+    };
+
+    use crate::{
+        testing, BySlug, DeleteRepository, DieselRepository, MealPlanRepository, SyncRepository,
+        WriteRepository,
+    };
+
+    const REMOTE: &str = "https://recipes.example.com";
+
+    /// A new database with a user to act as.
+    async fn database(name: &str) -> DieselRepository {
+        let repo = testing::database(&format!("sync-{name}")).await;
+        testing::as_user(&repo, "alice").await
+    }
+
+    /// What a client does to sync, with the server's side done in-process
+    /// rather than over HTTP.
+    async fn sync(client: &DieselRepository, server: &DieselRepository) {
+        let cursor = client.sync_cursor(REMOTE).await.unwrap();
+        let (changes, pushed) = client.changes_since(cursor.pushed).await.unwrap();
+        let response = server
+            .sync(SyncRequest {
+                since: cursor.pulled,
+                changes,
+            })
+            .await
+            .unwrap();
+        client.apply_changes(response.changes).await.unwrap();
+        let cursor = SyncCursor {
+            pulled: Some(response.cursor),
+            pushed: Some(pushed),
+        };
+        client.set_sync_cursor(REMOTE, cursor).await.unwrap();
+    }
+
+    fn recipe(name: &str, ingredient: &str) -> RecipeInput {
+        RecipeInput {
+            name: name.to_string(),
+            ingredients: vec![IngredientInput {
+                name: ingredient.to_string(),
+                quantity: Some(2.0),
+                unit: Some("tbsp".to_string()),
+                notes: None,
+            }],
+            steps: vec!["Mix everything.".to_string()],
+            ..Default::default()
+        }
+    }
+
+    async fn create(repo: &DieselRepository, input: RecipeInput) -> RecipeDetail {
+        repo.create(input).await.unwrap()
+    }
+
+    async fn get(repo: &DieselRepository, slug: &str) -> Option<RecipeDetail> {
+        repo.get_by_slug(slug).await.unwrap()
+    }
+
+    async fn update(repo: &DieselRepository, slug: &str, input: RecipeInput) {
+        let id = get(repo, slug).await.unwrap().recipe.id.into();
+        let _: RecipeDetail = repo.update(id, input).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn recipes_sync_both_ways() {
+        let server = database("both-ways-server").await;
+        let laptop = database("both-ways-laptop").await;
+        let phone = database("both-ways-phone").await;
+
+        let created = create(&laptop, recipe("Pesto", "Basil")).await;
+        sync(&laptop, &server).await;
+        sync(&phone, &server).await;
+        let mut input = get(&phone, "pesto").await.unwrap().to_input();
+        assert_eq!(input, created.to_input());
+
+        input.steps.push("Serve with pasta.".to_string());
+        update(&phone, "pesto", input.clone()).await;
+        sync(&phone, &server).await;
+        sync(&laptop, &server).await;
+        assert_eq!(get(&laptop, "pesto").await.unwrap().to_input(), input);
+        assert_eq!(get(&server, "pesto").await.unwrap().to_input(), input);
+
+        // Syncing again changes nothing.
+        sync(&laptop, &server).await;
+        sync(&phone, &server).await;
+        assert_eq!(get(&phone, "pesto").await.unwrap().to_input(), input);
+    }
+
+    #[tokio::test]
+    async fn latest_edit_wins() {
+        let server = database("latest-server").await;
+        let laptop = database("latest-laptop").await;
+        let phone = database("latest-phone").await;
+        create(&laptop, recipe("Pesto", "Basil")).await;
+        sync(&laptop, &server).await;
+        sync(&phone, &server).await;
+
+        // Both edit the recipe while offline, the phone last.
+        update(&laptop, "pesto", recipe("Pesto", "Parsley")).await;
+        update(&phone, "pesto", recipe("Pesto", "Wild garlic")).await;
+
+        sync(&phone, &server).await;
+        sync(&laptop, &server).await;
+        sync(&phone, &server).await;
+        for repo in [&server, &laptop, &phone] {
+            let ingredient = &get(repo, "pesto").await.unwrap().ingredients[0];
+            assert_eq!(ingredient.name, "Wild garlic");
+        }
+    }
+
+    #[tokio::test]
+    async fn deletions_sync_unless_edited_later() {
+        let server = database("deletions-server").await;
+        let laptop = database("deletions-laptop").await;
+        let phone = database("deletions-phone").await;
+        create(&laptop, recipe("Pesto", "Basil")).await;
+        create(&laptop, recipe("Salsa", "Tomato")).await;
+        sync(&laptop, &server).await;
+        sync(&phone, &server).await;
+
+        // The laptop deletes both, but the phone edits one afterwards.
+        for slug in ["pesto", "salsa"] {
+            let id = get(&laptop, slug).await.unwrap().recipe.id.into();
+            DeleteRepository::<RecipeDetail>::delete(&laptop, id)
+                .await
+                .unwrap();
+        }
+        let edited = recipe("Salsa", "Tomatillo");
+        update(&phone, "salsa", edited.clone()).await;
+
+        sync(&laptop, &server).await;
+        sync(&phone, &server).await;
+        sync(&laptop, &server).await;
+        for repo in [&server, &laptop, &phone] {
+            assert!(get(repo, "pesto").await.is_none());
+            assert_eq!(get(repo, "salsa").await.unwrap().to_input(), edited);
+        }
+    }
+
+    #[tokio::test]
+    async fn meal_plans_sync_with_their_recipes() {
+        let server = database("plans-server").await;
+        let laptop = database("plans-laptop").await;
+        let phone = database("plans-phone").await;
+        let pesto = create(&laptop, recipe("Pesto", "Basil")).await;
+        let start = NaiveDate::from_ymd_opt(2024, 3, 4).unwrap();
+        let plan: MealPlan = laptop
+            .create(MealPlanInput {
+                name: "First week of March".to_string(),
+                start_date: start,
+                end_date: start + chrono::Duration::days(6),
+                shared: false,
+            })
+            .await
+            .unwrap();
+        let day = start + chrono::Duration::days(2);
+        laptop
+            .add_recipe(plan.id.into(), pesto.recipe.id.into(), day)
+            .await
+            .unwrap();
+
+        sync(&laptop, &server).await;
+        sync(&phone, &server).await;
+        let synced: MealPlanDetail = phone
+            .get_by_slug("first-week-of-march")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(synced.plan.start_date, start);
+        let entries: Vec<_> = synced
+            .days
+            .iter()
+            .flat_map(|d| d.entries.iter().map(|e| (d.date, e.recipe.slug.as_str())))
+            .collect();
+        assert_eq!(entries, [(day, "pesto")]);
+    }
+
+    #[tokio::test]
+    async fn slug_clashes_are_renamed() {
+        let server = database("clash-server").await;
+        let laptop = database("clash-laptop").await;
+        let phone = database("clash-phone").await;
+        create(&laptop, recipe("Pesto", "Basil")).await;
+        create(&phone, recipe("Pesto", "Rocket")).await;
+
+        sync(&laptop, &server).await;
+        sync(&phone, &server).await;
+        sync(&laptop, &server).await;
+        for repo in [&server, &laptop, &phone] {
+            let (changes, _) = repo.changes_since(None).await.unwrap();
+            assert_eq!(changes.recipes.len(), 2);
+        }
+        let pesto = get(&phone, "pesto").await.unwrap();
+        assert_eq!(pesto.ingredients[0].name, "Rocket");
+    }
+
+    #[tokio::test]
+    async fn rejected_changes_leave_the_rest_applied() {
+        let server = database("rejected").await;
+        let alices = create(&server, recipe("Pesto", "Basil")).await;
+        let (changes, _) = server.changes_since(None).await.unwrap();
+        let alices_sync_id = changes.recipes[0].sync_id.clone();
+
+        let bob = testing::as_user(&server, "bob").await;
+        let now = Utc::now().naive_utc();
+        let rejected = bob
+            .apply_changes(Changeset {
+                recipes: vec![
+                    SyncRecipe {
+                        sync_id: alices_sync_id.clone(),
+                        updated_at: now,
+                        recipe: recipe("Pesto", "Rocket"),
+                    },
+                    SyncRecipe {
+                        sync_id: "bobs-salsa".to_string(),
+                        updated_at: now,
+                        recipe: recipe("Salsa", "Tomato"),
+                    },
+                ],
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(rejected.len(), 1);
+        assert_eq!(rejected[0].sync_id, alices_sync_id);
+        assert_eq!(
+            get(&server, "pesto").await.unwrap().to_input(),
+            alices.to_input()
+        );
+        assert!(get(&bob, "salsa").await.is_some());
+    }
+
+    #[tokio::test]
+    async fn edits_from_the_future_are_taken_to_be_now() {
+        let server = database("future").await;
+        let synced = |ingredient: &str, updated_at| Changeset {
+            recipes: vec![SyncRecipe {
+                sync_id: "pesto".to_string(),
+                updated_at,
+                recipe: recipe("Pesto", ingredient),
+            }],
+            ..Default::default()
+        };
+        // A device whose clock is a year ahead edits the recipe, then one
+        // whose clock is right edits it afterwards.
+        let ahead = Utc::now().naive_utc() + chrono::Duration::days(365);
+        server.apply_changes(synced("Basil", ahead)).await.unwrap();
+        let now = Utc::now().naive_utc() + chrono::Duration::seconds(1);
+        server.apply_changes(synced("Parsley", now)).await.unwrap();
+        let ingredient = &get(&server, "pesto").await.unwrap().ingredients[0];
+        assert_eq!(ingredient.name, "Parsley");
+    }
+
+    #[tokio::test]
+    async fn deletions_only_affect_their_owner() {
+        let server = database("owned-deletions").await;
+        let bob = testing::as_user(&server, "bob").await;
+        let now = Utc::now().naive_utc();
+        // Bob deletes something with the same sync ID as a recipe Alice
+        // hasn't synced yet.
+        let rejected = bob
+            .apply_changes(Changeset {
+                deletions: vec![SyncDeletion {
+                    kind: SyncKind::Recipe,
+                    sync_id: "pesto".to_string(),
+                    deleted_at: now,
+                }],
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert!(rejected.is_empty());
+        server
+            .apply_changes(Changeset {
+                recipes: vec![SyncRecipe {
+                    sync_id: "pesto".to_string(),
+                    updated_at: now - chrono::Duration::minutes(1),
+                    recipe: recipe("Pesto", "Basil"),
+                }],
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert!(get(&server, "pesto").await.is_some());
+        let (changes, _) = server.changes_since(None).await.unwrap();
+        assert!(changes.deletions.is_empty());
+    }
+}
@@ -0,0 +1,160 @@
+use std::collections::HashMap;
+
+use recipe_db::prelude::*;
+
+use async_trait::async_trait;
+use diesel::prelude::*;
+use futures_util::TryStreamExt;
+
+use recipe_db::{
+    models::{NewRecipeTag, NewTag, RecipeId, RecipeTag, Tag as DbTag, TagId},
+    schema::{recipe_tags, tags},
+    slug::slugify,
+};
+use recipe_shared::{Tag as SharedTag, TagInput, ValidationErrors};
+
+use crate::{
+    recipe::{ensure_editable, mark_recipe_changed},
+    DieselRepository, Error, Repository, Result, TagRepository,
+};
+
+pub(crate) struct Tag(pub(crate) SharedTag);
+
+impl From<DbTag> for Tag {
+    fn from(db_tag: DbTag) -> Self {
+        Self(SharedTag {
+            id: db_tag.id.into(),
+            name: db_tag.name,
+            slug: db_tag.slug,
+            // The database constrains `kind` to the known values.
+            kind: db_tag.kind.parse().unwrap_or_default(),
+        })
+    }
+}
+
+#[async_trait]
+impl Repository<SharedTag> for DieselRepository {
+    type Id = TagId;
+    async fn get(&self, id: Self::Id) -> Result<Option<SharedTag>> {
+        let mut conn = self.pool.get().await?;
+        let tag: Option<DbTag> = DbTag::by_id(id).first(&mut conn).await.optional()?;
+        Ok(tag.map(|t| Tag::from(t).0))
+    }
+
+    async fn list(&self) -> Result<Vec<SharedTag>> {
+        let mut conn = self.pool.get().await?;
+        let tags = DbTag::all()
+            .order((tags::kind, tags::name))
+            .load_stream(&mut conn)
+            .await?;
+        Ok(tags.map_ok(|t| Tag::from(t).0).try_collect().await?)
+    }
+}
+
+#[async_trait]
+impl TagRepository<SharedTag> for DieselRepository {
+    type RecipeId = RecipeId;
+    type TagId = TagId;
+
+    async fn tag(&self, recipe_id: Self::RecipeId, tag: TagInput) -> Result<SharedTag> {
+        let user = self.require_user()?;
+        let mut errors = ValidationErrors::default();
+        if tag.name.trim().is_empty() {
+            errors.add("name", "must not be empty");
+            return Err(errors.into());
+        }
+        let mut conn = self.pool.get().await?;
+        ensure_editable(&mut conn, user, recipe_id).await?;
+        let tag = upsert_tag(&mut conn, &tag).await?;
+        diesel::insert_into(recipe_tags::table)
+            .values(&NewRecipeTag {
+                recipe_id,
+                tag_id: tag.id,
+            })
+            .on_conflict_do_nothing()
+            .execute(&mut conn)
+            .await
+            .map_err(|e| match e {
+                DieselError::DatabaseError(
+                    diesel::result::DatabaseErrorKind::ForeignKeyViolation,
+                    _,
+                ) => Error::NotFound,
+                e => e.into(),
+            })?;
+        mark_recipe_changed(&mut conn, recipe_id).await?;
+        Ok(Tag::from(tag).0)
+    }
+
+    async fn untag(&self, recipe_id: Self::RecipeId, tag_id: Self::TagId) -> Result<()> {
+        let user = self.require_user()?;
+        let mut conn = self.pool.get().await?;
+        ensure_editable(&mut conn, user, recipe_id).await?;
+        diesel::delete(
+            recipe_tags::table
+                .filter(recipe_tags::recipe_id.eq(recipe_id))
+                .filter(recipe_tags::tag_id.eq(tag_id)),
+        )
+        .execute(&mut conn)
+        .await?;
+        mark_recipe_changed(&mut conn, recipe_id).await
+    }
+}
+
+async fn upsert_tag(conn: &mut DbConnection, tag: &TagInput) -> Result<DbTag> {
+    let name = tag.name.trim();
+    Ok(diesel::insert_into(tags::table)
+        .values(&NewTag {
+            name,
+            slug: slugify(name),
+            kind: tag.kind.as_str(),
+        })
+        .on_conflict((tags::kind, tags::slug))
+        .do_update()
+        .set(tags::name.eq(tags::name))
+        .returning(DbTag::as_returning())
+        .get_result(conn)
+        .await?)
+}
+
+/// Replace the tags on a recipe with `tags`, creating any which don't exist yet.
+pub(crate) async fn set_recipe_tags(
+    conn: &mut DbConnection,
+    recipe_id: RecipeId,
+    tags: &[TagInput],
+) -> Result<()> {
+    diesel::delete(recipe_tags::table.filter(recipe_tags::recipe_id.eq(recipe_id)))
+        .execute(conn)
+        .await?;
+    for tag in tags {
+        let tag = upsert_tag(conn, tag).await?;
+        diesel::insert_into(recipe_tags::table)
+            .values(&NewRecipeTag {
+                recipe_id,
+                tag_id: tag.id,
+            })
+            .on_conflict_do_nothing()
+            .execute(conn)
+            .await?;
+    }
+    Ok(())
+}
+
+/// Fetch the tags of each of the given recipes.
+pub(crate) async fn load_recipe_tags(
+    conn: &mut DbConnection,
+    recipe_ids: &[RecipeId],
+) -> Result<HashMap<RecipeId, Vec<SharedTag>>> {
+    let rows: Vec<(RecipeTag, DbTag)> = RecipeTag::belonging_to_recipes(recipe_ids)
+        .inner_join(tags::table)
+        .order((tags::kind, tags::name))
+        .load(conn)
+        .await?;
+    let mut by_recipe: HashMap<RecipeId, Vec<SharedTag>> = HashMap::new();
+    for (recipe_tag, tag) in rows {
+        by_recipe
+            .entry(recipe_tag.recipe_id)
+            .or_default()
+            .push(Tag::from(tag).0);
+    }
+    Ok(by_recipe)
+}
@@ -0,0 +1,20 @@
+//! Helpers for the repository's tests, which run against SQLite databases in
+//! temporary files.
+
+use crate::{DieselRepository, UserRepository};
+
+/// A new, empty database named after the test using it, used anonymously.
+pub(crate) async fn database(name: &str) -> DieselRepository {
+    let path = std::env::temp_dir().join(format!("recipe-test-{}-{name}.db", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let url = path.to_string_lossy();
+    recipe_db::run_migrations(&url).unwrap();
+    DieselRepository::new(recipe_db::build_pool(&url).build().unwrap())
+}
+
+/// `repo` acting as the user called `username`, who is created if they don't
+/// exist yet.
+pub(crate) async fn as_user(repo: &DieselRepository, username: &str) -> DieselRepository {
+    let user = repo.local_user(username).await.unwrap();
+    repo.for_user(Some(user.id.into()))
+}
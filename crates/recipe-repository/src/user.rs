@@ -0,0 +1,282 @@
+use std::sync::OnceLock;
+
+use recipe_db::prelude::*;
+
+use argon2::{
+    password_hash::{rand_core::OsRng, rand_core::RngCore, SaltString},
+    Argon2, PasswordHash, PasswordHasher, PasswordVerifier,
+};
+use async_trait::async_trait;
+use chrono::{Duration, Utc};
+use diesel::prelude::*;
+use sha2::{Digest, Sha256};
+
+use recipe_db::{
+    models::{NewSession, NewUser, Session, User as DbUser, UserId},
+    schema::{sessions, users},
+};
+use recipe_shared::{Credentials, User as SharedUser, UserInput, ValidationErrors};
+
+use crate::{DieselRepository, Error, Result, UserRepository};
+
+/// How long a session lasts before the user has to sign in again.
+const SESSION_DAYS: i64 = 30;
+
+/// Stored in place of a password hash for users without a password, which
+/// never verifies since it isn't a valid hash.
+const NO_PASSWORD: &str = "!";
+
+/// A hash to check passwords against when signing in as a user who doesn't
+/// exist or has no password, so that it takes as long as for one who does.
+fn dummy_hash() -> &'static str {
+    static HASH: OnceLock<String> = OnceLock::new();
+    HASH.get_or_init(|| {
+        let salt = SaltString::generate(&mut OsRng);
+        Argon2::default()
+            .hash_password(b"not anyone's password", &salt)
+            .expect("hashing a fixed password succeeds")
+            .to_string()
+    })
+}
+
+/// Whether `password` matches `hash`, taking about as long either way.
+fn verify_password(password: &str, hash: &str) -> bool {
+    let argon2 = Argon2::default();
+    match PasswordHash::new(hash) {
+        Ok(hash) => argon2.verify_password(password.as_bytes(), &hash).is_ok(),
+        Err(_) => {
+            let hash = PasswordHash::new(dummy_hash()).expect("the dummy hash is valid");
+            let _ = argon2.verify_password(password.as_bytes(), &hash);
+            false
+        }
+    }
+}
+
+/// Sessions are stored by a hash of their token, so that the tokens
+/// themselves can't be read from the database.
+fn hash_token(token: &str) -> String {
+    Sha256::digest(token.as_bytes())
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect()
+}
+
+pub(crate) struct User(pub(crate) SharedUser);
+
+impl From<DbUser> for User {
+    fn from(db_user: DbUser) -> Self {
+        Self(SharedUser {
+            id: db_user.id.into(),
+            username: db_user.username,
+            display_name: db_user.display_name,
+        })
+    }
+}
+
+#[async_trait]
+impl UserRepository for DieselRepository {
+    type UserId = UserId;
+
+    async fn register(&self, input: UserInput) -> Result<SharedUser> {
+        input.validate()?;
+        let username = input.username.trim();
+        let display_name = input
+            .display_name
+            .as_deref()
+            .map(str::trim)
+            .filter(|n| !n.is_empty())
+            .unwrap_or(username);
+        let salt = SaltString::generate(&mut OsRng);
+        let password_hash = Argon2::default()
+            .hash_password(input.password.as_bytes(), &salt)?
+            .to_string();
+        let mut conn = self.pool.get().await?;
+        let user: DbUser = diesel::insert_into(users::table)
+            .values(&NewUser {
+                username,
+                display_name,
+                password_hash: &password_hash,
+            })
+            .returning(DbUser::as_returning())
+            .get_result(&mut conn)
+            .await
+            .map_err(|e| match e {
+                DieselError::DatabaseError(
+                    diesel::result::DatabaseErrorKind::UniqueViolation,
+                    _,
+                ) => {
+                    let mut errors = ValidationErrors::default();
+                    errors.add("username", "username is already taken");
+                    errors.into()
+                }
+                e => Error::from(e),
+            })?;
+        Ok(User::from(user).0)
+    }
+
+    async fn authenticate(&self, credentials: Credentials) -> Result<Option<SharedUser>> {
+        let mut conn = self.pool.get().await?;
+        let user: Option<DbUser> = DbUser::by_username(credentials.username.trim())
+            .select(DbUser::as_select())
+            .first(&mut conn)
+            .await
+            .optional()?;
+        // Unknown usernames are checked against a dummy hash, so that how
+        // long this takes doesn't give away which usernames exist.
+        let hash = user
+            .as_ref()
+            .map_or(NO_PASSWORD, |user| user.password_hash.as_str());
+        let verified = verify_password(&credentials.password, hash);
+        Ok(user.filter(|_| verified).map(|user| User::from(user).0))
+    }
+
+    async fn start_session(&self, user_id: Self::UserId) -> Result<String> {
+        let mut bytes = [0u8; 32];
+        OsRng.fill_bytes(&mut bytes);
+        let token: String = bytes.iter().map(|b| format!("{b:02x}")).collect();
+        let mut conn = self.pool.get().await?;
+        diesel::insert_into(sessions::table)
+            .values(&NewSession {
+                token_hash: &hash_token(&token),
+                user_id,
+                expires_at: (Utc::now() + Duration::days(SESSION_DAYS)).naive_utc(),
+            })
+            .execute(&mut conn)
+            .await?;
+        Ok(token)
+    }
+
+    async fn session_user(&self, token: &str) -> Result<Option<SharedUser>> {
+        let mut conn = self.pool.get().await?;
+        let user: Option<(Session, DbUser)> = sessions::table
+            .inner_join(users::table)
+            .filter(sessions::token_hash.eq(hash_token(token)))
+            .filter(sessions::expires_at.gt(Utc::now().naive_utc()))
+            .select((Session::as_select(), DbUser::as_select()))
+            .first(&mut conn)
+            .await
+            .optional()?;
+        Ok(user.map(|(_, user)| User::from(user).0))
+    }
+
+    async fn end_session(&self, token: &str) -> Result<()> {
+        let mut conn = self.pool.get().await?;
+        diesel::delete(sessions::table.find(hash_token(token)))
+            .execute(&mut conn)
+            .await?;
+        Ok(())
+    }
+
+    async fn local_user(&self, username: &str) -> Result<SharedUser> {
+        let mut conn = self.pool.get().await?;
+        let existing: Option<DbUser> = DbUser::by_username(username)
+            .select(DbUser::as_select())
+            .first(&mut conn)
+            .await
+            .optional()?;
+        let user = match existing {
+            Some(user) => user,
+            None => {
+                diesel::insert_into(users::table)
+                    .values(&NewUser {
+                        username,
+                        display_name: username,
+                        password_hash: NO_PASSWORD,
+                    })
+                    .returning(DbUser::as_returning())
+                    .get_result(&mut conn)
+                    .await?
+            }
+        };
+        Ok(User::from(user).0)
+    }
+}
+
+#[cfg(all(test, feature = "sqlite"))]
+mod tests {
+    use super::*;
+
+    use crate::testing;
+
+    async fn alice(name: &str) -> DieselRepository {
+        let repo = testing::database(&format!("user-{name}")).await;
+        repo.register(UserInput {
+            username: "alice".to_string(),
+            display_name: None,
+            password: "correct horse battery".to_string(),
+        })
+        .await
+        .unwrap();
+        repo
+    }
+
+    fn credentials(username: &str, password: &str) -> Credentials {
+        Credentials {
+            username: username.to_string(),
+            password: password.to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn only_the_right_password_signs_in() {
+        let repo = alice("passwords").await;
+        let user = repo
+            .authenticate(credentials(" alice ", "correct horse battery"))
+            .await
+            .unwrap();
+        assert_eq!(user.unwrap().username, "alice");
+        for (username, password) in [
+            ("alice", "wrong horse battery"),
+            ("alice", ""),
+            ("bob", "correct horse battery"),
+        ] {
+            let user = repo
+                .authenticate(credentials(username, password))
+                .await
+                .unwrap();
+            assert!(user.is_none(), "{username} {password}");
+        }
+
+        // Local users have no password, so can't sign in at all.
+        repo.local_user("carol").await.unwrap();
+        let user = repo.authenticate(credentials("carol", "")).await.unwrap();
+        assert!(user.is_none());
+    }
+
+    #[tokio::test]
+    async fn sessions_last_until_they_end_or_expire() {
+        let repo = alice("sessions").await;
+        let user: UserId = repo.local_user("alice").await.unwrap().id.into();
+        let token = repo.start_session(user).await.unwrap();
+        assert_eq!(
+            repo.session_user(&token).await.unwrap().unwrap().username,
+            "alice"
+        );
+
+        // Only a hash of the token is stored, which can't be used in its
+        // place.
+        let mut conn = repo.pool.get().await.unwrap();
+        let stored: Vec<String> = sessions::table
+            .select(sessions::token_hash)
+            .load(&mut conn)
+            .await
+            .unwrap();
+        assert_eq!(stored, [hash_token(&token)]);
+        assert!(repo.session_user(&stored[0]).await.unwrap().is_none());
+
+        repo.end_session(&token).await.unwrap();
+        assert!(repo.session_user(&token).await.unwrap().is_none());
+
+        let expired = "expired";
+        diesel::insert_into(sessions::table)
+            .values(&NewSession {
+                token_hash: &hash_token(expired),
+                user_id: user,
+                expires_at: (Utc::now() - Duration::minutes(1)).naive_utc(),
+            })
+            .execute(&mut conn)
+            .await
+            .unwrap();
+        assert!(repo.session_user(expired).await.unwrap().is_none());
+    }
+}
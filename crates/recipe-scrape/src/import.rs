@@ -0,0 +1,210 @@
+//! Importers for the export formats of other recipe managers, producing
+//! [`ScrapedRecipe`]s so imported recipes are reviewed and saved the same way
+//! as scraped ones.
+
+use std::{
+    fs::File,
+    io::{self, Read, Seek},
+    path::Path,
+};
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use url::Url;
+
+use crate::{ScrapedIngredient, ScrapedRecipe};
+
+mod cooklang;
+mod mealie;
+mod paprika;
+
+pub use cooklang::parse as parse_cooklang;
+pub use mealie::parse as parse_mealie;
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("I/O error: {0}")]
+    Io(#[from] io::Error),
+    #[error("zip error: {0}")]
+    Zip(#[from] zip::result::ZipError),
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("unsupported file {0}")]
+    UnsupportedFile(String),
+    #[error("invalid recipe: {0}")]
+    Invalid(String),
+}
+
+/// A recipe manager whose exports can be imported.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportFormat {
+    /// Paprika's `.paprikarecipes` archives of gzipped JSON recipes, or a
+    /// single `.paprikarecipe`.
+    Paprika,
+    /// Mealie's JSON recipes, either a single recipe, a list of them or a
+    /// zip archive of recipe files.
+    Mealie,
+    /// Cooklang `.cook` files, one recipe per file.
+    Cooklang,
+}
+
+impl ImportFormat {
+    /// Guess the format of a file from its extension.
+    pub fn detect(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_lowercase();
+        match extension.as_str() {
+            "paprikarecipes" | "paprikarecipe" => Some(Self::Paprika),
+            "json" | "zip" => Some(Self::Mealie),
+            "cook" => Some(Self::Cooklang),
+            _ => None,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Paprika => "Paprika",
+            Self::Mealie => "Mealie",
+            Self::Cooklang => "Cooklang",
+        }
+    }
+}
+
+/// Read every recipe in an exported file, detecting its format from the
+/// extension.
+///
+/// Recipes which don't say where they came from are given the file's URL as
+/// their source.
+pub fn import_file(path: &Path) -> Result<Vec<ScrapedRecipe>, Error> {
+    let unsupported = || Error::UnsupportedFile(path.display().to_string());
+    let format = ImportFormat::detect(path).ok_or_else(unsupported)?;
+    let source = Url::from_file_path(path.canonicalize()?).map_err(|_| unsupported())?;
+    let mut file = File::open(path)?;
+    let is_zip = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| ["zip", "paprikarecipes"].contains(&e.to_lowercase().as_str()));
+    match format {
+        ImportFormat::Paprika if is_zip => paprika::parse_archive(file, &source),
+        ImportFormat::Paprika => Ok(vec![paprika::parse_recipe(file, &source)?]),
+        ImportFormat::Mealie if is_zip => mealie::parse_archive(file, &source),
+        ImportFormat::Mealie => {
+            let mut text = String::new();
+            file.read_to_string(&mut text)?;
+            mealie::parse(&text, &source)
+        }
+        ImportFormat::Cooklang => {
+            let mut text = String::new();
+            file.read_to_string(&mut text)?;
+            let name = path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .unwrap_or_default();
+            Ok(vec![cooklang::parse(name, &text, &source)?])
+        }
+    }
+}
+
+/// Read the contents of every file in a zip archive whose name ends with
+/// `extension`.
+fn archive_entries<R: Read + Seek>(reader: R, extension: &str) -> Result<Vec<Vec<u8>>, Error> {
+    let mut archive = zip::ZipArchive::new(reader)?;
+    let mut entries = Vec::new();
+    for i in 0..archive.len() {
+        let mut file = archive.by_index(i)?;
+        if !file.is_file() || !file.name().to_lowercase().ends_with(extension) {
+            continue;
+        }
+        let mut contents = Vec::with_capacity(file.size() as usize);
+        file.read_to_end(&mut contents)?;
+        entries.push(contents);
+    }
+    Ok(entries)
+}
+
+static DURATION_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)(?P<amount>\d+(\.\d+)?)\s*(?P<unit>hours?|hrs?|h|minutes?|mins?|m)?").unwrap()
+});
+
+/// Parse a free text duration such as "1 hr 10 mins" or an ISO 8601 duration
+/// like `PT1H10M` into minutes. Numbers without a unit are taken as minutes.
+fn parse_minutes(text: &str) -> Option<u32> {
+    let text = text.trim();
+    if text.starts_with('P') {
+        if let Ok(duration) = iso8601_duration::Duration::parse(text) {
+            return duration.num_minutes().map(|m| m.ceil() as u32);
+        }
+    }
+    let mut minutes = 0.0;
+    let mut found = false;
+    for captures in DURATION_REGEX.captures_iter(text) {
+        let Ok(amount) = captures["amount"].parse::<f64>() else {
+            continue;
+        };
+        let unit = captures.name("unit").map(|u| u.as_str().to_lowercase());
+        minutes += match unit.as_deref() {
+            Some(unit) if unit.starts_with('h') => amount * 60.0,
+            _ => amount,
+        };
+        found = true;
+    }
+    found.then_some(minutes.ceil() as u32)
+}
+
+static SERVINGS_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"\d+").unwrap());
+
+/// Parse the first number in text like "Serves 4" or "4-6 servings".
+fn parse_servings(text: &str) -> Option<u32> {
+    SERVINGS_REGEX
+        .find(text)
+        .and_then(|m| m.as_str().parse().ok())
+        .filter(|servings| *servings > 0)
+}
+
+/// Split text into its non-empty, trimmed lines.
+fn lines(text: &str) -> Vec<String> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Parse ingredient lines, returning them along with warnings for any which
+/// weren't fully understood.
+fn ingredients(lines: &[String]) -> (Vec<ScrapedIngredient>, Vec<String>) {
+    let ingredients: Vec<_> = lines
+        .iter()
+        .map(|line| ScrapedIngredient::parse_lenient(line))
+        .collect();
+    let warnings = ScrapedRecipe::ingredient_warnings(&ingredients);
+    (ingredients, warnings)
+}
+
+/// A recipe's own source URL if it has a valid one, or `fallback` otherwise.
+fn source(url: Option<&str>, fallback: &Url) -> Url {
+    url.and_then(|url| Url::parse(url.trim()).ok())
+        .unwrap_or_else(|| fallback.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_minutes, parse_servings};
+
+    #[test]
+    fn minutes() {
+        assert_eq!(parse_minutes("15 mins"), Some(15));
+        assert_eq!(parse_minutes("1 hr 10 mins"), Some(70));
+        assert_eq!(parse_minutes("1h30m"), Some(90));
+        assert_eq!(parse_minutes("2 hours"), Some(120));
+        assert_eq!(parse_minutes("PT45M"), Some(45));
+        assert_eq!(parse_minutes("20"), Some(20));
+        assert_eq!(parse_minutes("overnight"), None);
+    }
+
+    #[test]
+    fn servings() {
+        assert_eq!(parse_servings("Serves 4"), Some(4));
+        assert_eq!(parse_servings("4-6 servings"), Some(4));
+        assert_eq!(parse_servings("a few"), None);
+    }
+}
@@ -0,0 +1,344 @@
+//! A parser for the [Cooklang](https://cooklang.org/docs/spec/) recipe markup.
+//!
+//! Ingredients are marked up within the steps, e.g. `Add @salt{1%tsp}`, with
+//! cookware (`#pan{}`) and timers (`~{10%minutes}`) marked up the same way.
+//! Metadata is given either as `>> key: value` lines or as YAML-style front
+//! matter between `---` lines.
+
+use url::Url;
+
+use super::{parse_minutes, parse_servings, source, Error};
+use crate::{ingredient::Amount, ParseStatus, ScrapedIngredient, ScrapedRecipe, Unit};
+
+/// Parse a Cooklang recipe, named `name` unless its metadata gives a title.
+pub fn parse(name: &str, text: &str, fallback: &Url) -> Result<ScrapedRecipe, Error> {
+    let text = strip_block_comments(text);
+    let (mut metadata, body) = front_matter(&text);
+    let mut notes = Vec::new();
+    let mut steps = Vec::new();
+    let mut paragraph: Vec<String> = Vec::new();
+    let mut ingredients: Vec<ScrapedIngredient> = Vec::new();
+    let mut warnings = Vec::new();
+
+    for line in body.lines() {
+        let line = match line.find("--") {
+            Some(i) => &line[..i],
+            None => line,
+        };
+        let line = line.trim();
+        if let Some(entry) = line.strip_prefix(">>") {
+            if let Some((key, value)) = entry.split_once(':') {
+                metadata.push((key.trim().to_lowercase(), value.trim().to_string()));
+            }
+        } else if let Some(note) = line.strip_prefix('>') {
+            notes.push(note.trim().to_string());
+        } else if line.is_empty() || line.starts_with('=') {
+            // Sections aren't kept, but they do separate steps.
+            end_paragraph(&mut paragraph, &mut steps);
+        } else {
+            let step = parse_step(line, &mut ingredients, &mut warnings);
+            if !step.is_empty() {
+                paragraph.push(step);
+            }
+        }
+    }
+    end_paragraph(&mut paragraph, &mut steps);
+
+    let get = |keys: &[&str]| {
+        metadata
+            .iter()
+            .find(|(key, value)| keys.contains(&key.as_str()) && !value.is_empty())
+            .map(|(_, value)| value.clone())
+    };
+    let list = |keys: &[&str]| -> Vec<String> {
+        get(keys)
+            .map(|value| {
+                value
+                    .trim_start_matches('[')
+                    .trim_end_matches(']')
+                    .split(',')
+                    .map(|item| item.trim().trim_matches('"').to_string())
+                    .filter(|item| !item.is_empty())
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+    let description = get(&["description", "introduction"]);
+    notes.retain(|note| !note.is_empty());
+    warnings.extend(ScrapedRecipe::ingredient_warnings(&ingredients));
+    Ok(ScrapedRecipe {
+        name: get(&["title"]).unwrap_or_else(|| name.to_string()),
+        source: source(get(&["source", "source.url"]).as_deref(), fallback),
+        description,
+        notes: (!notes.is_empty()).then(|| notes.join("\n\n")),
+        prep_time_minutes: get(&["prep time", "prep_time", "time.prep"])
+            .as_deref()
+            .and_then(parse_minutes),
+        cooking_time_minutes: get(&["cook time", "cook_time", "time.cook"])
+            .as_deref()
+            .and_then(parse_minutes),
+        servings: get(&["servings", "serves", "yield"])
+            .as_deref()
+            .and_then(parse_servings),
+        ingredients,
+        steps,
+        image_url: get(&["image"]),
+        categories: list(&["course", "category"]),
+        cuisines: list(&["cuisine"]),
+        keywords: list(&["tags"]),
+        diets: list(&["diet"]),
+        nutrition: None,
+        warnings,
+    })
+}
+
+/// Finish the current step, whose lines are joined into one.
+fn end_paragraph(paragraph: &mut Vec<String>, steps: &mut Vec<String>) {
+    if !paragraph.is_empty() {
+        steps.push(paragraph.join(" "));
+        paragraph.clear();
+    }
+}
+
+/// Remove `[- ... -]` comments, which may span several lines.
+fn strip_block_comments(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find("[-") {
+        out.push_str(&rest[..start]);
+        rest = match rest[start..].find("-]") {
+            Some(end) => &rest[start + end + 2..],
+            None => "",
+        };
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Split YAML-style front matter from the rest of the recipe, reading it as
+/// simple `key: value` pairs.
+fn front_matter(text: &str) -> (Vec<(String, String)>, &str) {
+    let trimmed = text.trim_start();
+    let Some(rest) = trimmed.strip_prefix("---") else {
+        return (Vec::new(), text);
+    };
+    let Some(end) = rest.find("\n---") else {
+        return (Vec::new(), text);
+    };
+    let metadata = rest[..end]
+        .lines()
+        .filter_map(|line| line.split_once(':'))
+        .map(|(key, value)| (key.trim().to_lowercase(), value.trim().to_string()))
+        .collect();
+    let body = &rest[end + 4..];
+    let body = body.split_once('\n').map_or("", |(_, body)| body);
+    (metadata, body)
+}
+
+/// Characters which can't appear in the name of a multi-word ingredient,
+/// cookware or timer, so a `{` after them belongs to something else.
+const NAME_TERMINATORS: &[char] = &[
+    '@', '#', '~', '{', '}', '.', ',', ';', ':', '!', '?', '(', ')',
+];
+
+/// Replace the markup in a line of a step with plain text, adding any
+/// ingredients to `ingredients`.
+fn parse_step(
+    line: &str,
+    ingredients: &mut Vec<ScrapedIngredient>,
+    warnings: &mut Vec<String>,
+) -> String {
+    let mut out = String::with_capacity(line.len());
+    let mut rest = line;
+    while let Some(i) = rest.find(['@', '#', '~']) {
+        out.push_str(&rest[..i]);
+        let marker = rest[i..].chars().next().expect("found a marker");
+        let after = &rest[i + 1..];
+        let Some((name, amount, remainder)) = component(after) else {
+            out.push(marker);
+            rest = after;
+            continue;
+        };
+        rest = remainder;
+        match marker {
+            '@' => {
+                // Modifiers mark ingredients as optional, hidden from the
+                // step's text, or references to earlier ones.
+                let reference = name.starts_with('&');
+                let hidden = name.starts_with('-');
+                let name = name.trim_start_matches(['?', '&', '-', '+']).trim();
+                let (note, remainder) = match rest.strip_prefix('(') {
+                    Some(after) => match after.split_once(')') {
+                        Some((note, remainder)) => (Some(note.trim()), remainder),
+                        None => (None, rest),
+                    },
+                    None => (None, rest),
+                };
+                rest = remainder;
+                if !hidden {
+                    out.push_str(name);
+                }
+                if !reference {
+                    add_ingredient(ingredients, warnings, name, amount, note);
+                }
+            }
+            '#' => out.push_str(name),
+            _ => {
+                let (quantity, unit) = split_amount(amount.unwrap_or_default());
+                let text = [quantity, unit.unwrap_or_default()].join(" ");
+                out.push_str(text.trim());
+            }
+        }
+    }
+    out.push_str(rest);
+    out.trim().to_string()
+}
+
+/// Read the name and optional `{amount}` of a component after its marker,
+/// returning them with the rest of the line.
+fn component(text: &str) -> Option<(&str, Option<&str>, &str)> {
+    if let Some(open) = text.find('{') {
+        let name = &text[..open];
+        if !name.contains(NAME_TERMINATORS) {
+            let close = text[open..].find('}')? + open;
+            let amount = text[open + 1..close].trim();
+            let amount = (!amount.is_empty()).then_some(amount);
+            return Some((name.trim(), amount, &text[close + 1..]));
+        }
+    }
+    let end = text
+        .char_indices()
+        .find(|(_, c)| !(c.is_alphanumeric() || ['_', '-', '?', '&', '+'].contains(c)))
+        .map_or(text.len(), |(i, _)| i);
+    // Trailing hyphens are punctuation, not part of the name.
+    let name = text[..end].trim_end_matches('-');
+    (!name.is_empty()).then(|| (name, None, &text[name.len()..]))
+}
+
+/// Split an amount like `250%g` into its quantity and unit.
+fn split_amount(amount: &str) -> (&str, Option<&str>) {
+    match amount.split_once('%') {
+        Some((quantity, unit)) => (quantity.trim(), Some(unit.trim()).filter(|u| !u.is_empty())),
+        None => (amount.trim(), None),
+    }
+}
+
+fn add_ingredient(
+    ingredients: &mut Vec<ScrapedIngredient>,
+    warnings: &mut Vec<String>,
+    name: &str,
+    amount: Option<&str>,
+    note: Option<&str>,
+) {
+    let (quantity, unit) = split_amount(amount.unwrap_or_default());
+    let parsed = (!quantity.is_empty()).then(|| quantity.parse::<Amount>());
+    let mut problems = Vec::new();
+    let amount = match parsed {
+        Some(Ok(amount)) => Some(amount.as_f64()),
+        Some(Err(e)) => {
+            problems.push(e.to_string());
+            None
+        }
+        None => None,
+    };
+    let unit: Option<Unit> = unit.and_then(|u| u.parse().ok());
+
+    if let Some(existing) = ingredients.iter_mut().find(|i| {
+        i.name
+            .as_deref()
+            .is_some_and(|n| n.eq_ignore_ascii_case(name))
+    }) {
+        // Ingredients used in several steps are listed once, with their
+        // amounts added up when they're in the same unit.
+        match (existing.amount, amount) {
+            (Some(a), Some(b)) if existing.unit == unit => existing.amount = Some(a + b),
+            (_, None) => {}
+            _ => warnings.push(format!(
+                "{name} is used more than once in different units; only the first amount was kept"
+            )),
+        }
+        return;
+    }
+
+    let unit_text = unit.as_ref().map(Unit::to_string).unwrap_or_default();
+    let raw = [quantity, unit_text.as_str(), name]
+        .into_iter()
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ");
+    let raw = match note {
+        Some(note) if !note.is_empty() => format!("{raw}, {note}"),
+        _ => raw,
+    };
+    ingredients.push(ScrapedIngredient {
+        raw,
+        name: Some(name.to_string()),
+        amount,
+        unit,
+        instructions: note.filter(|n| !n.is_empty()).map(str::to_string),
+        status: if problems.is_empty() {
+            ParseStatus::Parsed
+        } else {
+            ParseStatus::Partial(problems.join("; "))
+        },
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use url::Url;
+
+    use super::parse;
+
+    const PANCAKES: &str = "\
+---
+title: Easy pancakes
+servings: 4
+tags: [breakfast, quick]
+---
+
+> Best eaten straight away.
+
+Crack the @eggs{3} into a blender, then add the @plain flour{125%g}, @milk{250%ml} \
+and a pinch of @salt. -- or use a bowl and whisk
+
+Blitz until smooth, then rest for ~{20%minutes}.
+
+Heat a #large frying pan{} and fry ladlefuls of batter, adding more @milk{50%ml} \
+if the batter thickens.
+";
+
+    #[test]
+    fn pancakes() {
+        let fallback: Url = "file:///recipes/pancakes.cook".parse().unwrap();
+        let recipe = parse("pancakes", PANCAKES, &fallback).unwrap();
+        assert_eq!(recipe.name, "Easy pancakes");
+        assert_eq!(recipe.source, fallback);
+        assert_eq!(recipe.servings, Some(4));
+        assert_eq!(recipe.keywords, vec!["breakfast", "quick"]);
+        assert_eq!(recipe.notes.as_deref(), Some("Best eaten straight away."));
+        assert_eq!(
+            recipe.steps,
+            vec![
+                "Crack the eggs into a blender, then add the plain flour, milk and a pinch of salt.",
+                "Blitz until smooth, then rest for 20 minutes.",
+                "Heat a large frying pan and fry ladlefuls of batter, adding more milk if the batter thickens.",
+            ]
+        );
+        let ingredients: Vec<_> = recipe
+            .ingredients
+            .iter()
+            .map(|i| (i.name.as_deref().unwrap(), i.amount))
+            .collect();
+        assert_eq!(
+            ingredients,
+            vec![
+                ("eggs", Some(3.0)),
+                ("plain flour", Some(125.0)),
+                ("milk", Some(300.0)),
+                ("salt", None),
+            ]
+        );
+        assert!(recipe.warnings.is_empty());
+    }
+}
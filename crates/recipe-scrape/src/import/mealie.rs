@@ -0,0 +1,242 @@
+use std::io::{Read, Seek};
+
+use serde::Deserialize;
+use serde_json::Value;
+use url::Url;
+
+use super::{archive_entries, ingredients, parse_minutes, parse_servings, source, Error};
+use crate::{ScrapedNutrition, ScrapedRecipe};
+
+#[derive(Debug, Deserialize)]
+struct Named {
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum Ingredient {
+    Text(String),
+    Structured(StructuredIngredient),
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct StructuredIngredient {
+    original_text: Option<String>,
+    display: Option<String>,
+    quantity: Option<f64>,
+    unit: Option<Unit>,
+    food: Option<Named>,
+    note: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Unit {
+    name: String,
+    abbreviation: Option<String>,
+}
+
+impl Ingredient {
+    /// The ingredient as a single line of text for the ingredient parser,
+    /// preferring the text it was originally entered as.
+    fn line(self) -> String {
+        let ingredient = match self {
+            Self::Text(text) => return text,
+            Self::Structured(ingredient) => ingredient,
+        };
+        let given = [&ingredient.original_text, &ingredient.display]
+            .into_iter()
+            .flatten()
+            .find(|text| !text.trim().is_empty());
+        if let Some(text) = given {
+            return text.clone();
+        }
+        let unit = ingredient
+            .unit
+            .map(|u| u.abbreviation.filter(|a| !a.is_empty()).unwrap_or(u.name));
+        let parts = [
+            ingredient
+                .quantity
+                .filter(|q| *q > 0.0)
+                .map(|q| q.to_string()),
+            unit,
+            ingredient.food.map(|f| f.name),
+        ];
+        let mut line = parts.into_iter().flatten().collect::<Vec<_>>().join(" ");
+        if let Some(note) = ingredient.note.filter(|n| !n.trim().is_empty()) {
+            if line.is_empty() {
+                line = note;
+            } else {
+                line = format!("{line}, {note}");
+            }
+        }
+        line
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct Instruction {
+    text: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Note {
+    title: Option<String>,
+    text: String,
+}
+
+/// Mealie's nutrition, as numbers in text with fixed units: kilocalories,
+/// grams, and milligrams for sodium.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Nutrition {
+    calories: Option<String>,
+    fat_content: Option<String>,
+    saturated_fat_content: Option<String>,
+    carbohydrate_content: Option<String>,
+    sugar_content: Option<String>,
+    fiber_content: Option<String>,
+    protein_content: Option<String>,
+    sodium_content: Option<String>,
+}
+
+fn number(value: &Option<String>) -> Option<f64> {
+    value.as_deref()?.trim().parse().ok()
+}
+
+impl From<Nutrition> for ScrapedNutrition {
+    fn from(n: Nutrition) -> Self {
+        Self {
+            energy_kcal: number(&n.calories),
+            fat_g: number(&n.fat_content),
+            saturated_fat_g: number(&n.saturated_fat_content),
+            carbohydrate_g: number(&n.carbohydrate_content),
+            sugar_g: number(&n.sugar_content),
+            fibre_g: number(&n.fiber_content),
+            protein_g: number(&n.protein_content),
+            // Salt is roughly two and a half times the weight of its sodium.
+            salt_g: number(&n.sodium_content).map(|sodium| sodium / 1000.0 * 2.5),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MealieRecipe {
+    name: String,
+    description: Option<String>,
+    recipe_yield: Option<String>,
+    recipe_servings: Option<f64>,
+    prep_time: Option<String>,
+    perform_time: Option<String>,
+    cook_time: Option<String>,
+    #[serde(rename = "orgURL")]
+    org_url: Option<String>,
+    #[serde(default)]
+    recipe_category: Vec<Named>,
+    #[serde(default)]
+    tags: Vec<Named>,
+    #[serde(default)]
+    recipe_ingredient: Vec<Ingredient>,
+    #[serde(default)]
+    recipe_instructions: Vec<Instruction>,
+    #[serde(default)]
+    notes: Vec<Note>,
+    nutrition: Option<Nutrition>,
+}
+
+impl MealieRecipe {
+    fn into_scraped(self, fallback: &Url) -> ScrapedRecipe {
+        let lines: Vec<_> = self
+            .recipe_ingredient
+            .into_iter()
+            .map(Ingredient::line)
+            .filter(|line| !line.trim().is_empty())
+            .collect();
+        let (ingredients, warnings) = ingredients(&lines);
+        let notes: Vec<_> = self
+            .notes
+            .into_iter()
+            .filter(|note| !note.text.trim().is_empty())
+            .map(|note| match note.title.filter(|t| !t.trim().is_empty()) {
+                Some(title) => format!("{title}: {}", note.text.trim()),
+                None => note.text.trim().to_string(),
+            })
+            .collect();
+        let servings = self
+            .recipe_servings
+            .filter(|s| *s >= 1.0)
+            .map(|s| s.round() as u32)
+            .or_else(|| self.recipe_yield.as_deref().and_then(parse_servings));
+        ScrapedRecipe {
+            source: source(self.org_url.as_deref(), fallback),
+            description: self.description.filter(|d| !d.trim().is_empty()),
+            notes: (!notes.is_empty()).then(|| notes.join("\n\n")),
+            prep_time_minutes: self.prep_time.as_deref().and_then(parse_minutes),
+            // Mealie calls the cooking time "perform time", with "cook time"
+            // only present in older exports.
+            cooking_time_minutes: self
+                .perform_time
+                .or(self.cook_time)
+                .as_deref()
+                .and_then(parse_minutes),
+            servings,
+            ingredients,
+            steps: self
+                .recipe_instructions
+                .into_iter()
+                .map(|step| step.text.trim().to_string())
+                .filter(|text| !text.is_empty())
+                .collect(),
+            image_url: None,
+            categories: self.recipe_category.into_iter().map(|c| c.name).collect(),
+            cuisines: Vec::new(),
+            keywords: self.tags.into_iter().map(|t| t.name).collect(),
+            diets: Vec::new(),
+            nutrition: self
+                .nutrition
+                .map(ScrapedNutrition::from)
+                .filter(|n| *n != ScrapedNutrition::default()),
+            warnings,
+            name: self.name,
+        }
+    }
+}
+
+/// Read Mealie recipe JSON, which may be a single recipe, a list of them, or
+/// a page of them from Mealie's API.
+pub fn parse(text: &str, fallback: &Url) -> Result<Vec<ScrapedRecipe>, Error> {
+    let value: Value = serde_json::from_str(text)?;
+    let recipes = match value {
+        Value::Array(recipes) => recipes,
+        Value::Object(mut object) => match object.remove("items") {
+            Some(Value::Array(recipes)) => recipes,
+            _ => vec![Value::Object(object)],
+        },
+        _ => {
+            return Err(Error::Invalid(
+                "expected a recipe or list of recipes".to_string(),
+            ))
+        }
+    };
+    recipes
+        .into_iter()
+        .map(|recipe| {
+            let recipe: MealieRecipe = serde_json::from_value(recipe)?;
+            Ok(recipe.into_scraped(fallback))
+        })
+        .collect()
+}
+
+/// Read every JSON recipe file in a zip archive of Mealie exports.
+pub fn parse_archive<R: Read + Seek>(
+    reader: R,
+    fallback: &Url,
+) -> Result<Vec<ScrapedRecipe>, Error> {
+    let mut recipes = Vec::new();
+    for entry in archive_entries(reader, ".json")? {
+        let text = String::from_utf8(entry).map_err(|e| Error::Invalid(e.to_string()))?;
+        recipes.extend(parse(&text, fallback)?);
+    }
+    Ok(recipes)
+}
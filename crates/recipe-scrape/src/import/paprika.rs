@@ -0,0 +1,68 @@
+use std::io::{Read, Seek};
+
+use flate2::read::GzDecoder;
+use serde::Deserialize;
+use url::Url;
+
+use super::{archive_entries, ingredients, lines, parse_minutes, parse_servings, source, Error};
+use crate::ScrapedRecipe;
+
+/// A recipe as exported by Paprika, where most fields are free text.
+#[derive(Debug, Deserialize)]
+struct PaprikaRecipe {
+    name: String,
+    #[serde(default)]
+    ingredients: String,
+    #[serde(default)]
+    directions: String,
+    description: Option<String>,
+    notes: Option<String>,
+    servings: Option<String>,
+    prep_time: Option<String>,
+    cook_time: Option<String>,
+    source_url: Option<String>,
+    image_url: Option<String>,
+    #[serde(default)]
+    categories: Vec<String>,
+    nutritional_info: Option<String>,
+}
+
+/// Read every recipe in a `.paprikarecipes` archive, which holds one gzipped
+/// JSON file per recipe.
+pub fn parse_archive<R: Read + Seek>(
+    reader: R,
+    fallback: &Url,
+) -> Result<Vec<ScrapedRecipe>, Error> {
+    archive_entries(reader, ".paprikarecipe")?
+        .into_iter()
+        .map(|entry| parse_recipe(entry.as_slice(), fallback))
+        .collect()
+}
+
+/// Read a single gzipped `.paprikarecipe`.
+pub fn parse_recipe<R: Read>(reader: R, fallback: &Url) -> Result<ScrapedRecipe, Error> {
+    let recipe: PaprikaRecipe = serde_json::from_reader(GzDecoder::new(reader))?;
+    let (ingredients, mut warnings) = ingredients(&lines(&recipe.ingredients));
+    let nonempty = |text: Option<String>| text.filter(|t| !t.trim().is_empty());
+    if nonempty(recipe.nutritional_info).is_some() {
+        warnings.push("Paprika's free text nutritional information wasn't imported".to_string());
+    }
+    Ok(ScrapedRecipe {
+        source: source(recipe.source_url.as_deref(), fallback),
+        description: nonempty(recipe.description),
+        notes: nonempty(recipe.notes),
+        prep_time_minutes: recipe.prep_time.as_deref().and_then(parse_minutes),
+        cooking_time_minutes: recipe.cook_time.as_deref().and_then(parse_minutes),
+        servings: recipe.servings.as_deref().and_then(parse_servings),
+        ingredients,
+        steps: lines(&recipe.directions),
+        image_url: nonempty(recipe.image_url),
+        categories: recipe.categories,
+        cuisines: Vec::new(),
+        keywords: Vec::new(),
+        diets: Vec::new(),
+        nutrition: None,
+        warnings,
+        name: recipe.name,
+    })
+}
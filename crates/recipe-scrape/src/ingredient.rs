@@ -25,6 +25,29 @@ pub enum Error {
     AmountAndUnit(String),
 }
 
+/// How much of an ingredient line the parser managed to understand.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "status", content = "message", rename_all = "snake_case")]
+pub enum ParseStatus {
+    /// Every part of the line was understood.
+    #[default]
+    Parsed,
+    /// The line was parsed, but part of it couldn't be understood.
+    Partial(String),
+    /// Nothing useful could be parsed; only the raw text is available.
+    Failed(String),
+}
+
+impl ParseStatus {
+    /// The diagnostic message, if the line wasn't fully parsed.
+    pub fn message(&self) -> Option<&str> {
+        match self {
+            Self::Parsed => None,
+            Self::Partial(message) | Self::Failed(message) => Some(message),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ScrapedIngredient {
     pub raw: String,
@@ -32,9 +55,23 @@ pub struct ScrapedIngredient {
     pub amount: Option<f64>,
     pub unit: Option<Unit>,
     pub instructions: Option<String>,
+    #[serde(default)]
+    pub status: ParseStatus,
 }
 
 impl ScrapedIngredient {
+    /// An ingredient for which nothing but the raw text is known.
+    pub fn unparsed(raw: &str, reason: impl Into<String>) -> Self {
+        Self {
+            raw: raw.to_string(),
+            name: None,
+            amount: None,
+            unit: None,
+            instructions: None,
+            status: ParseStatus::Failed(reason.into()),
+        }
+    }
+
     pub fn canonicalize(&self) -> Option<(f64, Unit)> {
         match (self.amount, &self.unit) {
             (Some(amount), Some(unit)) => {
@@ -88,6 +125,23 @@ pub enum Unit {
 }
 
 impl Unit {
+    /// Convert an amount in this unit to grams, if it's a unit of mass.
+    pub fn grams(&self, amount: f64) -> Option<f64> {
+        match self {
+            Self::Mass(mass) => Some(amount * mass.canonicalize()),
+            _ => None,
+        }
+    }
+
+    /// Convert an amount in this unit to millilitres, if it's a unit of volume.
+    pub fn millilitres(&self, amount: f64) -> Option<f64> {
+        match self {
+            Self::Volume(volume) => Some(amount * volume.canonicalize() * 1000.0),
+            Self::Spoon(spoon) => Some(amount * spoon.canonicalize() * 1000.0),
+            _ => None,
+        }
+    }
+
     fn canonical_unit(&self) -> Self {
         match self {
             Self::Mass(_) => Self::Mass(MassUnit::Grams),
@@ -357,60 +411,90 @@ impl fmt::Display for SpoonUnit {
     }
 }
 
-#[cfg(feature = "scraper")]
+#[cfg(feature = "parse")]
 static INGREDIENT_REGEX: once_cell::sync::Lazy<regex::Regex> = once_cell::sync::Lazy::new(|| {
     regex::Regex::new(
         r"^(?P<amount>[0-9¼½¾⅓⅔⅛⅜⅝⅞⅙⅚⅕⅖⅗⅘./]*)?\s*(x\s*)?((?P<unit>ml|millilitre|l|litre|tsp|teaspoon|tbsp|cup|kg|g|gram|oz|ounce|pinch of|pinch|handful of|handful|(small|large) pack) )?\s?(?P<rest>(?P<ingredient>[^,\n]*)((,\s*)(?P<instructions>.*))?)$",
     ).unwrap()
 });
 
-#[cfg(feature = "scraper")]
+#[cfg(feature = "parse")]
 impl FromStr for ScrapedIngredient {
     type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.trim().is_empty() {
+            return Err(Error::Empty);
+        }
         let Some(caps) = INGREDIENT_REGEX.captures(s) else {
-            return Err(Error::NoMatch(ScrapedIngredient {
-                raw: s.to_string(),
-                name: None,
-                amount: None,
-                unit: None,
-                instructions: None,
-            }));
+            return Err(Error::NoMatch(ScrapedIngredient::unparsed(
+                s,
+                "no regex match",
+            )));
         };
+        let mut problems = Vec::new();
+        let amount = caps
+            .name("amount")
+            .map(|m| m.as_str().trim())
+            .filter(|m| !m.is_empty())
+            .and_then(|m| match m.parse::<Amount>() {
+                Ok(amount) => Some(amount.as_f64()),
+                Err(e) => {
+                    problems.push(e.to_string());
+                    None
+                }
+            });
+        let name = caps
+            .name("ingredient")
+            .map(|m| m.as_str().trim().to_string())
+            .filter(|name| !name.is_empty());
+        if name.is_none() {
+            problems.push("no ingredient name found".to_string());
+        }
         Ok(ScrapedIngredient {
             raw: s.to_string(),
-            name: caps
-                .name("ingredient")
-                .map(|m| m.as_str().trim().to_string()),
-            amount: caps.name("amount").and_then(|m| {
-                m.as_str()
-                    .trim()
-                    .parse::<Amount>()
-                    .ok()
-                    // TODO: write `Amount` type with `as_f64` method
-                    // and proper `FromStr` impl which handles fractions (Unicode or with /)
-                    .map(|amount| amount.as_f64())
-            }),
+            name,
+            amount,
             unit: caps
                 .name("unit")
                 .and_then(|m| m.as_str().trim().parse::<Unit>().ok()),
             instructions: caps.name("instructions").map(|m| m.as_str().to_string()),
+            status: if problems.is_empty() {
+                ParseStatus::Parsed
+            } else {
+                ParseStatus::Partial(problems.join("; "))
+            },
         })
     }
 }
 
-#[cfg(feature = "scraper")]
-struct Amount(f64);
+#[cfg(feature = "parse")]
+impl ScrapedIngredient {
+    /// Parse an ingredient line without failing.
+    ///
+    /// The raw text is always kept, and anything the parser couldn't handle
+    /// is recorded in [`ScrapedIngredient::status`] instead of being returned
+    /// as an error.
+    pub fn parse_lenient(s: &str) -> Self {
+        match s.parse() {
+            Ok(ingredient) => ingredient,
+            Err(Error::NoMatch(ingredient)) => ingredient,
+            Err(e) => Self::unparsed(s, e.to_string()),
+        }
+    }
+}
 
-#[cfg(feature = "scraper")]
+#[cfg(feature = "parse")]
+pub(crate) struct Amount(f64);
+
+#[cfg(feature = "parse")]
 impl Amount {
-    fn as_f64(&self) -> f64 {
+    pub(crate) fn as_f64(&self) -> f64 {
         self.0
     }
 }
 
-#[cfg(feature = "scraper")]
+#[cfg(feature = "parse")]
 impl FromStr for Amount {
     type Err = Error;
 
@@ -465,6 +549,7 @@ mod tests {
                     amount: Some(0.5),
                     unit: Some(Unit::Volume(VolumeUnit::Cups)),
                     instructions: None,
+                    status: ParseStatus::Parsed,
                 },
             ),
             (
@@ -475,6 +560,7 @@ mod tests {
                     amount: Some(1.0),
                     unit: None,
                     instructions: Some("chopped".to_string()),
+                    status: ParseStatus::Parsed,
                 },
             ),
             (
@@ -485,6 +571,7 @@ mod tests {
                     amount: Some(200.0),
                     unit: Some(Unit::Mass(MassUnit::Grams)),
                     instructions: None,
+                    status: ParseStatus::Parsed,
                 },
             ),
             (
@@ -495,6 +582,7 @@ mod tests {
                     amount: Some(100.0),
                     unit: Some(Unit::Volume(VolumeUnit::Millilitres)),
                     instructions: None,
+                    status: ParseStatus::Parsed,
                 },
             ),
             (
@@ -505,6 +593,7 @@ mod tests {
                     amount: Some(400.0),
                     unit: Some(Unit::Mass(MassUnit::Grams)),
                     instructions: None,
+                    status: ParseStatus::Parsed,
                 },
             ),
             (
@@ -515,6 +604,7 @@ mod tests {
                     amount: Some(4.0),
                     unit: None,
                     instructions: Some("sliced".to_string()),
+                    status: ParseStatus::Parsed,
                 },
             ),
             (
@@ -525,6 +615,7 @@ mod tests {
                     amount: Some(125.0),
                     unit: Some(Unit::Mass(MassUnit::Grams)),
                     instructions: Some("chopped into chunks".to_string()),
+                    status: ParseStatus::Parsed,
                 },
             ),
             (
@@ -535,6 +626,7 @@ mod tests {
                     amount: Some(0.25),
                     unit: None,
                     instructions: Some("finely shredded".to_string()),
+                    status: ParseStatus::Parsed,
                 },
             ),
             (
@@ -546,6 +638,7 @@ mod tests {
                     amount: Some(0.8),
                     unit: Some(Unit::Mass(MassUnit::Kilograms)),
                     instructions: Some("shoulder or leg, cut into large chunks".to_string()),
+                    status: ParseStatus::Parsed,
                 },
             ),
             (
@@ -556,6 +649,7 @@ mod tests {
                     amount: None,
                     unit: None,
                     instructions: Some("to serve (optional)".to_string()),
+                    status: ParseStatus::Parsed,
                 },
             ),
         ];
@@ -576,6 +670,7 @@ mod tests {
                     amount: Some(0.5),
                     unit: Some(Unit::Volume(VolumeUnit::Cups)),
                     instructions: None,
+                    status: ParseStatus::Parsed,
                 },
                 "142ml sugar", // TODO: figure out how to convert this from volume to mass...
             ),
@@ -586,6 +681,7 @@ mod tests {
                     amount: Some(1.0),
                     unit: None,
                     instructions: Some("chopped".to_string()),
+                    status: ParseStatus::Parsed,
                 },
                 "1 tomato, chopped",
             ),
@@ -596,6 +692,7 @@ mod tests {
                     amount: Some(200.0),
                     unit: Some(Unit::Mass(MassUnit::Grams)),
                     instructions: None,
+                    status: ParseStatus::Parsed,
                 },
                 "200g cashews",
             ),
@@ -606,6 +703,7 @@ mod tests {
                     amount: Some(100.0),
                     unit: Some(Unit::Volume(VolumeUnit::Millilitres)),
                     instructions: None,
+                    status: ParseStatus::Parsed,
                 },
                 "100ml milk",
             ),
@@ -616,6 +714,7 @@ mod tests {
                     amount: Some(400.0),
                     unit: Some(Unit::Mass(MassUnit::Grams)),
                     instructions: None,
+                    status: ParseStatus::Parsed,
                 },
                 "400g rigatoni or penne",
             ),
@@ -626,6 +725,7 @@ mod tests {
                     amount: Some(4.0),
                     unit: None,
                     instructions: Some("sliced".to_string()),
+                    status: ParseStatus::Parsed,
                 },
                 "4 garlic cloves, sliced",
             ),
@@ -636,6 +736,7 @@ mod tests {
                     amount: Some(125.0),
                     unit: Some(Unit::Mass(MassUnit::Grams)),
                     instructions: Some("chopped into chunks".to_string()),
+                    status: ParseStatus::Parsed,
                 },
                 "125g ball mozzarella, chopped into chunks",
             ),
@@ -646,6 +747,7 @@ mod tests {
                     amount: Some(0.25),
                     unit: None,
                     instructions: Some("finely shredded".to_string()),
+                    status: ParseStatus::Parsed,
                 },
                 "¼ white cabbage, finely shredded",
             ),
@@ -657,6 +759,7 @@ mod tests {
                     amount: Some(0.8),
                     unit: Some(Unit::Mass(MassUnit::Kilograms)),
                     instructions: Some("shoulder or leg, cut into large chunks".to_string()),
+                    status: ParseStatus::Parsed,
                 },
                 "800g lamb, shoulder or leg, cut into large chunks",
             ),
@@ -667,6 +770,7 @@ mod tests {
                     amount: None,
                     unit: None,
                     instructions: Some("to serve (optional)".to_string()),
+                    status: ParseStatus::Parsed,
                 },
                 "tomato ketchup, to serve (optional)",
             ),
@@ -711,4 +815,40 @@ mod tests {
             assert_eq!(actual, *expected);
         }
     }
+
+    #[test]
+    fn parse_lenient() {
+        let cases = [
+            ("", ParseStatus::Failed("empty ingredient".to_string())),
+            (
+                "2",
+                ParseStatus::Partial("no ingredient name found".to_string()),
+            ),
+            (
+                "1/0 cup flour",
+                ParseStatus::Partial("parsing amount from 1/0".to_string()),
+            ),
+            ("200g cashews", ParseStatus::Parsed),
+        ];
+        for (input, expected) in cases.iter() {
+            let actual = ScrapedIngredient::parse_lenient(input);
+            assert_eq!(actual.raw, *input);
+            assert_eq!(actual.status, *expected);
+        }
+    }
+
+    #[test]
+    fn metric_conversions() {
+        let kg = Unit::Mass(MassUnit::Kilograms);
+        assert_eq!(kg.grams(0.5), Some(500.0));
+        assert_eq!(kg.millilitres(0.5), None);
+
+        let tbsp = Unit::Spoon(SpoonUnit::Tablespoons);
+        assert_eq!(tbsp.millilitres(2.0), Some(30.0));
+        assert_eq!(tbsp.grams(2.0), None);
+
+        let litres = Unit::Volume(VolumeUnit::Litres);
+        assert_eq!(litres.millilitres(1.5), Some(1500.0));
+        assert_eq!(Unit::Other("handful".to_string()).grams(1.0), None);
+    }
 }
@@ -1,13 +1,23 @@
+#[cfg(feature = "import")]
+pub mod import;
 mod ingredient;
 #[cfg(feature = "scraper")]
 mod scraper;
+#[cfg(feature = "parse")]
+mod step;
 
 use serde::{Deserialize, Serialize};
 use url::Url;
 
-pub use ingredient::ScrapedIngredient;
+use recipe_shared::{
+    Diet, IngredientInput, Nutrition, RecipeInput, TagInput, TagKind, ValidationErrors,
+};
+
+pub use ingredient::{MassUnit, ParseStatus, ScrapedIngredient, SpoonUnit, Unit, VolumeUnit};
 #[cfg(feature = "scraper")]
 pub use scraper::RecipeScraper;
+#[cfg(feature = "parse")]
+pub use step::{mentions_ingredient, step_timers, StepTimer};
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct ScrapedRecipe {
@@ -19,5 +29,242 @@ pub struct ScrapedRecipe {
     pub cooking_time_minutes: Option<u32>,
     pub servings: Option<u32>,
     pub ingredients: Vec<ScrapedIngredient>,
+    /// The method, one entry per step.
+    #[serde(default)]
+    pub steps: Vec<String>,
     pub image_url: Option<String>,
+    /// Classifications given by the source, e.g. "Dinner" or "Italian".
+    #[serde(default)]
+    pub categories: Vec<String>,
+    #[serde(default)]
+    pub cuisines: Vec<String>,
+    #[serde(default)]
+    pub keywords: Vec<String>,
+    /// schema.org `RestrictedDiet` values from `suitableForDiet`, e.g.
+    /// `https://schema.org/VeganDiet`.
+    #[serde(default)]
+    pub diets: Vec<String>,
+    #[serde(default)]
+    pub nutrition: Option<ScrapedNutrition>,
+    /// Problems encountered while scraping which didn't prevent the recipe
+    /// from being returned, such as ingredient lines that couldn't be parsed.
+    #[serde(default)]
+    pub warnings: Vec<String>,
+}
+
+/// Nutrition per serving as declared by the source.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ScrapedNutrition {
+    pub energy_kcal: Option<f64>,
+    pub fat_g: Option<f64>,
+    pub saturated_fat_g: Option<f64>,
+    pub carbohydrate_g: Option<f64>,
+    pub sugar_g: Option<f64>,
+    pub fibre_g: Option<f64>,
+    pub protein_g: Option<f64>,
+    pub salt_g: Option<f64>,
+}
+
+impl From<ScrapedNutrition> for Nutrition {
+    fn from(n: ScrapedNutrition) -> Self {
+        Self {
+            energy_kcal: n.energy_kcal,
+            fat_g: n.fat_g,
+            saturated_fat_g: n.saturated_fat_g,
+            carbohydrate_g: n.carbohydrate_g,
+            sugar_g: n.sugar_g,
+            fibre_g: n.fibre_g,
+            protein_g: n.protein_g,
+            salt_g: n.salt_g,
+        }
+    }
+}
+
+impl ScrapedIngredient {
+    /// Convert into the input for saving, failing if the line couldn't be
+    /// understood well enough to find the ingredient's name.
+    pub fn to_input(&self) -> Result<IngredientInput, String> {
+        if let ParseStatus::Failed(message) = &self.status {
+            return Err(message.clone());
+        }
+        let name = self
+            .name
+            .clone()
+            .filter(|n| !n.is_empty())
+            .ok_or_else(|| "couldn't find an ingredient name".to_string())?;
+        Ok(IngredientInput {
+            name,
+            quantity: self.amount,
+            unit: self.unit.as_ref().map(|u| u.to_string()),
+            notes: self.instructions.clone(),
+        })
+    }
+}
+
+impl ScrapedRecipe {
+    /// The categories, cuisines and keywords the source gave the recipe, as
+    /// tags.
+    pub fn tags(&self) -> Vec<TagInput> {
+        let kinds = [
+            (TagKind::Category, &self.categories),
+            (TagKind::Cuisine, &self.cuisines),
+            (TagKind::Tag, &self.keywords),
+        ];
+        let mut tags: Vec<TagInput> = Vec::new();
+        for (kind, names) in kinds {
+            for name in names {
+                let tag = TagInput::new(kind, name.trim());
+                if !tag.name.is_empty() && !tags.contains(&tag) {
+                    tags.push(tag);
+                }
+            }
+        }
+        tags
+    }
+
+    /// The input for saving the recipe with `ingredients`, which may have
+    /// been corrected since the recipe was scraped.
+    pub fn recipe_input(&self, ingredients: Vec<IngredientInput>) -> RecipeInput {
+        RecipeInput {
+            name: self.name.trim().to_string(),
+            source: Some(self.source.to_string()),
+            notes: self.notes.clone().or_else(|| self.description.clone()),
+            image_url: self.image_url.clone(),
+            prep_time_minutes: self.prep_time_minutes.map(|m| m as i32),
+            cooking_time_minutes: self.cooking_time_minutes.map(|m| m as i32),
+            servings: self.servings.map(|s| s as i32),
+            ingredients,
+            steps: self.steps.clone(),
+            tags: self.tags(),
+            diets: self
+                .diets
+                .iter()
+                .filter_map(|d| Diet::from_schema_org(d))
+                .collect(),
+            nutrition: self.nutrition.clone().map(Nutrition::from),
+            shared: false,
+        }
+    }
+
+    /// The input for saving the recipe with its ingredients as scraped,
+    /// along with every problem preventing it from being saved.
+    pub fn to_input(&self) -> Result<RecipeInput, ValidationErrors> {
+        let mut errors = ValidationErrors::default();
+        let mut ingredients = Vec::with_capacity(self.ingredients.len());
+        for (i, ingredient) in self.ingredients.iter().enumerate() {
+            match ingredient.to_input() {
+                Ok(ingredient) => ingredients.push(ingredient),
+                Err(e) => errors.add(&format!("ingredients.{i}"), e),
+            }
+        }
+        let input = self.recipe_input(ingredients);
+        if let Err(e) = input.validate() {
+            errors.merge(e);
+        }
+        if errors.is_empty() {
+            Ok(input)
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Like [`ScrapedRecipe::to_input`], but ingredient lines which couldn't
+    /// be understood are added to the notes rather than preventing the recipe
+    /// from being saved. Those lines are returned along with the input.
+    pub fn to_input_lenient(&self) -> Result<(RecipeInput, Vec<&str>), ValidationErrors> {
+        let mut ingredients = Vec::with_capacity(self.ingredients.len());
+        let mut unparsed = Vec::new();
+        for ingredient in &self.ingredients {
+            match ingredient.to_input() {
+                Ok(ingredient) => ingredients.push(ingredient),
+                Err(_) => unparsed.push(ingredient.raw.trim()),
+            }
+        }
+        let mut input = self.recipe_input(ingredients);
+        if !unparsed.is_empty() {
+            let mut notes = input.notes.take().unwrap_or_default();
+            if !notes.is_empty() {
+                notes.push_str("\n\n");
+            }
+            notes.push_str("Ingredients which couldn't be understood:");
+            for line in &unparsed {
+                notes.push_str("\n- ");
+                notes.push_str(line);
+            }
+            input.notes = Some(notes);
+        }
+        input.validate()?;
+        Ok((input, unparsed))
+    }
+
+    /// Build warnings for any ingredients which weren't fully parsed.
+    pub fn ingredient_warnings(ingredients: &[ScrapedIngredient]) -> Vec<String> {
+        ingredients
+            .iter()
+            .enumerate()
+            .filter_map(|(i, ingredient)| {
+                ingredient.status.message().map(|message| {
+                    format!("ingredient {} ({:?}): {}", i + 1, ingredient.raw, message)
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn recipe(notes: Option<&str>, ingredients: Vec<ScrapedIngredient>) -> ScrapedRecipe {
+        ScrapedRecipe {
+            name: "Pancakes".to_string(),
+            source: Url::parse("https://example.com/pancakes").unwrap(),
+            description: None,
+            notes: notes.map(str::to_string),
+            prep_time_minutes: None,
+            cooking_time_minutes: None,
+            servings: None,
+            ingredients,
+            steps: vec!["Mix and fry.".to_string()],
+            image_url: None,
+            categories: Vec::new(),
+            cuisines: Vec::new(),
+            keywords: Vec::new(),
+            diets: Vec::new(),
+            nutrition: None,
+            warnings: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn unparsed_ingredients_are_kept_in_the_notes() {
+        let flour = ScrapedIngredient {
+            name: Some("flour".to_string()),
+            amount: Some(100.0),
+            unit: Some(Unit::Mass(MassUnit::Grams)),
+            status: ParseStatus::Parsed,
+            ..ScrapedIngredient::unparsed("100g flour", "")
+        };
+        let recipe = recipe(
+            Some("Best on a Tuesday."),
+            vec![
+                flour,
+                ScrapedIngredient::unparsed(" a splash of milk ", "no match"),
+            ],
+        );
+        assert!(recipe.to_input().is_err());
+
+        let (input, unparsed) = recipe.to_input_lenient().unwrap();
+        assert_eq!(unparsed, ["a splash of milk"]);
+        assert_eq!(input.ingredients.len(), 1);
+        assert_eq!(input.ingredients[0].name, "flour");
+        assert_eq!(
+            input.notes.as_deref(),
+            Some("Best on a Tuesday.\n\nIngredients which couldn't be understood:\n- a splash of milk")
+        );
+
+        let (input, unparsed) = recipe(None, Vec::new()).to_input_lenient().unwrap();
+        assert!(unparsed.is_empty());
+        assert_eq!(input.notes, None);
+    }
 }
@@ -10,4 +10,7 @@ async fn main() {
     let scraper = recipe_scrape::RecipeScraper::new();
     let recipe = scraper.scrape(url).await.unwrap();
     println!("{:#?}", recipe);
+    for warning in &recipe.warnings {
+        eprintln!("warning: {}", warning);
+    }
 }
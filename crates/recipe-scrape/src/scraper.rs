@@ -6,7 +6,7 @@ use serde::de::Error as DeError;
 
 mod bbc_good_food;
 
-use crate::{ingredient, ScrapedRecipe};
+use crate::ScrapedRecipe;
 
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
@@ -18,10 +18,10 @@ pub enum Error {
     NotARecipe,
     #[error("unsupported host {0}")]
     UnrecognisedHost(String),
+    #[error("{0} isn't a web page; only http and https URLs can be scraped")]
+    NotAWebPage(String),
     #[error("JSON error: {0}")]
     Json(#[from] serde_json::Error),
-    #[error("parsing ingredients: {0}")]
-    Ingredient(#[from] ingredient::Error),
 }
 
 #[derive(Clone, Debug)]
@@ -30,7 +30,7 @@ pub struct RecipeScraper {
 }
 
 impl RecipeScraper {
-    const USER_AGENT: &'static str = concat!(
+    pub const DEFAULT_USER_AGENT: &'static str = concat!(
         env!("CARGO_PKG_NAME"),
         "/",
         env!("CARGO_PKG_VERSION"),
@@ -38,27 +38,58 @@ impl RecipeScraper {
         env!("CARGO_PKG_REPOSITORY"),
         ")"
     );
+    pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
 
     pub fn new() -> Self {
+        Self::with_options(Self::DEFAULT_TIMEOUT, Self::DEFAULT_USER_AGENT)
+            .expect("failed to build reqwest client; check host TLS config")
+    }
+
+    /// A scraper which gives up on pages after `timeout`, identifying itself
+    /// as `user_agent`.
+    pub fn with_options(timeout: Duration, user_agent: &str) -> Result<Self, Error> {
         let client = reqwest::Client::builder()
-            .user_agent(Self::USER_AGENT)
-            .timeout(Duration::from_secs(10));
-        Self::with_client(
-            client
-                .build()
-                .expect("failed to build reqwest client; check host TLS config"),
-        )
+            .user_agent(user_agent)
+            .timeout(timeout)
+            .build()?;
+        Ok(Self::with_client(client))
     }
 
     pub fn with_client(client: reqwest::Client) -> Self {
         Self { client }
     }
 
+    /// The host of `url`, if it's a web page which could be scraped.
+    pub fn web_page_host(url: &Url) -> Result<&str, Error> {
+        match url.host_str() {
+            Some(host) if matches!(url.scheme(), "http" | "https") => Ok(host),
+            _ => Err(Error::NotAWebPage(url.to_string())),
+        }
+    }
+
+    /// Scrape the recipe from the page at `url`, counting successes and
+    /// failures per host in the `recipe_scrapes_total` metric.
     pub async fn scrape(&self, url: Url) -> Result<ScrapedRecipe, Error> {
-        let host = url.host_str().expect("fetched URL to have valid host");
-        let scraper = SCRAPERS
-            .get(host)
-            .ok_or(Error::UnrecognisedHost(host.to_string()))?;
+        let host = Self::web_page_host(&url)?;
+        // Hosts which can't be scraped are lumped together, so that the
+        // metric can't grow without bound.
+        let Some(scraper) = SCRAPERS.get(host).copied() else {
+            metrics::counter!("recipe_scrapes_total", "host" => "other", "outcome" => "failure")
+                .increment(1);
+            return Err(Error::UnrecognisedHost(host.to_string()));
+        };
+        let result = self.scrape_with(scraper, url).await;
+        let outcome = if result.is_ok() { "success" } else { "failure" };
+        metrics::counter!("recipe_scrapes_total", "host" => scraper.host(), "outcome" => outcome)
+            .increment(1);
+        result
+    }
+
+    async fn scrape_with(
+        &self,
+        scraper: &'static dyn Scraper,
+        url: Url,
+    ) -> Result<ScrapedRecipe, Error> {
         let response = self
             .client
             .get(url.clone())
@@ -112,17 +143,26 @@ pub static SCRAPERS: Lazy<HashMap<&'static str, &'static dyn Scraper>> = Lazy::n
 mod tests {
     use reqwest::Url;
 
-    use super::RecipeScraper;
+    use super::{Error, RecipeScraper};
 
     #[test]
     fn user_agent() {
         // TODO: this will fail once we bump the version of the lib.
         assert_eq!(
-            RecipeScraper::USER_AGENT,
+            RecipeScraper::DEFAULT_USER_AGENT,
             "recipe-scrape/0.1.0 (https://github.com/sd2k/recipes)"
         );
     }
 
+    #[tokio::test]
+    async fn only_web_pages_are_scraped() {
+        for url in ["data:text/html,<p>Pancakes</p>", "file:///etc/passwd"] {
+            let url: Url = url.parse().unwrap();
+            let result = RecipeScraper::new().scrape(url).await;
+            assert!(matches!(result, Err(Error::NotAWebPage(_))), "{result:?}");
+        }
+    }
+
     #[tokio::test]
     async fn scrape_bbc_good_food() {
         let url: Url = "https://www.bbcgoodfood.com/recipes/sausage-pasta-bake"
@@ -6,7 +6,7 @@ use serde::{Deserialize, Serialize};
 
 use crate::{
     scraper::{Error, Scraper},
-    ScrapedRecipe,
+    ScrapedIngredient, ScrapedNutrition, ScrapedRecipe,
 };
 
 pub struct BBCGoodFoodScraper;
@@ -48,6 +48,99 @@ impl Yield {
     }
 }
 
+/// A schema.org text property which may be given as a single
+/// comma-separated string or as a list.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(untagged)]
+enum StringOrList {
+    String(String),
+    List(Vec<String>),
+}
+
+impl Default for StringOrList {
+    fn default() -> Self {
+        Self::List(Vec::new())
+    }
+}
+
+impl StringOrList {
+    fn into_vec(self) -> Vec<String> {
+        let items = match self {
+            Self::String(s) => s.split(',').map(str::to_string).collect(),
+            Self::List(items) => items,
+        };
+        items
+            .into_iter()
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+            .collect()
+    }
+}
+
+/// Flatten a schema.org `recipeInstructions` value, which may be text, a list
+/// of `HowToStep`s or `HowToSection`s holding steps, into the text of each
+/// step.
+fn instructions(value: &serde_json::Value) -> Vec<String> {
+    use serde_json::Value;
+    match value {
+        Value::String(text) => text
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(str::to_string)
+            .collect(),
+        Value::Array(items) => items.iter().flat_map(instructions).collect(),
+        Value::Object(object) => match object.get("itemListElement") {
+            Some(steps) => instructions(steps),
+            None => object.get("text").map(instructions).unwrap_or_default(),
+        },
+        _ => Vec::new(),
+    }
+}
+
+static NUTRIENT_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?P<amount>\d+(\.\d+)?)\s*(?P<unit>mg|g|kcal)?").unwrap());
+
+/// A schema.org `NutritionInformation`, whose values are text like "12g".
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Nutrition {
+    calories: Option<String>,
+    fat_content: Option<String>,
+    saturated_fat_content: Option<String>,
+    carbohydrate_content: Option<String>,
+    sugar_content: Option<String>,
+    fiber_content: Option<String>,
+    protein_content: Option<String>,
+    sodium_content: Option<String>,
+}
+
+/// Parse a nutrient amount into kilocalories or grams.
+fn nutrient_amount(value: &Option<String>) -> Option<f64> {
+    let captures = NUTRIENT_REGEX.captures(value.as_deref()?)?;
+    let amount: f64 = captures.name("amount")?.as_str().parse().ok()?;
+    match captures.name("unit").map(|u| u.as_str()) {
+        Some("mg") => Some(amount / 1000.0),
+        _ => Some(amount),
+    }
+}
+
+impl From<Nutrition> for ScrapedNutrition {
+    fn from(nutrition: Nutrition) -> Self {
+        Self {
+            energy_kcal: nutrient_amount(&nutrition.calories),
+            fat_g: nutrient_amount(&nutrition.fat_content),
+            saturated_fat_g: nutrient_amount(&nutrition.saturated_fat_content),
+            carbohydrate_g: nutrient_amount(&nutrition.carbohydrate_content),
+            sugar_g: nutrient_amount(&nutrition.sugar_content),
+            fibre_g: nutrient_amount(&nutrition.fiber_content),
+            protein_g: nutrient_amount(&nutrition.protein_content),
+            // Salt is roughly two and a half times the weight of its sodium.
+            salt_g: nutrient_amount(&nutrition.sodium_content).map(|sodium| sodium * 2.5),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 struct Image {
@@ -64,7 +157,18 @@ pub struct BBCGoodFoodRecipe {
     recipe_yield: Yield,
     #[serde(alias = "recipeIngredient")]
     ingredients: Vec<String>,
+    #[serde(default)]
+    recipe_instructions: serde_json::Value,
     image: Option<Image>,
+    #[serde(default)]
+    recipe_category: StringOrList,
+    #[serde(default)]
+    recipe_cuisine: StringOrList,
+    #[serde(default)]
+    keywords: StringOrList,
+    #[serde(default)]
+    suitable_for_diet: StringOrList,
+    nutrition: Option<Nutrition>,
 }
 
 impl Scraper for BBCGoodFoodScraper {
@@ -74,6 +178,11 @@ impl Scraper for BBCGoodFoodScraper {
 
     fn scrape(&self, url: Url, value: serde_json::Value) -> Result<ScrapedRecipe, Error> {
         let recipe: BBCGoodFoodRecipe = serde_json::from_value(value)?;
+        let ingredients: Vec<_> = recipe
+            .ingredients
+            .iter()
+            .map(|x| ScrapedIngredient::parse_lenient(x))
+            .collect();
         Ok(ScrapedRecipe {
             name: recipe.name,
             description: Some(recipe.description),
@@ -82,12 +191,15 @@ impl Scraper for BBCGoodFoodScraper {
             prep_time_minutes: recipe.prep_time.num_minutes().map(|x| x.ceil() as u32),
             cooking_time_minutes: recipe.cook_time.num_minutes().map(|x| x.ceil() as u32),
             servings: recipe.recipe_yield.as_u32(),
-            ingredients: recipe
-                .ingredients
-                .into_iter()
-                .map(|x| x.parse())
-                .collect::<Result<Vec<_>, _>>()?,
+            warnings: ScrapedRecipe::ingredient_warnings(&ingredients),
+            ingredients,
+            steps: instructions(&recipe.recipe_instructions),
             image_url: recipe.image.map(|x| x.url),
+            categories: recipe.recipe_category.into_vec(),
+            cuisines: recipe.recipe_cuisine.into_vec(),
+            keywords: recipe.keywords.into_vec(),
+            diets: recipe.suitable_for_diet.into_vec(),
+            nutrition: recipe.nutrition.map(ScrapedNutrition::from),
         })
     }
 }
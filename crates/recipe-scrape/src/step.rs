@@ -0,0 +1,112 @@
+//! Reading a step of a recipe's method for the things worth acting on while
+//! cooking: how long to wait, and which ingredients it uses.
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+/// A length of time mentioned in a step, e.g. "simmer for 20 mins".
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StepTimer {
+    /// The time as written in the step, e.g. "20-25 mins".
+    pub label: String,
+    /// How long the timer runs for. For a range this is the shorter end, so
+    /// there's time to check before it's overdone.
+    pub seconds: u32,
+}
+
+static DURATION_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"(?xi)
+        \b(?P<amount>\d+(?:\.\d+)?|an?)
+        (?:\s*(?:-|–|to)\s*\d+(?:\.\d+)?)?
+        [\s-]*(?P<unit>hours?|hrs?|minutes?|mins?|seconds?|secs?)\b
+        (?:,?\s*(?:and\s+)?(?P<extra>\d+)\s*(?P<extra_unit>minutes?|mins?|seconds?|secs?)\b)?
+        ",
+    )
+    .unwrap()
+});
+
+/// The number of seconds in one of `unit`, which is one of the units matched
+/// by [`DURATION_REGEX`].
+fn unit_seconds(unit: &str) -> f64 {
+    match unit.as_bytes()[0].to_ascii_lowercase() {
+        b'h' => 3600.0,
+        b'm' => 60.0,
+        _ => 1.0,
+    }
+}
+
+/// Every length of time mentioned in `step`, in the order they appear.
+pub fn step_timers(step: &str) -> Vec<StepTimer> {
+    DURATION_REGEX
+        .captures_iter(step)
+        .filter_map(|captures| {
+            let amount = match &captures["amount"] {
+                a if a.eq_ignore_ascii_case("a") || a.eq_ignore_ascii_case("an") => 1.0,
+                a => a.parse::<f64>().ok()?,
+            };
+            let mut seconds = amount * unit_seconds(&captures["unit"]);
+            if let (Some(extra), Some(unit)) = (captures.name("extra"), captures.name("extra_unit"))
+            {
+                seconds += extra.as_str().parse::<f64>().ok()? * unit_seconds(unit.as_str());
+            }
+            (seconds >= 1.0).then(|| StepTimer {
+                label: captures[0].trim().to_string(),
+                seconds: seconds.round() as u32,
+            })
+        })
+        .collect()
+}
+
+/// Whether `step` seems to use the ingredient called `name`: either its
+/// whole name, or for names of several words the last one, so "red onion"
+/// is found in "fry the onion".
+pub fn mentions_ingredient(step: &str, name: &str) -> bool {
+    let step = step.to_lowercase();
+    let name = name.trim().to_lowercase();
+    let last = name.split_whitespace().last().unwrap_or_default();
+    let words = step
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>();
+    let contains_word = |word: &str| {
+        !word.is_empty()
+            && words
+                .windows(word.split_whitespace().count())
+                .any(|window| window.join(" ") == word)
+    };
+    contains_word(&name) || (last.len() > 2 && contains_word(last))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn seconds(step: &str) -> Vec<u32> {
+        step_timers(step).into_iter().map(|t| t.seconds).collect()
+    }
+
+    #[test]
+    fn finds_timers() {
+        assert_eq!(seconds("Simmer for 20 mins until thick."), [1200]);
+        assert_eq!(seconds("Bake for 1 hr 15 mins"), [4500]);
+        assert_eq!(seconds("Roast for 1 hour and 10 minutes."), [4200]);
+        assert_eq!(seconds("Leave to prove for an hour"), [3600]);
+        assert_eq!(seconds("Rest for 1.5 hours"), [5400]);
+        assert_eq!(seconds("Fry for 30 seconds, then 2-3 mins more"), [30, 120]);
+        assert_eq!(seconds("Heat the oven to 200C, gas 6."), Vec::<u32>::new());
+        assert_eq!(
+            step_timers("Cook for 20-25 minutes")[0].label,
+            "20-25 minutes"
+        );
+    }
+
+    #[test]
+    fn finds_ingredients() {
+        assert!(mentions_ingredient("Fry the onion until soft", "red onion"));
+        assert!(mentions_ingredient("Add the olive oil", "Olive oil"));
+        assert!(!mentions_ingredient("Add the onions", "onion"));
+        assert!(!mentions_ingredient("Stir in the peas", "pea shoots"));
+        assert!(!mentions_ingredient("Season well", ""));
+    }
+}
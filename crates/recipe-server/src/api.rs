@@ -0,0 +1,357 @@
+//! A versioned JSON API for programmatic access to recipes and meal plans,
+//! served under `/api/v1` alongside the Dioxus server functions.
+//!
+//! Requests are made on behalf of the user whose session token is given,
+//! either in the session cookie or as a bearer token, and anonymously
+//! otherwise. The OpenAPI document is served at `/api/v1/openapi.json`.
+
+use axum::{
+    async_trait,
+    extract::FromRequestParts,
+    http::{header, request::Parts, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    routing::{delete, get, post},
+    Json, Router,
+};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, OpenApi, ToSchema};
+
+use recipe_app::server::AppState;
+use recipe_repository::{DieselRepository, UserRepository};
+use recipe_shared::{
+    Changeset, Credentials, Diet, DietInfo, DietaryFlag, Ingredient, IngredientInput, MealPlan,
+    MealPlanDay, MealPlanDetail, MealPlanEntry, MealPlanInput, Measurement, Nutrition,
+    NutritionSource, PageRequest, Recipe, RecipeDetail, RecipeIngredient, RecipeInput,
+    RecipeNutrition, RecipeSort, RecipeStep, ShoppingList, ShoppingListItem, SyncDeletion,
+    SyncKind, SyncMealPlan, SyncMealPlanEntry, SyncRecipe, SyncRejection, SyncRequest,
+    SyncResponse, Tag, TagInput, TagKind, ValidationErrors,
+};
+
+use crate::session_token;
+
+pub(crate) mod health;
+mod ingredients;
+mod meal_plans;
+mod pdf;
+mod recipes;
+mod scrape;
+mod sync;
+
+/// The number of items in a page when the request doesn't say.
+const DEFAULT_PER_PAGE: usize = 50;
+/// The most items a single page can hold.
+const MAX_PER_PAGE: usize = 200;
+
+#[derive(OpenApi)]
+#[openapi(
+    info(title = "Recipes API", description = "Recipes, ingredients and meal plans."),
+    paths(
+        recipes::list,
+        recipes::create,
+        recipes::get,
+        recipes::update,
+        recipes::delete,
+        recipes::export,
+        pdf::recipe,
+        pdf::cookbook,
+        ingredients::list,
+        ingredients::list_measurements,
+        meal_plans::list,
+        meal_plans::create,
+        meal_plans::get,
+        meal_plans::update,
+        meal_plans::delete,
+        meal_plans::add_entry,
+        meal_plans::remove_entry,
+        meal_plans::shopping_list,
+        pdf::meal_plan,
+        scrape::scrape,
+        sync::sign_in,
+        sync::sync,
+        health::health,
+    ),
+    components(schemas(
+        ErrorBody,
+        RecipePage,
+        IngredientPage,
+        MeasurementPage,
+        MealPlanPage,
+        Recipe,
+        RecipeDetail,
+        RecipeInput,
+        RecipeIngredient,
+        RecipeStep,
+        RecipeNutrition,
+        RecipeSort,
+        IngredientInput,
+        Ingredient,
+        Measurement,
+        Tag,
+        TagInput,
+        TagKind,
+        Diet,
+        DietInfo,
+        DietaryFlag,
+        Nutrition,
+        NutritionSource,
+        MealPlan,
+        MealPlanDay,
+        MealPlanDetail,
+        MealPlanEntry,
+        MealPlanInput,
+        ShoppingList,
+        ShoppingListItem,
+        meal_plans::NewEntry,
+        scrape::ScrapeRequest,
+        sync::Session,
+        Credentials,
+        Changeset,
+        SyncRecipe,
+        SyncMealPlan,
+        SyncMealPlanEntry,
+        SyncDeletion,
+        SyncKind,
+        SyncRejection,
+        SyncRequest,
+        SyncResponse,
+        ValidationErrors,
+        health::Health,
+    )),
+    tags(
+        (name = "recipes"),
+        (name = "ingredients"),
+        (name = "meal plans"),
+        (name = "sync"),
+        (name = "health"),
+    )
+)]
+pub struct ApiDoc;
+
+pub fn router(state: AppState) -> Router {
+    Router::new()
+        .route("/openapi.json", get(openapi))
+        .route("/recipes", get(recipes::list).post(recipes::create))
+        .route(
+            "/recipes/:slug",
+            get(recipes::get)
+                .put(recipes::update)
+                .delete(recipes::delete),
+        )
+        .route("/recipes/:slug/export/:format", get(recipes::export))
+        .route("/recipes/:slug/pdf", get(pdf::recipe))
+        .route("/cookbook", get(pdf::cookbook))
+        .route("/ingredients", get(ingredients::list))
+        .route("/measurements", get(ingredients::list_measurements))
+        .route(
+            "/meal-plans",
+            get(meal_plans::list).post(meal_plans::create),
+        )
+        .route(
+            "/meal-plans/:slug",
+            get(meal_plans::get)
+                .put(meal_plans::update)
+                .delete(meal_plans::delete),
+        )
+        .route("/meal-plans/:slug/pdf", get(pdf::meal_plan))
+        .route(
+            "/meal-plans/:slug/shopping-list",
+            get(meal_plans::shopping_list),
+        )
+        .route("/meal-plans/:slug/entries", post(meal_plans::add_entry))
+        .route(
+            "/meal-plans/:slug/entries/:id",
+            delete(meal_plans::remove_entry),
+        )
+        .route("/scrape", post(scrape::scrape))
+        .route("/sessions", post(sync::sign_in))
+        .route("/sync", post(sync::sync))
+        .route("/health", get(health::health))
+        .with_state(state)
+}
+
+async fn openapi() -> Json<utoipa::openapi::OpenApi> {
+    Json(ApiDoc::openapi())
+}
+
+/// The repository, acting on behalf of whoever made the request.
+pub struct Repo(pub DieselRepository);
+
+#[async_trait]
+impl FromRequestParts<AppState> for Repo {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &AppState) -> Result<Self, ApiError> {
+        let token = bearer_token(&parts.headers).or_else(|| session_token(&parts.headers));
+        let user = match token {
+            Some(token) => state.repo.session_user(&token).await?,
+            None => None,
+        };
+        Ok(Self(state.repo.for_user(user.map(|u| u.id.into()))))
+    }
+}
+
+fn bearer_token(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(|token| token.trim().to_string())
+}
+
+/// Which page of a list to return.
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct Pagination {
+    /// The page to return, starting from 1.
+    page: Option<usize>,
+    /// How many items to return per page, at most 200.
+    per_page: Option<usize>,
+}
+
+impl Pagination {
+    fn page(&self) -> usize {
+        self.page.unwrap_or(1).max(1)
+    }
+
+    fn per_page(&self) -> usize {
+        self.per_page
+            .unwrap_or(DEFAULT_PER_PAGE)
+            .clamp(1, MAX_PER_PAGE)
+    }
+
+    /// The window of items to load, for lists paged by the repository. Pages
+    /// too far along to count to are past the end of any list.
+    fn request(&self) -> PageRequest {
+        PageRequest {
+            offset: (self.page() - 1).saturating_mul(self.per_page()),
+            limit: self.per_page(),
+        }
+    }
+
+    /// Take the requested page from a complete list.
+    fn apply<T>(&self, items: Vec<T>) -> Page<T> {
+        let PageRequest { offset, limit } = self.request();
+        let total = items.len();
+        let items = items.into_iter().skip(offset).take(limit).collect();
+        Page {
+            items,
+            page: self.page(),
+            per_page: self.per_page(),
+            total,
+        }
+    }
+}
+
+/// One page of a longer list.
+#[derive(Debug, Serialize, ToSchema)]
+#[aliases(
+    RecipePage = Page<Recipe>,
+    IngredientPage = Page<Ingredient>,
+    MeasurementPage = Page<Measurement>,
+    MealPlanPage = Page<MealPlan>,
+)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub page: usize,
+    pub per_page: usize,
+    /// The number of items across all pages.
+    pub total: usize,
+}
+
+/// The body of every error response.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ErrorBody {
+    /// A machine readable description of the error, such as `not_found`.
+    pub code: &'static str,
+    pub message: String,
+    /// The problems with each field of the input, for `invalid_input` errors.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fields: Option<ValidationErrors>,
+}
+
+#[derive(Debug)]
+pub enum ApiError {
+    Repository(recipe_repository::Error),
+    BadRequest(String),
+    Upstream(String),
+}
+
+impl From<recipe_repository::Error> for ApiError {
+    fn from(e: recipe_repository::Error) -> Self {
+        Self::Repository(e)
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        use recipe_repository::Error;
+
+        let (status, code, fields) = match &self {
+            Self::Repository(Error::Validation(errors)) => (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                "invalid_input",
+                Some(errors.clone()),
+            ),
+            Self::Repository(Error::NotFound) => (StatusCode::NOT_FOUND, "not_found", None),
+            Self::Repository(Error::Unauthenticated) => {
+                (StatusCode::UNAUTHORIZED, "unauthenticated", None)
+            }
+            Self::Repository(Error::Forbidden) => (StatusCode::FORBIDDEN, "forbidden", None),
+            Self::Repository(e) if e.is_conflict() => (StatusCode::CONFLICT, "conflict", None),
+            Self::Repository(e) => {
+                tracing::error!(error = ?e, "API request failed");
+                (StatusCode::INTERNAL_SERVER_ERROR, "internal", None)
+            }
+            Self::BadRequest(_) => (StatusCode::BAD_REQUEST, "bad_request", None),
+            Self::Upstream(_) => (StatusCode::BAD_GATEWAY, "upstream", None),
+        };
+        let message = match self {
+            Self::Repository(e) => e.to_string(),
+            Self::BadRequest(message) | Self::Upstream(message) => message,
+        };
+        let body = ErrorBody {
+            code,
+            message,
+            fields,
+        };
+        (status, Json(body)).into_response()
+    }
+}
+
+pub type ApiResult<T> = Result<T, ApiError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pagination(page: usize, per_page: usize) -> Pagination {
+        Pagination {
+            page: Some(page),
+            per_page: Some(per_page),
+        }
+    }
+
+    #[test]
+    fn pages_are_windows_of_the_list() {
+        let page = pagination(2, 2).apply(vec![1, 2, 3, 4, 5]);
+        assert_eq!(page.items, [3, 4]);
+        assert_eq!(page.total, 5);
+        assert_eq!(
+            pagination(0, 0).request(),
+            PageRequest {
+                offset: 0,
+                limit: 1
+            }
+        );
+    }
+
+    #[test]
+    fn huge_pages_are_past_the_end() {
+        let request = pagination(usize::MAX, MAX_PER_PAGE).request();
+        assert_eq!(request.offset, usize::MAX);
+        assert!(pagination(usize::MAX, 10)
+            .apply(vec![1, 2, 3])
+            .items
+            .is_empty());
+    }
+}
@@ -0,0 +1,52 @@
+use axum::{extract::State, http::StatusCode, Json};
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use recipe_app::server::AppState;
+
+/// Whether the server's database is ready for it.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct Health {
+    /// `ok`, `migrations_pending` if the database needs migrating, or
+    /// `database_unreachable` if it can't be connected to.
+    pub status: &'static str,
+    /// The migrations which haven't been run, oldest first.
+    pub pending_migrations: Vec<String>,
+}
+
+/// Check that the database can be reached and is fully migrated. This is
+/// also served at `/readyz` for probes.
+#[utoipa::path(
+    get,
+    path = "/api/v1/health",
+    tag = "health",
+    responses(
+        (status = 200, body = Health),
+        (status = 503, body = Health, description = "The database can't be reached or needs migrating"),
+    )
+)]
+pub async fn health(State(state): State<AppState>) -> (StatusCode, Json<Health>) {
+    let (code, status, pending_migrations) = match state.repo.pending_migrations().await {
+        Ok(pending) if pending.is_empty() => (StatusCode::OK, "ok", pending),
+        Ok(pending) => (
+            StatusCode::SERVICE_UNAVAILABLE,
+            "migrations_pending",
+            pending,
+        ),
+        Err(e) => {
+            tracing::warn!(error = ?e, "database unreachable");
+            (
+                StatusCode::SERVICE_UNAVAILABLE,
+                "database_unreachable",
+                Vec::new(),
+            )
+        }
+    };
+    (
+        code,
+        Json(Health {
+            status,
+            pending_migrations,
+        }),
+    )
+}
@@ -0,0 +1,36 @@
+use axum::{extract::Query, Json};
+
+use recipe_repository::Repository;
+use recipe_shared::{Ingredient, Measurement};
+
+use super::{ApiResult, IngredientPage, MeasurementPage, Pagination, Repo};
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/ingredients",
+    tag = "ingredients",
+    params(Pagination),
+    responses((status = 200, body = IngredientPage))
+)]
+pub async fn list(
+    Repo(repo): Repo,
+    Query(pagination): Query<Pagination>,
+) -> ApiResult<Json<IngredientPage>> {
+    let ingredients = Repository::<Ingredient>::list(&repo).await?;
+    Ok(Json(pagination.apply(ingredients)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/measurements",
+    tag = "ingredients",
+    params(Pagination),
+    responses((status = 200, body = MeasurementPage))
+)]
+pub async fn list_measurements(
+    Repo(repo): Repo,
+    Query(pagination): Query<Pagination>,
+) -> ApiResult<Json<MeasurementPage>> {
+    let measurements = Repository::<Measurement>::list(&repo).await?;
+    Ok(Json(pagination.apply(measurements)))
+}
@@ -0,0 +1,210 @@
+use axum::{
+    extract::{Path, Query},
+    http::StatusCode,
+    Json,
+};
+use chrono::NaiveDate;
+use serde::Deserialize;
+use utoipa::ToSchema;
+
+use recipe_repository::{
+    BySlug, DeleteRepository, Error, MealPlanRepository, Repository, WriteRepository,
+};
+use recipe_shared::{MealPlan, MealPlanDetail, MealPlanInput, RecipeDetail, ShoppingList};
+
+use super::{ApiResult, ErrorBody, MealPlanPage, Pagination, Repo};
+
+/// A recipe to schedule on a day of a meal plan.
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct NewEntry {
+    pub recipe_id: i64,
+    pub day: NaiveDate,
+}
+
+/// List the meal plans visible to the current user, most recent first.
+#[utoipa::path(
+    get,
+    path = "/api/v1/meal-plans",
+    tag = "meal plans",
+    params(Pagination),
+    responses((status = 200, body = MealPlanPage))
+)]
+pub async fn list(
+    Repo(repo): Repo,
+    Query(pagination): Query<Pagination>,
+) -> ApiResult<Json<MealPlanPage>> {
+    let plans = Repository::<MealPlan>::list(&repo).await?;
+    Ok(Json(pagination.apply(plans)))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/meal-plans",
+    tag = "meal plans",
+    request_body = MealPlanInput,
+    responses(
+        (status = 201, body = MealPlan),
+        (status = 401, body = ErrorBody),
+        (status = 422, body = ErrorBody),
+    )
+)]
+pub async fn create(
+    Repo(repo): Repo,
+    Json(input): Json<MealPlanInput>,
+) -> ApiResult<(StatusCode, Json<MealPlan>)> {
+    let plan = repo.create(input).await?;
+    Ok((StatusCode::CREATED, Json(plan)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/meal-plans/{slug}",
+    tag = "meal plans",
+    params(("slug" = String, Path, description = "The meal plan's slug")),
+    responses(
+        (status = 200, body = MealPlanDetail),
+        (status = 404, body = ErrorBody),
+    )
+)]
+pub async fn get(Repo(repo): Repo, Path(slug): Path<String>) -> ApiResult<Json<MealPlanDetail>> {
+    Ok(Json(find(&repo, &slug).await?))
+}
+
+/// Rename a meal plan or change its dates.
+#[utoipa::path(
+    put,
+    path = "/api/v1/meal-plans/{slug}",
+    tag = "meal plans",
+    params(("slug" = String, Path, description = "The meal plan's slug")),
+    request_body = MealPlanInput,
+    responses(
+        (status = 200, body = MealPlan),
+        (status = 401, body = ErrorBody),
+        (status = 404, body = ErrorBody),
+        (status = 422, body = ErrorBody),
+    )
+)]
+pub async fn update(
+    Repo(repo): Repo,
+    Path(slug): Path<String>,
+    Json(input): Json<MealPlanInput>,
+) -> ApiResult<Json<MealPlan>> {
+    let existing = find(&repo, &slug).await?;
+    let plan = repo.update(existing.plan.id.into(), input).await?;
+    Ok(Json(plan))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/meal-plans/{slug}",
+    tag = "meal plans",
+    params(("slug" = String, Path, description = "The meal plan's slug")),
+    responses(
+        (status = 204),
+        (status = 401, body = ErrorBody),
+        (status = 404, body = ErrorBody),
+    )
+)]
+pub async fn delete(Repo(repo): Repo, Path(slug): Path<String>) -> ApiResult<StatusCode> {
+    let existing = find(&repo, &slug).await?;
+    DeleteRepository::<MealPlan>::delete(&repo, existing.plan.id.into()).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/meal-plans/{slug}/entries",
+    tag = "meal plans",
+    params(("slug" = String, Path, description = "The meal plan's slug")),
+    request_body = NewEntry,
+    responses(
+        (status = 204),
+        (status = 401, body = ErrorBody),
+        (status = 404, body = ErrorBody),
+        (status = 422, body = ErrorBody),
+    )
+)]
+pub async fn add_entry(
+    Repo(repo): Repo,
+    Path(slug): Path<String>,
+    Json(entry): Json<NewEntry>,
+) -> ApiResult<StatusCode> {
+    let plan = find(&repo, &slug).await?;
+    repo.add_recipe(plan.plan.id.into(), entry.recipe_id.into(), entry.day)
+        .await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/meal-plans/{slug}/entries/{id}",
+    tag = "meal plans",
+    params(
+        ("slug" = String, Path, description = "The meal plan's slug"),
+        ("id" = i64, Path, description = "The id of the entry to remove"),
+    ),
+    responses(
+        (status = 204),
+        (status = 401, body = ErrorBody),
+        (status = 404, body = ErrorBody),
+    )
+)]
+pub async fn remove_entry(
+    Repo(repo): Repo,
+    Path((slug, id)): Path<(String, i64)>,
+) -> ApiResult<StatusCode> {
+    let plan = find(&repo, &slug).await?;
+    let belongs = plan
+        .days
+        .iter()
+        .flat_map(|day| &day.entries)
+        .any(|entry| entry.id == id);
+    if !belongs {
+        return Err(Error::NotFound.into());
+    }
+    repo.remove_recipe(id.into()).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Everything needed to cook a meal plan's recipes, with amounts of the same
+/// ingredient added together.
+#[utoipa::path(
+    get,
+    path = "/api/v1/meal-plans/{slug}/shopping-list",
+    tag = "meal plans",
+    params(("slug" = String, Path, description = "The meal plan's slug")),
+    responses(
+        (status = 200, body = ShoppingList),
+        (status = 404, body = ErrorBody),
+    )
+)]
+pub async fn shopping_list(
+    Repo(repo): Repo,
+    Path(slug): Path<String>,
+) -> ApiResult<Json<ShoppingList>> {
+    let plan = find(&repo, &slug).await?;
+    Ok(Json(shopping_list_for(&repo, &plan).await?))
+}
+
+/// The shopping list for `plan`, loading each of its recipes once.
+pub(super) async fn shopping_list_for(
+    repo: &recipe_repository::DieselRepository,
+    plan: &MealPlanDetail,
+) -> ApiResult<ShoppingList> {
+    let mut recipes: Vec<RecipeDetail> = Vec::new();
+    for entry in plan.days.iter().flat_map(|day| &day.entries) {
+        if !recipes.iter().any(|r| r.recipe.id == entry.recipe.id) {
+            let recipe = BySlug::<RecipeDetail>::get_by_slug(repo, &entry.recipe.slug)
+                .await?
+                .ok_or(Error::NotFound)?;
+            recipes.push(recipe);
+        }
+    }
+    Ok(ShoppingList::for_meal_plan(plan, &recipes))
+}
+
+async fn find(repo: &recipe_repository::DieselRepository, slug: &str) -> ApiResult<MealPlanDetail> {
+    BySlug::<MealPlanDetail>::get_by_slug(repo, slug)
+        .await?
+        .ok_or_else(|| Error::NotFound.into())
+}
@@ -0,0 +1,122 @@
+use axum::{
+    extract::{Path, Query},
+    http::header,
+    response::IntoResponse,
+};
+use serde::Deserialize;
+use utoipa::IntoParams;
+
+use recipe_export::pdf;
+use recipe_repository::{BySlug, DieselRepository, Error};
+use recipe_shared::{MealPlanDetail, RecipeDetail};
+
+use super::{meal_plans::shopping_list_for, ApiError, ApiResult, ErrorBody, Repo};
+
+/// The most recipes a single cookbook can hold.
+const MAX_COOKBOOK_RECIPES: usize = 100;
+
+/// Respond with a PDF, shown in the browser so it can be printed straight
+/// away.
+fn pdf_response(file_name: &str, pdf: Vec<u8>) -> impl IntoResponse {
+    let headers = [
+        (header::CONTENT_TYPE, "application/pdf".to_string()),
+        (
+            header::CONTENT_DISPOSITION,
+            format!("inline; filename=\"{file_name}.pdf\""),
+        ),
+    ];
+    (headers, pdf)
+}
+
+async fn load_recipe(repo: &DieselRepository, slug: &str) -> ApiResult<RecipeDetail> {
+    BySlug::<RecipeDetail>::get_by_slug(repo, slug)
+        .await?
+        .ok_or(ApiError::Repository(Error::NotFound))
+}
+
+/// A recipe card for printing.
+#[utoipa::path(
+    get,
+    path = "/api/v1/recipes/{slug}/pdf",
+    tag = "recipes",
+    params(("slug" = String, Path, description = "The recipe's slug")),
+    responses(
+        (status = 200, description = "The recipe card", content_type = "application/pdf"),
+        (status = 404, body = ErrorBody),
+    )
+)]
+pub async fn recipe(Repo(repo): Repo, Path(slug): Path<String>) -> ApiResult<impl IntoResponse> {
+    let detail = load_recipe(&repo, &slug).await?;
+    Ok(pdf_response(&slug, pdf::recipe_card(&detail)))
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct CookbookQuery {
+    /// The slugs of the recipes to include, separated by commas, in the
+    /// order they should appear.
+    recipes: String,
+    /// The title shown above the table of contents.
+    title: Option<String>,
+}
+
+/// A book of several recipes with a table of contents.
+#[utoipa::path(
+    get,
+    path = "/api/v1/cookbook",
+    tag = "recipes",
+    params(CookbookQuery),
+    responses(
+        (status = 200, description = "The cookbook", content_type = "application/pdf"),
+        (status = 400, body = ErrorBody),
+        (status = 404, body = ErrorBody),
+    )
+)]
+pub async fn cookbook(
+    Repo(repo): Repo,
+    Query(query): Query<CookbookQuery>,
+) -> ApiResult<impl IntoResponse> {
+    let slugs: Vec<_> = query
+        .recipes
+        .split(',')
+        .map(str::trim)
+        .filter(|slug| !slug.is_empty())
+        .collect();
+    if slugs.is_empty() {
+        return Err(ApiError::BadRequest("no recipes were given".to_string()));
+    }
+    if slugs.len() > MAX_COOKBOOK_RECIPES {
+        return Err(ApiError::BadRequest(format!(
+            "cookbooks can hold at most {MAX_COOKBOOK_RECIPES} recipes"
+        )));
+    }
+    let mut recipes = Vec::with_capacity(slugs.len());
+    for slug in slugs {
+        recipes.push(load_recipe(&repo, slug).await?);
+    }
+    let title = query
+        .title
+        .filter(|title| !title.trim().is_empty())
+        .unwrap_or_else(|| "Cookbook".to_string());
+    Ok(pdf_response("cookbook", pdf::cookbook(&title, &recipes)))
+}
+
+/// A meal plan's weeks at a glance, followed by the shopping list for all of
+/// its recipes.
+#[utoipa::path(
+    get,
+    path = "/api/v1/meal-plans/{slug}/pdf",
+    tag = "meal plans",
+    params(("slug" = String, Path, description = "The meal plan's slug")),
+    responses(
+        (status = 200, description = "The meal plan", content_type = "application/pdf"),
+        (status = 404, body = ErrorBody),
+    )
+)]
+pub async fn meal_plan(Repo(repo): Repo, Path(slug): Path<String>) -> ApiResult<impl IntoResponse> {
+    let Some(detail) = BySlug::<MealPlanDetail>::get_by_slug(&repo, &slug).await? else {
+        return Err(Error::NotFound.into());
+    };
+    let shopping_list = shopping_list_for(&repo, &detail).await?;
+    Ok(pdf_response(&slug, pdf::meal_plan(&detail, &shopping_list)))
+}
@@ -0,0 +1,175 @@
+use axum::{
+    extract::{Path, Query},
+    http::{header, StatusCode},
+    response::IntoResponse,
+    Json,
+};
+use serde::Deserialize;
+use utoipa::IntoParams;
+
+use recipe_export::Format;
+use recipe_repository::{BySlug, DeleteRepository, Search, WriteRepository};
+use recipe_shared::{RecipeDetail, RecipeFilter, RecipeInput, RecipeSort};
+
+use super::{ApiError, ApiResult, ErrorBody, Page, Pagination, RecipePage, Repo};
+
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct RecipeQuery {
+    /// Only return recipes whose name contains this text.
+    q: Option<String>,
+    /// The order to list recipes in, by name if not given.
+    sort: Option<RecipeSort>,
+}
+
+/// List the recipes visible to the current user.
+#[utoipa::path(
+    get,
+    path = "/api/v1/recipes",
+    tag = "recipes",
+    params(RecipeQuery, Pagination),
+    responses((status = 200, body = RecipePage))
+)]
+pub async fn list(
+    Repo(repo): Repo,
+    Query(query): Query<RecipeQuery>,
+    Query(pagination): Query<Pagination>,
+) -> ApiResult<Json<RecipePage>> {
+    let filter = RecipeFilter {
+        query: query.q,
+        sort: query.sort.unwrap_or_default(),
+        page: Some(pagination.request()),
+        ..Default::default()
+    };
+    let search = repo.search(filter).await?;
+    Ok(Json(Page {
+        items: search.recipes,
+        page: pagination.page(),
+        per_page: pagination.per_page(),
+        total: search.total,
+    }))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/recipes",
+    tag = "recipes",
+    request_body = RecipeInput,
+    responses(
+        (status = 201, body = RecipeDetail),
+        (status = 401, body = ErrorBody),
+        (status = 422, body = ErrorBody),
+    )
+)]
+pub async fn create(
+    Repo(repo): Repo,
+    Json(input): Json<RecipeInput>,
+) -> ApiResult<(StatusCode, Json<RecipeDetail>)> {
+    let detail = repo.create(input).await?;
+    Ok((StatusCode::CREATED, Json(detail)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/recipes/{slug}",
+    tag = "recipes",
+    params(("slug" = String, Path, description = "The recipe's slug")),
+    responses(
+        (status = 200, body = RecipeDetail),
+        (status = 404, body = ErrorBody),
+    )
+)]
+pub async fn get(Repo(repo): Repo, Path(slug): Path<String>) -> ApiResult<Json<RecipeDetail>> {
+    BySlug::<RecipeDetail>::get_by_slug(&repo, &slug)
+        .await?
+        .map(Json)
+        .ok_or(ApiError::Repository(recipe_repository::Error::NotFound))
+}
+
+/// Replace a recipe, including its ingredients, steps and tags.
+#[utoipa::path(
+    put,
+    path = "/api/v1/recipes/{slug}",
+    tag = "recipes",
+    params(("slug" = String, Path, description = "The recipe's slug")),
+    request_body = RecipeInput,
+    responses(
+        (status = 200, body = RecipeDetail),
+        (status = 401, body = ErrorBody),
+        (status = 404, body = ErrorBody),
+        (status = 422, body = ErrorBody),
+    )
+)]
+pub async fn update(
+    Repo(repo): Repo,
+    Path(slug): Path<String>,
+    Json(input): Json<RecipeInput>,
+) -> ApiResult<Json<RecipeDetail>> {
+    let Some(existing) = BySlug::<RecipeDetail>::get_by_slug(&repo, &slug).await? else {
+        return Err(recipe_repository::Error::NotFound.into());
+    };
+    let detail = repo.update(existing.recipe.id.into(), input).await?;
+    Ok(Json(detail))
+}
+
+/// Delete a recipe, taking it off any meal plans it's planned on.
+#[utoipa::path(
+    delete,
+    path = "/api/v1/recipes/{slug}",
+    tag = "recipes",
+    params(("slug" = String, Path, description = "The recipe's slug")),
+    responses(
+        (status = 204),
+        (status = 401, body = ErrorBody),
+        (status = 404, body = ErrorBody),
+    )
+)]
+pub async fn delete(Repo(repo): Repo, Path(slug): Path<String>) -> ApiResult<StatusCode> {
+    let Some(existing) = BySlug::<RecipeDetail>::get_by_slug(&repo, &slug).await? else {
+        return Err(recipe_repository::Error::NotFound.into());
+    };
+    DeleteRepository::<RecipeDetail>::delete(&repo, existing.recipe.id.into()).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Download a recipe as Markdown, schema.org JSON-LD, Cooklang or a printable
+/// HTML page. The HTML page is shown in the browser rather than saved so it
+/// can be printed straight away.
+#[utoipa::path(
+    get,
+    path = "/api/v1/recipes/{slug}/export/{format}",
+    tag = "recipes",
+    params(
+        ("slug" = String, Path, description = "The recipe's slug"),
+        ("format" = String, Path, description = "One of `markdown`, `json-ld`, `html` or `cooklang`"),
+    ),
+    responses(
+        (status = 200, description = "The rendered recipe", content_type = "text/plain"),
+        (status = 400, body = ErrorBody),
+        (status = 404, body = ErrorBody),
+    )
+)]
+pub async fn export(
+    Repo(repo): Repo,
+    Path((slug, format)): Path<(String, String)>,
+) -> ApiResult<impl IntoResponse> {
+    let format: Format = format.parse().map_err(ApiError::BadRequest)?;
+    let Some(detail) = BySlug::<RecipeDetail>::get_by_slug(&repo, &slug).await? else {
+        return Err(recipe_repository::Error::NotFound.into());
+    };
+    let disposition = match format {
+        Format::Html => "inline",
+        Format::Markdown | Format::JsonLd | Format::Cooklang => "attachment",
+    };
+    let headers = [
+        (header::CONTENT_TYPE, format.content_type().to_string()),
+        (
+            header::CONTENT_DISPOSITION,
+            format!(
+                "{disposition}; filename=\"{}\"",
+                format.file_name(&detail.recipe)
+            ),
+        ),
+    ];
+    Ok((headers, format.render(&detail)))
+}
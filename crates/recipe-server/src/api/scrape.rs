@@ -0,0 +1,43 @@
+use axum::{extract::State, Json};
+use serde::Deserialize;
+use utoipa::ToSchema;
+
+use recipe_app::server::AppState;
+use recipe_scrape::{RecipeScraper, ScrapedRecipe};
+
+use super::{ApiError, ApiResult, ErrorBody};
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ScrapeRequest {
+    /// The address of a page containing a recipe.
+    pub url: String,
+}
+
+/// Scrape a recipe from a web page without saving it.
+#[utoipa::path(
+    post,
+    path = "/api/v1/scrape",
+    tag = "recipes",
+    request_body = ScrapeRequest,
+    responses(
+        (status = 200, body = Object, description = "The recipe found on the page"),
+        (status = 400, body = ErrorBody),
+        (status = 502, body = ErrorBody),
+    )
+)]
+pub async fn scrape(
+    State(state): State<AppState>,
+    Json(request): Json<ScrapeRequest>,
+) -> ApiResult<Json<ScrapedRecipe>> {
+    let url: url::Url = request
+        .url
+        .parse()
+        .map_err(|e| ApiError::BadRequest(format!("invalid URL: {e}")))?;
+    RecipeScraper::web_page_host(&url).map_err(|e| ApiError::BadRequest(e.to_string()))?;
+    let recipe = state
+        .scraper
+        .scrape(url)
+        .await
+        .map_err(|e| ApiError::Upstream(e.to_string()))?;
+    Ok(Json(recipe))
+}
@@ -0,0 +1,66 @@
+use axum::Json;
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use recipe_repository::{Error, SyncRepository, UserRepository};
+use recipe_shared::{Credentials, SyncRequest, SyncResponse};
+
+use super::{ApiResult, ErrorBody, Repo};
+
+/// A session token, to send as a bearer token with later requests.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct Session {
+    pub token: String,
+}
+
+/// Sign in, for clients which can't keep the session cookie, such as the
+/// desktop app when syncing.
+#[utoipa::path(
+    post,
+    path = "/api/v1/sessions",
+    tag = "sync",
+    request_body = Credentials,
+    responses(
+        (status = 200, body = Session),
+        (status = 401, body = ErrorBody),
+    )
+)]
+pub async fn sign_in(
+    Repo(repo): Repo,
+    Json(credentials): Json<Credentials>,
+) -> ApiResult<Json<Session>> {
+    let user = repo
+        .authenticate(credentials)
+        .await?
+        .ok_or(Error::Unauthenticated)?;
+    let token = repo.start_session(user.id.into()).await?;
+    Ok(Json(Session { token }))
+}
+
+/// Exchange changes with a client which keeps its own copy of the current
+/// user's recipes and meal plans.
+///
+/// The client sends everything it changed since it last synced, along with
+/// the cursor it was given then, and gets back everything changed here since
+/// that cursor and a new cursor for next time. Each recipe and meal plan is
+/// kept as it was last edited, wherever that was. Changes which can't be
+/// applied, such as those to another user's recipes, are listed in
+/// `rejected`, and the rest are applied anyway.
+#[utoipa::path(
+    post,
+    path = "/api/v1/sync",
+    tag = "sync",
+    request_body = SyncRequest,
+    responses(
+        (status = 200, body = SyncResponse),
+        (status = 401, body = ErrorBody),
+        (status = 403, body = ErrorBody),
+        (status = 422, body = ErrorBody),
+    )
+)]
+pub async fn sync(
+    Repo(repo): Repo,
+    Json(request): Json<SyncRequest>,
+) -> ApiResult<Json<SyncResponse>> {
+    Ok(Json(repo.sync(request).await?))
+}
@@ -0,0 +1,124 @@
+use std::{
+    env::{self, args},
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{anyhow, bail, Context, Result};
+
+use recipe_repository::{DieselRepository, UserRepository, WriteRepository};
+use recipe_scrape::import::{import_file, ImportFormat};
+use recipe_shared::{Credentials, RecipeDetail};
+
+/// Import recipes exported from Paprika, Mealie or Cooklang, e.g.
+/// `RECIPE_PASSWORD=... recipe-import alice ~/Downloads/export.paprikarecipes`.
+///
+/// Directories are searched for files in any supported format. Recipes are
+/// owned by the given user. Ingredient lines which weren't understood are kept
+/// in the recipe's notes and reported, and recipes which can't be saved at
+/// all, for instance because they have no name, are reported and skipped.
+#[tokio::main]
+async fn main() -> Result<()> {
+    let mut args = args().skip(1);
+    let usage = || anyhow!("usage: recipe-import <username> <file-or-directory>...");
+    let username = args.next().ok_or_else(usage)?;
+    let paths: Vec<PathBuf> = args.map(PathBuf::from).collect();
+    if paths.is_empty() {
+        return Err(usage());
+    }
+    let password = env::var("RECIPE_PASSWORD").context("RECIPE_PASSWORD must be set")?;
+
+    let mut files = Vec::new();
+    for path in paths {
+        // Files named explicitly are always tried, so unsupported ones are
+        // reported rather than ignored.
+        if path.is_dir() {
+            collect_files(&path, &mut files)
+                .with_context(|| format!("reading {}", path.display()))?;
+        } else {
+            files.push(path);
+        }
+    }
+    if files.is_empty() {
+        bail!("no Paprika, Mealie or Cooklang files found");
+    }
+
+    let pool = recipe_db::create_pool().context("creating db pool")?;
+    let repo = DieselRepository::new(pool);
+    let user = repo
+        .authenticate(Credentials { username, password })
+        .await
+        .context("signing in")?
+        .ok_or_else(|| anyhow!("wrong username or password"))?;
+    let repo = repo.for_user(Some(user.id.into()));
+
+    let mut recipes = Vec::new();
+    let mut failed_files = 0;
+    for file in &files {
+        match import_file(file) {
+            Ok(imported) => recipes.extend(imported),
+            Err(e) => {
+                failed_files += 1;
+                eprintln!("{}: {e}", file.display());
+            }
+        }
+    }
+
+    let total = recipes.len();
+    let mut imported = 0;
+    for (i, recipe) in recipes.iter().enumerate() {
+        let progress = format!("[{}/{total}] {}", i + 1, recipe.name);
+        let (input, unparsed) = match recipe.to_input_lenient() {
+            Ok(lenient) => lenient,
+            Err(errors) => {
+                eprintln!("{progress}: skipped, {errors}");
+                continue;
+            }
+        };
+        match WriteRepository::<RecipeDetail>::create(&repo, input).await {
+            Ok(detail) => {
+                imported += 1;
+                let warnings = match recipe.warnings.len() {
+                    0 => String::new(),
+                    n => format!(" with {n} warning(s)"),
+                };
+                eprintln!("{progress}: imported as {}{warnings}", detail.recipe.slug);
+                for warning in &recipe.warnings {
+                    eprintln!("    {warning}");
+                }
+                for line in &unparsed {
+                    eprintln!("    kept {line:?} in the notes, as it couldn't be understood");
+                }
+            }
+            Err(e) => eprintln!("{progress}: failed to save, {e}"),
+        }
+    }
+
+    eprintln!(
+        "Imported {imported} of {total} recipe(s) from {} file(s)",
+        files.len() - failed_files
+    );
+    if failed_files > 0 {
+        eprintln!("{failed_files} file(s) couldn't be read");
+    }
+    Ok(())
+}
+
+/// Add `path` to `files` if it's in a supported format, or every such file
+/// beneath it if it's a directory.
+fn collect_files(path: &Path, files: &mut Vec<PathBuf>) -> Result<()> {
+    if !path.is_dir() {
+        if ImportFormat::detect(path).is_some() {
+            files.push(path.to_path_buf());
+        }
+        return Ok(());
+    }
+    let mut entries = fs::read_dir(path)?
+        .map(|entry| entry.map(|e| e.path()))
+        .collect::<Result<Vec<_>, _>>()?;
+    entries.sort();
+    for entry in entries {
+        collect_files(&entry, files)?;
+    }
+    Ok(())
+}
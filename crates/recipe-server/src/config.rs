@@ -0,0 +1,443 @@
+//! Settings for the server, read from a TOML file, the environment and the
+//! command line.
+//!
+//! Each setting is taken from the first of these which has it: a command
+//! line flag, an environment variable, the file given by `--config` (or
+//! `RECIPE_CONFIG`), and finally a default. For example:
+//!
+//! ```toml
+//! [server]
+//! bind = "0.0.0.0:8080"
+//! assets_dir = "/srv/recipes/dist"
+//! hot_reload = false
+//! secure_cookies = true
+//!
+//! [database]
+//! url = "postgres://recipes@localhost/recipes"
+//! pool_size = 16
+//! migrate_on_startup = true
+//!
+//! [log]
+//! format = "json"
+//! filter = "info,recipe_server=debug"
+//!
+//! [scraper]
+//! timeout_seconds = 20
+//! user_agent = "my-recipes/1.0"
+//! ```
+
+use std::{
+    fs, io,
+    net::SocketAddr,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use clap::{Parser, Subcommand, ValueEnum};
+use serde::Deserialize;
+use tracing_subscriber::EnvFilter;
+
+use recipe_scrape::RecipeScraper;
+
+use crate::{HotReload, DEFAULT_ASSETS_DIR};
+
+const DEFAULT_BIND: &str = "127.0.0.1:8080";
+const DEFAULT_LOG_FILTER: &str = "info";
+
+#[derive(Debug, thiserror::Error)]
+pub enum ConfigError {
+    #[error("reading {}: {source}", path.display())]
+    Read { path: PathBuf, source: io::Error },
+    #[error("parsing {}: {source}", path.display())]
+    Parse {
+        path: PathBuf,
+        source: toml::de::Error,
+    },
+    #[error("{0} must be set")]
+    Missing(&'static str),
+    #[error("invalid {setting}: {reason}")]
+    Invalid {
+        setting: &'static str,
+        reason: String,
+    },
+}
+
+impl ConfigError {
+    fn invalid(setting: &'static str, reason: impl ToString) -> Self {
+        Self::Invalid {
+            setting,
+            reason: reason.to_string(),
+        }
+    }
+}
+
+/// How log lines are written.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    /// One line per event, with its spans.
+    #[default]
+    Full,
+    /// One shorter line per event.
+    Compact,
+    /// Several lines per event, for reading in a terminal.
+    Pretty,
+    /// One JSON object per event, for log collectors.
+    Json,
+}
+
+/// What to do instead of serving the app.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Subcommand)]
+pub enum Command {
+    /// Run any pending database migrations and exit.
+    Migrate,
+}
+
+/// The command line flags, each of which can also be set in the environment.
+#[derive(Debug, Default, Parser)]
+#[command(version, about = "Serve the recipe app and API.")]
+pub struct Args {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+    /// A TOML file to read settings from.
+    #[arg(long, env = "RECIPE_CONFIG")]
+    pub config: Option<PathBuf>,
+    /// The address to listen on [default: 127.0.0.1:8080].
+    #[arg(long, env = "RECIPE_BIND")]
+    pub bind: Option<String>,
+    /// The built web app to serve.
+    #[arg(long, env = "RECIPE_ASSETS_DIR")]
+    pub assets_dir: Option<PathBuf>,
+    /// Whether to accept hot reloads from `dioxus serve` [default: true].
+    #[arg(long, env = "RECIPE_HOT_RELOAD")]
+    pub hot_reload: Option<bool>,
+    /// Whether the server is reached over HTTPS, so the session cookie should
+    /// only be sent over it [default: false].
+    #[arg(long, env = "RECIPE_SECURE_COOKIES")]
+    pub secure_cookies: Option<bool>,
+    /// The database to connect to.
+    #[arg(long, env = "DATABASE_URL", hide_env_values = true)]
+    pub database_url: Option<String>,
+    /// The most database connections to open at once.
+    #[arg(long, env = "RECIPE_POOL_SIZE")]
+    pub pool_size: Option<usize>,
+    /// Whether to run pending migrations before serving [default: false].
+    #[arg(long, env = "RECIPE_MIGRATE_ON_STARTUP")]
+    pub migrate_on_startup: Option<bool>,
+    /// How to write log lines.
+    #[arg(long, env = "RECIPE_LOG_FORMAT")]
+    pub log_format: Option<LogFormat>,
+    /// Which log lines to write, as a `tracing` filter [default: info].
+    #[arg(long, env = "RUST_LOG")]
+    pub log_filter: Option<String>,
+    /// How long to wait for a page when scraping a recipe, in seconds
+    /// [default: 10].
+    #[arg(long, env = "RECIPE_SCRAPER_TIMEOUT")]
+    pub scraper_timeout: Option<u64>,
+    /// The user agent to scrape recipes as.
+    #[arg(long, env = "RECIPE_SCRAPER_USER_AGENT")]
+    pub scraper_user_agent: Option<String>,
+}
+
+/// The settings file, in which everything is optional.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FileConfig {
+    pub server: ServerSection,
+    pub database: DatabaseSection,
+    pub log: LogSection,
+    pub scraper: ScraperSection,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ServerSection {
+    pub bind: Option<String>,
+    pub assets_dir: Option<PathBuf>,
+    pub hot_reload: Option<bool>,
+    pub secure_cookies: Option<bool>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DatabaseSection {
+    pub url: Option<String>,
+    pub pool_size: Option<usize>,
+    pub migrate_on_startup: Option<bool>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LogSection {
+    pub format: Option<LogFormat>,
+    pub filter: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ScraperSection {
+    pub timeout_seconds: Option<u64>,
+    pub user_agent: Option<String>,
+}
+
+impl FileConfig {
+    pub fn read(path: &Path) -> Result<Self, ConfigError> {
+        let contents = fs::read_to_string(path).map_err(|source| ConfigError::Read {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        toml::from_str(&contents).map_err(|source| ConfigError::Parse {
+            path: path.to_path_buf(),
+            source,
+        })
+    }
+}
+
+/// The server's settings, checked and with defaults filled in.
+#[derive(Debug, Clone)]
+pub struct Config {
+    pub command: Option<Command>,
+    pub bind: SocketAddr,
+    pub assets_dir: PathBuf,
+    pub hot_reload: HotReload,
+    pub secure_cookies: bool,
+    pub database_url: String,
+    /// The size of the connection pool, or `None` for the pool's default.
+    pub pool_size: Option<usize>,
+    pub migrate_on_startup: bool,
+    pub log_format: LogFormat,
+    pub log_filter: String,
+    pub scraper_timeout: Duration,
+    pub scraper_user_agent: String,
+}
+
+impl Config {
+    /// Read the settings file named in `args`, if any, and combine it with
+    /// `args`.
+    pub fn load(args: Args) -> Result<Self, ConfigError> {
+        let file = match &args.config {
+            Some(path) => FileConfig::read(path)?,
+            None => FileConfig::default(),
+        };
+        Self::resolve(args, file)
+    }
+
+    /// Combine settings from the command line or environment with those from
+    /// a file, preferring the former, and check them.
+    pub fn resolve(args: Args, file: FileConfig) -> Result<Self, ConfigError> {
+        let bind = args
+            .bind
+            .or(file.server.bind)
+            .unwrap_or_else(|| DEFAULT_BIND.to_string());
+        let bind = bind
+            .parse()
+            .map_err(|e| ConfigError::invalid("bind address", format!("{bind}: {e}")))?;
+
+        let assets_dir = args
+            .assets_dir
+            .or(file.server.assets_dir)
+            .unwrap_or_else(|| PathBuf::from(DEFAULT_ASSETS_DIR));
+        // Migrating doesn't serve anything, so can be done from anywhere.
+        if args.command.is_none() && !assets_dir.is_dir() {
+            return Err(ConfigError::invalid(
+                "assets directory",
+                format!(
+                    "{} isn't a directory; build the web app or set --assets-dir",
+                    assets_dir.display()
+                ),
+            ));
+        }
+
+        let database_url = args
+            .database_url
+            .or(file.database.url)
+            .filter(|url| !url.is_empty())
+            .ok_or(ConfigError::Missing("DATABASE_URL"))?;
+        let pool_size = args.pool_size.or(file.database.pool_size);
+        if pool_size == Some(0) {
+            return Err(ConfigError::invalid("pool size", "must be at least 1"));
+        }
+
+        let log_filter = args
+            .log_filter
+            .or(file.log.filter)
+            .unwrap_or_else(|| DEFAULT_LOG_FILTER.to_string());
+        EnvFilter::try_new(&log_filter).map_err(|e| ConfigError::invalid("log filter", e))?;
+
+        let scraper_timeout = args
+            .scraper_timeout
+            .or(file.scraper.timeout_seconds)
+            .map(Duration::from_secs)
+            .unwrap_or(RecipeScraper::DEFAULT_TIMEOUT);
+        if scraper_timeout.is_zero() {
+            return Err(ConfigError::invalid(
+                "scraper timeout",
+                "must be at least 1 second",
+            ));
+        }
+        let scraper_user_agent = args
+            .scraper_user_agent
+            .or(file.scraper.user_agent)
+            .unwrap_or_else(|| RecipeScraper::DEFAULT_USER_AGENT.to_string());
+        if scraper_user_agent.is_empty()
+            || !scraper_user_agent
+                .chars()
+                .all(|c| c.is_ascii() && !c.is_ascii_control())
+        {
+            return Err(ConfigError::invalid(
+                "scraper user agent",
+                "must be non-empty printable ASCII",
+            ));
+        }
+
+        Ok(Self {
+            command: args.command,
+            bind,
+            assets_dir,
+            hot_reload: args
+                .hot_reload
+                .or(file.server.hot_reload)
+                .unwrap_or(true)
+                .into(),
+            secure_cookies: args
+                .secure_cookies
+                .or(file.server.secure_cookies)
+                .unwrap_or(false),
+            database_url,
+            pool_size,
+            migrate_on_startup: args
+                .migrate_on_startup
+                .or(file.database.migrate_on_startup)
+                .unwrap_or(false),
+            log_format: args.log_format.or(file.log.format).unwrap_or_default(),
+            log_filter,
+            scraper_timeout,
+            scraper_user_agent,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args() -> Args {
+        Args {
+            assets_dir: Some(PathBuf::from(env!("CARGO_MANIFEST_DIR"))),
+            database_url: Some("postgres://localhost/recipes".to_string()),
+            ..Args::default()
+        }
+    }
+
+    #[test]
+    fn defaults() {
+        let config = Config::resolve(args(), FileConfig::default()).unwrap();
+        assert_eq!(config.bind, DEFAULT_BIND.parse().unwrap());
+        assert_eq!(config.hot_reload, HotReload::On);
+        assert!(!config.secure_cookies);
+        assert_eq!(config.pool_size, None);
+        assert!(!config.migrate_on_startup);
+        assert_eq!(config.log_format, LogFormat::Full);
+        assert_eq!(config.scraper_timeout, RecipeScraper::DEFAULT_TIMEOUT);
+    }
+
+    #[test]
+    fn flags_override_the_file() {
+        let file: FileConfig = toml::from_str(
+            r#"
+            [server]
+            bind = "0.0.0.0:80"
+            hot_reload = false
+            secure_cookies = true
+
+            [database]
+            pool_size = 4
+
+            [log]
+            format = "json"
+
+            [scraper]
+            timeout_seconds = 30
+            "#,
+        )
+        .unwrap();
+        let args = Args {
+            bind: Some("0.0.0.0:8000".to_string()),
+            ..args()
+        };
+        let config = Config::resolve(args, file).unwrap();
+        assert_eq!(config.bind, "0.0.0.0:8000".parse().unwrap());
+        assert_eq!(config.hot_reload, HotReload::Off);
+        assert!(config.secure_cookies);
+        assert_eq!(config.pool_size, Some(4));
+        assert_eq!(config.log_format, LogFormat::Json);
+        assert_eq!(config.scraper_timeout, Duration::from_secs(30));
+    }
+
+    #[test]
+    fn invalid_settings_are_errors() {
+        let invalid = |args: Args| Config::resolve(args, FileConfig::default()).unwrap_err();
+        assert!(matches!(
+            invalid(Args {
+                database_url: None,
+                ..args()
+            }),
+            ConfigError::Missing("DATABASE_URL")
+        ));
+        assert!(matches!(
+            invalid(Args {
+                bind: Some("localhost".to_string()),
+                ..args()
+            }),
+            ConfigError::Invalid {
+                setting: "bind address",
+                ..
+            }
+        ));
+        assert!(matches!(
+            invalid(Args {
+                assets_dir: Some(PathBuf::from("/does/not/exist")),
+                ..args()
+            }),
+            ConfigError::Invalid {
+                setting: "assets directory",
+                ..
+            }
+        ));
+        assert!(matches!(
+            invalid(Args {
+                pool_size: Some(0),
+                ..args()
+            }),
+            ConfigError::Invalid {
+                setting: "pool size",
+                ..
+            }
+        ));
+        assert!(matches!(
+            invalid(Args {
+                scraper_timeout: Some(0),
+                ..args()
+            }),
+            ConfigError::Invalid {
+                setting: "scraper timeout",
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn migrating_needs_no_assets() {
+        let args = Args {
+            command: Some(Command::Migrate),
+            assets_dir: Some(PathBuf::from("/does/not/exist")),
+            ..args()
+        };
+        assert!(Config::resolve(args, FileConfig::default()).is_ok());
+    }
+
+    #[test]
+    fn unknown_file_settings_are_errors() {
+        assert!(toml::from_str::<FileConfig>("[server]\nport = 8080\n").is_err());
+    }
+}
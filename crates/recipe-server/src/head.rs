@@ -0,0 +1,126 @@
+//! Additions to the `<head>` of server-rendered pages. Dioxus renders every
+//! page into the same `index.html`, so anything specific to a page, such as a
+//! recipe's schema.org data for search engines and link previews, is spliced
+//! in afterwards.
+
+use axum::{
+    body::{boxed, Full, HttpBody},
+    http::{header, StatusCode},
+    response::Response,
+};
+
+use recipe_app::server::{AppState, CurrentUser};
+use recipe_export::to_json_ld;
+use recipe_repository::BySlug;
+use recipe_shared::RecipeDetail;
+
+/// The tags to add to the head of the page at `path`, if it's one which
+/// has any.
+pub(crate) async fn for_path(state: &AppState, user: &CurrentUser, path: &str) -> Option<String> {
+    let slug = recipe_slug(path)?;
+    let repo = state.repo.for_user(user.0.as_ref().map(|u| u.id.into()));
+    match BySlug::<RecipeDetail>::get_by_slug(&repo, slug).await {
+        Ok(detail) => detail.as_ref().map(recipe_tags),
+        Err(e) => {
+            tracing::warn!(%slug, error = %e, "failed to load recipe for page head");
+            None
+        }
+    }
+}
+
+/// The slug of the recipe shown at `path`, if it's a recipe's page.
+fn recipe_slug(path: &str) -> Option<&str> {
+    let slug = path.trim_end_matches('/').strip_prefix("/recipes/")?;
+    (!slug.is_empty() && slug != "new" && !slug.contains('/')).then_some(slug)
+}
+
+fn recipe_tags(detail: &RecipeDetail) -> String {
+    let recipe = &detail.recipe;
+    let mut tags = format!(
+        r#"<meta property="og:type" content="article"><meta property="og:title" content="{}">"#,
+        escape(&recipe.name)
+    );
+    if let Some(notes) = &recipe.notes {
+        let notes = escape(notes);
+        tags.push_str(&format!(
+            r#"<meta name="description" content="{notes}"><meta property="og:description" content="{notes}">"#
+        ));
+    }
+    if let Some(url) = &recipe.image_url {
+        tags.push_str(&format!(
+            r#"<meta property="og:image" content="{}">"#,
+            escape(url)
+        ));
+    }
+    // `<` only appears inside JSON strings, where escaping it stops a
+    // recipe from closing the script element early.
+    let json_ld = to_json_ld(detail).replace('<', "\\u003c");
+    tags.push_str(&format!(
+        r#"<script type="application/ld+json">{json_ld}</script>"#
+    ));
+    tags
+}
+
+/// Escape text for use in an HTML attribute.
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('"', "&quot;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// Add `tags` to the end of the head of a rendered page. Responses which
+/// aren't successfully rendered pages are passed through untouched.
+pub(crate) async fn insert(res: Response, tags: &str) -> Response {
+    let is_html = res
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .map_or(true, |value| value.starts_with("text/html"));
+    if res.status() != StatusCode::OK || !is_html {
+        return res;
+    }
+    let (mut parts, mut body) = res.into_parts();
+    let mut html = Vec::new();
+    while let Some(chunk) = body.data().await {
+        match chunk {
+            Ok(chunk) => html.extend_from_slice(&chunk),
+            Err(e) => {
+                tracing::error!(error = %e, "failed to read rendered page");
+                parts.status = StatusCode::INTERNAL_SERVER_ERROR;
+                return Response::from_parts(parts, boxed(Full::from("")));
+            }
+        }
+    }
+    let html = String::from_utf8_lossy(&html).replacen("</head>", &format!("{tags}</head>"), 1);
+    parts.headers.remove(header::CONTENT_LENGTH);
+    Response::from_parts(parts, boxed(Full::from(html)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_recipe_pages_have_a_slug() {
+        assert_eq!(
+            recipe_slug("/recipes/sausage-pasta-bake"),
+            Some("sausage-pasta-bake")
+        );
+        assert_eq!(
+            recipe_slug("/recipes/sausage-pasta-bake/"),
+            Some("sausage-pasta-bake")
+        );
+        assert_eq!(recipe_slug("/recipes/sausage-pasta-bake/edit"), None);
+        assert_eq!(recipe_slug("/recipes/new"), None);
+        assert_eq!(recipe_slug("/plans/this-week"), None);
+    }
+
+    #[test]
+    fn escapes_attributes() {
+        assert_eq!(
+            escape(r#"Mac & "cheese" <3"#),
+            "Mac &amp; &quot;cheese&quot; &lt;3"
+        );
+    }
+}
@@ -0,0 +1,20 @@
+//! Probes for whatever runs the server: `/healthz` answers as long as the
+//! server is up, and `/readyz` only while the database can be reached and is
+//! fully migrated, the same as `/api/v1/health`.
+
+use axum::{routing::get, Router};
+
+use recipe_app::server::AppState;
+
+use crate::api::health::health;
+
+pub(crate) fn router(state: AppState) -> Router {
+    Router::new()
+        .route("/healthz", get(healthz))
+        .route("/readyz", get(health))
+        .with_state(state)
+}
+
+async fn healthz() -> &'static str {
+    "ok"
+}
@@ -1,14 +1,36 @@
+use std::{path::PathBuf, time::Instant};
+
 use axum::{
     body::Body,
-    http::{Response, StatusCode},
+    extract::State,
+    http::{header, HeaderMap, HeaderValue, Request, Response, StatusCode},
+    response::IntoResponse,
     routing::get,
     Router,
 };
 use dioxus_fullstack::{axum_adapter::DioxusRouterExt, prelude::*, server_fn_service};
+use metrics_exporter_prometheus::PrometheusHandle;
 use tower::ServiceBuilder;
 use tower_http::{compression::CompressionLayer, trace::TraceLayer};
 
-use recipe_app::{server::AppState, Route};
+use recipe_app::{
+    server::{AppState, CurrentUser, SessionChange, SESSION_COOKIE},
+    AppError, Route,
+};
+use recipe_repository::UserRepository;
+
+mod api;
+pub mod config;
+mod head;
+mod health;
+pub mod metrics;
+
+/// How long the browser keeps the session cookie, matching the lifetime of
+/// sessions in the database.
+const SESSION_MAX_AGE_SECONDS: i64 = 30 * 24 * 60 * 60;
+
+/// Where the web app is built to, relative to this crate.
+pub const DEFAULT_ASSETS_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../recipe-web/dist");
 
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
 pub enum HotReload {
@@ -17,53 +39,226 @@ pub enum HotReload {
     Off,
 }
 
-pub fn router(state: AppState, hot_reload: HotReload) -> Router {
+impl From<bool> for HotReload {
+    fn from(on: bool) -> Self {
+        if on {
+            Self::On
+        } else {
+            Self::Off
+        }
+    }
+}
+
+/// How the router serves the web app.
+#[derive(Debug, Clone)]
+pub struct RouterOptions {
+    /// The built web app, served as static files.
+    pub assets_dir: PathBuf,
+    pub hot_reload: HotReload,
+    /// Serve these metrics at `/metrics`, if given.
+    pub metrics: Option<PrometheusHandle>,
+    /// Mark the session cookie `Secure`, so browsers only send it over HTTPS.
+    pub secure_cookies: bool,
+}
+
+impl Default for RouterOptions {
+    fn default() -> Self {
+        Self {
+            assets_dir: PathBuf::from(DEFAULT_ASSETS_DIR),
+            hot_reload: HotReload::Off,
+            metrics: None,
+            secure_cookies: false,
+        }
+    }
+}
+
+pub fn router(state: AppState, options: RouterOptions) -> Router {
     recipe_app::server::register_explicit();
 
-    let assets_path = concat!(env!("CARGO_MANIFEST_DIR"), "/../recipe-web/dist");
+    // The serve config holds on to the path for as long as the server runs,
+    // and the router is only built once.
+    let assets_path: &'static str = Box::leak(options.assets_dir.to_string_lossy().into());
     let cfg = ServeConfigBuilder::new_with_router(FullstackRouterConfig::<Route>::default())
         .assets_path(assets_path)
         .build();
 
     let ssr_state = SSRState::new(&cfg);
+    let secure_cookies = options.secure_cookies;
     let mut router = Router::new()
         .serve_static_assets(assets_path)
         .register_server_fns_with_handler("", |func| {
             let state = state.clone();
             move |req| async move {
+                let function = req.uri().path().trim_start_matches('/').to_string();
+                let started = Instant::now();
+                let token = session_token(req.headers());
+                let user = current_user(&state, token.as_deref()).await;
                 let mut server_context = DioxusServerContext::default();
-                if server_context.insert(state).is_err() {
+                if server_context.insert(state.clone()).is_err()
+                    || server_context.insert(user).is_err()
+                {
                     return Response::builder()
                         .status(StatusCode::INTERNAL_SERVER_ERROR)
                         .body(Body::from("Error injecting state"))
                         .expect("create error response");
                 }
-                let mut service = server_fn_service(server_context, func);
-                match service.run(req).await {
-                    Ok(res) => res,
+                let mut service = server_fn_service(server_context.clone(), func);
+                let res = match service.run(req).await {
+                    Ok(mut res) => {
+                        if let Some(change) = server_context.get::<SessionChange>() {
+                            apply_session_change(
+                                &state,
+                                token.as_deref(),
+                                change,
+                                secure_cookies,
+                                &mut res,
+                            )
+                            .await;
+                        }
+                        res
+                    }
                     Err(e) => {
-                        let mut res = Response::new(Body::from(e.to_string()));
-                        *res.status_mut() = match e {
-                            ServerFnError::Request(_) => StatusCode::BAD_GATEWAY,
-                            _ => StatusCode::INTERNAL_SERVER_ERROR,
-                        };
+                        // The client parses the error back out of the body.
+                        let body = serde_json::to_string(&e).unwrap_or_else(|_| e.to_string());
+                        let mut res = Response::new(Body::from(body));
+                        *res.status_mut() = server_fn_error_status(e);
                         res
                     }
-                }
+                };
+                metrics::record_server_fn(&function, res.status().as_u16(), started.elapsed());
+                res
             }
         });
-    if matches!(hot_reload, HotReload::On) {
+    router = router
+        .nest("/api/v1", api::router(state.clone()))
+        .merge(health::router(state.clone()));
+    if let Some(handle) = options.metrics {
+        router = router.merge(metrics::router(state.clone(), handle));
+    }
+    if matches!(options.hot_reload, HotReload::On) {
         router = router.connect_hot_reload();
     }
-    router
-        .fallback(get(render_handler_with_context).with_state((
-            move |cfg| cfg.insert(state.clone()).unwrap(),
-            cfg,
-            ssr_state,
-        )))
-        .layer(
-            ServiceBuilder::new()
-                .layer(CompressionLayer::new().gzip(true))
-                .layer(TraceLayer::new_for_http()),
-        )
+    // Pages are rendered as whoever is signed in, so that the data loaded
+    // while rendering is what they'll see once the page is running.
+    let render = move |req: Request<Body>| {
+        let state = state.clone();
+        let cfg = cfg.clone();
+        let ssr_state = ssr_state.clone();
+        async move {
+            let token = session_token(req.headers());
+            let user = current_user(&state, token.as_deref()).await;
+            let head = head::for_path(&state, &user, req.uri().path()).await;
+            let inject = move |ctx: &mut DioxusServerContext| {
+                ctx.insert(state.clone()).unwrap();
+                ctx.insert(user.clone()).unwrap();
+            };
+            let res = render_handler_with_context(State((inject, cfg, ssr_state)), req)
+                .await
+                .into_response();
+            match head {
+                Some(tags) => head::insert(res, &tags).await,
+                None => res,
+            }
+        }
+    };
+    router.fallback(get(render)).layer(
+        ServiceBuilder::new()
+            .layer(CompressionLayer::new().gzip(true))
+            .layer(TraceLayer::new_for_http()),
+    )
+}
+
+/// The status to respond to a failed server function with. Errors from the
+/// app carry an [`AppError`], which knows its own status.
+fn server_fn_error_status(e: ServerFnError) -> StatusCode {
+    match e {
+        ServerFnError::Args(_)
+        | ServerFnError::MissingArg(_)
+        | ServerFnError::Deserialization(_) => StatusCode::BAD_REQUEST,
+        ServerFnError::Registration(_) => StatusCode::NOT_FOUND,
+        ServerFnError::Request(_) => StatusCode::BAD_GATEWAY,
+        e @ ServerFnError::ServerError(_) => StatusCode::from_u16(AppError::from(e).status())
+            .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR),
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+/// The user whose session `token` belongs to, if it's still valid.
+async fn current_user(state: &AppState, token: Option<&str>) -> CurrentUser {
+    let Some(token) = token else {
+        return CurrentUser(None);
+    };
+    let user = state.repo.session_user(token).await.unwrap_or_else(|e| {
+        tracing::warn!(error = %e, "failed to load session");
+        None
+    });
+    CurrentUser(user)
+}
+
+/// Read the session token from the request's cookies.
+fn session_token(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get_all(header::COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|cookies| cookies.split(';'))
+        .filter_map(|cookie| cookie.trim().split_once('='))
+        .find(|(name, _)| *name == SESSION_COOKIE)
+        .map(|(_, value)| value.to_string())
+}
+
+/// Set or clear the session cookie, ending the old session if there was one.
+async fn apply_session_change(
+    state: &AppState,
+    old_token: Option<&str>,
+    change: SessionChange,
+    secure: bool,
+    res: &mut Response<Body>,
+) {
+    if let Some(token) = old_token {
+        if let Err(e) = state.repo.end_session(token).await {
+            tracing::warn!(error = %e, "failed to end session");
+        }
+    }
+    let mut cookie = match change {
+        SessionChange::Start(token) => format!(
+            "{SESSION_COOKIE}={token}; Path=/; HttpOnly; SameSite=Lax; Max-Age={SESSION_MAX_AGE_SECONDS}"
+        ),
+        SessionChange::End => format!("{SESSION_COOKIE}=; Path=/; HttpOnly; SameSite=Lax; Max-Age=0"),
+    };
+    if secure {
+        cookie.push_str("; Secure");
+    }
+    match HeaderValue::from_str(&cookie) {
+        Ok(value) => {
+            res.headers_mut().append(header::SET_COOKIE, value);
+        }
+        Err(e) => tracing::error!(error = %e, "invalid session cookie"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use recipe_shared::ValidationErrors;
+
+    use super::*;
+
+    #[test]
+    fn app_errors_are_sent_with_their_status() {
+        for (error, status) in [
+            (AppError::NotFound, StatusCode::NOT_FOUND),
+            (AppError::Forbidden, StatusCode::FORBIDDEN),
+            (
+                AppError::from(ValidationErrors::default()),
+                StatusCode::UNPROCESSABLE_ENTITY,
+            ),
+        ] {
+            assert_eq!(server_fn_error_status(error.into()), status);
+        }
+        let other = ServerFnError::ServerError("boom".to_string());
+        assert_eq!(
+            server_fn_error_status(other),
+            StatusCode::INTERNAL_SERVER_ERROR
+        );
+    }
 }
@@ -1,30 +1,80 @@
+use anyhow::{Context, Result};
+use clap::Parser;
 use tracing_error::ErrorLayer;
 use tracing_subscriber::{prelude::*, EnvFilter};
 
 use recipe_app::server::AppState;
+use recipe_repository::DieselRepository;
+use recipe_scrape::RecipeScraper;
+use recipe_server::{
+    config::{Args, Command, Config, LogFormat},
+    RouterOptions,
+};
 
-pub fn setup_tracing() {
-    let filter_layer = EnvFilter::try_from_default_env()
-        .or_else(|_| EnvFilter::try_new("info"))
-        .unwrap();
+pub fn setup_tracing(config: &Config) -> Result<()> {
+    let fmt_layer = match config.log_format {
+        LogFormat::Full => tracing_subscriber::fmt::layer().boxed(),
+        LogFormat::Compact => tracing_subscriber::fmt::layer().compact().boxed(),
+        LogFormat::Pretty => tracing_subscriber::fmt::layer().pretty().boxed(),
+        LogFormat::Json => tracing_subscriber::fmt::layer().json().boxed(),
+    };
+    let filter_layer = EnvFilter::try_new(&config.log_filter)?;
     let error_layer = ErrorLayer::default();
 
     tracing_subscriber::registry()
-        .with(tracing_subscriber::fmt::layer())
+        .with(fmt_layer)
         .with(filter_layer)
         .with(error_layer)
         .init();
+    Ok(())
 }
 
 #[tokio::main]
-async fn main() {
-    setup_tracing();
-    let addr = std::net::SocketAddr::from(([127, 0, 0, 1], 8080));
-    let pool = recipe_db::create_pool().expect("create db pool");
-    let repo = recipe_repository::DieselRepository::new(pool);
-    let state = AppState::new(repo);
-    axum::Server::bind(&addr)
-        .serve(recipe_server::router(state, recipe_server::HotReload::On).into_make_service())
-        .await
-        .unwrap()
+async fn main() -> Result<()> {
+    dotenvy::dotenv().ok();
+    let config = Config::load(Args::parse())?;
+    setup_tracing(&config)?;
+
+    match config.command {
+        Some(Command::Migrate) => return migrate(&config.database_url).await,
+        None if config.migrate_on_startup => migrate(&config.database_url).await?,
+        None => {}
+    }
+
+    let mut pool = recipe_db::build_pool(&config.database_url);
+    if let Some(size) = config.pool_size {
+        pool = pool.max_size(size);
+    }
+    let repo = DieselRepository::new(pool.build().context("creating db pool")?);
+    let scraper = RecipeScraper::with_options(config.scraper_timeout, &config.scraper_user_agent)
+        .context("creating scraper")?;
+    let state = AppState::new(repo).with_scraper(scraper);
+    let metrics = recipe_server::metrics::install().context("installing metrics recorder")?;
+    let options = RouterOptions {
+        assets_dir: config.assets_dir,
+        hot_reload: config.hot_reload,
+        metrics: Some(metrics),
+        secure_cookies: config.secure_cookies,
+    };
+
+    tracing::info!(addr = %config.bind, "listening");
+    axum::Server::try_bind(&config.bind)
+        .with_context(|| format!("binding to {}", config.bind))?
+        .serve(recipe_server::router(state, options).into_make_service())
+        .await?;
+    Ok(())
+}
+
+/// Run any pending migrations, waiting for other servers doing the same.
+async fn migrate(database_url: &str) -> Result<()> {
+    let database_url = database_url.to_string();
+    let versions =
+        tokio::task::spawn_blocking(move || recipe_db::run_migrations(&database_url)).await??;
+    if versions.is_empty() {
+        tracing::info!("database is up to date");
+    }
+    for version in versions {
+        tracing::info!(%version, "ran migration");
+    }
+    Ok(())
 }
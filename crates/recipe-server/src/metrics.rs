@@ -0,0 +1,54 @@
+//! Prometheus metrics, served at `/metrics`.
+//!
+//! Besides what's recorded here, the scraper counts scrapes per host and the
+//! database records query timings, all through the `metrics` crate.
+
+use std::time::Duration;
+
+use axum::{extract::State, routing::get, Router};
+use metrics_exporter_prometheus::{BuildError, PrometheusBuilder, PrometheusHandle};
+
+use recipe_app::server::AppState;
+
+/// The bucket boundaries, in seconds, of the timing histograms.
+const BUCKETS: &[f64] = &[
+    0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+/// Record metrics from the whole process, including database query timings,
+/// to be rendered with the returned handle. This can only be done once.
+pub fn install() -> Result<PrometheusHandle, BuildError> {
+    let handle = PrometheusBuilder::new()
+        .set_buckets(BUCKETS)?
+        .install_recorder()?;
+    if let Err(e) = recipe_db::metrics::install() {
+        tracing::warn!(error = %e, "failed to time database queries");
+    }
+    Ok(handle)
+}
+
+/// Record how long a server function took to respond, and with what.
+pub(crate) fn record_server_fn(function: &str, status: u16, elapsed: Duration) {
+    metrics::histogram!(
+        "server_fn_duration_seconds",
+        "function" => function.to_string(),
+        "status" => status.to_string(),
+    )
+    .record(elapsed);
+}
+
+pub(crate) fn router(state: AppState, handle: PrometheusHandle) -> Router {
+    Router::new()
+        .route("/metrics", get(render))
+        .with_state((state, handle))
+}
+
+async fn render(State((state, handle)): State<(AppState, PrometheusHandle)>) -> String {
+    // The pool's size is read when scraped rather than tracked as it changes.
+    let pool = state.repo.pool_status();
+    metrics::gauge!("db_pool_max_size").set(pool.max_size as f64);
+    metrics::gauge!("db_pool_size").set(pool.size as f64);
+    metrics::gauge!("db_pool_available").set(pool.available as f64);
+    metrics::gauge!("db_pool_waiting").set(pool.waiting as f64);
+    handle.render()
+}
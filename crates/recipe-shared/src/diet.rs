@@ -0,0 +1,587 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt,
+    str::FromStr,
+};
+
+use serde::{Deserialize, Serialize};
+
+/// Something an ingredient contains which matters to people with allergies
+/// or dietary restrictions.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum DietaryFlag {
+    Gluten,
+    Dairy,
+    Egg,
+    /// Tree nuts, e.g. almonds and cashews.
+    Nuts,
+    Peanuts,
+    Soy,
+    Sesame,
+    Fish,
+    Shellfish,
+    /// Meat, poultry and products made from them, such as gelatine.
+    Meat,
+    /// Other animal products which aren't vegan, such as honey.
+    AnimalProduct,
+}
+
+/// Words which indicate that an ingredient carries a flag, along with words
+/// which cancel the match (e.g. "almond milk" isn't dairy). Entries with a
+/// space match that phrase rather than a single word.
+const KEYWORDS: &[(DietaryFlag, &[&str], &[&str])] = &[
+    (
+        DietaryFlag::Gluten,
+        &[
+            "flour",
+            "bread",
+            "breadcrumb",
+            "pasta",
+            "spaghetti",
+            "penne",
+            "noodle",
+            "couscous",
+            "wheat",
+            "barley",
+            "rye",
+            "semolina",
+            "bulgur",
+            "tortilla",
+            "pastry",
+            "biscuit",
+            "spelt",
+            "seitan",
+            "farro",
+            "freekeh",
+            "kamut",
+            "einkorn",
+            "emmer",
+            "triticale",
+            "durum",
+            "malt",
+            "malted",
+            "orzo",
+            "udon",
+            "panko",
+            "crouton",
+            "cracker",
+            "beer",
+            "lager",
+            "stout",
+            "soy sauce",
+            "teriyaki",
+            "hoisin",
+            "worcestershire",
+        ],
+        &[
+            "gluten-free",
+            "rice",
+            "cornflour",
+            "corn",
+            "buckwheat",
+            "chickpea",
+            "gram",
+        ],
+    ),
+    (
+        DietaryFlag::Dairy,
+        &[
+            "milk",
+            "butter",
+            "cheese",
+            "cream",
+            "yogurt",
+            "yoghurt",
+            "creme",
+            "crème",
+            "fraiche",
+            "fraîche",
+            "parmesan",
+            "mozzarella",
+            "cheddar",
+            "ricotta",
+            "mascarpone",
+            "ghee",
+            "buttermilk",
+            "feta",
+            "halloumi",
+        ],
+        &[
+            "almond",
+            "oat",
+            "soy",
+            "soya",
+            "coconut",
+            "rice",
+            "vegan",
+            "plant",
+            "peanut",
+            "cashew",
+            "cocoa",
+            "shea",
+            "nut",
+            "tartar",
+            "dairy-free",
+        ],
+    ),
+    (
+        DietaryFlag::Egg,
+        &["egg", "mayonnaise", "mayo", "meringue"],
+        &["vegan"],
+    ),
+    (
+        DietaryFlag::Nuts,
+        &[
+            "almond",
+            "cashew",
+            "walnut",
+            "pecan",
+            "hazelnut",
+            "pistachio",
+            "macadamia",
+            "brazil",
+            "pine",
+            "nut",
+        ],
+        &["nut-free"],
+    ),
+    (DietaryFlag::Peanuts, &["peanut", "groundnut"], &[]),
+    (
+        DietaryFlag::Soy,
+        &["soy", "soya", "tofu", "tempeh", "edamame", "miso"],
+        &[],
+    ),
+    (DietaryFlag::Sesame, &["sesame", "tahini"], &[]),
+    (
+        DietaryFlag::Fish,
+        &[
+            "fish",
+            "salmon",
+            "tuna",
+            "cod",
+            "haddock",
+            "anchovy",
+            "sardine",
+            "mackerel",
+            "trout",
+            "worcestershire",
+        ],
+        &["vegan"],
+    ),
+    (
+        DietaryFlag::Shellfish,
+        &[
+            "prawn", "shrimp", "crab", "lobster", "mussel", "clam", "oyster", "scallop", "squid",
+        ],
+        &[],
+    ),
+    (
+        DietaryFlag::Meat,
+        &[
+            "chicken",
+            "beef",
+            "pork",
+            "lamb",
+            "bacon",
+            "ham",
+            "sausage",
+            "mince",
+            "steak",
+            "turkey",
+            "duck",
+            "chorizo",
+            "pancetta",
+            "prosciutto",
+            "salami",
+            "gelatine",
+            "gelatin",
+            "lard",
+        ],
+        &[
+            "vegetable",
+            "veggie",
+            "vegan",
+            "vegetarian",
+            "meat-free",
+            "plant",
+        ],
+    ),
+    (DietaryFlag::AnimalProduct, &["honey"], &[]),
+];
+
+impl DietaryFlag {
+    pub const ALL: [Self; 11] = [
+        Self::Gluten,
+        Self::Dairy,
+        Self::Egg,
+        Self::Nuts,
+        Self::Peanuts,
+        Self::Soy,
+        Self::Sesame,
+        Self::Fish,
+        Self::Shellfish,
+        Self::Meat,
+        Self::AnimalProduct,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Gluten => "gluten",
+            Self::Dairy => "dairy",
+            Self::Egg => "egg",
+            Self::Nuts => "nuts",
+            Self::Peanuts => "peanuts",
+            Self::Soy => "soy",
+            Self::Sesame => "sesame",
+            Self::Fish => "fish",
+            Self::Shellfish => "shellfish",
+            Self::Meat => "meat",
+            Self::AnimalProduct => "animal_product",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Gluten => "Gluten",
+            Self::Dairy => "Dairy",
+            Self::Egg => "Egg",
+            Self::Nuts => "Tree nuts",
+            Self::Peanuts => "Peanuts",
+            Self::Soy => "Soy",
+            Self::Sesame => "Sesame",
+            Self::Fish => "Fish",
+            Self::Shellfish => "Shellfish",
+            Self::Meat => "Meat",
+            Self::AnimalProduct => "Other animal product",
+        }
+    }
+
+    /// Guess the flags of an ingredient from its name.
+    ///
+    /// This is a best effort based on common words, so the result should be
+    /// reviewed by a person before being relied upon.
+    pub fn detect(name: &str) -> BTreeSet<Self> {
+        let name = name.to_lowercase();
+        let words: Vec<&str> = name
+            .split(|c: char| !c.is_alphabetic() && c != '-')
+            .filter(|w| !w.is_empty())
+            .collect();
+        let padded = format!(" {} ", words.join(" "));
+        let any_in = |list: &[&str]| {
+            words.iter().any(|w| word_in(w, list))
+                || list
+                    .iter()
+                    .filter(|entry| entry.contains(' '))
+                    .any(|phrase| padded.contains(&format!(" {phrase} ")))
+        };
+        KEYWORDS
+            .iter()
+            .filter(|(_, keywords, exceptions)| any_in(keywords) && !any_in(exceptions))
+            .map(|(flag, _, _)| *flag)
+            .collect()
+    }
+}
+
+/// Whether `word`, or what may be its singular form, is one of `list`.
+fn word_in(word: &str, list: &[&str]) -> bool {
+    let singulars = [
+        word.strip_suffix("ies").map(|w| format!("{w}y")),
+        word.strip_suffix("oes").map(|w| format!("{w}o")),
+        word.strip_suffix('s').map(str::to_string),
+    ];
+    list.contains(&word)
+        || singulars
+            .iter()
+            .flatten()
+            .any(|singular| list.contains(&singular.as_str()))
+}
+
+impl fmt::Display for DietaryFlag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for DietaryFlag {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|flag| flag.as_str() == s)
+            .ok_or_else(|| format!("unknown dietary flag {s}"))
+    }
+}
+
+/// A diet which recipes can be filtered by.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum Diet {
+    Vegetarian,
+    Vegan,
+    GlutenFree,
+    DairyFree,
+    EggFree,
+    NutFree,
+}
+
+impl Diet {
+    pub const ALL: [Self; 6] = [
+        Self::Vegetarian,
+        Self::Vegan,
+        Self::GlutenFree,
+        Self::DairyFree,
+        Self::EggFree,
+        Self::NutFree,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Vegetarian => "vegetarian",
+            Self::Vegan => "vegan",
+            Self::GlutenFree => "gluten_free",
+            Self::DairyFree => "dairy_free",
+            Self::EggFree => "egg_free",
+            Self::NutFree => "nut_free",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Vegetarian => "Vegetarian",
+            Self::Vegan => "Vegan",
+            Self::GlutenFree => "Gluten free",
+            Self::DairyFree => "Dairy free",
+            Self::EggFree => "Egg free",
+            Self::NutFree => "Nut free",
+        }
+    }
+
+    /// The flags which an ingredient must not have for a recipe using it to
+    /// be suitable for this diet.
+    pub fn excludes(&self) -> &'static [DietaryFlag] {
+        use DietaryFlag::*;
+        match self {
+            Self::Vegetarian => &[Meat, Fish, Shellfish],
+            Self::Vegan => &[Meat, Fish, Shellfish, Dairy, Egg, AnimalProduct],
+            Self::GlutenFree => &[Gluten],
+            Self::DairyFree => &[Dairy],
+            Self::EggFree => &[Egg],
+            Self::NutFree => &[Nuts, Peanuts],
+        }
+    }
+
+    /// Map a schema.org `RestrictedDiet` value, either as a bare name like
+    /// `VeganDiet` or a full URL, onto a diet.
+    pub fn from_schema_org(value: &str) -> Option<Self> {
+        let name = value.trim().rsplit('/').next().unwrap_or_default();
+        match name {
+            "VegetarianDiet" => Some(Self::Vegetarian),
+            "VeganDiet" => Some(Self::Vegan),
+            "GlutenFreeDiet" => Some(Self::GlutenFree),
+            "LowLactoseDiet" => Some(Self::DairyFree),
+            _ => None,
+        }
+    }
+
+    /// The schema.org `RestrictedDiet` URL for this diet, if there is one.
+    pub fn schema_org(&self) -> Option<&'static str> {
+        match self {
+            Self::Vegetarian => Some("https://schema.org/VegetarianDiet"),
+            Self::Vegan => Some("https://schema.org/VeganDiet"),
+            Self::GlutenFree => Some("https://schema.org/GlutenFreeDiet"),
+            Self::DairyFree => Some("https://schema.org/LowLactoseDiet"),
+            Self::EggFree | Self::NutFree => None,
+        }
+    }
+}
+
+impl fmt::Display for Diet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Diet {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|diet| diet.as_str() == s)
+            .ok_or_else(|| format!("unknown diet {s}"))
+    }
+}
+
+/// The dietary information of a recipe, derived from its ingredients.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct DietInfo {
+    /// Each flag present in the recipe, with the names of the ingredients
+    /// which carry it.
+    pub flags: BTreeMap<DietaryFlag, Vec<String>>,
+    /// Diets the recipe was declared suitable for, e.g. by the site it was
+    /// scraped from.
+    pub declared: Vec<Diet>,
+    /// Ingredients whose flags were guessed and haven't been checked.
+    pub unreviewed: Vec<String>,
+}
+
+impl DietInfo {
+    pub fn is_suitable_for(&self, diet: Diet) -> bool {
+        !diet.excludes().iter().any(|f| self.flags.contains_key(f))
+    }
+
+    pub fn suitable_diets(&self) -> Vec<Diet> {
+        Diet::ALL
+            .into_iter()
+            .filter(|diet| self.is_suitable_for(*diet))
+            .collect()
+    }
+
+    /// Declared diets which the ingredients contradict.
+    pub fn conflicts(&self) -> Vec<Diet> {
+        self.declared
+            .iter()
+            .copied()
+            .filter(|diet| !self.is_suitable_for(*diet))
+            .collect()
+    }
+}
+
+/// The dietary flags recorded for an ingredient.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct IngredientDiet {
+    pub ingredient_id: i64,
+    pub name: String,
+    pub flags: BTreeSet<DietaryFlag>,
+    /// Whether a person has confirmed the flags, rather than them having
+    /// been guessed from the name.
+    pub reviewed: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use DietaryFlag::*;
+
+    #[test]
+    fn detects_flags() {
+        let cases: &[(&str, &[DietaryFlag])] = &[
+            ("plain flour", &[Gluten]),
+            ("egg noodles", &[Gluten, Egg]),
+            ("eggs", &[Egg]),
+            ("mayo", &[Egg]),
+            ("unsalted butter", &[Dairy]),
+            ("goat's cheese", &[Dairy]),
+            ("crème fraîche", &[Dairy]),
+            ("ground almonds", &[Nuts]),
+            ("pine nuts", &[Nuts]),
+            ("almond milk", &[Nuts]),
+            ("peanut butter", &[Peanuts]),
+            ("peanuts", &[Peanuts]),
+            ("soy sauce", &[Soy, Gluten]),
+            ("dark soy sauce", &[Soy, Gluten]),
+            ("teriyaki sauce", &[Gluten]),
+            ("tahini", &[Sesame]),
+            ("anchovies", &[Fish]),
+            ("worcestershire sauce", &[Fish, Gluten]),
+            ("spelt flour", &[Gluten]),
+            ("seitan", &[Gluten]),
+            ("farro", &[Gluten]),
+            ("malt vinegar", &[Gluten]),
+            ("panko breadcrumbs", &[Gluten]),
+            ("udon noodles", &[Gluten]),
+            ("king prawns", &[Shellfish]),
+            ("chicken thighs", &[Meat]),
+            ("gelatine", &[Meat]),
+            ("honey", &[AnimalProduct]),
+            // Names which look like they carry a flag but don't.
+            ("rice flour", &[]),
+            ("cornflour", &[]),
+            ("gram flour", &[]),
+            ("buckwheat", &[]),
+            ("buckwheat flour", &[]),
+            ("rice noodles", &[]),
+            ("gluten-free soy sauce", &[Soy]),
+            ("vegan mayonnaise", &[]),
+            ("coconut cream", &[]),
+            ("coconut milk", &[]),
+            ("oat milk", &[]),
+            ("cocoa butter", &[]),
+            ("cream of tartar", &[]),
+            ("dairy-free cheese", &[]),
+            ("vegetarian sausages", &[]),
+            ("butternut squash", &[]),
+            ("nutmeg", &[]),
+            ("eggplant", &[]),
+            ("pineapple", &[]),
+            ("tomatoes", &[]),
+        ];
+        for (name, expected) in cases {
+            let expected: BTreeSet<_> = expected.iter().copied().collect();
+            assert_eq!(DietaryFlag::detect(name), expected, "{name}");
+        }
+    }
+
+    fn info(ingredients: &[&str]) -> DietInfo {
+        let mut info = DietInfo::default();
+        for name in ingredients {
+            for flag in DietaryFlag::detect(name) {
+                info.flags.entry(flag).or_default().push(name.to_string());
+            }
+        }
+        info
+    }
+
+    #[test]
+    fn diets_exclude_flags() {
+        use Diet::*;
+
+        let cases: &[(&[&str], &[Diet])] = &[
+            (
+                &["spaghetti", "tomatoes", "olive oil"],
+                &[Vegetarian, Vegan, DairyFree, EggFree, NutFree],
+            ),
+            (
+                &["rice flour", "almond milk", "maple syrup"],
+                &[Vegetarian, Vegan, GlutenFree, DairyFree, EggFree],
+            ),
+            (
+                &["eggs", "milk", "sugar"],
+                &[Vegetarian, GlutenFree, NutFree],
+            ),
+            (
+                &["rice", "honey"],
+                &[Vegetarian, GlutenFree, DairyFree, EggFree, NutFree],
+            ),
+            (
+                &["peanut butter", "oats"],
+                &[Vegetarian, Vegan, GlutenFree, DairyFree, EggFree],
+            ),
+            (
+                &["anchovies", "potatoes"],
+                &[GlutenFree, DairyFree, EggFree, NutFree],
+            ),
+        ];
+        for (ingredients, expected) in cases {
+            assert_eq!(
+                info(ingredients).suitable_diets(),
+                *expected,
+                "{ingredients:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn declared_diets_conflict_with_ingredients() {
+        let mut info = info(&["vegan mayonnaise", "honey"]);
+        info.declared = vec![Diet::EggFree, Diet::Vegan];
+        assert_eq!(info.conflicts(), [Diet::Vegan]);
+    }
+}
@@ -0,0 +1,111 @@
+use std::{fmt, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{MealPlan, Recipe, User, ValidationErrors};
+
+/// What a member of a household is allowed to do.
+#[derive(
+    Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum HouseholdRole {
+    /// Can manage the members of the household, as well as editing.
+    Owner,
+    /// Can change the recipes and meal plans shared into the household.
+    Editor,
+    /// Can only see what has been shared into the household.
+    #[default]
+    Viewer,
+}
+
+impl HouseholdRole {
+    pub const ALL: [Self; 3] = [Self::Owner, Self::Editor, Self::Viewer];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Owner => "owner",
+            Self::Editor => "editor",
+            Self::Viewer => "viewer",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Owner => "Owner",
+            Self::Editor => "Editor",
+            Self::Viewer => "Viewer",
+        }
+    }
+
+    pub fn can_edit(&self) -> bool {
+        matches!(self, Self::Owner | Self::Editor)
+    }
+
+    pub fn can_manage(&self) -> bool {
+        matches!(self, Self::Owner)
+    }
+}
+
+impl fmt::Display for HouseholdRole {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for HouseholdRole {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "owner" => Ok(Self::Owner),
+            "editor" => Ok(Self::Editor),
+            "viewer" => Ok(Self::Viewer),
+            other => Err(format!("unknown household role {other}")),
+        }
+    }
+}
+
+/// A household the current user belongs to.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Household {
+    pub id: i64,
+    pub name: String,
+    /// The current user's role in the household.
+    pub role: HouseholdRole,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct HouseholdMember {
+    pub user: User,
+    pub role: HouseholdRole,
+}
+
+/// A household along with its members and everything shared into it.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct HouseholdDetail {
+    pub household: Household,
+    pub members: Vec<HouseholdMember>,
+    pub recipes: Vec<Recipe>,
+    pub meal_plans: Vec<MealPlan>,
+}
+
+/// The data needed to create or rename a household.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct HouseholdInput {
+    pub name: String,
+}
+
+impl HouseholdInput {
+    pub fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::default();
+        if self.name.trim().is_empty() {
+            errors.add("name", "name is required");
+        }
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
@@ -0,0 +1,161 @@
+use std::{collections::BTreeMap, fmt};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Diet, Nutrition, TagInput};
+
+/// The data needed to create or update a recipe.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct RecipeInput {
+    pub name: String,
+    pub source: Option<String>,
+    pub notes: Option<String>,
+    pub image_url: Option<String>,
+    pub prep_time_minutes: Option<i32>,
+    pub cooking_time_minutes: Option<i32>,
+    pub servings: Option<i32>,
+    pub ingredients: Vec<IngredientInput>,
+    pub steps: Vec<String>,
+    #[serde(default)]
+    pub tags: Vec<TagInput>,
+    /// Diets the recipe is declared suitable for, which are checked
+    /// against its ingredients.
+    #[serde(default)]
+    pub diets: Vec<Diet>,
+    /// Nutrition per serving, if known. Otherwise it's estimated from the
+    /// ingredients.
+    #[serde(default)]
+    pub nutrition: Option<Nutrition>,
+    /// Whether other users can see the recipe.
+    #[serde(default)]
+    pub shared: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct IngredientInput {
+    pub name: String,
+    pub quantity: Option<f64>,
+    pub unit: Option<String>,
+    pub notes: Option<String>,
+}
+
+impl RecipeInput {
+    /// Check the input for problems, returning all of them keyed by field.
+    ///
+    /// Ingredients and steps are keyed by their index, e.g. `ingredients.2`.
+    pub fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::default();
+        if self.name.trim().is_empty() {
+            errors.add("name", "name is required");
+        }
+        for (field, value) in [
+            ("prep_time_minutes", self.prep_time_minutes),
+            ("cooking_time_minutes", self.cooking_time_minutes),
+        ] {
+            if value.is_some_and(|v| v < 0) {
+                errors.add(field, "must not be negative");
+            }
+        }
+        if self.servings.is_some_and(|s| s <= 0) {
+            errors.add("servings", "must be at least 1");
+        }
+
+        let mut seen = Vec::with_capacity(self.ingredients.len());
+        for (i, ingredient) in self.ingredients.iter().enumerate() {
+            let field = format!("ingredients.{i}");
+            let name = ingredient.name.trim().to_lowercase();
+            if name.is_empty() {
+                errors.add(&field, "ingredient name is required");
+            } else if seen.contains(&name) {
+                errors.add(&field, "ingredient is listed more than once");
+            }
+            if ingredient
+                .quantity
+                .is_some_and(|q| !q.is_finite() || q <= 0.0)
+            {
+                errors.add(&field, "quantity must be positive");
+            }
+            seen.push(name);
+        }
+
+        for (i, tag) in self.tags.iter().enumerate() {
+            if tag.name.trim().is_empty() {
+                errors.add(&format!("tags.{i}"), "tag name must not be empty");
+            }
+        }
+
+        if let Some(nutrition) = &self.nutrition {
+            if nutrition
+                .rows()
+                .iter()
+                .any(|(_, _, v)| v.is_some_and(|v| !v.is_finite() || v < 0.0))
+            {
+                errors.add("nutrition", "values must not be negative");
+            }
+        }
+
+        for (i, step) in self.steps.iter().enumerate() {
+            if step.trim().is_empty() {
+                errors.add(&format!("steps.{i}"), "step must not be empty");
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+/// Validation failures keyed by the name of the offending field.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ValidationErrors(BTreeMap<String, Vec<String>>);
+
+impl ValidationErrors {
+    pub fn add(&mut self, field: &str, message: impl Into<String>) {
+        self.0
+            .entry(field.to_string())
+            .or_default()
+            .push(message.into());
+    }
+
+    pub fn get(&self, field: &str) -> &[String] {
+        self.0.get(field).map(Vec::as_slice).unwrap_or_default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn merge(&mut self, other: Self) {
+        for (field, messages) in other.0 {
+            self.0.entry(field).or_default().extend(messages);
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &[String])> {
+        self.0.iter().map(|(k, v)| (k.as_str(), v.as_slice()))
+    }
+}
+
+impl fmt::Display for ValidationErrors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut first = true;
+        for (field, messages) in self.iter() {
+            for message in messages {
+                if !first {
+                    write!(f, "; ")?;
+                }
+                write!(f, "{field}: {message}")?;
+                first = false;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ValidationErrors {}
@@ -1,7 +1,35 @@
 use chrono::prelude::*;
 use serde::{Deserialize, Serialize};
 
+mod diet;
+mod household;
+mod input;
+mod meal_plan;
+mod nutrition;
+mod search;
+mod shopping;
+mod sync;
+mod tag;
+mod user;
+
+pub use diet::{Diet, DietInfo, DietaryFlag, IngredientDiet};
+pub use household::{Household, HouseholdDetail, HouseholdInput, HouseholdMember, HouseholdRole};
+pub use input::{IngredientInput, RecipeInput, ValidationErrors};
+pub use meal_plan::{
+    MealPlan, MealPlanDay, MealPlanDetail, MealPlanEntry, MealPlanInput, MAX_MEAL_PLAN_DAYS,
+};
+pub use nutrition::{Nutrition, NutritionSource, RecipeNutrition};
+pub use search::{PageRequest, RecipeFilter, RecipeSearch, RecipeSort, RECIPE_PAGE_SIZE};
+pub use shopping::{ShoppingList, ShoppingListItem};
+pub use sync::{
+    Changeset, SyncCursor, SyncDeletion, SyncKind, SyncMealPlan, SyncMealPlanEntry, SyncRecipe,
+    SyncRejection, SyncRequest, SyncResponse,
+};
+pub use tag::{Tag, TagFacet, TagInput, TagKind};
+pub use user::{Credentials, User, UserInput, MIN_PASSWORD_LENGTH};
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct Recipe {
     pub id: i64,
     pub created_at: NaiveDateTime,
@@ -12,4 +40,126 @@ pub struct Recipe {
     pub prep_time_minutes: Option<i32>,
     pub cooking_time_minutes: Option<i32>,
     pub image_url: Option<String>,
+    pub servings: Option<i32>,
+    /// The user who added the recipe, if any. Recipes without an owner can be
+    /// seen and changed by everyone.
+    pub owner_id: Option<i64>,
+    /// Whether other users can see the recipe.
+    pub shared: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct Ingredient {
+    pub id: i64,
+    pub name: String,
+    pub slug: String,
+}
+
+/// A unit ingredients are measured in.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct Measurement {
+    pub id: i64,
+    pub name: String,
+    pub slug: String,
+    pub abbreviation: Option<String>,
+}
+
+/// An ingredient as used in a specific recipe, with its quantity and unit.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct RecipeIngredient {
+    pub ingredient_id: i64,
+    pub name: String,
+    pub quantity: Option<f64>,
+    /// The abbreviation of the measurement used, if any.
+    ///
+    /// `None` means the ingredient is counted (e.g. "2 onions").
+    pub unit: Option<String>,
+    pub notes: Option<String>,
+}
+
+impl RecipeIngredient {
+    /// Render the ingredient back into a single line of text, in a form
+    /// which the ingredient parser understands.
+    pub fn to_line(&self) -> String {
+        let mut line = String::new();
+        if let Some(quantity) = self.quantity {
+            line.push_str(&quantity.to_string());
+            line.push(' ');
+        }
+        if let Some(unit) = &self.unit {
+            line.push_str(unit);
+            line.push(' ');
+        }
+        line.push_str(&self.name);
+        if let Some(notes) = &self.notes {
+            line.push_str(", ");
+            line.push_str(notes);
+        }
+        line
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct RecipeStep {
+    pub step_number: i32,
+    pub description: String,
+}
+
+/// A recipe along with its ordered ingredients and steps.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct RecipeDetail {
+    pub recipe: Recipe,
+    pub ingredients: Vec<RecipeIngredient>,
+    pub steps: Vec<RecipeStep>,
+    pub tags: Vec<Tag>,
+    pub diet: DietInfo,
+    pub nutrition: RecipeNutrition,
+    /// The households the recipe is shared into which the current user
+    /// belongs to.
+    #[serde(default)]
+    pub household_ids: Vec<i64>,
+    /// Whether the current user may change the recipe.
+    #[serde(default)]
+    pub editable: bool,
+}
+
+impl RecipeDetail {
+    /// The input which would save the recipe as it is, e.g. to copy it to
+    /// another database.
+    pub fn to_input(&self) -> RecipeInput {
+        let recipe = &self.recipe;
+        RecipeInput {
+            name: recipe.name.clone(),
+            source: recipe.source.clone(),
+            notes: recipe.notes.clone(),
+            image_url: recipe.image_url.clone(),
+            prep_time_minutes: recipe.prep_time_minutes,
+            cooking_time_minutes: recipe.cooking_time_minutes,
+            servings: recipe.servings,
+            ingredients: self
+                .ingredients
+                .iter()
+                .map(|ingredient| IngredientInput {
+                    name: ingredient.name.clone(),
+                    quantity: ingredient.quantity,
+                    unit: ingredient.unit.clone(),
+                    notes: ingredient.notes.clone(),
+                })
+                .collect(),
+            steps: self.steps.iter().map(|s| s.description.clone()).collect(),
+            tags: self
+                .tags
+                .iter()
+                .map(|tag| TagInput::new(tag.kind, &tag.name))
+                .collect(),
+            diets: self.diet.declared.clone(),
+            nutrition: self.nutrition.declared,
+            shared: recipe.shared,
+        }
+    }
 }
@@ -0,0 +1,131 @@
+use chrono::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{Nutrition, NutritionSource, Recipe, ValidationErrors};
+
+/// The longest meal plan which can be created, in days.
+pub const MAX_MEAL_PLAN_DAYS: i64 = 31;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct MealPlan {
+    pub id: i64,
+    pub name: String,
+    pub slug: String,
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    pub owner_id: Option<i64>,
+    /// Whether other users can see the plan.
+    pub shared: bool,
+}
+
+impl MealPlan {
+    /// Every day covered by the plan, in order.
+    pub fn dates(&self) -> Vec<NaiveDate> {
+        self.start_date
+            .iter_days()
+            .take_while(|d| *d <= self.end_date)
+            .collect()
+    }
+}
+
+/// A recipe planned for a particular day.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct MealPlanEntry {
+    pub id: i64,
+    pub day: NaiveDate,
+    pub recipe: Recipe,
+    /// Nutrition of one serving of the recipe.
+    pub nutrition: Nutrition,
+    pub nutrition_source: NutritionSource,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct MealPlanDay {
+    pub date: NaiveDate,
+    pub entries: Vec<MealPlanEntry>,
+}
+
+impl MealPlanDay {
+    /// The nutrition of one serving of every recipe planned for the day.
+    pub fn nutrition(&self) -> Nutrition {
+        self.entries.iter().map(|entry| entry.nutrition).sum()
+    }
+}
+
+/// A meal plan with its recipes grouped by day.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct MealPlanDetail {
+    pub plan: MealPlan,
+    pub days: Vec<MealPlanDay>,
+    /// The households the plan is shared into which the current user
+    /// belongs to.
+    #[serde(default)]
+    pub household_ids: Vec<i64>,
+    /// Whether the current user may change the plan.
+    #[serde(default)]
+    pub editable: bool,
+}
+
+impl MealPlanDetail {
+    /// Group `entries` into the days of `plan`. Entries falling outside the
+    /// plan's dates are dropped.
+    pub fn new(plan: MealPlan, entries: Vec<MealPlanEntry>) -> Self {
+        let mut days: Vec<MealPlanDay> = plan
+            .dates()
+            .into_iter()
+            .map(|date| MealPlanDay {
+                date,
+                entries: Vec::new(),
+            })
+            .collect();
+        for entry in entries {
+            if let Some(day) = days.iter_mut().find(|d| d.date == entry.day) {
+                day.entries.push(entry);
+            }
+        }
+        Self {
+            plan,
+            days,
+            household_ids: Vec::new(),
+            editable: false,
+        }
+    }
+}
+
+/// The data needed to create or update a meal plan.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct MealPlanInput {
+    pub name: String,
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    #[serde(default)]
+    pub shared: bool,
+}
+
+impl MealPlanInput {
+    pub fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::default();
+        if self.name.trim().is_empty() {
+            errors.add("name", "name is required");
+        }
+        let days = (self.end_date - self.start_date).num_days() + 1;
+        if days < 1 {
+            errors.add("end_date", "must not be before the start date");
+        } else if days > MAX_MEAL_PLAN_DAYS {
+            errors.add(
+                "end_date",
+                format!("plans can be at most {MAX_MEAL_PLAN_DAYS} days long"),
+            );
+        }
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
@@ -0,0 +1,131 @@
+use std::ops::Add;
+
+use serde::{Deserialize, Serialize};
+
+/// Nutritional values of some amount of food.
+///
+/// Each value is optional since sources often only declare some of them.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct Nutrition {
+    pub energy_kcal: Option<f64>,
+    pub fat_g: Option<f64>,
+    pub saturated_fat_g: Option<f64>,
+    pub carbohydrate_g: Option<f64>,
+    pub sugar_g: Option<f64>,
+    pub fibre_g: Option<f64>,
+    pub protein_g: Option<f64>,
+    pub salt_g: Option<f64>,
+}
+
+impl Nutrition {
+    pub fn is_empty(&self) -> bool {
+        self.rows().iter().all(|(_, _, value)| value.is_none())
+    }
+
+    /// Multiply every value by `factor`, e.g. to go from one serving to many.
+    pub fn scale(&self, factor: f64) -> Self {
+        let scale = |v: Option<f64>| v.map(|v| v * factor);
+        Self {
+            energy_kcal: scale(self.energy_kcal),
+            fat_g: scale(self.fat_g),
+            saturated_fat_g: scale(self.saturated_fat_g),
+            carbohydrate_g: scale(self.carbohydrate_g),
+            sugar_g: scale(self.sugar_g),
+            fibre_g: scale(self.fibre_g),
+            protein_g: scale(self.protein_g),
+            salt_g: scale(self.salt_g),
+        }
+    }
+
+    /// Build from values given in the same order as [`Nutrition::rows`].
+    pub fn from_values(values: [Option<f64>; 8]) -> Self {
+        let [energy_kcal, fat_g, saturated_fat_g, carbohydrate_g, sugar_g, fibre_g, protein_g, salt_g] =
+            values;
+        Self {
+            energy_kcal,
+            fat_g,
+            saturated_fat_g,
+            carbohydrate_g,
+            sugar_g,
+            fibre_g,
+            protein_g,
+            salt_g,
+        }
+    }
+
+    /// The values along with a label and unit for display.
+    pub fn rows(&self) -> [(&'static str, &'static str, Option<f64>); 8] {
+        [
+            ("Energy", "kcal", self.energy_kcal),
+            ("Fat", "g", self.fat_g),
+            ("Saturates", "g", self.saturated_fat_g),
+            ("Carbohydrate", "g", self.carbohydrate_g),
+            ("Sugars", "g", self.sugar_g),
+            ("Fibre", "g", self.fibre_g),
+            ("Protein", "g", self.protein_g),
+            ("Salt", "g", self.salt_g),
+        ]
+    }
+}
+
+impl Add for Nutrition {
+    type Output = Self;
+
+    /// Sum two sets of values, treating a value missing from only one side
+    /// as zero.
+    fn add(self, other: Self) -> Self {
+        let add = |a: Option<f64>, b: Option<f64>| match (a, b) {
+            (Some(a), Some(b)) => Some(a + b),
+            (a, b) => a.or(b),
+        };
+        Self {
+            energy_kcal: add(self.energy_kcal, other.energy_kcal),
+            fat_g: add(self.fat_g, other.fat_g),
+            saturated_fat_g: add(self.saturated_fat_g, other.saturated_fat_g),
+            carbohydrate_g: add(self.carbohydrate_g, other.carbohydrate_g),
+            sugar_g: add(self.sugar_g, other.sugar_g),
+            fibre_g: add(self.fibre_g, other.fibre_g),
+            protein_g: add(self.protein_g, other.protein_g),
+            salt_g: add(self.salt_g, other.salt_g),
+        }
+    }
+}
+
+impl std::iter::Sum for Nutrition {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::default(), Add::add)
+    }
+}
+
+/// Where the nutritional values of a recipe came from.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum NutritionSource {
+    /// Given by the recipe's source or entered by hand.
+    Declared,
+    /// Estimated from the ingredients using the bundled nutrient data.
+    Estimated,
+}
+
+/// The nutrition of a recipe, per serving.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct RecipeNutrition {
+    pub declared: Option<Nutrition>,
+    pub estimated: Nutrition,
+    /// Ingredients which couldn't be included in the estimate, either because
+    /// there's no nutrient data for them or their amount couldn't be weighed.
+    pub unestimated: Vec<String>,
+}
+
+impl RecipeNutrition {
+    /// The best available values per serving, preferring declared ones.
+    pub fn per_serving(&self) -> (Nutrition, NutritionSource) {
+        match self.declared {
+            Some(declared) if !declared.is_empty() => (declared, NutritionSource::Declared),
+            _ => (self.estimated, NutritionSource::Estimated),
+        }
+    }
+}
@@ -0,0 +1,123 @@
+use std::{fmt, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Diet, Recipe, TagFacet};
+
+/// How many recipes are loaded at a time when paging through them.
+pub const RECIPE_PAGE_SIZE: usize = 24;
+
+/// The order recipes are listed in.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum RecipeSort {
+    /// Alphabetically by name.
+    #[default]
+    Name,
+    /// Most recently added first.
+    Newest,
+    /// Quickest first, by preparation and cooking time combined. Recipes
+    /// without any times come last.
+    TotalTime,
+}
+
+impl RecipeSort {
+    pub const ALL: [Self; 3] = [Self::Name, Self::Newest, Self::TotalTime];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Name => "name",
+            Self::Newest => "newest",
+            Self::TotalTime => "total_time",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Name => "Name",
+            Self::Newest => "Newest",
+            Self::TotalTime => "Quickest",
+        }
+    }
+}
+
+impl fmt::Display for RecipeSort {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for RecipeSort {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "name" => Ok(Self::Name),
+            "newest" => Ok(Self::Newest),
+            "total_time" => Ok(Self::TotalTime),
+            other => Err(format!("unknown sort order {other}")),
+        }
+    }
+}
+
+/// A window of `limit` results starting at `offset`.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct PageRequest {
+    pub offset: usize,
+    pub limit: usize,
+}
+
+impl PageRequest {
+    /// The `index`th page of `RECIPE_PAGE_SIZE` results, counting from zero.
+    pub fn nth(index: usize) -> Self {
+        Self {
+            offset: index.saturating_mul(RECIPE_PAGE_SIZE),
+            limit: RECIPE_PAGE_SIZE,
+        }
+    }
+}
+
+impl Default for PageRequest {
+    fn default() -> Self {
+        Self::nth(0)
+    }
+}
+
+/// Criteria for narrowing down the list of recipes.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub struct RecipeFilter {
+    /// Free text matched against the recipe name.
+    pub query: Option<String>,
+    /// Only recipes carrying every one of these tags are returned.
+    pub tag_ids: Vec<i64>,
+    /// Only recipes whose ingredients suit every one of these diets are returned.
+    #[serde(default)]
+    pub diets: Vec<Diet>,
+    #[serde(default)]
+    pub sort: RecipeSort,
+    /// Which of the matching recipes to return, or all of them if `None`.
+    #[serde(default)]
+    pub page: Option<PageRequest>,
+}
+
+/// The recipes matching a [`RecipeFilter`], along with the tags present in
+/// those recipes so the results can be narrowed further.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct RecipeSearch {
+    pub recipes: Vec<Recipe>,
+    /// The tags of every matching recipe, not only those in this page.
+    pub facets: Vec<TagFacet>,
+    /// The number of matching recipes across all pages.
+    #[serde(default)]
+    pub total: usize,
+}
+
+impl RecipeSearch {
+    /// Whether there are more matching recipes after the requested page.
+    pub fn has_more(&self, page: Option<PageRequest>) -> bool {
+        page.is_some_and(|page| page.offset.saturating_add(self.recipes.len()) < self.total)
+    }
+}
@@ -0,0 +1,264 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{MealPlanDetail, RecipeDetail};
+
+/// An ingredient to buy, combined across every recipe which needs it.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ShoppingListItem {
+    pub ingredient_id: i64,
+    pub name: String,
+    /// The total amount needed, or `None` if no recipe gave an amount.
+    pub quantity: Option<f64>,
+    pub unit: Option<String>,
+    /// The names of the recipes needing the ingredient.
+    pub recipes: Vec<String>,
+}
+
+impl ShoppingListItem {
+    pub fn to_line(&self) -> String {
+        let mut line = String::new();
+        if let Some(quantity) = self.quantity {
+            line.push_str(&((quantity * 100.0).round() / 100.0).to_string());
+            line.push(' ');
+        }
+        if let Some(unit) = &self.unit {
+            line.push_str(unit);
+            line.push(' ');
+        }
+        line.push_str(&self.name);
+        line
+    }
+}
+
+/// Everything needed to cook a set of recipes.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ShoppingList {
+    /// The items, sorted by name.
+    pub items: Vec<ShoppingListItem>,
+}
+
+impl ShoppingList {
+    /// Combine the ingredients of `recipes`, adding up amounts of the same
+    /// ingredient measured in the same unit. A recipe given more than once is
+    /// counted each time.
+    pub fn new<'a>(recipes: impl IntoIterator<Item = &'a RecipeDetail>) -> Self {
+        let mut items: Vec<ShoppingListItem> = Vec::new();
+        for detail in recipes {
+            for ingredient in &detail.ingredients {
+                let existing = items.iter_mut().find(|item| {
+                    item.ingredient_id == ingredient.ingredient_id && item.unit == ingredient.unit
+                });
+                let Some(item) = existing else {
+                    items.push(ShoppingListItem {
+                        ingredient_id: ingredient.ingredient_id,
+                        name: ingredient.name.clone(),
+                        quantity: ingredient.quantity,
+                        unit: ingredient.unit.clone(),
+                        recipes: vec![detail.recipe.name.clone()],
+                    });
+                    continue;
+                };
+                item.quantity = match (item.quantity, ingredient.quantity) {
+                    (Some(a), Some(b)) => Some(a + b),
+                    (a, b) => a.or(b),
+                };
+                if !item.recipes.contains(&detail.recipe.name) {
+                    item.recipes.push(detail.recipe.name.clone());
+                }
+            }
+        }
+        items.sort_by_key(|item| item.name.to_lowercase());
+        Self { items }
+    }
+
+    /// Everything needed to cook `plan`, given the details of its recipes.
+    /// Each entry is one batch of its recipe, so recipes planned several
+    /// times are shopped for several times. Entries whose recipe isn't in
+    /// `recipes` are left out.
+    pub fn for_meal_plan(plan: &MealPlanDetail, recipes: &[RecipeDetail]) -> Self {
+        Self::new(
+            plan.days
+                .iter()
+                .flat_map(|day| &day.entries)
+                .filter_map(|entry| recipes.iter().find(|r| r.recipe.id == entry.recipe.id)),
+        )
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::*;
+    use crate::{
+        MealPlan, MealPlanEntry, NutritionSource, Recipe, RecipeIngredient, RecipeNutrition,
+    };
+
+    /// An ingredient's ID, name, quantity and unit.
+    type Line<'a> = (i64, &'a str, Option<f64>, Option<&'a str>);
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 3, day).unwrap()
+    }
+
+    fn recipe(id: i64, name: &str, lines: &[Line]) -> RecipeDetail {
+        RecipeDetail {
+            recipe: Recipe {
+                id,
+                created_at: date(1).and_hms_opt(0, 0, 0).unwrap(),
+                name: name.to_string(),
+                slug: name.to_lowercase(),
+                source: None,
+                notes: None,
+                prep_time_minutes: None,
+                cooking_time_minutes: None,
+                image_url: None,
+                servings: None,
+                owner_id: None,
+                shared: false,
+            },
+            ingredients: lines
+                .iter()
+                .map(|&(ingredient_id, name, quantity, unit)| RecipeIngredient {
+                    ingredient_id,
+                    name: name.to_string(),
+                    quantity,
+                    unit: unit.map(str::to_string),
+                    notes: None,
+                })
+                .collect(),
+            steps: Vec::new(),
+            tags: Vec::new(),
+            diet: Default::default(),
+            nutrition: RecipeNutrition::default(),
+            household_ids: Vec::new(),
+            editable: false,
+        }
+    }
+
+    /// A week's plan with each recipe on the given day.
+    fn plan(entries: &[(u32, &RecipeDetail)]) -> MealPlanDetail {
+        let plan = MealPlan {
+            id: 1,
+            name: "Week".to_string(),
+            slug: "week".to_string(),
+            start_date: date(1),
+            end_date: date(7),
+            owner_id: None,
+            shared: false,
+        };
+        let entries = entries
+            .iter()
+            .enumerate()
+            .map(|(i, &(day, detail))| MealPlanEntry {
+                id: i as i64,
+                day: date(day),
+                recipe: detail.recipe.clone(),
+                nutrition: Default::default(),
+                nutrition_source: NutritionSource::Estimated,
+            })
+            .collect();
+        MealPlanDetail::new(plan, entries)
+    }
+
+    /// Each item as a line, with the recipes needing it.
+    fn items(list: &ShoppingList) -> Vec<(String, Vec<&str>)> {
+        list.items
+            .iter()
+            .map(|item| {
+                let recipes = item.recipes.iter().map(String::as_str).collect();
+                (item.to_line(), recipes)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn amounts_are_added_across_recipes() {
+        let porridge = recipe(
+            1,
+            "Porridge",
+            &[
+                (1, "oats", Some(50.0), Some("g")),
+                (2, "milk", Some(300.0), Some("ml")),
+                (3, "salt", None, None),
+            ],
+        );
+        let flapjacks = recipe(
+            2,
+            "Flapjacks",
+            &[
+                (1, "oats", Some(250.0), Some("g")),
+                (4, "butter", Some(125.0), Some("g")),
+                (5, "Golden syrup", Some(3.0), Some("tbsp")),
+            ],
+        );
+        // Porridge is planned twice, so is shopped for twice.
+        let plan = plan(&[(1, &porridge), (2, &porridge), (3, &flapjacks)]);
+        let list = ShoppingList::for_meal_plan(&plan, &[porridge, flapjacks]);
+        assert_eq!(
+            items(&list),
+            [
+                ("125 g butter".to_string(), vec!["Flapjacks"]),
+                ("3 tbsp Golden syrup".to_string(), vec!["Flapjacks"]),
+                ("600 ml milk".to_string(), vec!["Porridge"]),
+                ("350 g oats".to_string(), vec!["Porridge", "Flapjacks"]),
+                ("salt".to_string(), vec!["Porridge"]),
+            ]
+        );
+    }
+
+    #[test]
+    fn amounts_in_different_units_are_kept_apart() {
+        let tea = recipe(1, "Tea", &[(1, "milk", Some(50.0), Some("ml"))]);
+        let custard = recipe(2, "Custard", &[(1, "milk", Some(1.0), Some("pint"))]);
+        let cocoa = recipe(3, "Cocoa", &[(1, "milk", Some(250.0), Some("ml"))]);
+        let plan = plan(&[(1, &tea), (1, &custard), (2, &cocoa)]);
+        let list = ShoppingList::for_meal_plan(&plan, &[tea, custard, cocoa]);
+        assert_eq!(
+            items(&list),
+            [
+                ("300 ml milk".to_string(), vec!["Tea", "Cocoa"]),
+                ("1 pint milk".to_string(), vec!["Custard"]),
+            ]
+        );
+    }
+
+    #[test]
+    fn missing_amounts_count_as_nothing() {
+        let omelette = recipe(1, "Omelette", &[(1, "eggs", None, None)]);
+        let cake = recipe(
+            2,
+            "Cake",
+            &[(1, "eggs", Some(3.0), None), (2, "vanilla", None, None)],
+        );
+        let custard = recipe(3, "Custard", &[(2, "vanilla", None, None)]);
+        let plan = plan(&[(1, &omelette), (2, &cake), (3, &custard)]);
+        let list = ShoppingList::for_meal_plan(&plan, &[omelette, cake, custard]);
+        assert_eq!(list.items[1].quantity, None);
+        assert_eq!(
+            items(&list),
+            [
+                ("3 eggs".to_string(), vec!["Omelette", "Cake"]),
+                ("vanilla".to_string(), vec!["Cake", "Custard"]),
+            ]
+        );
+    }
+
+    #[test]
+    fn entries_without_details_are_left_out() {
+        let toast = recipe(1, "Toast", &[(1, "bread", Some(2.0), Some("slices"))]);
+        let plan = plan(&[(1, &toast), (2, &toast)]);
+        assert!(ShoppingList::for_meal_plan(&plan, &[]).is_empty());
+        let list = ShoppingList::for_meal_plan(&plan, &[toast]);
+        assert_eq!(
+            items(&list),
+            [("4 slices bread".to_string(), vec!["Toast"])]
+        );
+    }
+}
@@ -0,0 +1,145 @@
+use std::{fmt, str::FromStr};
+
+use chrono::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{MealPlanInput, RecipeInput};
+
+/// A recipe as exchanged when syncing: all of it, so that whichever copy was
+/// edited last can replace the other.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct SyncRecipe {
+    /// Identifies the recipe in every database it's synced to.
+    pub sync_id: String,
+    /// When the recipe was last edited.
+    pub updated_at: NaiveDateTime,
+    pub recipe: RecipeInput,
+}
+
+/// A meal plan as exchanged when syncing, along with its entries.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct SyncMealPlan {
+    pub sync_id: String,
+    pub updated_at: NaiveDateTime,
+    pub meal_plan: MealPlanInput,
+    pub entries: Vec<SyncMealPlanEntry>,
+}
+
+/// A recipe planned on a day, referring to the recipe by its sync ID.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct SyncMealPlanEntry {
+    pub day: NaiveDate,
+    pub recipe: String,
+}
+
+/// The kinds of things which are synced.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum SyncKind {
+    Recipe,
+    MealPlan,
+}
+
+impl SyncKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Recipe => "recipe",
+            Self::MealPlan => "meal_plan",
+        }
+    }
+}
+
+impl fmt::Display for SyncKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for SyncKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "recipe" => Ok(Self::Recipe),
+            "meal_plan" => Ok(Self::MealPlan),
+            other => Err(format!("unknown sync kind {other}")),
+        }
+    }
+}
+
+/// A recipe or meal plan which was deleted.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct SyncDeletion {
+    pub kind: SyncKind,
+    pub sync_id: String,
+    pub deleted_at: NaiveDateTime,
+}
+
+/// Everything which changed in one database since it last synced.
+///
+/// Conflicts are settled per recipe or meal plan: the copy with the latest
+/// `updated_at` wins, and a deletion wins over edits made before it but not
+/// over those made after.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct Changeset {
+    #[serde(default)]
+    pub recipes: Vec<SyncRecipe>,
+    #[serde(default)]
+    pub meal_plans: Vec<SyncMealPlan>,
+    #[serde(default)]
+    pub deletions: Vec<SyncDeletion>,
+}
+
+impl Changeset {
+    pub fn is_empty(&self) -> bool {
+        self.recipes.is_empty() && self.meal_plans.is_empty() && self.deletions.is_empty()
+    }
+}
+
+/// A client's changes, sent to the server along with the cursor it was given
+/// last time.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct SyncRequest {
+    /// The `cursor` from the previous response, or nothing to fetch
+    /// everything.
+    pub since: Option<NaiveDateTime>,
+    pub changes: Changeset,
+}
+
+/// A change which wasn't applied, such as one to a recipe owned by someone
+/// else. The rest of the changes sent with it are still applied.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct SyncRejection {
+    pub kind: SyncKind,
+    pub sync_id: String,
+    pub reason: String,
+}
+
+/// The server's changes since the client's cursor.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct SyncResponse {
+    /// The `since` to send next time.
+    pub cursor: NaiveDateTime,
+    pub changes: Changeset,
+    /// The client's changes which weren't applied.
+    #[serde(default)]
+    pub rejected: Vec<SyncRejection>,
+}
+
+/// How far a client has synced with a server.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SyncCursor {
+    /// The cursor the server returned last time.
+    pub pulled: Option<NaiveDateTime>,
+    /// The cursor for the client's own changes, covering those it has sent.
+    pub pushed: Option<NaiveDateTime>,
+}
@@ -0,0 +1,93 @@
+use std::{fmt, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+/// The kind of classification a tag represents.
+#[derive(
+    Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash,
+)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum TagKind {
+    /// A free-form keyword, e.g. "quick" or "freezable".
+    #[default]
+    Tag,
+    /// The type of dish, e.g. "Dinner" or "Dessert".
+    Category,
+    /// The cuisine the recipe belongs to, e.g. "Italian".
+    Cuisine,
+}
+
+impl TagKind {
+    pub const ALL: [Self; 3] = [Self::Category, Self::Cuisine, Self::Tag];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Tag => "tag",
+            Self::Category => "category",
+            Self::Cuisine => "cuisine",
+        }
+    }
+
+    /// A human readable, pluralised label for headings.
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Tag => "Tags",
+            Self::Category => "Categories",
+            Self::Cuisine => "Cuisines",
+        }
+    }
+}
+
+impl fmt::Display for TagKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for TagKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "tag" => Ok(Self::Tag),
+            "category" => Ok(Self::Category),
+            "cuisine" => Ok(Self::Cuisine),
+            other => Err(format!("unknown tag kind {other}")),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct Tag {
+    pub id: i64,
+    pub name: String,
+    pub slug: String,
+    pub kind: TagKind,
+}
+
+/// A tag to attach to a recipe, created if it doesn't exist yet.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct TagInput {
+    pub name: String,
+    pub kind: TagKind,
+}
+
+impl TagInput {
+    pub fn new(kind: TagKind, name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            kind,
+        }
+    }
+}
+
+/// A tag along with how many recipes in a result set carry it.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct TagFacet {
+    pub tag: Tag,
+    pub count: usize,
+}
@@ -0,0 +1,60 @@
+use serde::{Deserialize, Serialize};
+
+use crate::ValidationErrors;
+
+/// The shortest password which will be accepted when registering.
+pub const MIN_PASSWORD_LENGTH: usize = 8;
+
+/// A registered user, without any of their credentials.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct User {
+    pub id: i64,
+    pub username: String,
+    pub display_name: String,
+}
+
+/// A username and password, as entered when signing in.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct Credentials {
+    pub username: String,
+    pub password: String,
+}
+
+/// The data needed to register a new user.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct UserInput {
+    pub username: String,
+    /// Shown in place of the username if given.
+    pub display_name: Option<String>,
+    pub password: String,
+}
+
+impl UserInput {
+    pub fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::default();
+        let username = self.username.trim();
+        if username.is_empty() {
+            errors.add("username", "username is required");
+        } else if !username
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+        {
+            errors.add(
+                "username",
+                "may only contain letters, numbers, '-', '_' and '.'",
+            );
+        }
+        if self.password.chars().count() < MIN_PASSWORD_LENGTH {
+            errors.add(
+                "password",
+                format!("must be at least {MIN_PASSWORD_LENGTH} characters"),
+            );
+        }
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
@@ -1,7 +1,7 @@
 use recipe_app::server::AppState;
 use recipe_db::DbPool;
 use recipe_repository::DieselRepository;
-use recipe_server::{router, HotReload};
+use recipe_server::{router, RouterOptions};
 
 mod pg_pool;
 
@@ -9,5 +9,13 @@ mod pg_pool;
 async fn main(#[pg_pool::ShuttleDbPool] pool: DbPool) -> shuttle_axum::ShuttleAxum {
     let repo = DieselRepository::new(pool);
     let state = AppState::new(repo);
-    Ok(router(state, HotReload::Off).into())
+    let metrics =
+        recipe_server::metrics::install().map_err(|e| shuttle_runtime::Error::Custom(e.into()))?;
+    let options = RouterOptions {
+        metrics: Some(metrics),
+        // Shuttle only serves over HTTPS.
+        secure_cookies: true,
+        ..RouterOptions::default()
+    };
+    Ok(router(state, options).into())
 }
@@ -59,6 +59,16 @@ impl ResourceBuilder<DbPool> for ShuttleDbPool {
             DbOutput::Local(local_uri) => local_uri.clone(),
             DbOutput::Info(info) => info.connection_string_private(),
         };
+        // Deployments can't run the diesel CLI, so migrate on start up. Other
+        // instances starting at the same time wait for the lock.
+        let url = connection_string.clone();
+        let versions = tokio::task::spawn_blocking(move || recipe_db::run_migrations(&url))
+            .await
+            .map_err(|e| Error::Database(e.to_string()))?
+            .map_err(|e| Error::Database(format!("{e:#}")))?;
+        for version in versions {
+            tracing::info!(%version, "ran migration");
+        }
         Ok(build_pool(&connection_string)
             .max_size(5)
             .build()